@@ -2,8 +2,11 @@
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use glyphgen::render_engines::ascii::{render_ascii, AsciiConfig, CharacterSet};
-use glyphgen::render_engines::text_stylizer::{stylize_text, GradientMode, UnicodeStyle};
+use glyphgen::render_engines::text_stylizer::{
+    stylize_text, GradientMode, TextAlignment, TextTransform, UnicodeStyle,
+};
 use glyphgen::render_engines::unicode::{render_unicode, UnicodeConfig, UnicodeMode};
+use glyphgen::render_engines::{ResizeFilter, PARALLEL_MIN_CELLS};
 use glyphgen::terminal_capabilities::ColorSupport;
 use image::{DynamicImage, RgbImage};
 
@@ -31,6 +34,7 @@ fn benchmark_ascii_render(c: &mut Criterion) {
             charset: CharacterSet::Extended,
             invert: false,
             edge_enhance: false,
+            ..AsciiConfig::default()
         };
 
         group.bench_function(format!("width_{}", width), |b| {
@@ -49,6 +53,7 @@ fn benchmark_ascii_with_edge_enhance(c: &mut Criterion) {
         charset: CharacterSet::Extended,
         invert: false,
         edge_enhance: false,
+        ..AsciiConfig::default()
     };
 
     let config_with = AsciiConfig {
@@ -56,6 +61,7 @@ fn benchmark_ascii_with_edge_enhance(c: &mut Criterion) {
         charset: CharacterSet::Extended,
         invert: false,
         edge_enhance: true,
+        ..AsciiConfig::default()
     };
 
     let mut group = c.benchmark_group("ASCII Edge Enhancement");
@@ -71,16 +77,98 @@ fn benchmark_ascii_with_edge_enhance(c: &mut Criterion) {
     group.finish();
 }
 
+fn benchmark_parallel_rows(c: &mut Criterion) {
+    let image = create_test_image(1920, 1080);
+
+    let mut group = c.benchmark_group("Parallel Rows");
+
+    for parallel in [false, true] {
+        let label = if parallel { "parallel" } else { "serial" };
+        let ascii = AsciiConfig {
+            target_width: 400,
+            parallel,
+            ..AsciiConfig::default()
+        };
+        let unicode = UnicodeConfig {
+            target_width: 400,
+            mode: UnicodeMode::HalfBlocks,
+            color_mode: ColorSupport::TrueColor,
+            parallel,
+            ..UnicodeConfig::default()
+        };
+
+        // Smaller grids render serially even with `parallel` set
+        for output in [
+            render_ascii(&image, &ascii).unwrap(),
+            render_unicode(&image, &unicode).unwrap(),
+        ] {
+            assert!(400 * output.lines().count() >= PARALLEL_MIN_CELLS);
+        }
+
+        group.bench_function(format!("ascii_width_400_{}", label), |b| {
+            b.iter(|| render_ascii(black_box(&image), black_box(&ascii)))
+        });
+        group.bench_function(format!("half_blocks_width_400_{}", label), |b| {
+            b.iter(|| render_unicode(black_box(&image), black_box(&unicode)))
+        });
+    }
+
+    group.finish();
+}
+
+fn benchmark_resize_filters(c: &mut Criterion) {
+    let image = create_test_image(1920, 1080);
+
+    let mut group = c.benchmark_group("Resize Filters");
+
+    for filter in [
+        ResizeFilter::Nearest,
+        ResizeFilter::Triangle,
+        ResizeFilter::CatmullRom,
+        ResizeFilter::Lanczos3,
+    ]
+    .iter()
+    {
+        let ascii_config = AsciiConfig {
+            target_width: 160,
+            resize_filter: *filter,
+            ..AsciiConfig::default()
+        };
+        let unicode_config = UnicodeConfig {
+            target_width: 160,
+            color_mode: ColorSupport::TrueColor,
+            resize_filter: *filter,
+            ..UnicodeConfig::default()
+        };
+
+        group.bench_function(format!("ascii_{:?}", filter), |b| {
+            b.iter(|| render_ascii(black_box(&image), black_box(&ascii_config)))
+        });
+        group.bench_function(format!("unicode_{:?}", filter), |b| {
+            b.iter(|| render_unicode(black_box(&image), black_box(&unicode_config)))
+        });
+    }
+
+    group.finish();
+}
+
 fn benchmark_unicode_render(c: &mut Criterion) {
     let image = create_test_image(800, 600);
 
     let mut group = c.benchmark_group("Unicode Rendering");
 
-    for mode in [UnicodeMode::Blocks, UnicodeMode::HalfBlocks, UnicodeMode::Braille].iter() {
+    for mode in [
+        UnicodeMode::Blocks,
+        UnicodeMode::HalfBlocks,
+        UnicodeMode::Braille,
+    ]
+    .iter()
+    {
         let config = UnicodeConfig {
             target_width: 80,
             mode: *mode,
             color_mode: ColorSupport::NoColor,
+            ..UnicodeConfig::default()
         };
 
         group.bench_function(format!("{:?}", mode), |b| {
@@ -108,6 +196,7 @@ fn benchmark_unicode_color_modes(c: &mut Criterion) {
             target_width: 80,
             mode: UnicodeMode::HalfBlocks,
             color_mode: *color_mode,
+            ..UnicodeConfig::default()
         };
 
         group.bench_function(format!("{:?}", color_mode), |b| {
@@ -135,10 +224,12 @@ fn benchmark_text_stylizer(c: &mut Criterion) {
             b.iter(|| {
                 stylize_text(
                     black_box(test_text),
+                    TextTransform::None,
                     black_box(*style),
                     black_box(GradientMode::None),
                     black_box((255, 0, 0)),
                     black_box((0, 0, 255)),
+                    TextAlignment::Left,
                 )
             })
         });
@@ -152,15 +243,23 @@ fn benchmark_text_gradients(c: &mut Criterion) {
 
     let mut group = c.benchmark_group("Text Gradients");
 
-    for gradient in [GradientMode::None, GradientMode::Horizontal, GradientMode::Rainbow].iter() {
+    for gradient in [
+        GradientMode::None,
+        GradientMode::Horizontal,
+        GradientMode::Rainbow,
+    ]
+    .iter()
+    {
         group.bench_function(format!("{:?}", gradient), |b| {
             b.iter(|| {
                 stylize_text(
                     black_box(test_text),
+                    TextTransform::None,
                     black_box(UnicodeStyle::Bold),
                     black_box(*gradient),
                     black_box((255, 0, 0)),
                     black_box((0, 0, 255)),
+                    TextAlignment::Left,
                 )
             })
         });
@@ -173,6 +272,8 @@ criterion_group!(
     benches,
     benchmark_ascii_render,
     benchmark_ascii_with_edge_enhance,
+    benchmark_parallel_rows,
+    benchmark_resize_filters,
     benchmark_unicode_render,
     benchmark_unicode_color_modes,
     benchmark_text_stylizer,
@@ -0,0 +1,918 @@
+//! Command-line helpers
+//!
+//! Run-mode selection and small utilities used by the non-interactive `--render-once` path.
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::color_space;
+use crate::config::{Config, TextPreferences};
+use crate::image_loader::{is_supported_format, is_url};
+use crate::render_engines::{
+    ascii::CharacterSet,
+    text_stylizer::{GradientMode, TextAlignment, UnicodeStyle},
+    unicode::UnicodeMode,
+};
+use crate::state::RenderMode;
+use crate::terminal_capabilities::{ColorSupport, TerminalCapabilities};
+
+/// Narrowest and widest `--width` accepted, matching the zoom limits
+const MIN_WIDTH: usize = 10;
+const MAX_WIDTH: usize = 4000;
+
+/// How the binary runs once its flags are parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    /// `--batch` or `--from-list`: render several images and exit
+    Batch,
+    /// Render one image and exit
+    RenderOnce,
+    /// Full-screen TUI
+    Interactive,
+}
+
+/// How much headless renders print besides errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogLevel {
+    /// `--quiet`: no "Saved ..." or "Rendered ..." lines
+    Quiet,
+    #[default]
+    Normal,
+    /// `--verbose`: also a [`RenderReport`] per render on stderr
+    Verbose,
+}
+
+/// What a headless render did, printed to stderr with `--verbose`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderReport {
+    /// Input image; `None` for the text stylizer
+    pub input: Option<PathBuf>,
+    /// Input image size in pixels, after any crop, rotation or flip
+    pub dimensions: Option<(u32, u32)>,
+    pub mode: RenderMode,
+    /// Output width in columns; `None` for the text stylizer
+    pub width: Option<usize>,
+    /// Charset, Unicode mode or text style
+    pub style: String,
+    pub color: ColorSupport,
+    pub format: String,
+    pub output_bytes: usize,
+    pub render_time_ms: u64,
+}
+
+impl RenderReport {
+    /// The report as one `key=value` line; missing values are `-` and values with
+    /// spaces are quoted
+    pub fn to_line(&self) -> String {
+        let fields = [
+            (
+                "input",
+                self.input
+                    .as_ref()
+                    .map_or("-".to_string(), |p| p.display().to_string()),
+            ),
+            (
+                "size",
+                self.dimensions
+                    .map_or("-".to_string(), |(w, h)| format!("{}x{}", w, h)),
+            ),
+            ("mode", self.mode.name().to_string()),
+            (
+                "width",
+                self.width.map_or("-".to_string(), |w| w.to_string()),
+            ),
+            ("style", self.style.clone()),
+            ("color", self.color.name().to_string()),
+            ("format", self.format.clone()),
+            ("bytes", self.output_bytes.to_string()),
+            ("time_ms", self.render_time_ms.to_string()),
+        ];
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|(key, value)| {
+                if value.is_empty() || value.contains([' ', '"', '=']) {
+                    format!("{}={:?}", key, value)
+                } else {
+                    format!("{}={}", key, value)
+                }
+            })
+            .collect();
+        format!("render {}", fields.join(" "))
+    }
+}
+
+/// Pick the run mode from the flags and whether stdout is a terminal
+///
+/// With stdout redirected the TUI can't run, so an `--image` (or `--text`) renders once
+/// instead (the caller sends it to stdout unless `--output` is set); without either it's an error.
+pub fn decide_run_mode(
+    batch: bool,
+    render_once: bool,
+    has_input: bool,
+    stdout_is_tty: bool,
+) -> Result<RunMode> {
+    if batch {
+        return Ok(RunMode::Batch);
+    }
+    if has_input && (render_once || !stdout_is_tty) {
+        return Ok(RunMode::RenderOnce);
+    }
+    if !stdout_is_tty {
+        anyhow::bail!(
+            "stdout is not a terminal; pass --image PATH to render it to stdout, or --batch DIR"
+        );
+    }
+    Ok(RunMode::Interactive)
+}
+
+/// Parse a `--mode` value, falling back to ASCII for unknown names
+pub fn parse_mode(mode: Option<&str>) -> RenderMode {
+    match mode.unwrap_or("ascii") {
+        "ascii" => RenderMode::ImageToAscii,
+        "unicode" => RenderMode::ImageToUnicode,
+        "text" => RenderMode::TextStylizer,
+        "color-ascii" => RenderMode::ColorAscii,
+        other => {
+            eprintln!("Unknown mode '{}', defaulting to ascii", other);
+            RenderMode::ImageToAscii
+        }
+    }
+}
+
+/// `--mode` to assume when none is given: `text` for `--text` without `--image`
+///
+/// `None` leaves [`parse_mode`] to fall back to ASCII.
+pub fn infer_mode(has_image: bool, has_text: bool) -> Option<&'static str> {
+    (has_text && !has_image).then_some("text")
+}
+
+/// Render settings given on the command line; `None` keeps the config value
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RenderOverrides {
+    /// `--width`, used by every image mode
+    pub width: Option<usize>,
+    /// `--charset`, for ASCII and Color ASCII
+    pub charset: Option<CharacterSet>,
+    /// `--unicode-mode`
+    pub unicode_mode: Option<UnicodeMode>,
+    /// `--color`, replacing the detected color level
+    pub color: Option<ColorSupport>,
+    /// `--text`, the text stylizer input
+    pub text: Option<String>,
+    /// `--style`
+    pub style: Option<UnicodeStyle>,
+    /// `--gradient`
+    pub gradient: Option<GradientMode>,
+    /// `--start-color`
+    pub start_color: Option<(u8, u8, u8)>,
+    /// `--end-color`
+    pub end_color: Option<(u8, u8, u8)>,
+    /// `--align`
+    pub alignment: Option<TextAlignment>,
+}
+
+/// Input and settings for a headless text stylizer render
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextOptions {
+    pub text: String,
+    pub style: UnicodeStyle,
+    pub gradient: GradientMode,
+    pub start_color: (u8, u8, u8),
+    pub end_color: (u8, u8, u8),
+    pub alignment: TextAlignment,
+}
+
+impl RenderOverrides {
+    /// Write the overrides into the preferences the headless renders read
+    pub fn apply_to(&self, config: &mut Config) {
+        if let Some(width) = self.width {
+            config.ascii.default_width = width;
+            config.unicode.default_width = width;
+        }
+        if let Some(charset) = &self.charset {
+            config.ascii.default_charset = charset.clone();
+        }
+        if let Some(mode) = self.unicode_mode {
+            config.unicode.default_mode = mode;
+        }
+    }
+
+    /// Text stylizer settings: the flags, then the config's style, gradient and alignment,
+    /// then defaults
+    pub fn text_options(&self, prefs: &TextPreferences) -> TextOptions {
+        TextOptions {
+            text: self
+                .text
+                .clone()
+                .unwrap_or_else(|| "Example Text".to_string()),
+            style: self
+                .style
+                .or_else(|| UnicodeStyle::from_name(&prefs.default_style))
+                .unwrap_or_default(),
+            gradient: self
+                .gradient
+                .or_else(|| GradientMode::from_name(&prefs.default_gradient))
+                .unwrap_or_default(),
+            start_color: self.start_color.unwrap_or((255, 0, 0)),
+            end_color: self.end_color.unwrap_or((0, 0, 255)),
+            alignment: self.alignment.unwrap_or(prefs.default_alignment),
+        }
+    }
+}
+
+/// Parse a `--width` value
+pub fn parse_width(value: &str) -> Result<usize> {
+    let width: usize = value
+        .trim()
+        .parse()
+        .with_context(|| format!("Invalid width '{}': expected a number", value))?;
+    if !(MIN_WIDTH..=MAX_WIDTH).contains(&width) {
+        bail!(
+            "Width {} is out of range (expected {}-{})",
+            width,
+            MIN_WIDTH,
+            MAX_WIDTH
+        );
+    }
+    Ok(width)
+}
+
+/// Parse a `--charset` value: `standard`, `extended` or `blocks`
+pub fn parse_charset(value: &str) -> Result<CharacterSet> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "standard" => Ok(CharacterSet::Standard),
+        "extended" => Ok(CharacterSet::Extended),
+        "blocks" => Ok(CharacterSet::Blocks),
+        _ => bail!(
+            "Unknown charset '{}' (expected standard, extended or blocks)",
+            value
+        ),
+    }
+}
+
+/// Parse a `--unicode-mode` value: `blocks`, `half-blocks` or `braille`
+pub fn parse_unicode_mode(value: &str) -> Result<UnicodeMode> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "blocks" => Ok(UnicodeMode::Blocks),
+        "half-blocks" | "halfblocks" => Ok(UnicodeMode::HalfBlocks),
+        "braille" => Ok(UnicodeMode::Braille),
+        _ => bail!(
+            "Unknown Unicode mode '{}' (expected blocks, half-blocks or braille)",
+            value
+        ),
+    }
+}
+
+/// Parse a `--color` value: `none`, `16`, `256` or `truecolor`
+pub fn parse_color(value: &str) -> Result<ColorSupport> {
+    ColorSupport::from_name(value).with_context(|| {
+        format!(
+            "Unknown color level '{}' (expected none, 16, 256 or truecolor)",
+            value
+        )
+    })
+}
+
+/// Parse a `--style` value by name, e.g. `bold-italic` or `fraktur`
+pub fn parse_style(value: &str) -> Result<UnicodeStyle> {
+    UnicodeStyle::from_name(value).with_context(|| {
+        let names: Vec<&str> = UnicodeStyle::all().iter().map(|s| s.name()).collect();
+        format!(
+            "Unknown style '{}' (expected one of: {})",
+            value,
+            names.join(", ")
+        )
+    })
+}
+
+/// Parse a `--gradient` value: `none`, `horizontal` or `rainbow`
+pub fn parse_gradient(value: &str) -> Result<GradientMode> {
+    GradientMode::from_name(value).with_context(|| {
+        format!(
+            "Unknown gradient '{}' (expected none, horizontal or rainbow)",
+            value
+        )
+    })
+}
+
+/// Parse an `--align` value: `left`, `center` or `right`
+pub fn parse_alignment(value: &str) -> Result<TextAlignment> {
+    TextAlignment::from_name(value).with_context(|| {
+        format!(
+            "Unknown alignment '{}' (expected left, center or right)",
+            value
+        )
+    })
+}
+
+/// Parse a `--start-color`/`--end-color` value written as `#RRGGBB`, `RRGGBB` or `#RGB`
+pub fn parse_hex_color(value: &str) -> Result<(u8, u8, u8)> {
+    color_space::parse_hex_color(value)
+        .with_context(|| format!("Invalid color '{}' (expected #RRGGBB or #RGB)", value))
+}
+
+/// Resolve the requested format name to one the mode can produce
+///
+/// Unknown names fall back to the mode's default (`txt`, or `ansi` for Unicode and
+/// Color ASCII, which have no plain-text form because they rely on colors).
+pub fn resolve_output_format(mode: RenderMode, requested: Option<&str>) -> &'static str {
+    let is_colored = matches!(mode, RenderMode::ImageToUnicode | RenderMode::ColorAscii);
+    match requested {
+        Some("html") => "html",
+        Some("png") => "png",
+        Some("svg") => "svg",
+        Some("ansi") => "ansi",
+        Some("txt") if !is_colored => "txt",
+        Some("md") if !is_colored => "md",
+        Some("json") => "json",
+        _ if is_colored => "ansi",
+        _ => "txt",
+    }
+}
+
+/// Infer an output format name from a file extension (`.html` → `"html"`, ...)
+///
+/// Returns `None` for stdout (`-`), missing, or unrecognised extensions.
+pub fn infer_output_format(path: &str) -> Option<&'static str> {
+    let ext = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "html" | "htm" => Some("html"),
+        "svg" => Some("svg"),
+        "png" => Some("png"),
+        "ansi" => Some("ansi"),
+        "txt" => Some("txt"),
+        "md" | "markdown" => Some("md"),
+        "json" => Some("json"),
+        _ => None,
+    }
+}
+
+/// Create the parent directory of `path` if it does not exist yet
+pub fn ensure_parent_dir(path: &Path) -> Result<()> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() && !parent.exists() => {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// List the supported image files directly inside `dir`, sorted by name
+pub fn collect_batch_inputs(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory {}", dir.display()))?;
+
+    let mut inputs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && is_supported_format(path))
+        .collect();
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// Environment variables capability detection reads, in the order `--check` lists them
+pub const CAPABILITY_ENV_VARS: &[&str] = &[
+    "NO_COLOR",
+    "FORCE_COLOR",
+    "COLORTERM",
+    "TERM",
+    "TMUX",
+    "WT_SESSION",
+    "TERM_PROGRAM",
+    "KITTY_WINDOW_ID",
+    "LANG",
+    "LC_ALL",
+    "LC_CTYPE",
+];
+
+/// `--check` report of the detected capabilities and the variables they came from
+///
+/// Variables are read through `var`, so unset ones show as `(unset)`.
+pub fn capability_report(
+    capabilities: &TerminalCapabilities,
+    var: impl Fn(&str) -> Option<String>,
+) -> String {
+    let mut report = String::from("Terminal\n");
+    report.push_str(&format!(
+        "  color:    {}\n",
+        capabilities.color_support.name()
+    ));
+    report.push_str(&format!("  unicode:  {:?}\n", capabilities.unicode_support));
+    report.push_str(&format!(
+        "  graphics: {:?}\n",
+        capabilities.graphics_protocol
+    ));
+    report.push_str(&format!(
+        "  size:     {}x{}\n",
+        capabilities.size.0, capabilities.size.1
+    ));
+
+    report.push_str("Environment\n");
+    for name in CAPABILITY_ENV_VARS {
+        match var(name) {
+            Some(value) => report.push_str(&format!("  {}={}\n", name, value)),
+            None => report.push_str(&format!("  {} (unset)\n", name)),
+        }
+    }
+    report
+}
+
+/// `--check` report of the settings a headless render would use
+pub fn settings_report(
+    config: &Config,
+    mode: RenderMode,
+    output_format: &str,
+    color: ColorSupport,
+) -> String {
+    let mut report = String::from("Effective settings\n");
+    report.push_str(&format!("  mode:          {}\n", mode.name()));
+    report.push_str(&format!("  output format: {}\n", output_format));
+    report.push_str(&format!(
+        "  width:         {} (ascii), {} (unicode)\n",
+        config.ascii.default_width, config.unicode.default_width
+    ));
+    report.push_str(&format!(
+        "  charset:       {}\n",
+        config.ascii.default_charset.name()
+    ));
+    report.push_str(&format!(
+        "  unicode mode:  {}\n",
+        config.unicode.default_mode.name()
+    ));
+    report.push_str(&format!("  color:         {}\n", color.name()));
+    report
+}
+
+/// Parse a `--from-list` file: one image path per line
+///
+/// Lines are trimmed; blank lines and lines starting with `#` are skipped.
+pub fn parse_path_list(contents: &str) -> Vec<PathBuf> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Read a `--from-list` file, keeping its order and expanding directories in place
+///
+/// Relative entries are resolved against the list's own directory, so the list works from
+/// any working directory; URLs are kept as written. Other entries are not checked, so a
+/// missing file is reported when it fails to render.
+pub fn read_path_list(list: &Path) -> Result<Vec<PathBuf>> {
+    let contents = std::fs::read_to_string(list)
+        .with_context(|| format!("Failed to read path list {}", list.display()))?;
+
+    let base = list.parent().unwrap_or_else(|| Path::new(""));
+    let mut inputs = Vec::new();
+    for entry in parse_path_list(&contents) {
+        let path = if entry.to_str().is_some_and(is_url) {
+            entry
+        } else {
+            base.join(entry)
+        };
+        if path.is_dir() {
+            inputs.extend(collect_batch_inputs(&path)?);
+        } else {
+            inputs.push(path);
+        }
+    }
+    Ok(inputs)
+}
+
+/// Destination for a batch render: `<stem>.<format>` in `out_dir`, or next to the input
+pub fn batch_output_path(input: &Path, out_dir: Option<&Path>, format: &str) -> PathBuf {
+    let stem = input.file_stem().unwrap_or_default();
+    let file_name = format!("{}.{}", stem.to_string_lossy(), format);
+    match out_dir {
+        Some(dir) => dir.join(file_name),
+        None => input.with_file_name(file_name),
+    }
+}
+
+/// [`batch_output_path`] for every input, in order, without two inputs sharing a file
+///
+/// Inputs that would collide (`cat.png` and `cat.jpg`, or one file listed twice) get
+/// `-2`, `-3`, ... after the stem instead of overwriting each other's output.
+pub fn batch_output_paths(
+    inputs: &[PathBuf],
+    out_dir: Option<&Path>,
+    format: &str,
+) -> Vec<PathBuf> {
+    let mut taken = HashSet::new();
+    inputs
+        .iter()
+        .map(|input| {
+            let dest = batch_output_path(input, out_dir, format);
+            let stem = dest
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            let mut unique = dest.clone();
+            let mut n = 2;
+            while !taken.insert(unique.clone()) {
+                unique = dest.with_file_name(format!("{}-{}.{}", stem, n, format));
+                n += 1;
+            }
+            unique
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide_run_mode() {
+        assert_eq!(
+            decide_run_mode(false, false, true, true).unwrap(),
+            RunMode::Interactive
+        );
+        assert_eq!(
+            decide_run_mode(false, false, false, true).unwrap(),
+            RunMode::Interactive
+        );
+        assert_eq!(
+            decide_run_mode(false, true, true, true).unwrap(),
+            RunMode::RenderOnce
+        );
+        assert_eq!(
+            decide_run_mode(true, false, false, false).unwrap(),
+            RunMode::Batch
+        );
+
+        // Redirected stdout renders the image once rather than drawing the TUI
+        assert_eq!(
+            decide_run_mode(false, false, true, false).unwrap(),
+            RunMode::RenderOnce
+        );
+        assert!(decide_run_mode(false, true, false, false).is_err());
+    }
+
+    #[test]
+    fn test_infer_mode_for_text_only() {
+        assert_eq!(infer_mode(false, true), Some("text"));
+        assert_eq!(
+            parse_mode(infer_mode(false, true)),
+            RenderMode::TextStylizer
+        );
+        // An image means an image mode, so ASCII stays the default
+        assert_eq!(infer_mode(true, true), None);
+        assert_eq!(infer_mode(false, false), None);
+    }
+
+    #[test]
+    fn test_infer_output_format() {
+        assert_eq!(infer_output_format("a.html"), Some("html"));
+        assert_eq!(infer_output_format("out/b.SVG"), Some("svg"));
+        assert_eq!(infer_output_format("c.png"), Some("png"));
+        assert_eq!(infer_output_format("d.ansi"), Some("ansi"));
+        assert_eq!(infer_output_format("e.txt"), Some("txt"));
+        assert_eq!(infer_output_format("g.md"), Some("md"));
+        assert_eq!(infer_output_format("h.markdown"), Some("md"));
+        assert_eq!(infer_output_format("i.json"), Some("json"));
+        assert_eq!(infer_output_format("f.bin"), None);
+        assert_eq!(infer_output_format("-"), None);
+        assert_eq!(infer_output_format("noext"), None);
+    }
+
+    #[test]
+    fn test_ensure_parent_dir_creates_missing_dirs() {
+        let root = tempfile::tempdir().unwrap();
+        let target = root.path().join("nested").join("out.txt");
+
+        ensure_parent_dir(&target).unwrap();
+        assert!(target.parent().unwrap().is_dir());
+
+        // Bare filenames have no parent to create
+        ensure_parent_dir(Path::new("out.txt")).unwrap();
+    }
+
+    #[test]
+    fn test_parse_render_flags() {
+        assert_eq!(parse_width("120").unwrap(), 120);
+        assert!(parse_width("wide").is_err());
+        assert!(parse_width("5").is_err());
+        assert!(parse_width("5000").is_err());
+
+        assert_eq!(parse_charset("standard").unwrap(), CharacterSet::Standard);
+        assert_eq!(parse_charset("Blocks").unwrap(), CharacterSet::Blocks);
+        let err = parse_charset("fancy").unwrap_err().to_string();
+        assert!(err.contains("fancy") && err.contains("extended"));
+
+        assert_eq!(parse_unicode_mode("braille").unwrap(), UnicodeMode::Braille);
+        assert_eq!(
+            parse_unicode_mode("half-blocks").unwrap(),
+            UnicodeMode::HalfBlocks
+        );
+        assert!(parse_unicode_mode("sextants").is_err());
+
+        assert_eq!(parse_color("256").unwrap(), ColorSupport::Color256);
+        assert_eq!(parse_color("none").unwrap(), ColorSupport::NoColor);
+        assert!(parse_color("rainbow")
+            .unwrap_err()
+            .to_string()
+            .contains("truecolor"));
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#ff8000").unwrap(), (255, 128, 0));
+        assert_eq!(parse_hex_color("00FF7f").unwrap(), (0, 255, 127));
+        assert_eq!(parse_hex_color("#fff").unwrap(), (255, 255, 255));
+        assert!(parse_hex_color("#12345g").is_err());
+        assert!(parse_hex_color("red")
+            .unwrap_err()
+            .to_string()
+            .contains("#RRGGBB"));
+    }
+
+    #[test]
+    fn test_parse_style_and_gradient() {
+        assert_eq!(parse_style("fraktur").unwrap(), UnicodeStyle::Fraktur);
+        assert_eq!(
+            parse_style("bold-italic").unwrap(),
+            UnicodeStyle::BoldItalic
+        );
+        let err = parse_style("gothic").unwrap_err().to_string();
+        assert!(err.contains("gothic") && err.contains("Double-Struck"));
+
+        assert_eq!(parse_gradient("rainbow").unwrap(), GradientMode::Rainbow);
+        assert!(parse_gradient("diagonal").is_err());
+
+        assert_eq!(parse_alignment("Center").unwrap(), TextAlignment::Center);
+        assert!(parse_alignment("justify").is_err());
+    }
+
+    #[test]
+    fn test_text_options_fall_back_to_config() {
+        let prefs = TextPreferences {
+            default_style: "Script".to_string(),
+            default_gradient: "Rainbow".to_string(),
+            default_alignment: TextAlignment::Center,
+        };
+        let options = RenderOverrides::default().text_options(&prefs);
+        assert_eq!(options.text, "Example Text");
+        assert_eq!(options.style, UnicodeStyle::Script);
+        assert_eq!(options.gradient, GradientMode::Rainbow);
+        assert_eq!(options.alignment, TextAlignment::Center);
+
+        let overrides = RenderOverrides {
+            text: Some("Hello".to_string()),
+            style: Some(UnicodeStyle::Circled),
+            start_color: Some((1, 2, 3)),
+            alignment: Some(TextAlignment::Left),
+            ..RenderOverrides::default()
+        };
+        let options = overrides.text_options(&prefs);
+        assert_eq!(options.text, "Hello");
+        assert_eq!(options.style, UnicodeStyle::Circled);
+        assert_eq!(options.gradient, GradientMode::Rainbow);
+        assert_eq!(options.start_color, (1, 2, 3));
+        assert_eq!(options.end_color, (0, 0, 255));
+        assert_eq!(options.alignment, TextAlignment::Left);
+    }
+
+    #[test]
+    fn test_overrides_replace_config_defaults() {
+        let mut config = Config::default();
+        RenderOverrides::default().apply_to(&mut config);
+        assert_eq!(config.ascii.default_width, 80);
+        assert_eq!(config.ascii.default_charset, CharacterSet::Extended);
+
+        let overrides = RenderOverrides {
+            width: Some(120),
+            charset: Some(CharacterSet::Blocks),
+            unicode_mode: Some(UnicodeMode::Braille),
+            ..RenderOverrides::default()
+        };
+        overrides.apply_to(&mut config);
+        assert_eq!(config.ascii.default_width, 120);
+        assert_eq!(config.unicode.default_width, 120);
+        assert_eq!(config.ascii.default_charset, CharacterSet::Blocks);
+        assert_eq!(config.unicode.default_mode, UnicodeMode::Braille);
+    }
+
+    #[test]
+    fn test_resolve_output_format() {
+        assert_eq!(resolve_output_format(RenderMode::ImageToAscii, None), "txt");
+        assert_eq!(
+            resolve_output_format(RenderMode::ImageToUnicode, None),
+            "ansi"
+        );
+        assert_eq!(
+            resolve_output_format(RenderMode::ImageToUnicode, Some("txt")),
+            "ansi"
+        );
+        assert_eq!(
+            resolve_output_format(RenderMode::ColorAscii, Some("txt")),
+            "ansi"
+        );
+        assert_eq!(
+            resolve_output_format(RenderMode::TextStylizer, Some("svg")),
+            "svg"
+        );
+        assert_eq!(
+            resolve_output_format(RenderMode::ImageToAscii, Some("bogus")),
+            "txt"
+        );
+        assert_eq!(
+            resolve_output_format(RenderMode::ImageToAscii, Some("md")),
+            "md"
+        );
+        assert_eq!(
+            resolve_output_format(RenderMode::ImageToUnicode, Some("md")),
+            "ansi"
+        );
+        assert_eq!(
+            resolve_output_format(RenderMode::ImageToUnicode, Some("json")),
+            "json"
+        );
+    }
+
+    #[test]
+    fn test_batch_output_path() {
+        let input = Path::new("shots/cat.jpeg");
+        assert_eq!(
+            batch_output_path(input, None, "txt"),
+            PathBuf::from("shots/cat.txt")
+        );
+        assert_eq!(
+            batch_output_path(input, Some(Path::new("out")), "html"),
+            PathBuf::from("out/cat.html")
+        );
+    }
+
+    #[test]
+    fn test_batch_output_paths_avoid_collisions() {
+        let inputs: Vec<PathBuf> = ["a/cat.png", "b/cat.jpg", "cat-2.gif", "a/cat.png"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        assert_eq!(
+            batch_output_paths(&inputs, Some(Path::new("out")), "txt"),
+            [
+                "out/cat.txt",
+                "out/cat-2.txt",
+                "out/cat-2-2.txt",
+                "out/cat-3.txt"
+            ]
+            .iter()
+            .map(PathBuf::from)
+            .collect::<Vec<_>>()
+        );
+        // Next to their inputs, only the repeated file collides
+        assert_eq!(
+            batch_output_paths(&inputs, None, "txt")[..2],
+            [PathBuf::from("a/cat.txt"), PathBuf::from("b/cat.txt")]
+        );
+    }
+
+    #[test]
+    fn test_collect_batch_inputs_skips_unsupported() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("sub.png")).unwrap();
+        for name in ["b.png", "a.JPG", "notes.txt", "noext"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+
+        let inputs = collect_batch_inputs(dir).unwrap();
+        let names: Vec<_> = inputs
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["a.JPG", "b.png"]);
+    }
+
+    #[test]
+    fn test_capability_report_lists_detection_and_env() {
+        let capabilities = TerminalCapabilities {
+            color_support: ColorSupport::Color256,
+            unicode_support: crate::terminal_capabilities::UnicodeSupport::Basic,
+            graphics_protocol: crate::terminal_capabilities::GraphicsProtocol::Sixel,
+            size: (120, 40),
+            ..Default::default()
+        };
+        let report = capability_report(&capabilities, |name| match name {
+            "TERM" => Some("xterm-256color".to_string()),
+            "NO_COLOR" => Some(String::new()),
+            _ => None,
+        });
+
+        assert!(report.starts_with("Terminal\n"));
+        assert!(report.contains("  color:    256 Colors\n"));
+        assert!(report.contains("  unicode:  Basic\n"));
+        assert!(report.contains("  graphics: Sixel\n"));
+        assert!(report.contains("  size:     120x40\n"));
+        assert!(report.contains("  TERM=xterm-256color\n"));
+        assert!(report.contains("  NO_COLOR=\n"));
+        assert!(report.contains("  COLORTERM (unset)\n"));
+        assert_eq!(
+            report.lines().count(),
+            6 + CAPABILITY_ENV_VARS.len(),
+            "{}",
+            report
+        );
+    }
+
+    #[test]
+    fn test_render_report_line() {
+        let report = RenderReport {
+            input: Some(PathBuf::from("photos/cat.png")),
+            dimensions: Some((640, 480)),
+            mode: RenderMode::ImageToUnicode,
+            width: Some(120),
+            style: "Half-Blocks".to_string(),
+            color: ColorSupport::Color256,
+            format: "ansi".to_string(),
+            output_bytes: 48_213,
+            render_time_ms: 37,
+        };
+        assert_eq!(
+            report.to_line(),
+            "render input=photos/cat.png size=640x480 mode=\"Unicode Art\" width=120 \
+             style=Half-Blocks color=\"256 Colors\" format=ansi bytes=48213 time_ms=37"
+        );
+
+        // Text renders have no input image or width
+        let text = RenderReport {
+            input: None,
+            dimensions: None,
+            mode: RenderMode::TextStylizer,
+            width: None,
+            style: "Bold".to_string(),
+            ..report
+        };
+        let line = text.to_line();
+        assert!(line.starts_with("render input=- size=- mode="), "{}", line);
+        assert!(line.contains(" width=- style=Bold "), "{}", line);
+        assert_eq!(LogLevel::default(), LogLevel::Normal);
+    }
+
+    #[test]
+    fn test_settings_report() {
+        let report = settings_report(
+            &Config::default(),
+            RenderMode::ImageToUnicode,
+            "ansi",
+            ColorSupport::TrueColor,
+        );
+        assert!(report.contains("  output format: ansi\n"));
+        assert!(report.contains("  width:         80 (ascii), 80 (unicode)\n"));
+        assert!(report.contains("  color:         True Color\n"));
+    }
+
+    #[test]
+    fn test_parse_path_list_skips_comments_and_blanks() {
+        let list =
+            "# curated set\n\n  b.png  \n\t\na.jpg\n   # indented comment\nsub dir/c.gif\r\n";
+        assert_eq!(
+            parse_path_list(list),
+            vec![
+                PathBuf::from("b.png"),
+                PathBuf::from("a.jpg"),
+                PathBuf::from("sub dir/c.gif"),
+            ]
+        );
+        assert!(parse_path_list("").is_empty());
+    }
+
+    #[test]
+    fn test_read_path_list_expands_directories_in_order() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("set")).unwrap();
+        for name in ["set/y.png", "set/x.png", "set/notes.txt"] {
+            std::fs::write(dir.join(name), b"").unwrap();
+        }
+        let list = dir.join("list.txt");
+        let first = dir.join("z.png");
+        // Relative entries are found next to the list, not in the working directory
+        std::fs::write(
+            &list,
+            format!(
+                "{}\n# skip\nset\nhttps://example.com/a.png\n",
+                first.display()
+            ),
+        )
+        .unwrap();
+
+        let inputs = read_path_list(&list).unwrap();
+        assert_eq!(
+            inputs,
+            vec![
+                first,
+                dir.join("set/x.png"),
+                dir.join("set/y.png"),
+                PathBuf::from("https://example.com/a.png")
+            ]
+        );
+        assert!(read_path_list(&dir.join("missing.txt")).is_err());
+    }
+}
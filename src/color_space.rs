@@ -4,6 +4,11 @@
 
 use crate::terminal_capabilities::ColorSupport;
 
+/// Parse a `#RRGGBB` or `#RGB` color, as typed into the color prompt or `--start-color`
+pub fn parse_hex_color(hex: &str) -> Option<(u8, u8, u8)> {
+    Rgb::from_hex_shorthand(hex).map(|rgb| rgb.to_tuple())
+}
+
 /// RGB color type
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Rgb {
@@ -28,6 +33,129 @@ impl Rgb {
     pub fn to_tuple(&self) -> (u8, u8, u8) {
         (self.r, self.g, self.b)
     }
+
+    /// Parse a `#rrggbb` (or `rrggbb`) hex string
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Self::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// Parse a `#rrggbb` or `#rgb` shorthand hex string (the `#` is optional)
+    pub fn from_hex_shorthand(hex: &str) -> Option<Self> {
+        let hex = hex.trim();
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if digits.len() != 3 || !digits.is_ascii() {
+            return Self::from_hex(hex);
+        }
+        // Each digit is doubled: #f80 is #ff8800
+        let channel = |i: usize| {
+            u8::from_str_radix(&digits[i..i + 1], 16)
+                .ok()
+                .map(|d| d * 17)
+        };
+        Some(Self::new(channel(0)?, channel(1)?, channel(2)?))
+    }
+
+    /// Convert sRGB to CIE L*a*b* (D65 white point)
+    pub fn to_lab(&self) -> (f32, f32, f32) {
+        let linear = |c: u8| {
+            let c = c as f32 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = (linear(self.r), linear(self.g), linear(self.b));
+
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+        let f = |t: f32| {
+            if t > 0.008856 {
+                t.cbrt()
+            } else {
+                7.787 * t + 16.0 / 116.0
+            }
+        };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+
+        (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+    }
+
+    /// Convert to HSV: hue in degrees (0-360), saturation and value in 0.0-1.0
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let r = self.r as f32 / 255.0;
+        let g = self.g as f32 / 255.0;
+        let b = self.b as f32 / 255.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let hue = if delta == 0.0 {
+            0.0
+        } else if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+        let saturation = if max == 0.0 { 0.0 } else { delta / max };
+
+        (hue, saturation, max)
+    }
+
+    /// Build a color from HSV; hue wraps, saturation and value are clamped to 0.0-1.0
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32) -> Self {
+        let hue = hue.rem_euclid(360.0);
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+
+        let chroma = value * saturation;
+        let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+        let m = value - chroma;
+        let (r, g, b) = match (hue / 60.0) as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        let channel = |c: f32| ((c + m) * 255.0).round() as u8;
+
+        Self::new(channel(r), channel(g), channel(b))
+    }
+
+    /// Scale saturation by `factor`, keeping hue and value
+    pub fn saturate(&self, factor: f32) -> Self {
+        let (hue, saturation, value) = self.to_hsv();
+        Self::from_hsv(hue, saturation * factor, value)
+    }
+}
+
+/// Perceptual distance between two colors (CIE76 ΔE)
+pub fn delta_e(a: Rgb, b: Rgb) -> f32 {
+    let (l1, a1, b1) = a.to_lab();
+    let (l2, a2, b2) = b.to_lab();
+    ((l1 - l2).powi(2) + (a1 - a2).powi(2) + (b1 - b2).powi(2)).sqrt()
+}
+
+/// Index of the palette entry nearest to `rgb` by ΔE; 0 for an empty palette
+pub fn quantize_to_palette(rgb: Rgb, palette: &[Rgb]) -> usize {
+    palette
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| delta_e(rgb, **a).total_cmp(&delta_e(rgb, **b)))
+        .map(|(index, _)| index)
+        .unwrap_or(0)
 }
 
 /// Convert RGB to perceptual luminance (0.0 to 1.0)
@@ -229,7 +357,7 @@ mod tests {
         let red = Rgb::new(255, 0, 0);
         let code = quantize_to_ansi256(red);
         // Should be in color cube range 16-231
-        assert!(code >= 16 && code <= 231);
+        assert!((16..=231).contains(&code));
     }
 
     #[test]
@@ -243,6 +371,58 @@ mod tests {
         assert!((mid.b as i16 - 127).abs() <= 1);
     }
 
+    #[test]
+    fn test_hex_parsing() {
+        assert_eq!(Rgb::from_hex("#ff8000"), Some(Rgb::new(255, 128, 0)));
+        assert_eq!(Rgb::from_hex("282828"), Some(Rgb::new(40, 40, 40)));
+        assert_eq!(Rgb::from_hex("#fff"), None);
+        assert_eq!(Rgb::from_hex("#gg0000"), None);
+    }
+
+    #[test]
+    fn test_parse_hex_color_with_shorthand() {
+        assert_eq!(parse_hex_color("#F00"), Some((255, 0, 0)));
+        assert_eq!(parse_hex_color("#ff0000"), Some((255, 0, 0)));
+        assert_eq!(parse_hex_color("0f8"), Some((0, 255, 136)));
+        assert_eq!(parse_hex_color(" #1a2B3c "), Some((26, 43, 60)));
+
+        assert_eq!(parse_hex_color(""), None);
+        assert_eq!(parse_hex_color("#"), None);
+        assert_eq!(parse_hex_color("#ff00"), None);
+        assert_eq!(parse_hex_color("#ggg"), None);
+        assert_eq!(parse_hex_color("#ff00000"), None);
+        assert_eq!(parse_hex_color("red"), None);
+    }
+
+    #[test]
+    fn test_primaries_map_to_nearest_palette_entry() {
+        let palette = [
+            Rgb::new(200, 30, 30),
+            Rgb::new(30, 180, 40),
+            Rgb::new(40, 60, 210),
+        ];
+        assert_eq!(quantize_to_palette(Rgb::new(255, 0, 0), &palette), 0);
+        assert_eq!(quantize_to_palette(Rgb::new(0, 255, 0), &palette), 1);
+        assert_eq!(quantize_to_palette(Rgb::new(0, 0, 255), &palette), 2);
+        assert_eq!(quantize_to_palette(Rgb::new(255, 0, 0), &[]), 0);
+    }
+
+    #[test]
+    fn test_hsv_round_trip() {
+        for rgb in [
+            Rgb::new(255, 0, 0),
+            Rgb::new(12, 200, 90),
+            Rgb::new(40, 60, 210),
+            Rgb::new(128, 128, 128),
+            Rgb::new(250, 5, 180),
+        ] {
+            let (h, s, v) = rgb.to_hsv();
+            assert_eq!(Rgb::from_hsv(h, s, v), rgb);
+        }
+        assert_eq!(Rgb::new(255, 0, 0).to_hsv(), (0.0, 1.0, 1.0));
+        assert_eq!(Rgb::new(200, 30, 30).saturate(0.0), Rgb::new(200, 200, 200));
+    }
+
     #[test]
     fn test_ansi_fg_format() {
         let red = Rgb::new(255, 0, 0);
@@ -4,27 +4,91 @@
 
 use anyhow::Result;
 use directories::ProjectDirs;
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
-use crate::render_engines::{ascii::CharacterSet, unicode::UnicodeMode};
+use crate::color_space::Rgb;
+use crate::redraw::DEFAULT_MAX_FPS;
+use crate::render_engines::{
+    ascii::{CharacterSet, EdgeMethod, LumaCoeffs},
+    text_stylizer::{GradientMode, TextAlignment, TextTransform, UnicodeStyle},
+    unicode::UnicodeMode,
+    ResizeFilter, SizingMode, DEFAULT_FONT_ASPECT, DEFAULT_SATURATION,
+};
+use crate::terminal_capabilities::ColorSupport;
+
+/// Schema version written by this build; bump it when adding a section or renaming a key
+pub const CONFIG_VERSION: u32 = 3;
+
+/// Near-black page color behind HTML exports, like a typical terminal
+pub const DEFAULT_HTML_BACKGROUND: Rgb = Rgb {
+    r: 0x0c,
+    g: 0x0c,
+    b: 0x0c,
+};
+
+/// Off-white page color behind HTML exports of inverted art, drawn for light backgrounds
+pub const DEFAULT_HTML_LIGHT_BACKGROUND: Rgb = Rgb {
+    r: 0xf5,
+    g: 0xf5,
+    b: 0xf5,
+};
+
+/// Files written before the version field existed are treated as version 1
+fn legacy_config_version() -> u32 {
+    1
+}
 
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Schema version of the file this config was read from
+    #[serde(default = "legacy_config_version")]
+    pub version: u32,
+    /// Image loaded most recently in the TUI, offered for reload on the next start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_image: Option<PathBuf>,
+    #[serde(default)]
     pub ascii: AsciiPreferences,
+    #[serde(default)]
     pub unicode: UnicodePreferences,
+    #[serde(default)]
     pub text: TextPreferences,
+    #[serde(default)]
     pub ui: UiPreferences,
+    #[serde(default)]
+    pub theme: ThemePreferences,
+    #[serde(default)]
+    pub palette: PalettePreferences,
+    #[serde(default)]
+    pub terminal: TerminalPreferences,
+    /// Action name → key specs; actions left out keep their default keys
+    #[serde(default)]
+    pub keybindings: BTreeMap<String, Vec<String>>,
+    /// Named render settings saved from the preset prompt
+    #[serde(default)]
+    pub presets: BTreeMap<String, Preset>,
+    /// Keys dropped while loading because their values didn't fit, e.g. "ascii.default_width"
+    #[serde(skip)]
+    pub defaulted_keys: Vec<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
+            last_image: None,
             ascii: AsciiPreferences::default(),
             unicode: UnicodePreferences::default(),
             text: TextPreferences::default(),
             ui: UiPreferences::default(),
+            theme: ThemePreferences::default(),
+            palette: PalettePreferences::default(),
+            terminal: TerminalPreferences::default(),
+            keybindings: BTreeMap::new(),
+            presets: BTreeMap::new(),
+            defaulted_keys: Vec::new(),
         }
     }
 }
@@ -32,27 +96,121 @@ impl Default for Config {
 impl Config {
     /// Load configuration from file
     pub fn load() -> Result<Self> {
-        let path = Self::config_path()?;
+        Self::load_from(&Self::config_path()?)
+    }
+
+    /// Load configuration from `path`, rewriting it if it was migrated
+    ///
+    /// A file with invalid keys is left alone so the rewrite doesn't replace
+    /// the user's values with defaults before they have seen the warning.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let (config, migrated) = Self::read_from(path)?;
+        if migrated && config.defaulted_keys.is_empty() {
+            // A read-only config still loads; it is migrated again next time
+            let _ = config.save_to(path);
+        }
+        Ok(config)
+    }
+
+    /// Load configuration without writing it back, e.g. for `--check`
+    pub fn read() -> Result<(Self, bool)> {
+        Self::read_from(&Self::config_path()?)
+    }
+
+    /// Parse the config at `path` if there is one, returning whether it needs migrating
+    pub fn read_from(path: &Path) -> Result<(Self, bool)> {
         if path.exists() {
-            let contents = std::fs::read_to_string(&path)?;
-            let config: Config = toml::from_str(&contents)?;
-            Ok(config)
+            Self::parse(&std::fs::read_to_string(path)?)
         } else {
-            Ok(Config::default())
+            Ok((Config::default(), false))
+        }
+    }
+
+    /// Parse config TOML, returning whether it was upgraded from an older version
+    ///
+    /// Keys with invalid values fall back to their defaults and are listed in
+    /// `defaulted_keys`; only a TOML syntax error fails the whole file.
+    pub fn parse(contents: &str) -> Result<(Self, bool)> {
+        let table: toml::Table = contents.parse()?;
+        let mut defaulted_keys = Vec::new();
+
+        let mut sections = toml::Table::new();
+        for (name, value) in table {
+            let value = match value {
+                toml::Value::Table(section) => toml::Value::Table(match name.as_str() {
+                    "ascii" => valid_keys::<AsciiPreferences>(&name, section, &mut defaulted_keys),
+                    "unicode" => {
+                        valid_keys::<UnicodePreferences>(&name, section, &mut defaulted_keys)
+                    }
+                    "text" => valid_keys::<TextPreferences>(&name, section, &mut defaulted_keys),
+                    "ui" => valid_keys::<UiPreferences>(&name, section, &mut defaulted_keys),
+                    "theme" => valid_keys::<ThemePreferences>(&name, section, &mut defaulted_keys),
+                    "palette" => {
+                        valid_keys::<PalettePreferences>(&name, section, &mut defaulted_keys)
+                    }
+                    "terminal" => {
+                        valid_keys::<TerminalPreferences>(&name, section, &mut defaulted_keys)
+                    }
+                    "keybindings" => valid_keys::<BTreeMap<String, Vec<String>>>(
+                        &name,
+                        section,
+                        &mut defaulted_keys,
+                    ),
+                    "presets" => {
+                        valid_keys::<BTreeMap<String, Preset>>(&name, section, &mut defaulted_keys)
+                    }
+                    _ => section,
+                }),
+                other => other,
+            };
+            sections.insert(name, value);
+        }
+
+        // Whatever still doesn't fit at the top level (e.g. `ascii = 3`) is dropped too
+        let sections = valid_keys::<Config>("", sections, &mut defaulted_keys);
+        let mut config: Config = toml::Value::Table(sections).try_into()?;
+        config.defaulted_keys = defaulted_keys;
+        let migrated = config.migrate();
+        Ok((config, migrated))
+    }
+
+    /// Bring an older schema up to `CONFIG_VERSION`
+    ///
+    /// Sections added since the file was written are filled with defaults while
+    /// parsing; renamed keys are carried over here.
+    fn migrate(&mut self) -> bool {
+        if self.version >= CONFIG_VERSION {
+            return false;
+        }
+        // v3 replaced `ui.auto_fit_width` with `ui.sizing`
+        if self.ui.auto_fit_width {
+            self.ui.sizing = SizingMode::FitWidth;
+            self.ui.auto_fit_width = false;
         }
+        self.version = CONFIG_VERSION;
+        true
     }
 
     /// Save configuration to file
     pub fn save(&self) -> Result<()> {
-        let path = Self::config_path()?;
+        self.save_to(&Self::config_path()?)
+    }
+
+    /// Save configuration to `path`, creating its directory if needed
+    pub fn save_to(&self, path: &Path) -> Result<()> {
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent)?;
         }
         let contents = toml::to_string_pretty(self)?;
-        std::fs::write(&path, contents)?;
+        std::fs::write(path, contents)?;
         Ok(())
     }
 
+    /// `last_image`, unless it has been moved or deleted since
+    pub fn reloadable_last_image(&self) -> Option<&Path> {
+        self.last_image.as_deref().filter(|path| path.is_file())
+    }
+
     /// Get the configuration file path
     pub fn config_path() -> Result<PathBuf> {
         if let Some(proj_dirs) = ProjectDirs::from("com", "glyphgen", "glyphgen") {
@@ -64,12 +222,83 @@ impl Config {
     }
 }
 
+/// Keep the keys of `table` that deserialize as `T`, recording the rest in `dropped`
+///
+/// Keys are added one at a time, so a bad value only costs its own key. `T` must
+/// default every missing field for this to keep anything.
+fn valid_keys<T: DeserializeOwned>(
+    section: &str,
+    table: toml::Table,
+    dropped: &mut Vec<String>,
+) -> toml::Table {
+    let mut kept = toml::Table::new();
+    for (key, value) in table {
+        let mut candidate = kept.clone();
+        candidate.insert(key.clone(), value);
+        if toml::Value::Table(candidate.clone())
+            .try_into::<T>()
+            .is_ok()
+        {
+            kept = candidate;
+        } else if section.is_empty() {
+            dropped.push(key);
+        } else {
+            dropped.push(format!("{}.{}", section, key));
+        }
+    }
+    kept
+}
+
 /// ASCII rendering preferences
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AsciiPreferences {
     pub default_charset: CharacterSet,
     pub default_width: usize,
     pub edge_enhance: bool,
+    /// Edge blend weight (0.0-1.0); 0.3 keeps the classic 70/30 blend
+    #[serde(default = "default_edge_strength")]
+    pub edge_strength: f32,
+    /// Edge detection operator used when edge enhancement is on
+    #[serde(default)]
+    pub edge_method: EdgeMethod,
+    #[serde(default)]
+    pub resize_filter: ResizeFilter,
+    #[serde(default)]
+    pub luma: LumaCoeffs,
+    /// Last custom dark-to-light ramp entered in the charset prompt
+    #[serde(default)]
+    pub custom_charset: String,
+    /// Render transparent pixels as spaces regardless of their color
+    #[serde(default = "default_alpha_as_space")]
+    pub alpha_as_space: bool,
+    /// Right-pad output lines to equal display width
+    #[serde(default)]
+    pub pad_lines: bool,
+    /// Saturation multiplier applied before rendering (0.0-3.0)
+    #[serde(default = "default_saturation")]
+    pub saturation: f32,
+    /// Stretch the 1st-99th percentile luminance to the full range before mapping
+    #[serde(default)]
+    pub auto_levels: bool,
+    /// Character drawn in place of the ramp's darkest one (unset keeps the ramp's own)
+    #[serde(default)]
+    pub empty_char: Option<char>,
+    /// Keep the raw pixel aspect instead of correcting for tall terminal cells
+    #[serde(default)]
+    pub preserve_pixel_aspect: bool,
+}
+
+fn default_edge_strength() -> f32 {
+    0.3
+}
+
+fn default_alpha_as_space() -> bool {
+    true
+}
+
+fn default_saturation() -> f32 {
+    DEFAULT_SATURATION
 }
 
 impl Default for AsciiPreferences {
@@ -78,15 +307,48 @@ impl Default for AsciiPreferences {
             default_charset: CharacterSet::Extended,
             default_width: 80,
             edge_enhance: false,
+            edge_strength: default_edge_strength(),
+            edge_method: EdgeMethod::Sobel,
+            resize_filter: ResizeFilter::Lanczos3,
+            luma: LumaCoeffs::Bt601,
+            custom_charset: String::new(),
+            alpha_as_space: default_alpha_as_space(),
+            pad_lines: false,
+            saturation: default_saturation(),
+            auto_levels: false,
+            empty_char: None,
+            preserve_pixel_aspect: false,
         }
     }
 }
 
 /// Unicode rendering preferences
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UnicodePreferences {
     pub default_mode: UnicodeMode,
     pub default_width: usize,
+    #[serde(default)]
+    pub resize_filter: ResizeFilter,
+    /// RGB color that transparent pixels are blended onto in Half-Blocks mode
+    #[serde(default)]
+    pub background: (u8, u8, u8),
+    /// Draw Half-Blocks cells as one full block when the halves differ by at
+    /// most this much per channel (0 merges only identical colors)
+    #[serde(default)]
+    pub merge_threshold: u8,
+    /// Snap Blocks/Half-Blocks colors to the `[palette]` colors
+    #[serde(default)]
+    pub use_palette: bool,
+    /// Saturation multiplier applied before color quantization (0.0-3.0)
+    #[serde(default = "default_saturation")]
+    pub saturation: f32,
+    /// Stretch the 1st-99th percentile luminance to the full range before quantization
+    #[serde(default)]
+    pub auto_levels: bool,
+    /// Keep the raw pixel aspect instead of correcting for tall terminal cells
+    #[serde(default)]
+    pub preserve_pixel_aspect: bool,
 }
 
 impl Default for UnicodePreferences {
@@ -94,15 +356,25 @@ impl Default for UnicodePreferences {
         Self {
             default_mode: UnicodeMode::HalfBlocks,
             default_width: 80,
+            resize_filter: ResizeFilter::Lanczos3,
+            background: (0, 0, 0),
+            merge_threshold: 0,
+            use_palette: false,
+            saturation: default_saturation(),
+            auto_levels: false,
+            preserve_pixel_aspect: false,
         }
     }
 }
 
 /// Text stylizer preferences
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TextPreferences {
     pub default_style: String,
     pub default_gradient: String,
+    /// Alignment of multi-line text when `--align` isn't given
+    pub default_alignment: TextAlignment,
 }
 
 impl Default for TextPreferences {
@@ -110,15 +382,81 @@ impl Default for TextPreferences {
         Self {
             default_style: "Bold".to_string(),
             default_gradient: "None".to_string(),
+            default_alignment: TextAlignment::Left,
         }
     }
 }
 
 /// UI preferences
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UiPreferences {
     pub show_line_numbers: bool,
     pub word_wrap: bool,
+    /// Show decoded image and preview sizes in the status bar
+    #[serde(default)]
+    pub show_memory_usage: bool,
+    /// How image modes pick their width; the fit modes re-fit on load and resize
+    #[serde(default)]
+    pub sizing: SizingMode,
+    /// Pre-v3 spelling of `sizing = "FitWidth"`, read by `migrate` and never written
+    #[serde(default, skip_serializing)]
+    pub auto_fit_width: bool,
+    /// Terminal cell width divided by height (0.3-0.8), used to keep renders unstretched
+    #[serde(default = "default_font_aspect")]
+    pub font_aspect: f32,
+    /// Frame rate cap (1-240); the screen is only redrawn after something changes
+    #[serde(default = "default_max_fps")]
+    pub max_fps: u32,
+    /// Ring the terminal bell when a render takes longer than this many milliseconds; 0 is off
+    #[serde(default)]
+    pub notify_after_ms: u64,
+    /// Send an OSC 9 desktop notification instead of the bell
+    #[serde(default)]
+    pub notify_desktop: bool,
+    /// Silence render notifications while keeping `notify_after_ms`
+    #[serde(default)]
+    pub do_not_disturb: bool,
+    /// Page color behind HTML exports, as `#rrggbb` or `#rgb`
+    #[serde(default = "default_html_background")]
+    pub html_background: String,
+    /// Page color behind HTML exports of inverted renders, which are drawn for light backgrounds
+    #[serde(default = "default_html_light_background")]
+    pub html_light_background: String,
+}
+
+impl UiPreferences {
+    /// Whether a render that took `render_time_ms` should notify
+    pub fn should_notify(&self, render_time_ms: u64) -> bool {
+        !self.do_not_disturb && self.notify_after_ms > 0 && render_time_ms > self.notify_after_ms
+    }
+
+    /// Background color for HTML exports of art drawn for a dark (or light) background,
+    /// falling back to the defaults if unparseable
+    pub fn html_background(&self, dark_background: bool) -> Rgb {
+        if dark_background {
+            Rgb::from_hex_shorthand(&self.html_background).unwrap_or(DEFAULT_HTML_BACKGROUND)
+        } else {
+            Rgb::from_hex_shorthand(&self.html_light_background)
+                .unwrap_or(DEFAULT_HTML_LIGHT_BACKGROUND)
+        }
+    }
+}
+
+fn default_font_aspect() -> f32 {
+    DEFAULT_FONT_ASPECT
+}
+
+fn default_max_fps() -> u32 {
+    DEFAULT_MAX_FPS
+}
+
+fn default_html_background() -> String {
+    "#0c0c0c".to_string()
+}
+
+fn default_html_light_background() -> String {
+    "#f5f5f5".to_string()
 }
 
 impl Default for UiPreferences {
@@ -126,10 +464,169 @@ impl Default for UiPreferences {
         Self {
             show_line_numbers: false,
             word_wrap: false,
+            show_memory_usage: false,
+            sizing: SizingMode::Fixed,
+            auto_fit_width: false,
+            font_aspect: default_font_aspect(),
+            max_fps: default_max_fps(),
+            notify_after_ms: 0,
+            notify_desktop: false,
+            do_not_disturb: false,
+            html_background: default_html_background(),
+            html_light_background: default_html_light_background(),
+        }
+    }
+}
+
+/// Custom output palette, as `#rrggbb` hex strings
+///
+/// In 16/256-color modes an entry's position is emitted as the color index, so
+/// list a terminal scheme's colors in slot order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PalettePreferences {
+    pub colors: Vec<String>,
+}
+
+impl PalettePreferences {
+    /// Parse the palette colors, failing on the first invalid entry
+    pub fn parse(&self) -> Result<Vec<Rgb>> {
+        self.colors
+            .iter()
+            .map(|hex| {
+                Rgb::from_hex(hex).ok_or_else(|| anyhow::anyhow!("Invalid palette color '{}'", hex))
+            })
+            .collect()
+    }
+}
+
+/// Terminal overrides for when capability detection guesses wrong
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerminalPreferences {
+    /// Color level to use instead of the detected one: `none`, `16`, `256` or
+    /// `truecolor`; empty auto-detects
+    pub force_color: String,
+    /// Measure how wide the terminal draws Braille glyphs at startup, and warn
+    /// if Braille mode would misalign; adds a short startup delay
+    pub probe_glyph_width: bool,
+    /// Re-render TrueColor image output with 256 colors when it is larger than this
+    /// many bytes; 0 is off
+    pub max_output_bytes: usize,
+}
+
+impl TerminalPreferences {
+    /// Parse `force_color`; `None` means auto-detect
+    pub fn force_color(&self) -> Result<Option<ColorSupport>> {
+        if self.force_color.trim().is_empty() {
+            return Ok(None);
+        }
+        ColorSupport::from_name(&self.force_color)
+            .map(Some)
+            .ok_or_else(|| anyhow::anyhow!("Invalid force_color '{}'", self.force_color))
+    }
+}
+
+/// UI colors, as `#rrggbb` hex strings or color names
+///
+/// Values that fail to parse fall back to the built-in theme.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemePreferences {
+    pub accent: String,
+    pub highlight: String,
+    pub key: String,
+    pub text: String,
+    pub muted: String,
+    pub error: String,
+    pub border_focused: String,
+    pub border_unfocused: String,
+    pub title_bg: String,
+    pub status_bg: String,
+    pub file_info: String,
+    pub format_info: String,
+}
+
+impl Default for ThemePreferences {
+    fn default() -> Self {
+        Self {
+            accent: "cyan".to_string(),
+            highlight: "yellow".to_string(),
+            key: "green".to_string(),
+            text: "white".to_string(),
+            muted: "darkgray".to_string(),
+            error: "red".to_string(),
+            border_focused: "cyan".to_string(),
+            border_unfocused: "darkgray".to_string(),
+            title_bg: "darkgray".to_string(),
+            status_bg: "black".to_string(),
+            file_info: "blue".to_string(),
+            format_info: "magenta".to_string(),
         }
     }
 }
 
+/// A saved set of render settings; the mode tag means loading one also switches modes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum Preset {
+    Ascii {
+        charset: CharacterSet,
+        width: usize,
+        invert: bool,
+        edge_enhance: bool,
+        edge_method: EdgeMethod,
+        edge_strength: f32,
+        resize_filter: ResizeFilter,
+        luma: LumaCoeffs,
+        #[serde(default = "default_saturation")]
+        saturation: f32,
+        #[serde(default)]
+        preserve_pixel_aspect: bool,
+    },
+    Unicode {
+        unicode_mode: UnicodeMode,
+        width: usize,
+        color_mode: ColorSupport,
+        resize_filter: ResizeFilter,
+        #[serde(default)]
+        invert: bool,
+        #[serde(default)]
+        negate: bool,
+        #[serde(default)]
+        dither: bool,
+        #[serde(default = "default_saturation")]
+        saturation: f32,
+        #[serde(default)]
+        preserve_pixel_aspect: bool,
+    },
+    ColorAscii {
+        charset: CharacterSet,
+        width: usize,
+        invert: bool,
+        color_mode: ColorSupport,
+        resize_filter: ResizeFilter,
+        #[serde(default = "default_saturation")]
+        saturation: f32,
+        #[serde(default = "default_alpha_as_space")]
+        alpha_as_space: bool,
+        #[serde(default)]
+        auto_levels: bool,
+        #[serde(default)]
+        empty_char: Option<char>,
+    },
+    Text {
+        style: UnicodeStyle,
+        #[serde(default)]
+        transform: TextTransform,
+        gradient: GradientMode,
+        start_color: (u8, u8, u8),
+        end_color: (u8, u8, u8),
+        #[serde(default)]
+        alignment: TextAlignment,
+    },
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,4 +645,286 @@ mod tests {
         let parsed: Config = toml::from_str(&toml_str).unwrap();
         assert_eq!(parsed.ascii.default_width, config.ascii.default_width);
     }
+
+    #[test]
+    fn test_resize_filter_defaults_when_missing() {
+        let toml_str = r#"
+[ascii]
+default_charset = "Extended"
+default_width = 100
+edge_enhance = false
+
+[unicode]
+default_mode = "HalfBlocks"
+default_width = 80
+
+[text]
+default_style = "Bold"
+default_gradient = "None"
+
+[ui]
+show_line_numbers = false
+word_wrap = false
+"#;
+        let parsed: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(parsed.ascii.resize_filter, ResizeFilter::Lanczos3);
+        assert_eq!(parsed.ascii.edge_method, EdgeMethod::Sobel);
+        assert_eq!(parsed.unicode.resize_filter, ResizeFilter::Lanczos3);
+        assert_eq!(parsed.ascii.default_width, 100);
+        assert_eq!(parsed.ascii.saturation, 1.0);
+        assert_eq!(parsed.unicode.saturation, 1.0);
+    }
+
+    #[test]
+    fn test_v1_config_is_migrated() {
+        let toml_str = r#"
+unknown_top_level = true
+
+[ascii]
+default_charset = "Extended"
+default_width = 100
+edge_enhance = false
+
+[ui]
+show_line_numbers = true
+word_wrap = false
+"#;
+        let (config, migrated) = Config::parse(toml_str).unwrap();
+        assert!(migrated);
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.ascii.default_width, 100);
+        assert!(config.ui.show_line_numbers);
+        assert_eq!(config.unicode.default_width, 80);
+        assert!(config.keybindings.is_empty());
+        assert!(config.presets.is_empty());
+        assert_eq!(config.ui.sizing, SizingMode::Fixed);
+
+        let current = toml::to_string_pretty(&Config::default()).unwrap();
+        let (_, migrated) = Config::parse(&current).unwrap();
+        assert!(!migrated);
+    }
+
+    #[test]
+    fn test_auto_fit_width_migrates_to_sizing() {
+        let toml_str = "version = 2\n\n[ui]\nauto_fit_width = true\n";
+        let (config, migrated) = Config::parse(toml_str).unwrap();
+        assert!(migrated);
+        assert_eq!(config.ui.sizing, SizingMode::FitWidth);
+
+        let saved = toml::to_string_pretty(&config).unwrap();
+        assert!(saved.contains("sizing = \"FitWidth\""));
+        assert!(!saved.contains("auto_fit_width"));
+    }
+
+    #[test]
+    fn test_load_rewrites_migrated_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "[ascii]\ndefault_charset = \"Standard\"\ndefault_width = 64\nedge_enhance = false\n",
+        )
+        .unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(config.ascii.default_width, 64);
+
+        let rewritten = std::fs::read_to_string(&path).unwrap();
+        let reparsed: Config = toml::from_str(&rewritten).unwrap();
+        assert_eq!(reparsed.version, CONFIG_VERSION);
+        assert_eq!(reparsed.ascii.default_width, 64);
+    }
+
+    #[test]
+    fn test_read_reports_migration_without_rewriting() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let original = "[ui]\nauto_fit_width = true\n";
+        std::fs::write(&path, original).unwrap();
+
+        let (config, migrated) = Config::read_from(&path).unwrap();
+        assert!(migrated);
+        assert_eq!(config.ui.sizing, SizingMode::FitWidth);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+
+        let (_, migrated) = Config::read_from(&dir.path().join("missing.toml")).unwrap();
+        assert!(!migrated);
+    }
+
+    #[test]
+    fn test_load_keeps_migrated_file_with_invalid_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let original = "[ascii]\ndefault_charset = \"Standard\"\ndefault_width = \"wide\"\n\n[ui]\nauto_fit_width = true\n";
+        std::fs::write(&path, original).unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.ui.sizing, SizingMode::FitWidth);
+        assert_eq!(
+            config.defaulted_keys,
+            vec!["ascii.default_width".to_string()]
+        );
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), original);
+    }
+
+    #[test]
+    fn test_invalid_value_keeps_other_fields() {
+        let toml_str = r#"
+version = 3
+
+[ascii]
+default_charset = "Blocks"
+default_width = "wide"
+edge_enhance = true
+
+[ui]
+word_wrap = true
+
+[keybindings]
+quit = ["ctrl+q"]
+help = 42
+"#;
+        let (config, migrated) = Config::parse(toml_str).unwrap();
+        assert!(!migrated);
+        assert_eq!(config.ascii.default_charset, CharacterSet::Blocks);
+        assert!(config.ascii.edge_enhance);
+        assert_eq!(config.ascii.default_width, 80);
+        assert!(config.ui.word_wrap);
+        assert_eq!(config.keybindings["quit"], vec!["ctrl+q".to_string()]);
+        assert_eq!(
+            config.defaulted_keys,
+            vec![
+                "ascii.default_width".to_string(),
+                "keybindings.help".to_string()
+            ]
+        );
+
+        assert!(Config::parse("[ascii\n").is_err());
+    }
+
+    #[test]
+    fn test_last_image_round_trip_and_missing_file() {
+        assert!(!toml::to_string_pretty(&Config::default())
+            .unwrap()
+            .contains("last_image"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("last.png");
+        std::fs::write(&path, b"not really a png").unwrap();
+        let config = Config {
+            last_image: Some(path.clone()),
+            ..Config::default()
+        };
+        let (parsed, _) = Config::parse(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(parsed.last_image.as_deref(), Some(path.as_path()));
+        assert_eq!(parsed.reloadable_last_image(), Some(path.as_path()));
+
+        // A file deleted since the last session isn't offered
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(parsed.reloadable_last_image(), None);
+        assert_eq!(Config::default().reloadable_last_image(), None);
+    }
+
+    #[test]
+    fn test_terminal_force_color() {
+        let terminal: TerminalPreferences = toml::from_str("force_color = \"truecolor\"").unwrap();
+        assert_eq!(
+            terminal.force_color().unwrap(),
+            Some(ColorSupport::TrueColor)
+        );
+        assert_eq!(Config::default().terminal.force_color().unwrap(), None);
+        assert!(!Config::default().terminal.probe_glyph_width);
+        assert_eq!(Config::default().terminal.max_output_bytes, 0);
+
+        let bad = TerminalPreferences {
+            force_color: "lots".to_string(),
+            ..Default::default()
+        };
+        assert!(bad.force_color().is_err());
+    }
+
+    #[test]
+    fn test_should_notify_after_threshold() {
+        let mut ui = UiPreferences::default();
+        // Off by default
+        assert!(!ui.should_notify(60_000));
+
+        ui.notify_after_ms = 1000;
+        assert!(!ui.should_notify(999));
+        assert!(!ui.should_notify(1000));
+        assert!(ui.should_notify(1001));
+
+        ui.do_not_disturb = true;
+        assert!(!ui.should_notify(60_000));
+
+        let parsed: UiPreferences =
+            toml::from_str("notify_after_ms = 500\nnotify_desktop = true").unwrap();
+        assert!(parsed.should_notify(501));
+        assert!(parsed.notify_desktop);
+    }
+
+    #[test]
+    fn test_palette_parsing() {
+        let palette: PalettePreferences =
+            toml::from_str("colors = [\"#282828\", \"cc241d\"]").unwrap();
+        assert_eq!(
+            palette.parse().unwrap(),
+            vec![Rgb::new(40, 40, 40), Rgb::new(204, 36, 29)]
+        );
+
+        let bad = PalettePreferences {
+            colors: vec!["#282828".to_string(), "red".to_string()],
+        };
+        assert!(bad.parse().unwrap_err().to_string().contains("'red'"));
+    }
+
+    #[test]
+    fn test_theme_round_trip_and_partial_section() {
+        let mut config = Config::default();
+        config.theme.accent = "#112233".to_string();
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        let parsed: Config = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed.theme.accent, "#112233");
+        assert_eq!(parsed.theme.status_bg, "black");
+
+        // Missing keys in [theme] keep their defaults
+        let partial: ThemePreferences = toml::from_str("highlight = \"#ffcc00\"").unwrap();
+        assert_eq!(partial.highlight, "#ffcc00");
+        assert_eq!(partial.accent, "cyan");
+    }
+
+    #[test]
+    fn test_presets_round_trip() {
+        let ascii = Preset::Ascii {
+            charset: CharacterSet::Custom(" .oO@".to_string()),
+            width: 120,
+            invert: true,
+            edge_enhance: true,
+            edge_method: EdgeMethod::Prewitt,
+            edge_strength: 0.45,
+            resize_filter: ResizeFilter::Nearest,
+            luma: LumaCoeffs::Bt709,
+            saturation: 1.5,
+            preserve_pixel_aspect: true,
+        };
+        let text = Preset::Text {
+            style: UnicodeStyle::BoldItalic,
+            transform: TextTransform::Leet,
+            gradient: GradientMode::Rainbow,
+            start_color: (10, 20, 30),
+            end_color: (200, 100, 0),
+            alignment: TextAlignment::Center,
+        };
+
+        let mut config = Config::default();
+        config.presets.insert("sketch".to_string(), ascii.clone());
+        config.presets.insert("banner".to_string(), text.clone());
+
+        let toml_str = toml::to_string_pretty(&config).unwrap();
+        let parsed: Config = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed.presets.get("sketch"), Some(&ascii));
+        assert_eq!(parsed.presets.get("banner"), Some(&text));
+    }
 }
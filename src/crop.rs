@@ -0,0 +1,169 @@
+//! Image cropping
+//!
+//! A region of the input image to render instead of the whole picture, set with
+//! `--crop x,y,w,h` or the interactive crop overlay.
+
+use anyhow::{bail, Context, Result};
+use image::{DynamicImage, GenericImageView};
+
+/// Region of the source image in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl CropRect {
+    pub fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Parse a `x,y,w,h` spec such as `10,20,300,200`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let parts: Vec<&str> = spec.split(',').map(str::trim).collect();
+        if parts.len() != 4 {
+            bail!("Crop must be x,y,w,h (got '{}')", spec);
+        }
+        let mut values = [0u32; 4];
+        for (value, part) in values.iter_mut().zip(&parts) {
+            *value = part
+                .parse()
+                .with_context(|| format!("Invalid crop value '{}' in '{}'", part, spec))?;
+        }
+        let [x, y, width, height] = values;
+        if width == 0 || height == 0 {
+            bail!("Crop width and height must be at least 1");
+        }
+        Ok(Self::new(x, y, width, height))
+    }
+
+    /// Clamp the rectangle so it lies inside a `image_width` x `image_height` image
+    ///
+    /// The origin is pulled back onto the image and the size trimmed to fit; the result
+    /// is always at least one pixel.
+    pub fn clamp_to(&self, image_width: u32, image_height: u32) -> Self {
+        let x = self.x.min(image_width.saturating_sub(1));
+        let y = self.y.min(image_height.saturating_sub(1));
+        Self {
+            x,
+            y,
+            width: self.width.clamp(1, image_width.saturating_sub(x).max(1)),
+            height: self.height.clamp(1, image_height.saturating_sub(y).max(1)),
+        }
+    }
+
+    /// Whether the rectangle covers the whole image
+    pub fn is_full(&self, image_width: u32, image_height: u32) -> bool {
+        self.x == 0 && self.y == 0 && self.width >= image_width && self.height >= image_height
+    }
+
+    /// Copy the region out of `image`, clamping it to the image bounds first
+    pub fn apply(&self, image: &DynamicImage) -> DynamicImage {
+        let (width, height) = image.dimensions();
+        let rect = self.clamp_to(width, height);
+        image.crop_imm(rect.x, rect.y, rect.width, rect.height)
+    }
+
+    /// Move by (`dx`, `dy`) pixels, staying inside the image
+    pub fn translate(&self, dx: i64, dy: i64, image_width: u32, image_height: u32) -> Self {
+        let max_x = image_width.saturating_sub(self.width) as i64;
+        let max_y = image_height.saturating_sub(self.height) as i64;
+        Self {
+            x: (self.x as i64 + dx).clamp(0, max_x) as u32,
+            y: (self.y as i64 + dy).clamp(0, max_y) as u32,
+            ..*self
+        }
+        .clamp_to(image_width, image_height)
+    }
+
+    /// Grow or shrink by (`dw`, `dh`) pixels from the bottom-right corner, staying inside the image
+    pub fn resize(&self, dw: i64, dh: i64, image_width: u32, image_height: u32) -> Self {
+        Self {
+            width: (self.width as i64 + dw).max(1) as u32,
+            height: (self.height as i64 + dh).max(1) as u32,
+            ..*self
+        }
+        .clamp_to(image_width, image_height)
+    }
+
+    /// Short description, e.g. "300x200 at (10, 20)"
+    pub fn describe(&self) -> String {
+        format!("{}x{} at ({}, {})", self.width, self.height, self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_crop_spec() {
+        assert_eq!(
+            CropRect::parse("10,20,300,200").unwrap(),
+            CropRect::new(10, 20, 300, 200)
+        );
+        assert_eq!(
+            CropRect::parse(" 0, 0, 5 ,5 ").unwrap(),
+            CropRect::new(0, 0, 5, 5)
+        );
+
+        assert!(CropRect::parse("10,20,300").is_err());
+        assert!(CropRect::parse("10,20,300,200,1").is_err());
+        assert!(CropRect::parse("a,20,300,200").is_err());
+        assert!(CropRect::parse("-1,20,300,200").is_err());
+        assert!(CropRect::parse("10,20,0,200").is_err());
+    }
+
+    #[test]
+    fn test_clamp_keeps_rect_inside_image() {
+        // Already inside: unchanged
+        let inside = CropRect::new(10, 10, 20, 20);
+        assert_eq!(inside.clamp_to(100, 100), inside);
+
+        // Overhanging the right and bottom edges: trimmed
+        assert_eq!(
+            CropRect::new(90, 80, 50, 50).clamp_to(100, 100),
+            CropRect::new(90, 80, 10, 20)
+        );
+
+        // Entirely outside: pulled back to a single pixel on the edge
+        assert_eq!(
+            CropRect::new(500, 500, 10, 10).clamp_to(100, 100),
+            CropRect::new(99, 99, 1, 1)
+        );
+    }
+
+    #[test]
+    fn test_apply_crops_image() {
+        let image = DynamicImage::ImageRgb8(image::RgbImage::from_fn(40, 30, |x, _| {
+            image::Rgb([x as u8, 0, 0])
+        }));
+
+        let cropped = CropRect::new(10, 5, 20, 10).apply(&image);
+        assert_eq!(cropped.dimensions(), (20, 10));
+        assert_eq!(cropped.get_pixel(0, 0).0[0], 10);
+
+        let clamped = CropRect::new(30, 20, 100, 100).apply(&image);
+        assert_eq!(clamped.dimensions(), (10, 10));
+    }
+
+    #[test]
+    fn test_translate_and_resize_stay_in_bounds() {
+        let rect = CropRect::new(10, 10, 50, 50);
+        assert_eq!(
+            rect.translate(100, -100, 100, 100),
+            CropRect::new(50, 0, 50, 50)
+        );
+        assert_eq!(
+            rect.resize(100, -100, 100, 100),
+            CropRect::new(10, 10, 90, 1)
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! File browser for the load flow
+//!
+//! Lists one directory at a time, showing subdirectories and supported images only,
+//! so an image can be picked with the arrow keys instead of typing its path.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::image_loader::is_supported_format;
+
+/// One row of the browser listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+impl BrowserEntry {
+    /// Name as listed, with a trailing `/` on directories
+    pub fn label(&self) -> String {
+        if self.is_dir {
+            format!("{}/", self.name)
+        } else {
+            self.name.clone()
+        }
+    }
+}
+
+/// What activating the selected entry did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrowserAction {
+    /// Moved into a directory
+    Entered,
+    /// Picked an image to load
+    Selected(PathBuf),
+    /// Nothing to activate (empty listing)
+    None,
+}
+
+/// Directory listing with a selection cursor
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileBrowser {
+    pub dir: PathBuf,
+    /// `..` (when there is a parent) first, then directories, then images, each sorted by name
+    pub entries: Vec<BrowserEntry>,
+    pub selected: usize,
+}
+
+impl FileBrowser {
+    /// Open the browser on `dir`
+    pub fn open(dir: &Path) -> Result<Self> {
+        let dir = std::fs::canonicalize(dir)
+            .with_context(|| format!("Can't open folder {}", dir.display()))?;
+        let entries = list_entries(&dir)?;
+        Ok(Self {
+            dir,
+            entries,
+            selected: 0,
+        })
+    }
+
+    /// The entry under the cursor
+    pub fn selected_entry(&self) -> Option<&BrowserEntry> {
+        self.entries.get(self.selected)
+    }
+
+    /// Move the cursor down (or up), stopping at either end of the listing
+    pub fn move_selection(&mut self, down: bool) {
+        if down {
+            self.selected = (self.selected + 1).min(self.entries.len().saturating_sub(1));
+        } else {
+            self.selected = self.selected.saturating_sub(1);
+        }
+    }
+
+    /// Descend into the selected directory, or pick the selected image
+    pub fn activate(&mut self) -> Result<BrowserAction> {
+        let Some(entry) = self.selected_entry().cloned() else {
+            return Ok(BrowserAction::None);
+        };
+        if !entry.is_dir {
+            return Ok(BrowserAction::Selected(entry.path));
+        }
+        if entry.name == ".." {
+            self.ascend()?;
+        } else {
+            self.change_dir(entry.path, None)?;
+        }
+        Ok(BrowserAction::Entered)
+    }
+
+    /// Go up to the parent directory, selecting the directory just left
+    pub fn ascend(&mut self) -> Result<()> {
+        let Some(parent) = self.dir.parent().map(Path::to_path_buf) else {
+            return Ok(());
+        };
+        let previous = self.dir.clone();
+        self.change_dir(parent, Some(&previous))
+    }
+
+    /// Switch to `dir`, leaving the browser untouched if it can't be read
+    fn change_dir(&mut self, dir: PathBuf, select: Option<&Path>) -> Result<()> {
+        let entries = list_entries(&dir)?;
+        self.selected = select
+            .and_then(|path| entries.iter().position(|entry| entry.path == path))
+            .unwrap_or(0);
+        self.entries = entries;
+        self.dir = dir;
+        Ok(())
+    }
+}
+
+/// Subdirectories and supported images in `dir`, hidden entries left out
+fn list_entries(dir: &Path) -> Result<Vec<BrowserEntry>> {
+    let read_dir =
+        std::fs::read_dir(dir).with_context(|| format!("Can't open folder {}", dir.display()))?;
+
+    let mut dirs = Vec::new();
+    let mut images = Vec::new();
+    for entry in read_dir.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        // Follows symlinks, so a link to a folder browses like a folder
+        let is_dir = path.is_dir();
+        if is_dir {
+            dirs.push(BrowserEntry { name, path, is_dir });
+        } else if is_supported_format(&path) {
+            images.push(BrowserEntry { name, path, is_dir });
+        }
+    }
+    dirs.sort_by(|a, b| a.name.cmp(&b.name));
+    images.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut entries = Vec::with_capacity(dirs.len() + images.len() + 1);
+    if let Some(parent) = dir.parent() {
+        entries.push(BrowserEntry {
+            name: "..".to_string(),
+            path: parent.to_path_buf(),
+            is_dir: true,
+        });
+    }
+    entries.extend(dirs);
+    entries.extend(images);
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `root/{b.png, a.JPG, notes.txt, .hidden.png, photos/{cat.png}}`, removed with the `TempDir`
+    fn fixture() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("photos")).unwrap();
+        for file in [
+            "b.png",
+            "a.JPG",
+            "notes.txt",
+            ".hidden.png",
+            "photos/cat.png",
+        ] {
+            std::fs::write(root.join(file), b"").unwrap();
+        }
+        let root = std::fs::canonicalize(root).unwrap();
+        (dir, root)
+    }
+
+    fn labels(browser: &FileBrowser) -> Vec<String> {
+        browser.entries.iter().map(BrowserEntry::label).collect()
+    }
+
+    #[test]
+    fn test_listing_shows_folders_and_images() {
+        let (_dir, root) = fixture();
+        let browser = FileBrowser::open(&root).unwrap();
+        assert_eq!(labels(&browser), ["../", "photos/", "a.JPG", "b.png"]);
+        assert_eq!(browser.selected, 0);
+    }
+
+    #[test]
+    fn test_descend_ascend_and_select() {
+        let (_dir, root) = fixture();
+        let mut browser = FileBrowser::open(&root).unwrap();
+
+        // Descend into photos/
+        browser.move_selection(true);
+        assert_eq!(browser.activate().unwrap(), BrowserAction::Entered);
+        assert_eq!(browser.dir, root.join("photos"));
+        assert_eq!(labels(&browser), ["../", "cat.png"]);
+
+        // Selecting an image hands back its path without moving
+        browser.move_selection(true);
+        browser.move_selection(true);
+        assert_eq!(browser.selected, 1);
+        assert_eq!(
+            browser.activate().unwrap(),
+            BrowserAction::Selected(root.join("photos").join("cat.png"))
+        );
+
+        // ".." goes back up with the folder just left under the cursor
+        browser.move_selection(false);
+        browser.move_selection(false);
+        assert_eq!(browser.activate().unwrap(), BrowserAction::Entered);
+        assert_eq!(browser.dir, root);
+        assert_eq!(browser.selected_entry().unwrap().name, "photos");
+
+        browser.move_selection(true);
+        browser.move_selection(true);
+        assert_eq!(
+            browser.activate().unwrap(),
+            BrowserAction::Selected(root.join("b.png"))
+        );
+    }
+
+    #[test]
+    fn test_unreadable_folder_keeps_listing() {
+        let (_dir, root) = fixture();
+        let mut browser = FileBrowser::open(&root).unwrap();
+        std::fs::remove_dir_all(root.join("photos")).unwrap();
+
+        browser.move_selection(true);
+        assert!(browser.activate().is_err());
+        assert_eq!(browser.dir, root);
+        assert_eq!(browser.selected, 1);
+    }
+}
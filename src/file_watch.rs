@@ -0,0 +1,153 @@
+//! Input file watching
+//!
+//! Polls a file's modification time so the TUI can re-render on external edits.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Result of polling a watched file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WatchEvent {
+    /// Nothing to do
+    Unchanged,
+    /// The file changed and has been quiet for the debounce period
+    Changed,
+    /// The file could not be read (reported once until it reappears)
+    Missing(String),
+}
+
+/// Polls a file's mtime and reports debounced changes
+pub struct FileWatcher {
+    path: PathBuf,
+    last_mtime: Option<SystemTime>,
+    /// Time of the most recent unsettled change
+    pending_since: Option<Instant>,
+    debounce: Duration,
+    missing: bool,
+}
+
+impl FileWatcher {
+    /// Default quiet period before a change is reported
+    pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(250);
+
+    pub fn new(path: PathBuf) -> Self {
+        let last_mtime = modified(&path).ok();
+        Self {
+            path,
+            last_mtime,
+            pending_since: None,
+            debounce: Self::DEFAULT_DEBOUNCE,
+            missing: false,
+        }
+    }
+
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check the file's metadata; cheap enough to call every frame
+    pub fn poll(&mut self, now: Instant) -> WatchEvent {
+        let mtime = match modified(&self.path) {
+            Ok(mtime) => mtime,
+            Err(e) => {
+                self.pending_since = None;
+                if self.missing {
+                    return WatchEvent::Unchanged;
+                }
+                self.missing = true;
+                return WatchEvent::Missing(format!(
+                    "Watched file unavailable: {} ({})",
+                    self.path.display(),
+                    e
+                ));
+            }
+        };
+
+        // A file that reappears counts as a change
+        if self.missing {
+            self.missing = false;
+            self.last_mtime = None;
+        }
+
+        if self.last_mtime != Some(mtime) {
+            // Restart the quiet period on every new write
+            self.last_mtime = Some(mtime);
+            self.pending_since = Some(now);
+        }
+
+        match self.pending_since {
+            Some(since) if now.duration_since(since) >= self.debounce => {
+                self.pending_since = None;
+                WatchEvent::Changed
+            }
+            _ => WatchEvent::Unchanged,
+        }
+    }
+}
+
+fn modified(path: &Path) -> std::io::Result<SystemTime> {
+    std::fs::metadata(path)?.modified()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A file holding "v1" in a directory removed with the `TempDir`
+    fn temp_file() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("image.png");
+        std::fs::write(&path, b"v1").unwrap();
+        (dir, path)
+    }
+
+    fn touch(path: &Path, secs: u64) {
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(secs))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_change_is_debounced() {
+        let (_dir, path) = temp_file();
+        let mut watcher = FileWatcher::new(path.clone()).with_debounce(Duration::from_millis(100));
+        let start = Instant::now();
+
+        assert_eq!(watcher.poll(start), WatchEvent::Unchanged);
+
+        // Two rapid writes only produce one change, after the quiet period
+        touch(&path, 1_000);
+        assert_eq!(watcher.poll(start), WatchEvent::Unchanged);
+        touch(&path, 2_000);
+        let later = start + Duration::from_millis(50);
+        assert_eq!(watcher.poll(later), WatchEvent::Unchanged);
+        assert_eq!(
+            watcher.poll(later + Duration::from_millis(100)),
+            WatchEvent::Changed
+        );
+        assert_eq!(
+            watcher.poll(later + Duration::from_millis(500)),
+            WatchEvent::Unchanged
+        );
+    }
+
+    #[test]
+    fn test_missing_file_reported_once() {
+        let (_dir, path) = temp_file();
+        let mut watcher = FileWatcher::new(path.clone()).with_debounce(Duration::ZERO);
+        std::fs::remove_file(&path).unwrap();
+
+        let now = Instant::now();
+        assert!(matches!(watcher.poll(now), WatchEvent::Missing(_)));
+        assert_eq!(watcher.poll(now), WatchEvent::Unchanged);
+
+        // Recreating the file is picked up as a change
+        std::fs::write(&path, b"v2").unwrap();
+        assert_eq!(watcher.poll(now), WatchEvent::Changed);
+    }
+}
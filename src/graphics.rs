@@ -0,0 +1,324 @@
+//! Terminal graphics protocols (experimental)
+//!
+//! Encodes images for terminals that can display real pixels instead of
+//! character art. Supports DEC Sixel and the kitty graphics protocol.
+
+use anyhow::Result;
+use image::{DynamicImage, RgbImage};
+use std::fmt::Write;
+
+use crate::render_engines::ResizeFilter;
+
+/// Graphics protocol used for the image preview
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphicsMode {
+    /// Character art only
+    #[default]
+    Off,
+    /// DEC Sixel bitmaps
+    Sixel,
+    /// Kitty graphics protocol (kitty, Ghostty, WezTerm)
+    Kitty,
+}
+
+impl GraphicsMode {
+    /// Parse a `--graphics` value
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        match arg.to_ascii_lowercase().as_str() {
+            "off" | "none" => Some(GraphicsMode::Off),
+            "sixel" => Some(GraphicsMode::Sixel),
+            "kitty" => Some(GraphicsMode::Kitty),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GraphicsMode::Off => "Off",
+            GraphicsMode::Sixel => "Sixel",
+            GraphicsMode::Kitty => "Kitty",
+        }
+    }
+}
+
+/// Scale an image to fit within `max_width` x `max_height` pixels and encode it for `mode`
+///
+/// Returns an empty string for [`GraphicsMode::Off`].
+pub fn render_graphics(
+    image: &DynamicImage,
+    mode: GraphicsMode,
+    max_width: u32,
+    max_height: u32,
+    filter: ResizeFilter,
+) -> Result<String> {
+    match mode {
+        GraphicsMode::Off => Ok(String::new()),
+        GraphicsMode::Sixel => Ok(render_sixel(image, max_width, max_height, filter)),
+        GraphicsMode::Kitty => {
+            let resized = image.resize(max_width.max(1), max_height.max(1), filter.filter_type());
+            encode_kitty(&resized.to_rgb8())
+        }
+    }
+}
+
+/// Number of levels per channel in the Sixel palette (6x6x6 color cube)
+const CUBE_LEVELS: u16 = 6;
+const PALETTE_SIZE: usize = 216;
+
+/// Scale an image to fit within `max_width` x `max_height` pixels and encode it as Sixel
+pub fn render_sixel(
+    image: &DynamicImage,
+    max_width: u32,
+    max_height: u32,
+    filter: ResizeFilter,
+) -> String {
+    let resized = image.resize(max_width.max(1), max_height.max(1), filter.filter_type());
+    encode_sixel(&resized.to_rgb8())
+}
+
+/// Encode an RGB image as a Sixel DCS sequence using a 216-color palette
+pub fn encode_sixel(image: &RgbImage) -> String {
+    let (width, height) = image.dimensions();
+    let indices: Vec<usize> = image.pixels().map(|p| palette_index(p.0)).collect();
+
+    let mut out = String::new();
+    // DCS q, then raster attributes: 1:1 pixel aspect and image size
+    let _ = write!(out, "\x1bPq\"1;1;{};{}", width, height);
+
+    // Only define palette registers that are actually used
+    let mut used = [false; PALETTE_SIZE];
+    for &index in &indices {
+        used[index] = true;
+    }
+    for index in (0..PALETTE_SIZE).filter(|&i| used[i]) {
+        let (r, g, b) = palette_rgb(index);
+        let _ = write!(
+            out,
+            "#{};2;{};{};{}",
+            index,
+            percent(r),
+            percent(g),
+            percent(b)
+        );
+    }
+
+    // Each band covers six pixel rows; every color in the band is one pass
+    for band in (0..height).step_by(6) {
+        let rows = (height - band).min(6);
+
+        let mut band_used = [false; PALETTE_SIZE];
+        for y in band..band + rows {
+            for x in 0..width {
+                band_used[indices[(y * width + x) as usize]] = true;
+            }
+        }
+
+        let mut first_pass = true;
+        for color in (0..PALETTE_SIZE).filter(|&c| band_used[c]) {
+            if !first_pass {
+                out.push('$'); // Carriage return within the band
+            }
+            first_pass = false;
+            let _ = write!(out, "#{}", color);
+
+            let mut run: Option<(char, usize)> = None;
+            for x in 0..width {
+                let mut bits = 0u8;
+                for dy in 0..rows {
+                    if indices[((band + dy) * width + x) as usize] == color {
+                        bits |= 1 << dy;
+                    }
+                }
+                let c = (63 + bits) as char;
+                run = match run {
+                    Some((rc, n)) if rc == c => Some((rc, n + 1)),
+                    previous => {
+                        push_run(&mut out, previous);
+                        Some((c, 1))
+                    }
+                };
+            }
+            push_run(&mut out, run);
+        }
+        out.push('-'); // Next band
+    }
+
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Remove every kitty image placement from the screen and free the image data
+pub const KITTY_CLEAR: &str = "\x1b_Ga=d,d=A,q=2\x1b\\";
+
+/// Maximum base64 payload per kitty escape, as required by the protocol
+const KITTY_CHUNK_SIZE: usize = 4096;
+
+/// Encode an RGB image as kitty graphics escapes carrying PNG data
+///
+/// The image is displayed at the cursor without moving it (`C=1`) and
+/// responses are suppressed (`q=2`) so nothing is echoed into the input stream.
+pub fn encode_kitty(image: &RgbImage) -> Result<String> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| anyhow::anyhow!("Failed to encode PNG: {}", e))?;
+    let payload = base64_encode(&png);
+
+    let mut out = String::new();
+    let chunks: Vec<&[u8]> = payload.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        // Chunks are slices of ASCII base64, so they are valid UTF-8
+        let data = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            let _ = write!(out, "\x1b_Ga=T,f=100,C=1,q=2,m={};{}\x1b\\", more, data);
+        } else {
+            let _ = write!(out, "\x1b_Gm={};{}\x1b\\", more, data);
+        }
+    }
+    Ok(out)
+}
+
+/// Standard (RFC 4648) base64 with padding
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Emit a run of sixel characters, using `!n` repeat syntax for longer runs
+fn push_run(out: &mut String, run: Option<(char, usize)>) {
+    match run {
+        Some((c, n)) if n >= 4 => {
+            let _ = write!(out, "!{}{}", n, c);
+        }
+        Some((c, n)) => out.extend(std::iter::repeat(c).take(n)),
+        None => {}
+    }
+}
+
+/// Map an RGB pixel to its 6x6x6 color cube register
+fn palette_index([r, g, b]: [u8; 3]) -> usize {
+    let level = |v: u8| (v as u16 * (CUBE_LEVELS - 1) + 127) / 255;
+    (level(r) * CUBE_LEVELS * CUBE_LEVELS + level(g) * CUBE_LEVELS + level(b)) as usize
+}
+
+/// RGB value of a color cube register
+fn palette_rgb(index: usize) -> (u8, u8, u8) {
+    let levels = CUBE_LEVELS as usize;
+    let channel = |level: usize| (level * 255 / (levels - 1)) as u8;
+    (
+        channel(index / (levels * levels)),
+        channel(index / levels % levels),
+        channel(index % levels),
+    )
+}
+
+/// Sixel palette channels are percentages (0-100)
+fn percent(value: u8) -> u32 {
+    (value as u32 * 100 + 127) / 255
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graphics_mode_from_arg() {
+        assert_eq!(GraphicsMode::from_arg("sixel"), Some(GraphicsMode::Sixel));
+        assert_eq!(GraphicsMode::from_arg("SIXEL"), Some(GraphicsMode::Sixel));
+        assert_eq!(GraphicsMode::from_arg("off"), Some(GraphicsMode::Off));
+        assert_eq!(GraphicsMode::from_arg("kitty"), Some(GraphicsMode::Kitty));
+        assert_eq!(GraphicsMode::from_arg("iterm"), None);
+    }
+
+    #[test]
+    fn test_palette_round_trip() {
+        assert_eq!(palette_index([0, 0, 0]), 0);
+        assert_eq!(palette_index([255, 255, 255]), PALETTE_SIZE - 1);
+        assert_eq!(palette_rgb(palette_index([255, 0, 0])), (255, 0, 0));
+    }
+
+    #[test]
+    fn test_encode_sixel_structure() {
+        let img = RgbImage::from_fn(8, 7, |x, _| {
+            if x < 4 {
+                image::Rgb([255, 0, 0])
+            } else {
+                image::Rgb([0, 0, 255])
+            }
+        });
+        let sixel = encode_sixel(&img);
+
+        assert!(sixel.starts_with("\x1bPq\"1;1;8;7"));
+        assert!(sixel.ends_with("\x1b\\"));
+        // Red and blue registers are defined, and 7 rows need two bands
+        assert!(sixel.contains(&format!("#{};2;100;0;0", palette_index([255, 0, 0]))));
+        assert!(sixel.contains(&format!("#{};2;0;0;100", palette_index([0, 0, 255]))));
+        assert_eq!(sixel.matches('-').count(), 2);
+        // Four identical columns collapse into a repeat
+        assert!(sixel.contains("!4~"));
+    }
+
+    #[test]
+    fn test_render_sixel_fits_bounds() {
+        let img = DynamicImage::ImageRgb8(RgbImage::new(200, 100));
+        let sixel = render_sixel(&img, 50, 50, ResizeFilter::Nearest);
+        assert!(sixel.starts_with("\x1bPq\"1;1;50;25"));
+    }
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_encode_kitty_chunks() {
+        // Noise compresses poorly, forcing several chunks
+        let mut seed = 0x2545_f491u32;
+        let img = RgbImage::from_fn(64, 64, |_, _| {
+            seed ^= seed << 13;
+            seed ^= seed >> 17;
+            seed ^= seed << 5;
+            let [r, g, b, _] = seed.to_le_bytes();
+            image::Rgb([r, g, b])
+        });
+        let kitty = encode_kitty(&img).unwrap();
+
+        assert!(kitty.starts_with("\x1b_Ga=T,f=100,C=1,q=2,m=1;iVBORw0KGgo"));
+        assert!(kitty.contains("\x1b_Gm=1;"));
+        assert!(kitty.ends_with("\x1b\\"));
+        assert_eq!(kitty.matches("m=0;").count(), 1);
+        for chunk in kitty.split("\x1b\\").filter(|c| !c.is_empty()) {
+            let payload = chunk.split_once(';').unwrap().1;
+            assert!(payload.len() <= KITTY_CHUNK_SIZE);
+        }
+    }
+
+    #[test]
+    fn test_render_graphics_off_is_empty() {
+        let img = DynamicImage::ImageRgb8(RgbImage::new(4, 4));
+        let out = render_graphics(&img, GraphicsMode::Off, 10, 10, ResizeFilter::Nearest).unwrap();
+        assert!(out.is_empty());
+    }
+}
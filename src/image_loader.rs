@@ -3,7 +3,11 @@
 //! Handles async loading and decoding of images.
 
 use anyhow::{Context, Result};
-use image::DynamicImage;
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, DynamicImage, Frame, ImageFormat};
+use std::io::Cursor;
 use std::path::Path;
 
 /// Load an image from a file path
@@ -20,8 +24,180 @@ pub fn load_image_from_bytes(bytes: &[u8]) -> Result<DynamicImage> {
     Ok(img)
 }
 
+/// Whether `input` looks like an `http://` or `https://` URL rather than a file path
+pub fn is_url(input: &str) -> bool {
+    let lower = input.trim_start().to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+/// Load an image from a file path, or fetch it when the path is a URL
+///
+/// URLs need the `network` feature; without it they fail with an error saying so.
+pub fn load_image_from_source(source: &Path) -> Result<DynamicImage> {
+    match source.to_str().filter(|s| is_url(s)) {
+        #[cfg(feature = "network")]
+        Some(url) => load_image_from_url(url),
+        #[cfg(not(feature = "network"))]
+        Some(_) => {
+            anyhow::bail!("Loading from a URL needs glyphgen built with the `network` feature")
+        }
+        None => load_image(source),
+    }
+}
+
+/// How long a URL fetch may take, connection included
+#[cfg(feature = "network")]
+pub const URL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Largest download accepted from a URL
+#[cfg(feature = "network")]
+const MAX_URL_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Fetch an image over HTTP(S) and decode it
+#[cfg(feature = "network")]
+pub fn load_image_from_url(url: &str) -> Result<DynamicImage> {
+    fetch_image(url, URL_TIMEOUT)
+}
+
+#[cfg(feature = "network")]
+fn fetch_image(url: &str, timeout: std::time::Duration) -> Result<DynamicImage> {
+    use std::io::Read;
+
+    let agent = ureq::AgentBuilder::new().timeout(timeout).build();
+    let response = match agent.get(url).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(code, response)) => {
+            anyhow::bail!("URL returned HTTP {} {}", code, response.status_text())
+        }
+        Err(ureq::Error::Transport(transport)) if is_timeout(&transport) => {
+            anyhow::bail!(
+                "Timed out after {}s fetching {}",
+                timeout.as_secs_f32(),
+                url
+            )
+        }
+        Err(ureq::Error::Transport(transport)) => {
+            anyhow::bail!("Couldn't fetch {}: {}", url, transport)
+        }
+    };
+
+    let content_type = response.header("Content-Type").map(str::to_string);
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_URL_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Couldn't download {}", url))?;
+    anyhow::ensure!(
+        bytes.len() as u64 <= MAX_URL_BYTES,
+        "Image at {} is larger than {} MB",
+        url,
+        MAX_URL_BYTES / (1024 * 1024)
+    );
+    check_content_type(content_type.as_deref(), &bytes)?;
+    load_image_from_bytes(&bytes)
+}
+
+#[cfg(feature = "network")]
+fn is_timeout(transport: &ureq::Transport) -> bool {
+    std::error::Error::source(transport)
+        .and_then(|source| source.downcast_ref::<std::io::Error>())
+        .is_some_and(|e| {
+            matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+            )
+        })
+}
+
+/// Reject responses that aren't images, such as an HTML error page
+///
+/// Servers that send no type or a generic binary one are trusted if the bytes
+/// look like a known image format.
+#[cfg(feature = "network")]
+fn check_content_type(content_type: Option<&str>, bytes: &[u8]) -> Result<()> {
+    let mime = content_type
+        .and_then(|value| value.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase());
+    match mime.as_deref() {
+        Some(mime) if mime.starts_with("image/") => Ok(()),
+        None | Some("application/octet-stream") | Some("binary/octet-stream") => {
+            image::guess_format(bytes)
+                .map(|_| ())
+                .context("URL didn't return an image")
+        }
+        Some(other) => anyhow::bail!("URL returned {}, not an image", other),
+    }
+}
+
+/// One frame of an animated image
+#[derive(Debug, Clone)]
+pub struct AnimationFrame {
+    pub image: DynamicImage,
+    /// How long the frame stays on screen
+    pub delay_ms: u32,
+}
+
+/// Load every frame of an animated GIF, WebP or PNG (APNG), with per-frame delays
+///
+/// Still images, including non-animated WebP and PNG, load as a single frame with no delay.
+pub fn load_animation(path: &Path) -> Result<Vec<AnimationFrame>> {
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to load image: {:?}", path))?;
+    load_animation_from_bytes(&bytes)
+}
+
+/// Load every frame of an animated image from bytes (see [`load_animation`])
+pub fn load_animation_from_bytes(bytes: &[u8]) -> Result<Vec<AnimationFrame>> {
+    let frames = match image::guess_format(bytes) {
+        Ok(ImageFormat::Gif) => GifDecoder::new(Cursor::new(bytes))?
+            .into_frames()
+            .collect_frames()?,
+        Ok(ImageFormat::WebP) => {
+            let decoder = WebPDecoder::new(Cursor::new(bytes))?;
+            if !decoder.has_animation() {
+                return still_frame(bytes);
+            }
+            decoder.into_frames().collect_frames()?
+        }
+        Ok(ImageFormat::Png) => {
+            let decoder = PngDecoder::new(Cursor::new(bytes))?;
+            if !decoder.is_apng()? {
+                return still_frame(bytes);
+            }
+            decoder.apng()?.into_frames().collect_frames()?
+        }
+        _ => return still_frame(bytes),
+    };
+    let frames: Vec<_> = frames.into_iter().map(animation_frame).collect();
+    anyhow::ensure!(!frames.is_empty(), "Animation has no frames");
+    Ok(frames)
+}
+
+fn still_frame(bytes: &[u8]) -> Result<Vec<AnimationFrame>> {
+    Ok(vec![AnimationFrame {
+        image: load_image_from_bytes(bytes)?,
+        delay_ms: 0,
+    }])
+}
+
+fn animation_frame(frame: Frame) -> AnimationFrame {
+    let (numer, denom) = frame.delay().numer_denom_ms();
+    AnimationFrame {
+        delay_ms: numer.checked_div(denom).unwrap_or(0),
+        image: DynamicImage::ImageRgba8(frame.into_buffer()),
+    }
+}
+
+/// Build an image from a raw RGBA8 buffer (e.g. clipboard image data)
+pub fn image_from_rgba(width: usize, height: usize, bytes: Vec<u8>) -> Result<DynamicImage> {
+    let buffer = image::RgbaImage::from_raw(width as u32, height as u32, bytes)
+        .with_context(|| format!("Image data does not match its {}x{} size", width, height))?;
+    Ok(DynamicImage::ImageRgba8(buffer))
+}
+
 /// Supported image format extensions
-static SUPPORTED_EXTENSIONS_ARRAY: [&'static str; 8] = ["png", "jpg", "jpeg", "gif", "bmp", "webp", "tiff", "tif"];
+static SUPPORTED_EXTENSIONS_ARRAY: [&str; 8] =
+    ["png", "jpg", "jpeg", "gif", "bmp", "webp", "tiff", "tif"];
 
 /// Get supported image format extensions
 pub fn supported_extensions() -> &'static [&'static str] {
@@ -70,6 +246,91 @@ mod tests {
         assert!(!is_supported_format(&PathBuf::from("test")));
     }
 
+    #[test]
+    fn test_is_url() {
+        assert!(is_url("https://example.com/cat.png"));
+        assert!(is_url("HTTP://example.com/cat.png"));
+        assert!(!is_url("cat.png"));
+        assert!(!is_url("/home/me/https://odd-name.png"));
+        assert!(!is_url("ftp://example.com/cat.png"));
+    }
+
+    #[cfg(not(feature = "network"))]
+    #[test]
+    fn test_url_without_network_feature() {
+        let err = load_image_from_source(Path::new("https://example.com/cat.png")).unwrap_err();
+        assert!(err.to_string().contains("`network` feature"));
+    }
+
+    /// Answer one request on a local port with a canned response, returning the URL
+    #[cfg(feature = "network")]
+    fn serve_once(status: &'static str, content_type: &'static str, body: Vec<u8>) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/image", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.read(&mut [0u8; 1024]);
+            let head = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                status,
+                content_type,
+                body.len()
+            );
+            let _ = stream.write_all(head.as_bytes());
+            let _ = stream.write_all(&body);
+        });
+        url
+    }
+
+    #[cfg(feature = "network")]
+    fn png_bytes() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgb8(image::RgbImage::new(3, 2))
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn test_load_image_from_url() {
+        let url = serve_once("200 OK", "image/png", png_bytes());
+        let img = load_image_from_source(Path::new(&url)).unwrap();
+        assert_eq!((img.width(), img.height()), (3, 2));
+
+        // A generic binary type is fine when the bytes are an image
+        let url = serve_once("200 OK", "application/octet-stream", png_bytes());
+        assert!(load_image_from_url(&url).is_ok());
+    }
+
+    #[cfg(feature = "network")]
+    #[test]
+    fn test_url_errors_are_specific() {
+        let url = serve_once(
+            "200 OK",
+            "text/html; charset=utf-8",
+            b"<html></html>".to_vec(),
+        );
+        let err = load_image_from_url(&url).unwrap_err().to_string();
+        assert_eq!(err, "URL returned text/html, not an image");
+
+        let url = serve_once("404 Not Found", "text/plain", b"missing".to_vec());
+        let err = load_image_from_url(&url).unwrap_err().to_string();
+        assert_eq!(err, "URL returned HTTP 404 Not Found");
+
+        // A server that accepts the connection but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/slow.png", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let _connection = listener.accept();
+            std::thread::sleep(std::time::Duration::from_secs(2));
+        });
+        let err = fetch_image(&url, std::time::Duration::from_millis(200)).unwrap_err();
+        assert!(err.to_string().starts_with("Timed out after 0.2s"));
+    }
+
     #[test]
     fn test_load_from_bytes() {
         // Test with a simple test - just verify the function exists and handles errors
@@ -78,4 +339,96 @@ mod tests {
         let result = load_image_from_bytes(invalid_data);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_image_from_rgba() {
+        let bytes = vec![
+            255, 0, 0, 255, // red
+            0, 255, 0, 128, // half-transparent green
+        ];
+        let img = image_from_rgba(2, 1, bytes).unwrap();
+        assert_eq!((img.width(), img.height()), (2, 1));
+        let rgba = img.to_rgba8();
+        assert_eq!(rgba.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(rgba.get_pixel(1, 0).0, [0, 255, 0, 128]);
+
+        // Buffer too short for the claimed size
+        assert!(image_from_rgba(2, 2, vec![0; 8]).is_err());
+    }
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    /// Delay and top-left pixel of each frame
+    fn frame_summary(frames: &[AnimationFrame]) -> Vec<(u32, [u8; 4])> {
+        frames
+            .iter()
+            .map(|f| (f.delay_ms, f.image.to_rgba8().get_pixel(0, 0).0))
+            .collect()
+    }
+
+    #[test]
+    fn test_animated_webp_frames() {
+        // 1x1, a red frame for 100ms then a blue one for 250ms
+        let frames = load_animation(&fixture("two_frames.webp")).unwrap();
+        assert_eq!(
+            frame_summary(&frames),
+            vec![(100, [255, 0, 0, 255]), (250, [0, 0, 255, 255])]
+        );
+    }
+
+    #[test]
+    fn test_apng_frames() {
+        // Same frames as the WebP fixture
+        let frames = load_animation(&fixture("two_frames.png")).unwrap();
+        assert_eq!(
+            frame_summary(&frames),
+            vec![(100, [255, 0, 0, 255]), (250, [0, 0, 255, 255])]
+        );
+    }
+
+    #[test]
+    fn test_gif_frames() {
+        use image::codecs::gif::GifEncoder;
+        use image::{Delay, RgbaImage};
+
+        let mut bytes = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut bytes);
+            for (color, ms) in [([255, 0, 0, 255], 100), ([0, 0, 255, 255], 250)] {
+                let buffer = RgbaImage::from_pixel(1, 1, image::Rgba(color));
+                let delay = Delay::from_numer_denom_ms(ms, 1);
+                encoder
+                    .encode_frame(Frame::from_parts(buffer, 0, 0, delay))
+                    .unwrap();
+            }
+        }
+        let frames = load_animation_from_bytes(&bytes).unwrap();
+        assert_eq!(
+            frame_summary(&frames),
+            vec![(100, [255, 0, 0, 255]), (250, [0, 0, 255, 255])]
+        );
+    }
+
+    #[test]
+    fn test_still_image_is_one_frame() {
+        let mut bytes = Vec::new();
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+            2,
+            2,
+            image::Rgba([0, 255, 0, 255]),
+        ));
+        img.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+
+        let frames = load_animation_from_bytes(&bytes).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].delay_ms, 0);
+        assert_eq!((frames[0].image.width(), frames[0].image.height()), (2, 2));
+
+        assert!(load_animation_from_bytes(&[0u8; 10]).is_err());
+    }
 }
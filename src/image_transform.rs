@@ -0,0 +1,207 @@
+//! Rotate and flip transforms
+//!
+//! Orientation fixes applied to the loaded image before it is cropped and rendered.
+
+use image::DynamicImage;
+
+use crate::crop::CropRect;
+
+/// A single orientation change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageTransform {
+    /// 90° counter-clockwise
+    RotateLeft,
+    /// 90° clockwise
+    RotateRight,
+    /// Mirror left to right
+    FlipHorizontal,
+    /// Mirror top to bottom
+    FlipVertical,
+}
+
+impl ImageTransform {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImageTransform::RotateLeft => "Rotate left",
+            ImageTransform::RotateRight => "Rotate right",
+            ImageTransform::FlipHorizontal => "Flip horizontal",
+            ImageTransform::FlipVertical => "Flip vertical",
+        }
+    }
+
+    /// Parse a `--rotate` value (`left`/`270`/`-90`, `right`/`90`)
+    pub fn from_rotate_arg(arg: &str) -> Option<Self> {
+        match arg.to_ascii_lowercase().as_str() {
+            "left" | "270" | "-90" => Some(ImageTransform::RotateLeft),
+            "right" | "90" => Some(ImageTransform::RotateRight),
+            _ => None,
+        }
+    }
+
+    /// Parse a `--flip` value (`horizontal`/`h`, `vertical`/`v`)
+    pub fn from_flip_arg(arg: &str) -> Option<Self> {
+        match arg.to_ascii_lowercase().as_str() {
+            "horizontal" | "h" => Some(ImageTransform::FlipHorizontal),
+            "vertical" | "v" => Some(ImageTransform::FlipVertical),
+            _ => None,
+        }
+    }
+
+    /// The transform that undoes this one
+    pub fn inverse(&self) -> Self {
+        match self {
+            ImageTransform::RotateLeft => ImageTransform::RotateRight,
+            ImageTransform::RotateRight => ImageTransform::RotateLeft,
+            flip => *flip,
+        }
+    }
+
+    /// Apply to an image; each arm wraps the matching `image::imageops` function
+    pub fn apply(&self, image: &DynamicImage) -> DynamicImage {
+        match self {
+            ImageTransform::RotateLeft => image.rotate270(),
+            ImageTransform::RotateRight => image.rotate90(),
+            ImageTransform::FlipHorizontal => image.fliph(),
+            ImageTransform::FlipVertical => image.flipv(),
+        }
+    }
+
+    /// Map a region of a `width` x `height` image onto the same pixels after this transform
+    pub fn apply_to_rect(&self, rect: CropRect, width: u32, height: u32) -> CropRect {
+        let right_gap = width.saturating_sub(rect.x + rect.width);
+        let bottom_gap = height.saturating_sub(rect.y + rect.height);
+        match self {
+            ImageTransform::RotateLeft => CropRect::new(rect.y, right_gap, rect.height, rect.width),
+            ImageTransform::RotateRight => {
+                CropRect::new(bottom_gap, rect.x, rect.height, rect.width)
+            }
+            ImageTransform::FlipHorizontal => {
+                CropRect::new(right_gap, rect.y, rect.width, rect.height)
+            }
+            ImageTransform::FlipVertical => {
+                CropRect::new(rect.x, bottom_gap, rect.width, rect.height)
+            }
+        }
+    }
+}
+
+/// Apply a chain of transforms in order
+pub fn apply_chain(image: &DynamicImage, chain: &[ImageTransform]) -> DynamicImage {
+    let mut result = image.clone();
+    for transform in chain {
+        result = transform.apply(&result);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, RgbImage};
+
+    /// 3x2 image whose red channel encodes each pixel's position
+    fn numbered_image() -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::from_fn(3, 2, |x, y| {
+            image::Rgb([(y * 3 + x) as u8, 0, 0])
+        }))
+    }
+
+    fn red(image: &DynamicImage, x: u32, y: u32) -> u8 {
+        image.get_pixel(x, y).0[0]
+    }
+
+    #[test]
+    fn test_rotating_twice_restores_dimensions() {
+        let image = numbered_image();
+        let once = ImageTransform::RotateRight.apply(&image);
+        assert_eq!(once.dimensions(), (2, 3));
+
+        let twice = apply_chain(
+            &image,
+            &[ImageTransform::RotateRight, ImageTransform::RotateRight],
+        );
+        assert_eq!(twice.dimensions(), (3, 2));
+        // Upside down: the last pixel is now first
+        assert_eq!(red(&twice, 0, 0), 5);
+
+        let back = apply_chain(
+            &image,
+            &[ImageTransform::RotateLeft, ImageTransform::RotateRight],
+        );
+        assert_eq!(back.to_rgb8(), image.to_rgb8());
+    }
+
+    #[test]
+    fn test_chain_applies_in_order() {
+        let image = numbered_image();
+        // Flip then rotate: the original bottom-right pixel ends up top-left...
+        let flip_then_rotate = apply_chain(
+            &image,
+            &[ImageTransform::FlipHorizontal, ImageTransform::RotateRight],
+        );
+        // ...which differs from rotating first
+        let rotate_then_flip = apply_chain(
+            &image,
+            &[ImageTransform::RotateRight, ImageTransform::FlipHorizontal],
+        );
+        assert_eq!(red(&flip_then_rotate, 0, 0), 5);
+        assert_eq!(red(&rotate_then_flip, 0, 0), 0);
+        assert_eq!(apply_chain(&image, &[]).to_rgb8(), image.to_rgb8());
+    }
+
+    #[test]
+    fn test_inverse_undoes_transform() {
+        let image = numbered_image();
+        for transform in [
+            ImageTransform::RotateLeft,
+            ImageTransform::RotateRight,
+            ImageTransform::FlipHorizontal,
+            ImageTransform::FlipVertical,
+        ] {
+            let restored = apply_chain(&image, &[transform, transform.inverse()]);
+            assert_eq!(restored.to_rgb8(), image.to_rgb8(), "{}", transform.name());
+        }
+    }
+
+    #[test]
+    fn test_rect_follows_the_same_pixels() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(7, 5, |x, y| {
+            image::Rgb([(y * 7 + x) as u8, 0, 0])
+        }));
+        let rect = CropRect::new(1, 2, 4, 2);
+        for transform in [
+            ImageTransform::RotateLeft,
+            ImageTransform::RotateRight,
+            ImageTransform::FlipHorizontal,
+            ImageTransform::FlipVertical,
+        ] {
+            let crop_then_transform = transform.apply(&rect.apply(&image));
+            let mapped = transform.apply_to_rect(rect, 7, 5);
+            let transform_then_crop = mapped.apply(&transform.apply(&image));
+            assert_eq!(
+                crop_then_transform.to_rgb8(),
+                transform_then_crop.to_rgb8(),
+                "{}",
+                transform.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_cli_values() {
+        assert_eq!(
+            ImageTransform::from_rotate_arg("left"),
+            Some(ImageTransform::RotateLeft)
+        );
+        assert_eq!(
+            ImageTransform::from_rotate_arg("90"),
+            Some(ImageTransform::RotateRight)
+        );
+        assert_eq!(
+            ImageTransform::from_flip_arg("V"),
+            Some(ImageTransform::FlipVertical)
+        );
+        assert_eq!(ImageTransform::from_rotate_arg("sideways"), None);
+        assert_eq!(ImageTransform::from_flip_arg("diagonal"), None);
+    }
+}
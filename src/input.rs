@@ -4,15 +4,34 @@
 
 use anyhow::Result;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
-
+use std::path::PathBuf;
+
+use crate::color_space::{rgb_to_luminance_bt601, Rgb};
+use crate::config::{DEFAULT_HTML_BACKGROUND, DEFAULT_HTML_LIGHT_BACKGROUND};
+use crate::image_loader::image_from_rgba;
+use crate::image_transform::ImageTransform;
+use crate::keybindings::Action;
+use crate::render_engines::{
+    ascii::{CharacterSet, EdgeMethod},
+    clamp_saturation, ResizeFilter,
+};
 use crate::state::{AppState, FocusedWidget, RenderMode};
+use crate::unicode_handler::{is_csi_final_byte, skip_escape_sequence, strip_ansi_codes};
 
 /// Handle an input event
 pub fn handle_event(event: Event, state: &mut AppState) -> Result<()> {
     match event {
         Event::Key(key_event) => handle_key_event(key_event, state),
         Event::Resize(_, _) => Ok(()), // Already handled in main loop
-        Event::Mouse(_) => Ok(()),      // Mouse support can be added later
+        Event::Mouse(_) => Ok(()),     // Mouse support can be added later
+        Event::FocusGained => {
+            state.focused = true;
+            Ok(())
+        }
+        Event::FocusLost => {
+            state.focused = false;
+            Ok(())
+        }
         _ => Ok(()),
     }
 }
@@ -24,46 +43,56 @@ fn handle_key_event(key: KeyEvent, state: &mut AppState) -> Result<()> {
         return handle_help_input(key, state);
     }
 
+    // Handle the file browser opened from the load prompt
+    if state.file_browser.is_some() {
+        return handle_file_browser_input(key, state);
+    }
+
     // Handle interactive load prompt
     if state.load_prompt_active {
         return handle_load_prompt_input(key, state);
     }
 
+    // Handle custom charset prompt
+    if state.charset_prompt_active {
+        return handle_charset_prompt_input(key, state);
+    }
+
+    // Handle text color prompt
+    if state.color_prompt_active {
+        return handle_color_prompt_input(key, state);
+    }
+
+    // Handle preset prompt
+    if state.preset_prompt_active {
+        return handle_preset_prompt_input(key, state);
+    }
+
+    // Handle render history overlay
+    if state.history_overlay_active {
+        return handle_history_input(key, state);
+    }
+
+    // Handle crop overlay
+    if state.crop_overlay_active {
+        return handle_crop_input(key, state);
+    }
+
     // Handle text input mode
     if state.text_state.editing_text {
         return handle_text_input(key, state);
     }
 
-    // Global shortcuts
-    match key.code {
-        KeyCode::Char('q') | KeyCode::Char('Q') => {
-            state.should_quit = true;
-            return Ok(());
-        }
-        KeyCode::Char('?') => {
-            state.show_help = true;
-            return Ok(());
+    // Configurable bindings take precedence over the widget's own keys
+    if let Some(action) = state.keybindings.action_for(&key) {
+        if action.available_in(state.focus) {
+            return run_action(action, state);
         }
-        KeyCode::Char('o') | KeyCode::Char('O') => {
-            // Cycle output format based on current mode
-            let is_unicode = matches!(state.current_mode, RenderMode::ImageToUnicode);
-            state.preview_output_format = state.preview_output_format.next_for_mode(is_unicode);
-            state.set_status(&format!("Output format: {}", state.preview_output_format.name()), false);
-            return Ok(());
-        }
-        KeyCode::Tab => {
-            if key.modifiers.contains(KeyModifiers::SHIFT) {
-                state.focus = state.focus.prev();
-            } else {
-                state.focus = state.focus.next();
-            }
-            return Ok(());
-        }
-        KeyCode::Esc => {
-            // Could be used to cancel operations
-            return Ok(());
-        }
-        _ => {}
+    }
+
+    if key.code == KeyCode::Esc {
+        // Could be used to cancel operations
+        return Ok(());
     }
 
     // Context-sensitive handling
@@ -74,12 +103,120 @@ fn handle_key_event(key: KeyEvent, state: &mut AppState) -> Result<()> {
     }
 }
 
+/// Run a bound action
+fn run_action(action: Action, state: &mut AppState) -> Result<()> {
+    match action {
+        Action::Quit => state.should_quit = true,
+        Action::Help => state.open_help(),
+        Action::CycleFormat => {
+            // Cycle output format based on current mode
+            let is_unicode = matches!(state.current_mode, RenderMode::ImageToUnicode);
+            state.preview_output_format = state.preview_output_format.next_for_mode(is_unicode);
+            state.set_status(
+                &format!("Output format: {}", state.preview_output_format.name()),
+                false,
+            );
+        }
+        Action::NextWidget => state.focus = state.focus.next(),
+        Action::PrevWidget => state.focus = state.focus.prev(),
+        Action::Load => state.start_load_prompt(),
+        Action::ReloadLast => state.reload_last_image(),
+        Action::Paste => paste_from_clipboard(state)?,
+        Action::Save => save_output(state)?,
+        Action::Copy => copy_to_clipboard(state, false)?,
+        Action::CopyRaw => copy_to_clipboard(state, true)?,
+        Action::Render => {
+            // In the control panel the render key also flips boolean settings
+            if state.focus != FocusedWidget::ControlPanel
+                || !record_change(state, toggle_current_setting)
+            {
+                state.trigger_render();
+            }
+        }
+        Action::WordWrap => state.toggle_word_wrap(),
+        Action::LineNumbers => state.toggle_line_numbers(),
+        Action::MemoryUsage => state.toggle_memory_usage(),
+        Action::Compare => state.toggle_compare_mode(),
+        Action::CompareLock => state.toggle_compare_scroll_lock(),
+        Action::Presets => state.start_preset_prompt(),
+        Action::AutoFit => state.cycle_sizing(),
+        Action::FontAspectUp => state.adjust_font_aspect(true),
+        Action::FontAspectDown => state.adjust_font_aspect(false),
+        Action::CycleColor => record_change(state, AppState::cycle_color_mode),
+        Action::PreserveScroll => state.toggle_preserve_scroll(),
+        Action::Undo => state.undo(),
+        Action::Redo => state.redo(),
+        Action::History => state.open_history(),
+        Action::Crop => state.open_crop(),
+        Action::RotateLeft => state.apply_transform(ImageTransform::RotateLeft),
+        Action::RotateRight => state.apply_transform(ImageTransform::RotateRight),
+        Action::FlipHorizontal => state.apply_transform(ImageTransform::FlipHorizontal),
+        Action::FlipVertical => state.apply_transform(ImageTransform::FlipVertical),
+        Action::ResetTransforms => state.reset_transforms(),
+    }
+    Ok(())
+}
+
 /// Handle input when help overlay is shown
 fn handle_help_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
+    let is_help_key = state.keybindings.action_for(&key) == Some(Action::Help);
+    if is_help_key || matches!(key.code, KeyCode::Esc | KeyCode::Enter) {
+        state.show_help = false;
+        return Ok(());
+    }
     match key.code {
-        KeyCode::Char('?') | KeyCode::Esc | KeyCode::Enter => {
-            state.show_help = false;
-        }
+        KeyCode::Up | KeyCode::Char('k') => state.scroll_help_up(1),
+        KeyCode::Down | KeyCode::Char('j') => state.scroll_help_down(1),
+        KeyCode::PageUp => state.scroll_help_up(state.help_page_rows()),
+        KeyCode::PageDown => state.scroll_help_down(state.help_page_rows()),
+        KeyCode::Home => state.help_scroll = 0,
+        KeyCode::End => state.scroll_help_to_end(),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle input while the render history overlay is shown
+fn handle_history_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
+    let is_history_key = state.keybindings.action_for(&key) == Some(Action::History);
+    if is_history_key || key.code == KeyCode::Esc {
+        state.close_history();
+        return Ok(());
+    }
+    match key.code {
+        KeyCode::Up | KeyCode::Char('k') => state.move_history_selection(false),
+        KeyCode::Down | KeyCode::Char('j') => state.move_history_selection(true),
+        KeyCode::Enter => state.restore_history(state.history_selected),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle input while the crop overlay is shown
+///
+/// Arrows (or h/j/k/l) move the selection; with Shift (or H/J/K/L) they resize it.
+fn handle_crop_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
+    let is_crop_key = state.keybindings.action_for(&key) == Some(Action::Crop);
+    if is_crop_key || key.code == KeyCode::Esc {
+        state.close_crop();
+        return Ok(());
+    }
+    let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+    match key.code {
+        KeyCode::Left => state.nudge_crop_selection(-1, 0, shift),
+        KeyCode::Right => state.nudge_crop_selection(1, 0, shift),
+        KeyCode::Up => state.nudge_crop_selection(0, -1, shift),
+        KeyCode::Down => state.nudge_crop_selection(0, 1, shift),
+        KeyCode::Char('h') => state.nudge_crop_selection(-1, 0, false),
+        KeyCode::Char('l') => state.nudge_crop_selection(1, 0, false),
+        KeyCode::Char('k') => state.nudge_crop_selection(0, -1, false),
+        KeyCode::Char('j') => state.nudge_crop_selection(0, 1, false),
+        KeyCode::Char('H') => state.nudge_crop_selection(-1, 0, true),
+        KeyCode::Char('L') => state.nudge_crop_selection(1, 0, true),
+        KeyCode::Char('K') => state.nudge_crop_selection(0, -1, true),
+        KeyCode::Char('J') => state.nudge_crop_selection(0, 1, true),
+        KeyCode::Enter => state.apply_crop_selection(),
+        KeyCode::Backspace | KeyCode::Delete => state.clear_crop(),
         _ => {}
     }
     Ok(())
@@ -114,6 +251,12 @@ fn handle_load_prompt_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
         KeyCode::Enter => {
             state.submit_load_prompt();
         }
+        KeyCode::Tab => {
+            state.complete_load_prompt();
+        }
+        KeyCode::Down => {
+            state.open_file_browser();
+        }
         KeyCode::Backspace => {
             state.load_prompt_input.pop();
         }
@@ -125,6 +268,86 @@ fn handle_load_prompt_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
     Ok(())
 }
 
+/// Handle input while the file browser is shown
+///
+/// Arrows (or h/j/k/l) navigate; Enter or → opens a folder or loads an image.
+fn handle_file_browser_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
+    match key.code {
+        KeyCode::Esc => state.close_file_browser(),
+        KeyCode::Up | KeyCode::Char('k') => state.move_browser_selection(false),
+        KeyCode::Down | KeyCode::Char('j') => state.move_browser_selection(true),
+        KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => state.browser_activate(),
+        KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => state.browser_ascend(),
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle input for the custom charset prompt
+fn handle_charset_prompt_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
+    match key.code {
+        KeyCode::Esc => {
+            state.cancel_charset_prompt();
+        }
+        KeyCode::Enter => {
+            state.submit_charset_prompt();
+        }
+        KeyCode::Backspace => {
+            state.charset_prompt_input.pop();
+        }
+        KeyCode::Char(c) => {
+            state.charset_prompt_input.push(c);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle input for the text color prompt
+fn handle_color_prompt_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
+    match key.code {
+        KeyCode::Esc => {
+            state.cancel_color_prompt();
+        }
+        KeyCode::Enter => {
+            state.submit_color_prompt();
+        }
+        KeyCode::Backspace => {
+            state.color_prompt_input.pop();
+        }
+        KeyCode::Char(c) => {
+            state.color_prompt_input.push(c);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Handle input for the preset prompt
+fn handle_preset_prompt_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
+    match key.code {
+        KeyCode::Esc => {
+            state.cancel_preset_prompt();
+        }
+        KeyCode::Enter => {
+            state.submit_preset_prompt();
+        }
+        KeyCode::Tab => {
+            state.toggle_preset_prompt_saving();
+        }
+        KeyCode::Up => state.cycle_preset_name(false),
+        KeyCode::Down => state.cycle_preset_name(true),
+        KeyCode::Backspace => {
+            state.preset_prompt_input.pop();
+        }
+        KeyCode::Char(c) => {
+            state.preset_prompt_input.push(c);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
 /// Handle input for mode selector widget
 fn handle_mode_selector_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
     match key.code {
@@ -132,6 +355,7 @@ fn handle_mode_selector_input(key: KeyEvent, state: &mut AppState) -> Result<()>
         KeyCode::Char('1') => state.set_mode(RenderMode::ImageToAscii),
         KeyCode::Char('2') => state.set_mode(RenderMode::ImageToUnicode),
         KeyCode::Char('3') => state.set_mode(RenderMode::TextStylizer),
+        KeyCode::Char('4') => state.set_mode(RenderMode::ColorAscii),
 
         // Arrow navigation
         KeyCode::Up | KeyCode::Char('k') => {
@@ -161,12 +385,10 @@ fn handle_mode_selector_input(key: KeyEvent, state: &mut AppState) -> Result<()>
             // Mode already selected
         }
 
-        // Common actions
-        KeyCode::Char('l') | KeyCode::Char('L') => {
-            state.start_load_prompt();
-        }
-        KeyCode::Char(' ') => state.trigger_render(),
-        KeyCode::Char('s') | KeyCode::Char('S') => save_output(state)?,
+        // Lowercase aliases for the bound actions; nothing else uses these keys here
+        KeyCode::Char('l') => state.start_load_prompt(),
+        KeyCode::Char('v') => paste_from_clipboard(state)?,
+        KeyCode::Char('s') => save_output(state)?,
 
         _ => {}
     }
@@ -180,50 +402,63 @@ fn handle_control_panel_input(key: KeyEvent, state: &mut AppState) -> Result<()>
         KeyCode::Up | KeyCode::Char('k') => state.prev_setting(),
         KeyCode::Down | KeyCode::Char('j') => state.next_setting(),
 
-        // Adjust settings
-        KeyCode::Left | KeyCode::Char('h') => adjust_setting_left(state),
-        KeyCode::Right | KeyCode::Char('l') => adjust_setting_right(state),
-        KeyCode::Char('+') | KeyCode::Char('=') => adjust_setting_increase(state),
-        KeyCode::Char('-') | KeyCode::Char('_') => adjust_setting_decrease(state),
-
-        // Toggle/action
-        KeyCode::Char(' ') => {
-            if !toggle_current_setting(state) {
-                state.trigger_render();
-            }
+        // Adjust settings (each change can be undone)
+        KeyCode::Left | KeyCode::Char('h') => {
+            record_change(state, adjust_setting_left);
         }
-        KeyCode::Enter => {
-            // For text mode input
-            if matches!(state.current_mode, RenderMode::TextStylizer)
-                && state.text_state.selected_setting == 4
-            {
-                state.text_state.editing_text = true;
-                state.set_status("Editing text: type and press Enter (Esc to cancel)", false);
-            } else {
-                state.trigger_render();
-            }
+        KeyCode::Right | KeyCode::Char('l') => {
+            record_change(state, adjust_setting_right);
         }
-        // Also allow quick edit with 'e' when on Input field
-        KeyCode::Char('e') => {
-            if matches!(state.current_mode, RenderMode::TextStylizer)
-                && state.text_state.selected_setting == 4
-            {
-                state.text_state.editing_text = true;
-                state.set_status("Editing text: type and press Enter (Esc to cancel)", false);
-            }
+        KeyCode::Char('+') | KeyCode::Char('=') => {
+            record_change(state, adjust_setting_increase);
+        }
+        KeyCode::Char('-') | KeyCode::Char('_') => {
+            record_change(state, adjust_setting_decrease);
         }
 
-        // Actions
-        KeyCode::Char('L') => {
-            state.start_load_prompt();
+        // For text mode input and colors
+        KeyCode::Enter if !start_text_entry(state) => state.trigger_render(),
+        // Also allow quick edit with 'e' when on the Input or color fields
+        KeyCode::Char('e') => {
+            start_text_entry(state);
         }
-        KeyCode::Char('S') => save_output(state)?,
 
         _ => {}
     }
     Ok(())
 }
 
+/// Apply a settings change, recording an undo snapshot if it changed anything
+fn record_change<T>(state: &mut AppState, change: impl FnOnce(&mut AppState) -> T) -> T {
+    let before = state.settings_snapshot();
+    let result = change(state);
+    if state.settings_snapshot() != before {
+        state.record_undo(before);
+    }
+    result
+}
+
+/// Open the editor for the selected text stylizer field (input or a color), if any
+fn start_text_entry(state: &mut AppState) -> bool {
+    if state.current_mode != RenderMode::TextStylizer {
+        return false;
+    }
+    match state.text_state.selected_setting {
+        3 => state.start_color_prompt(false),
+        4 => state.start_color_prompt(true),
+        5 => start_text_editing(state),
+        _ => return false,
+    }
+    true
+}
+
+/// Begin editing the stylizer input; the whole edit session is one undo step
+fn start_text_editing(state: &mut AppState) {
+    state.push_undo_snapshot();
+    state.text_state.editing_text = true;
+    state.set_status("Editing text: type and press Enter (Esc to cancel)", false);
+}
+
 /// Handle input for preview widget
 fn handle_preview_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
     match key.code {
@@ -236,12 +471,7 @@ fn handle_preview_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
             state.preview_scroll = 0;
             state.preview_scroll_x = 0;
         }
-        KeyCode::End => {
-            if let Some(ref content) = state.preview_content {
-                let line_count = content.lines().count();
-                state.preview_scroll = line_count.saturating_sub(1);
-            }
-        }
+        KeyCode::End => state.scroll_to_end(),
 
         // Horizontal scrolling
         KeyCode::Left | KeyCode::Char('h') => state.scroll_left(5),
@@ -255,13 +485,9 @@ fn handle_preview_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
             state.adjust_zoom(false);
         }
 
-        // Actions
-        KeyCode::Char('s') | KeyCode::Char('S') => save_output(state)?,
-        KeyCode::Char('c') | KeyCode::Char('C') => copy_to_clipboard(state)?,
-        KeyCode::Char('L') => {
-            state.start_load_prompt();
-        }
-        KeyCode::Char(' ') => state.trigger_render(),
+        // Lowercase aliases for the bound actions
+        KeyCode::Char('s') => save_output(state)?,
+        KeyCode::Char('v') => paste_from_clipboard(state)?,
 
         _ => {}
     }
@@ -272,20 +498,43 @@ fn handle_preview_input(key: KeyEvent, state: &mut AppState) -> Result<()> {
 fn adjust_setting_left(state: &mut AppState) {
     match state.current_mode {
         RenderMode::ImageToAscii => match state.ascii_state.selected_setting {
-            1 => state.ascii_state.charset = state.ascii_state.charset.prev(),
-            4 => state.preview_output_format = state.preview_output_format.prev_for_mode(false),
+            1 => cycle_charset(state, CharacterSet::prev),
+            4 => cycle_edge_method(state, EdgeMethod::prev),
+            5 => cycle_resize_filter(state, ResizeFilter::prev),
+            6 => {
+                state.ascii_state.luma = state.ascii_state.luma.prev();
+                state.config.ascii.luma = state.ascii_state.luma;
+            }
+            7 => adjust_saturation(state, false),
+            9 => state.preview_output_format = state.preview_output_format.prev_for_mode(false),
             _ => {}
         },
         RenderMode::ImageToUnicode => match state.unicode_state.selected_setting {
-            1 => state.unicode_state.mode = state.unicode_state.mode.prev(),
+            1 => {
+                state.unicode_state.mode = state.unicode_state.mode.prev();
+                state.warn_if_braille_misaligned();
+            }
             2 => state.unicode_state.color_mode = state.unicode_state.color_mode.prev(),
-            3 => state.preview_output_format = state.preview_output_format.prev_for_mode(true),
+            3 => cycle_resize_filter(state, ResizeFilter::prev),
+            7 => adjust_saturation(state, false),
+            9 => state.preview_output_format = state.preview_output_format.prev_for_mode(true),
+            _ => {}
+        },
+        RenderMode::ColorAscii => match state.color_ascii_state.selected_setting {
+            1 => cycle_charset(state, CharacterSet::prev),
+            3 => state.color_ascii_state.color_mode = state.color_ascii_state.color_mode.prev(),
+            4 => cycle_resize_filter(state, ResizeFilter::prev),
+            5 => adjust_saturation(state, false),
+            6 => state.preview_output_format = state.preview_output_format.prev_for_mode(false),
             _ => {}
         },
         RenderMode::TextStylizer => match state.text_state.selected_setting {
             0 => state.text_state.style = state.text_state.style.prev(),
-            1 => state.text_state.gradient = state.text_state.gradient.prev(),
-            5 => state.preview_output_format = state.preview_output_format.prev_for_mode(false),
+            1 => state.text_state.transform = state.text_state.transform.prev(),
+            2 => state.text_state.gradient = state.text_state.gradient.prev(),
+            3 | 4 => state.text_state.cycle_color_channel(false),
+            7 => state.text_state.alignment = state.text_state.alignment.prev(),
+            8 => state.preview_output_format = state.preview_output_format.prev_for_mode(false),
             _ => {}
         },
     }
@@ -295,25 +544,111 @@ fn adjust_setting_left(state: &mut AppState) {
 fn adjust_setting_right(state: &mut AppState) {
     match state.current_mode {
         RenderMode::ImageToAscii => match state.ascii_state.selected_setting {
-            1 => state.ascii_state.charset = state.ascii_state.charset.next(),
-            4 => state.preview_output_format = state.preview_output_format.next_for_mode(false),
+            1 => cycle_charset(state, CharacterSet::next),
+            4 => cycle_edge_method(state, EdgeMethod::next),
+            5 => cycle_resize_filter(state, ResizeFilter::next),
+            6 => {
+                state.ascii_state.luma = state.ascii_state.luma.next();
+                state.config.ascii.luma = state.ascii_state.luma;
+            }
+            7 => adjust_saturation(state, true),
+            9 => state.preview_output_format = state.preview_output_format.next_for_mode(false),
             _ => {}
         },
         RenderMode::ImageToUnicode => match state.unicode_state.selected_setting {
-            1 => state.unicode_state.mode = state.unicode_state.mode.next(),
+            1 => {
+                state.unicode_state.mode = state.unicode_state.mode.next();
+                state.warn_if_braille_misaligned();
+            }
             2 => state.unicode_state.color_mode = state.unicode_state.color_mode.next(),
-            3 => state.preview_output_format = state.preview_output_format.next_for_mode(true),
+            3 => cycle_resize_filter(state, ResizeFilter::next),
+            7 => adjust_saturation(state, true),
+            9 => state.preview_output_format = state.preview_output_format.next_for_mode(true),
+            _ => {}
+        },
+        RenderMode::ColorAscii => match state.color_ascii_state.selected_setting {
+            1 => cycle_charset(state, CharacterSet::next),
+            3 => state.color_ascii_state.color_mode = state.color_ascii_state.color_mode.next(),
+            4 => cycle_resize_filter(state, ResizeFilter::next),
+            5 => adjust_saturation(state, true),
+            6 => state.preview_output_format = state.preview_output_format.next_for_mode(false),
             _ => {}
         },
         RenderMode::TextStylizer => match state.text_state.selected_setting {
             0 => state.text_state.style = state.text_state.style.next(),
-            1 => state.text_state.gradient = state.text_state.gradient.next(),
-            5 => state.preview_output_format = state.preview_output_format.next_for_mode(false),
+            1 => state.text_state.transform = state.text_state.transform.next(),
+            2 => state.text_state.gradient = state.text_state.gradient.next(),
+            3 | 4 => state.text_state.cycle_color_channel(true),
+            7 => state.text_state.alignment = state.text_state.alignment.next(),
+            8 => state.preview_output_format = state.preview_output_format.next_for_mode(false),
             _ => {}
         },
     }
 }
 
+/// Cycle the current mode's charset; landing on Custom asks for the ramp instead
+fn cycle_charset(state: &mut AppState, step: fn(&CharacterSet) -> CharacterSet) {
+    let charset = if state.current_mode == RenderMode::ColorAscii {
+        &mut state.color_ascii_state.charset
+    } else {
+        &mut state.ascii_state.charset
+    };
+    match step(charset) {
+        CharacterSet::Custom(_) => state.start_charset_prompt(),
+        next => *charset = next,
+    }
+}
+
+/// Cycle the edge detection operator and remember it as the default; only meaningful
+/// while edge enhance is on
+fn cycle_edge_method(state: &mut AppState, step: fn(&EdgeMethod) -> EdgeMethod) {
+    if state.ascii_state.edge_enhance {
+        state.ascii_state.edge_method = step(&state.ascii_state.edge_method);
+        state.config.ascii.edge_method = state.ascii_state.edge_method;
+    } else {
+        state.set_status("Enable Edge Enhance to choose an edge method", false);
+    }
+}
+
+/// Cycle the resize filter for the current image mode and remember it as the default
+fn cycle_resize_filter(state: &mut AppState, step: fn(&ResizeFilter) -> ResizeFilter) {
+    match state.current_mode {
+        RenderMode::ImageToAscii => {
+            state.ascii_state.resize_filter = step(&state.ascii_state.resize_filter);
+            state.config.ascii.resize_filter = state.ascii_state.resize_filter;
+        }
+        RenderMode::ImageToUnicode => {
+            state.unicode_state.resize_filter = step(&state.unicode_state.resize_filter);
+            state.config.unicode.resize_filter = state.unicode_state.resize_filter;
+        }
+        RenderMode::ColorAscii => {
+            state.color_ascii_state.resize_filter = step(&state.color_ascii_state.resize_filter);
+        }
+        RenderMode::TextStylizer => {}
+    }
+}
+
+/// Step the saturation multiplier for the current image mode and remember it as the default
+fn adjust_saturation(state: &mut AppState, increase: bool) {
+    let step = if increase { 0.1 } else { -0.1 };
+    // Round to one decimal so repeated steps don't drift
+    let step_from = |saturation: f32| (clamp_saturation(saturation + step) * 10.0).round() / 10.0;
+    match state.current_mode {
+        RenderMode::ImageToAscii => {
+            state.ascii_state.saturation = step_from(state.ascii_state.saturation);
+            state.config.ascii.saturation = state.ascii_state.saturation;
+        }
+        RenderMode::ImageToUnicode => {
+            state.unicode_state.saturation = step_from(state.unicode_state.saturation);
+            state.config.unicode.saturation = state.unicode_state.saturation;
+        }
+        RenderMode::ColorAscii => {
+            state.color_ascii_state.saturation = step_from(state.color_ascii_state.saturation);
+        }
+        RenderMode::TextStylizer => {}
+    }
+}
+
 /// Increase numeric setting
 fn adjust_setting_increase(state: &mut AppState) {
     match state.current_mode {
@@ -327,7 +662,16 @@ fn adjust_setting_increase(state: &mut AppState) {
                 state.unicode_state.width = (state.unicode_state.width + 10).min(300);
             }
         }
-        _ => {}
+        RenderMode::ColorAscii => {
+            if state.color_ascii_state.selected_setting == 0 {
+                state.color_ascii_state.width = (state.color_ascii_state.width + 10).min(300);
+            }
+        }
+        RenderMode::TextStylizer => match state.text_state.selected_setting {
+            3 => state.text_state.adjust_color_channel(false, true),
+            4 => state.text_state.adjust_color_channel(true, true),
+            _ => {}
+        },
     }
 }
 
@@ -344,7 +688,17 @@ fn adjust_setting_decrease(state: &mut AppState) {
                 state.unicode_state.width = state.unicode_state.width.saturating_sub(10).max(20);
             }
         }
-        _ => {}
+        RenderMode::ColorAscii => {
+            if state.color_ascii_state.selected_setting == 0 {
+                state.color_ascii_state.width =
+                    state.color_ascii_state.width.saturating_sub(10).max(20);
+            }
+        }
+        RenderMode::TextStylizer => match state.text_state.selected_setting {
+            3 => state.text_state.adjust_color_channel(false, false),
+            4 => state.text_state.adjust_color_channel(true, false),
+            _ => {}
+        },
     }
 }
 
@@ -360,17 +714,47 @@ fn toggle_current_setting(state: &mut AppState) -> bool {
                 state.ascii_state.edge_enhance = !state.ascii_state.edge_enhance;
                 true
             }
+            8 => {
+                state.ascii_state.preserve_pixel_aspect = !state.ascii_state.preserve_pixel_aspect;
+                true
+            }
             _ => false,
         },
-        RenderMode::TextStylizer => {
-            if state.text_state.selected_setting == 4 {
-                state.text_state.editing_text = true;
+        RenderMode::ImageToUnicode => match state.unicode_state.selected_setting {
+            4 => {
+                state.unicode_state.invert = !state.unicode_state.invert;
                 true
-            } else {
-                false
             }
-        }
-        _ => false,
+            5 => {
+                state.unicode_state.negate = !state.unicode_state.negate;
+                true
+            }
+            6 => {
+                state.unicode_state.dither = !state.unicode_state.dither;
+                true
+            }
+            8 => {
+                state.unicode_state.preserve_pixel_aspect =
+                    !state.unicode_state.preserve_pixel_aspect;
+                true
+            }
+            _ => false,
+        },
+        RenderMode::ColorAscii => match state.color_ascii_state.selected_setting {
+            2 => {
+                state.color_ascii_state.invert = !state.color_ascii_state.invert;
+                true
+            }
+            _ => false,
+        },
+        RenderMode::TextStylizer => match state.text_state.selected_setting {
+            3..=5 => start_text_entry(state),
+            6 => {
+                state.text_state.hyperlink = !state.text_state.hyperlink;
+                true
+            }
+            _ => false,
+        },
     }
 }
 
@@ -382,6 +766,7 @@ fn save_output(state: &mut AppState) -> Result<()> {
             RenderMode::ImageToAscii => "ascii_output",
             RenderMode::ImageToUnicode => "unicode_output",
             RenderMode::TextStylizer => "styled_text",
+            RenderMode::ColorAscii => "color_ascii_output",
         };
 
         let is_unicode = matches!(state.current_mode, RenderMode::ImageToUnicode);
@@ -396,7 +781,11 @@ fn save_output(state: &mut AppState) -> Result<()> {
             }
             crate::state::OutputFormat::Html => {
                 let filename = format!("{}.html", base);
-                let html = convert_ansi_to_html(content);
+                let dark_background = state.output_for_dark_background();
+                let html = convert_ansi_to_html_with_background(
+                    content,
+                    state.config.ui.html_background(dark_background),
+                );
                 match std::fs::write(&filename, html) {
                     Ok(_) => state.set_status(&format!("Saved to {}", filename), false),
                     Err(e) => state.set_status(&format!("Save failed: {}", e), true),
@@ -423,11 +812,33 @@ fn save_output(state: &mut AppState) -> Result<()> {
             }
             crate::state::OutputFormat::Svg => {
                 let filename = format!("{}.svg", base);
-                match export_to_svg(content, &filename) {
+                match export_to_svg(content, &filename, state.output_for_dark_background()) {
                     Ok(_) => state.set_status(&format!("Saved to {}", filename), false),
                     Err(e) => state.set_status(&format!("SVG export failed: {}", e), true),
                 }
             }
+            crate::state::OutputFormat::Markdown => {
+                if is_unicode {
+                    state.set_status("MD not supported for Unicode (use HTML/PNG/SVG)", true);
+                } else {
+                    let filename = format!("{}.md", base);
+                    match std::fs::write(&filename, convert_to_markdown(content)) {
+                        Ok(_) => state.set_status(&format!("Saved to {}", filename), false),
+                        Err(e) => state.set_status(&format!("Save failed: {}", e), true),
+                    }
+                }
+            }
+            crate::state::OutputFormat::Json => {
+                let filename = format!("{}.json", base);
+                let result = state
+                    .json_export(content)
+                    .to_json()
+                    .and_then(|json| std::fs::write(&filename, json).map_err(Into::into));
+                match result {
+                    Ok(_) => state.set_status(&format!("Saved to {}", filename), false),
+                    Err(e) => state.set_status(&format!("Save failed: {}", e), true),
+                }
+            }
         }
     } else {
         state.set_status("Nothing to save - render first", false);
@@ -435,9 +846,39 @@ fn save_output(state: &mut AppState) -> Result<()> {
     Ok(())
 }
 
-/// Convert ANSI-rendered content to a simple HTML document with inline styles
-/// Convert ANSI-rendered content to a simple HTML document with inline styles
-pub fn convert_ansi_to_html(content: &str) -> String {
+/// Wrap rendered content in a Markdown ```` ```text ```` code fence, colors stripped
+///
+/// The fence is one backtick longer than the longest backtick run in the content (and
+/// at least three), so art containing ```` ``` ```` can't close it early.
+pub fn convert_to_markdown(content: &str) -> String {
+    let clean = strip_ansi_codes(content);
+    let longest_run = clean.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat((longest_run + 1).max(3));
+    format!(
+        "{}text\n{}\n{}\n",
+        fence,
+        clean.trim_end_matches('\n'),
+        fence
+    )
+}
+
+/// Convert ANSI-rendered content to a simple HTML document with inline styles, on the
+/// default near-black background, or off-white if the art was drawn for a light
+/// background (e.g. inverted ASCII)
+pub fn convert_ansi_to_html(content: &str, dark_background: bool) -> String {
+    let background = if dark_background {
+        DEFAULT_HTML_BACKGROUND
+    } else {
+        DEFAULT_HTML_LIGHT_BACKGROUND
+    };
+    convert_ansi_to_html_with_background(content, background)
+}
+
+/// Convert ANSI-rendered content to an HTML document whose `<pre>` sits in a padded
+/// container of the given background color, so uncolored cells look as they do in a terminal
+///
+/// The default text color is light on dark backgrounds and dark on light ones.
+pub fn convert_ansi_to_html_with_background(content: &str, background: Rgb) -> String {
     fn css_color(c: &Option<ratatui::style::Color>) -> Option<String> {
         match c {
             Some(ratatui::style::Color::Rgb(r, g, b)) => Some(format!("rgb({},{},{})", r, g, b)),
@@ -450,37 +891,71 @@ pub fn convert_ansi_to_html(content: &str) -> String {
             Some(ratatui::style::Color::Magenta) => Some("magenta".into()),
             Some(ratatui::style::Color::Cyan) => Some("cyan".into()),
             Some(ratatui::style::Color::White) => Some("white".into()),
+            // Bright colors use the xterm palette entries 8-15
+            Some(ratatui::style::Color::DarkGray) => Some(indexed_to_css(8)),
+            Some(ratatui::style::Color::LightRed) => Some(indexed_to_css(9)),
+            Some(ratatui::style::Color::LightGreen) => Some(indexed_to_css(10)),
+            Some(ratatui::style::Color::LightYellow) => Some(indexed_to_css(11)),
+            Some(ratatui::style::Color::LightBlue) => Some(indexed_to_css(12)),
+            Some(ratatui::style::Color::LightMagenta) => Some(indexed_to_css(13)),
+            Some(ratatui::style::Color::LightCyan) => Some(indexed_to_css(14)),
             _ => None,
         }
     }
 
     fn escape_html(s: &str) -> String {
-        s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
     }
 
+    let text_color = if rgb_to_luminance_bt601(background.r, background.g, background.b) > 0.5 {
+        "#1e1e1e"
+    } else {
+        "#cccccc"
+    };
+
     let mut html = String::new();
-    html.push_str("<!doctype html>\n<html><head><meta charset=\"utf-8\"><style>pre{font-family:monospace;white-space:pre;}</style></head><body><pre>");
+    html.push_str("<!doctype html>\n<html><head><meta charset=\"utf-8\"><style>pre{font-family:monospace;white-space:pre;}</style></head><body>");
+    // Inline so the container keeps its colors when pasted as rich text
+    html.push_str(&format!(
+        "<div style=\"background:#{:02x}{:02x}{:02x};color:{};padding:1em;display:inline-block\"><pre>",
+        background.r, background.g, background.b, text_color
+    ));
 
     for line in content.lines() {
         let parts = parse_ansi_to_spans(line);
-        for (text, fg, bg) in parts {
+        for (text, style) in parts {
             let mut styles = Vec::new();
-            if let Some(fg_css) = css_color(&fg) {
+            if let Some(fg_css) = css_color(&style.fg) {
                 styles.push(format!("color:{}", fg_css));
             }
-            if let Some(bg_css) = css_color(&bg) {
+            if let Some(bg_css) = css_color(&style.bg) {
                 styles.push(format!("background-color:{}", bg_css));
             }
+            if style.bold {
+                styles.push("font-weight:bold".to_string());
+            }
+            if style.italic {
+                styles.push("font-style:italic".to_string());
+            }
+            if style.underline {
+                styles.push("text-decoration:underline".to_string());
+            }
             if styles.is_empty() {
                 html.push_str(&escape_html(&text));
             } else {
-                html.push_str(&format!("<span style=\"{}\">{}</span>", styles.join(";"), escape_html(&text)));
+                html.push_str(&format!(
+                    "<span style=\"{}\">{}</span>",
+                    styles.join(";"),
+                    escape_html(&text)
+                ));
             }
         }
         html.push('\n');
     }
 
-    html.push_str("</pre></body></html>");
+    html.push_str("</pre></div></body></html>");
     html
 }
 
@@ -508,14 +983,14 @@ fn indexed_to_css(n: u8) -> String {
             15 => "#ffffff".into(),
             _ => "#000000".into(),
         }
-    } else if n >= 16 && n <= 231 {
+    } else if (16..=231).contains(&n) {
         let idx = n - 16;
         let b = idx % 6;
         let g = (idx / 6) % 6;
         let r = (idx / 36) % 6;
-        let r = if r == 0 {0} else {r * 40 + 55};
-        let g = if g == 0 {0} else {g * 40 + 55};
-        let b = if b == 0 {0} else {b * 40 + 55};
+        let r = if r == 0 { 0 } else { r * 40 + 55 };
+        let g = if g == 0 { 0 } else { g * 40 + 55 };
+        let b = if b == 0 { 0 } else { b * 40 + 55 };
         format!("rgb({},{},{})", r, g, b)
     } else {
         // grayscale 232..255
@@ -525,19 +1000,40 @@ fn indexed_to_css(n: u8) -> String {
 }
 
 /// Copy output to clipboard
-fn copy_to_clipboard(state: &mut AppState) -> Result<()> {
+///
+/// `raw` keeps the ANSI escapes, for pasting into a terminal; otherwise they are
+/// stripped (or the art goes on as rich HTML when the output format is HTML).
+fn copy_to_clipboard(state: &mut AppState, raw: bool) -> Result<()> {
     if let Some(ref content) = state.preview_content {
-        let clean_content = strip_ansi_codes(content);
+        let text = clipboard_text(content, raw);
+        let html = if raw {
+            None
+        } else {
+            let dark_background = state.output_for_dark_background();
+            clipboard_html(
+                content,
+                state.preview_output_format,
+                state.config.ui.html_background(dark_background),
+            )
+        };
 
         match arboard::Clipboard::new() {
-            Ok(mut clipboard) => match clipboard.set_text(&clean_content) {
-                Ok(_) => {
-                    state.set_status("Copied to clipboard", false);
-                }
-                Err(e) => {
-                    state.set_status(&format!("Copy failed: {}", e), true);
+            Ok(mut clipboard) => {
+                // HTML goes on as rich text, with the plain art as the fallback flavor
+                let (result, message) = match html {
+                    Some(ref html) => (clipboard.set_html(html, Some(&text)), "Copied as HTML"),
+                    None if raw => (clipboard.set_text(&text), "Copied raw ANSI to clipboard"),
+                    None => (clipboard.set_text(&text), "Copied plain text to clipboard"),
+                };
+                match result {
+                    Ok(_) => {
+                        state.set_status(message, false);
+                    }
+                    Err(e) => {
+                        state.set_status(&format!("Copy failed: {}", e), true);
+                    }
                 }
-            },
+            }
             Err(e) => {
                 state.set_status(&format!("Clipboard unavailable: {}", e), true);
             }
@@ -548,47 +1044,114 @@ fn copy_to_clipboard(state: &mut AppState) -> Result<()> {
     Ok(())
 }
 
+/// Clipboard text for the preview: the render as-is, or with its ANSI escapes stripped
+fn clipboard_text(content: &str, raw: bool) -> String {
+    if raw {
+        content.to_string()
+    } else {
+        strip_ansi_codes(content)
+    }
+}
+
+/// HTML clipboard payload for the preview, if the HTML output format is selected
+fn clipboard_html(
+    content: &str,
+    format: crate::state::OutputFormat,
+    background: Rgb,
+) -> Option<String> {
+    match format {
+        crate::state::OutputFormat::Html => {
+            Some(convert_ansi_to_html_with_background(content, background))
+        }
+        _ => None,
+    }
+}
+
+/// Load an image from the clipboard as the input image
+fn paste_from_clipboard(state: &mut AppState) -> Result<()> {
+    let image = arboard::Clipboard::new()
+        .map_err(|e| format!("Clipboard unavailable: {}", e))
+        .and_then(|mut clipboard| {
+            clipboard.get_image().map_err(|e| match e {
+                arboard::Error::ContentNotAvailable => "Clipboard has no image".to_string(),
+                e => format!("Paste failed: {}", e),
+            })
+        })
+        .and_then(|data| {
+            image_from_rgba(data.width, data.height, data.bytes.into_owned())
+                .map_err(|e| format!("Paste failed: {}", e))
+        });
+
+    match image {
+        Ok(img) => state.set_input_image(PathBuf::from("<clipboard>"), img),
+        Err(msg) => state.set_status(&msg, true),
+    }
+    Ok(())
+}
+
+/// Text attributes in effect for a run of ANSI-styled text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct AnsiStyle {
+    pub fg: Option<ratatui::style::Color>,
+    pub bg: Option<ratatui::style::Color>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+}
+
+impl AnsiStyle {
+    /// Ratatui modifiers for the bold/italic/underline flags
+    pub fn modifiers(&self) -> ratatui::style::Modifier {
+        use ratatui::style::Modifier;
+
+        let mut modifiers = Modifier::empty();
+        if self.bold {
+            modifiers |= Modifier::BOLD;
+        }
+        if self.italic {
+            modifiers |= Modifier::ITALIC;
+        }
+        if self.underline {
+            modifiers |= Modifier::UNDERLINED;
+        }
+        modifiers
+    }
+}
+
 /// Parse ANSI color codes into Ratatui Span components
-/// Returns a Vec of (text, Option<fg_color>, Option<bg_color>)
-pub(crate) fn parse_ansi_to_spans(text: &str) -> Vec<(String, Option<ratatui::style::Color>, Option<ratatui::style::Color>)> {
+/// Returns a Vec of (text, style attributes)
+pub(crate) fn parse_ansi_to_spans(text: &str) -> Vec<(String, AnsiStyle)> {
     let mut result = Vec::new();
     let mut current_text = String::new();
-    let mut current_fg: Option<ratatui::style::Color> = None;
-    let mut current_bg: Option<ratatui::style::Color> = None;
+    let mut current_style = AnsiStyle::default();
     let mut chars = text.chars().peekable();
 
     while let Some(c) = chars.next() {
         if c == '\x1b' {
             // Push any accumulated text
             if !current_text.is_empty() {
-                result.push((std::mem::take(&mut current_text), current_fg, current_bg));
+                result.push((std::mem::take(&mut current_text), current_style));
             }
 
             // Parse escape sequence
             if chars.peek() == Some(&'[') {
                 chars.next(); // consume '['
                 let mut params = String::new();
-                
+
                 // Collect parameter bytes
                 while let Some(&next) = chars.peek() {
-                    if next.is_ascii_alphabetic() {
+                    if is_csi_final_byte(next) {
                         chars.next();
                         if next == 'm' {
-                            // SGR sequence - parse color codes
-                            parse_sgr_params(&params, &mut current_fg, &mut current_bg);
+                            // SGR sequence - parse color and attribute codes
+                            parse_sgr_params(&params, &mut current_style);
                         }
                         break;
                     }
                     params.push(chars.next().unwrap());
                 }
             } else {
-                // Skip other escape sequences
-                while let Some(&next) = chars.peek() {
-                    chars.next();
-                    if next == '\x07' || next.is_ascii_alphabetic() {
-                        break;
-                    }
-                }
+                skip_escape_sequence(&mut chars);
             }
         } else {
             current_text.push(c);
@@ -597,86 +1160,105 @@ pub(crate) fn parse_ansi_to_spans(text: &str) -> Vec<(String, Option<ratatui::st
 
     // Push remaining text
     if !current_text.is_empty() {
-        result.push((current_text, current_fg, current_bg));
+        result.push((current_text, current_style));
     }
 
     result
 }
 
 /// Parse SGR (Select Graphic Rendition) parameters
-fn parse_sgr_params(params: &str, fg: &mut Option<ratatui::style::Color>, bg: &mut Option<ratatui::style::Color>) {
+fn parse_sgr_params(params: &str, style: &mut AnsiStyle) {
     let parts: Vec<&str> = params.split(';').collect();
     let mut i = 0;
 
     while i < parts.len() {
         match parts[i] {
-            "0" => {
-                // Reset
-                *fg = None;
-                *bg = None;
-            }
-            "38" => {
-                // Foreground color
-                if i + 1 < parts.len() {
-                    if parts[i + 1] == "2" && i + 4 < parts.len() {
-                        // True color: 38;2;R;G;B
-                        if let (Ok(r), Ok(g), Ok(b)) = (
-                            parts[i + 2].parse::<u8>(),
-                            parts[i + 3].parse::<u8>(),
-                            parts[i + 4].parse::<u8>(),
-                        ) {
-                            *fg = Some(ratatui::style::Color::Rgb(r, g, b));
-                        }
-                        i += 4;
-                    } else if parts[i + 1] == "5" && i + 2 < parts.len() {
-                        // 256 color: 38;5;N
-                        if let Ok(n) = parts[i + 2].parse::<u8>() {
-                            *fg = Some(ratatui::style::Color::Indexed(n));
-                        }
-                        i += 2;
+            "0" | "" => {
+                // Reset (an empty parameter list means 0)
+                *style = AnsiStyle::default();
+            }
+            "1" => style.bold = true,
+            "3" => style.italic = true,
+            "4" => style.underline = true,
+            "22" => style.bold = false,
+            "23" => style.italic = false,
+            "24" => style.underline = false,
+            // Foreground color
+            "38" if i + 1 < parts.len() => {
+                if parts[i + 1] == "2" && i + 4 < parts.len() {
+                    // True color: 38;2;R;G;B
+                    if let (Ok(r), Ok(g), Ok(b)) = (
+                        parts[i + 2].parse::<u8>(),
+                        parts[i + 3].parse::<u8>(),
+                        parts[i + 4].parse::<u8>(),
+                    ) {
+                        style.fg = Some(ratatui::style::Color::Rgb(r, g, b));
+                    }
+                    i += 4;
+                } else if parts[i + 1] == "5" && i + 2 < parts.len() {
+                    // 256 color: 38;5;N
+                    if let Ok(n) = parts[i + 2].parse::<u8>() {
+                        style.fg = Some(ratatui::style::Color::Indexed(n));
                     }
+                    i += 2;
                 }
             }
-            "48" => {
-                // Background color
-                if i + 1 < parts.len() {
-                    if parts[i + 1] == "2" && i + 4 < parts.len() {
-                        // True color: 48;2;R;G;B
-                        if let (Ok(r), Ok(g), Ok(b)) = (
-                            parts[i + 2].parse::<u8>(),
-                            parts[i + 3].parse::<u8>(),
-                            parts[i + 4].parse::<u8>(),
-                        ) {
-                            *bg = Some(ratatui::style::Color::Rgb(r, g, b));
-                        }
-                        i += 4;
-                    } else if parts[i + 1] == "5" && i + 2 < parts.len() {
-                        // 256 color: 48;5;N
-                        if let Ok(n) = parts[i + 2].parse::<u8>() {
-                            *bg = Some(ratatui::style::Color::Indexed(n));
-                        }
-                        i += 2;
+            // Background color
+            "48" if i + 1 < parts.len() => {
+                if parts[i + 1] == "2" && i + 4 < parts.len() {
+                    // True color: 48;2;R;G;B
+                    if let (Ok(r), Ok(g), Ok(b)) = (
+                        parts[i + 2].parse::<u8>(),
+                        parts[i + 3].parse::<u8>(),
+                        parts[i + 4].parse::<u8>(),
+                    ) {
+                        style.bg = Some(ratatui::style::Color::Rgb(r, g, b));
+                    }
+                    i += 4;
+                } else if parts[i + 1] == "5" && i + 2 < parts.len() {
+                    // 256 color: 48;5;N
+                    if let Ok(n) = parts[i + 2].parse::<u8>() {
+                        style.bg = Some(ratatui::style::Color::Indexed(n));
                     }
+                    i += 2;
                 }
             }
             // Basic foreground colors (30-37)
-            "30" => *fg = Some(ratatui::style::Color::Black),
-            "31" => *fg = Some(ratatui::style::Color::Red),
-            "32" => *fg = Some(ratatui::style::Color::Green),
-            "33" => *fg = Some(ratatui::style::Color::Yellow),
-            "34" => *fg = Some(ratatui::style::Color::Blue),
-            "35" => *fg = Some(ratatui::style::Color::Magenta),
-            "36" => *fg = Some(ratatui::style::Color::Cyan),
-            "37" => *fg = Some(ratatui::style::Color::White),
+            "30" => style.fg = Some(ratatui::style::Color::Black),
+            "31" => style.fg = Some(ratatui::style::Color::Red),
+            "32" => style.fg = Some(ratatui::style::Color::Green),
+            "33" => style.fg = Some(ratatui::style::Color::Yellow),
+            "34" => style.fg = Some(ratatui::style::Color::Blue),
+            "35" => style.fg = Some(ratatui::style::Color::Magenta),
+            "36" => style.fg = Some(ratatui::style::Color::Cyan),
+            "37" => style.fg = Some(ratatui::style::Color::White),
             // Basic background colors (40-47)
-            "40" => *bg = Some(ratatui::style::Color::Black),
-            "41" => *bg = Some(ratatui::style::Color::Red),
-            "42" => *bg = Some(ratatui::style::Color::Green),
-            "43" => *bg = Some(ratatui::style::Color::Yellow),
-            "44" => *bg = Some(ratatui::style::Color::Blue),
-            "45" => *bg = Some(ratatui::style::Color::Magenta),
-            "46" => *bg = Some(ratatui::style::Color::Cyan),
-            "47" => *bg = Some(ratatui::style::Color::White),
+            "40" => style.bg = Some(ratatui::style::Color::Black),
+            "41" => style.bg = Some(ratatui::style::Color::Red),
+            "42" => style.bg = Some(ratatui::style::Color::Green),
+            "43" => style.bg = Some(ratatui::style::Color::Yellow),
+            "44" => style.bg = Some(ratatui::style::Color::Blue),
+            "45" => style.bg = Some(ratatui::style::Color::Magenta),
+            "46" => style.bg = Some(ratatui::style::Color::Cyan),
+            "47" => style.bg = Some(ratatui::style::Color::White),
+            // Bright foreground colors (90-97)
+            "90" => style.fg = Some(ratatui::style::Color::DarkGray),
+            "91" => style.fg = Some(ratatui::style::Color::LightRed),
+            "92" => style.fg = Some(ratatui::style::Color::LightGreen),
+            "93" => style.fg = Some(ratatui::style::Color::LightYellow),
+            "94" => style.fg = Some(ratatui::style::Color::LightBlue),
+            "95" => style.fg = Some(ratatui::style::Color::LightMagenta),
+            "96" => style.fg = Some(ratatui::style::Color::LightCyan),
+            "97" => style.fg = Some(ratatui::style::Color::White),
+            // Bright background colors (100-107)
+            "100" => style.bg = Some(ratatui::style::Color::DarkGray),
+            "101" => style.bg = Some(ratatui::style::Color::LightRed),
+            "102" => style.bg = Some(ratatui::style::Color::LightGreen),
+            "103" => style.bg = Some(ratatui::style::Color::LightYellow),
+            "104" => style.bg = Some(ratatui::style::Color::LightBlue),
+            "105" => style.bg = Some(ratatui::style::Color::LightMagenta),
+            "106" => style.bg = Some(ratatui::style::Color::LightCyan),
+            "107" => style.bg = Some(ratatui::style::Color::White),
             _ => {}
         }
         i += 1;
@@ -685,6 +1267,26 @@ fn parse_sgr_params(params: &str, fg: &mut Option<ratatui::style::Color>, bg: &m
 
 /// Export content to PNG using bundled font
 pub fn export_to_png(content: &str, path: &str) -> Result<()> {
+    let img = rasterize_content(content)?;
+    img.save(path)
+        .map_err(|e| anyhow::anyhow!("Failed to save PNG: {}", e))?;
+    Ok(())
+}
+
+/// Encode content as PNG bytes (for writing to stdout or other streams)
+pub fn export_to_png_bytes(content: &str) -> Result<Vec<u8>> {
+    let img = rasterize_content(content)?;
+    let mut bytes = Vec::new();
+    img.write_to(
+        &mut std::io::Cursor::new(&mut bytes),
+        image::ImageFormat::Png,
+    )
+    .map_err(|e| anyhow::anyhow!("Failed to encode PNG: {}", e))?;
+    Ok(bytes)
+}
+
+/// Rasterize ANSI content onto an image using the bundled font
+fn rasterize_content(content: &str) -> Result<image::RgbImage> {
     use ab_glyph::{FontRef, PxScale};
     use image::{Rgb, RgbImage};
 
@@ -701,7 +1303,11 @@ pub fn export_to_png(content: &str, path: &str) -> Result<()> {
 
     // Parse content dimensions
     let lines: Vec<&str> = content.lines().collect();
-    let max_chars = lines.iter().map(|l| strip_ansi_codes(l).chars().count()).max().unwrap_or(0);
+    let max_chars = lines
+        .iter()
+        .map(|l| strip_ansi_codes(l).chars().count())
+        .max()
+        .unwrap_or(0);
     let num_lines = lines.len();
 
     if max_chars == 0 || num_lines == 0 {
@@ -719,7 +1325,7 @@ pub fn export_to_png(content: &str, path: &str) -> Result<()> {
         let spans = parse_ansi_to_spans(line);
         let mut char_x = 0u32;
 
-        for (text, fg, bg) in spans {
+        for (text, AnsiStyle { fg, bg, .. }) in spans {
             for ch in text.chars() {
                 let px = char_x * cell_width;
                 let py = line_idx as u32 * cell_height;
@@ -753,14 +1359,25 @@ pub fn export_to_png(content: &str, path: &str) -> Result<()> {
         }
     }
 
-    img.save(path).map_err(|e| anyhow::anyhow!("Failed to save PNG: {}", e))?;
-    Ok(())
+    Ok(img)
 }
 
 /// Export content to SVG
-pub fn export_to_svg(content: &str, path: &str) -> Result<()> {
+pub fn export_to_svg(content: &str, path: &str, dark_background: bool) -> Result<()> {
+    let svg = render_svg(content, dark_background);
+    std::fs::write(path, svg).map_err(|e| anyhow::anyhow!("Failed to save SVG: {}", e))?;
+    Ok(())
+}
+
+/// Render content as an SVG document, black behind art drawn for a dark background
+/// and white behind art drawn for a light one
+pub fn render_svg(content: &str, dark_background: bool) -> String {
     let lines: Vec<&str> = content.lines().collect();
-    let max_chars = lines.iter().map(|l| strip_ansi_codes(l).chars().count()).max().unwrap_or(0);
+    let max_chars = lines
+        .iter()
+        .map(|l| strip_ansi_codes(l).chars().count())
+        .max()
+        .unwrap_or(0);
     let num_lines = lines.len();
 
     let cell_width = 8.4; // Approximate monospace character width
@@ -769,13 +1386,19 @@ pub fn export_to_svg(content: &str, path: &str) -> Result<()> {
 
     let svg_width = max_chars as f64 * cell_width;
     let svg_height = num_lines as f64 * cell_height;
+    // Uncolored text takes the opposite of the background
+    let (background, default_fg) = if dark_background {
+        ("#000", (255, 255, 255))
+    } else {
+        ("#fff", (0, 0, 0))
+    };
 
     let mut svg = format!(
         r#"<?xml version="1.0" encoding="UTF-8"?>
-<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" style="background:#000">
+<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" style="background:{}">
 <style>text {{ font-family: 'DejaVu Sans Mono', 'Courier New', monospace; font-size: {}px; }}</style>
 "#,
-        svg_width, svg_height, font_size
+        svg_width, svg_height, background, font_size
     );
 
     for (line_idx, line) in lines.iter().enumerate() {
@@ -783,7 +1406,7 @@ pub fn export_to_svg(content: &str, path: &str) -> Result<()> {
         let mut char_x = 0usize;
         let y = (line_idx as f64 + 1.0) * cell_height - 4.0; // Baseline offset
 
-        for (text, fg, bg) in spans {
+        for (text, AnsiStyle { fg, bg, .. }) in spans {
             for ch in text.chars() {
                 let x = char_x as f64 * cell_width;
 
@@ -796,12 +1419,14 @@ pub fn export_to_svg(content: &str, path: &str) -> Result<()> {
                         line_idx as f64 * cell_height,
                         cell_width,
                         cell_height,
-                        br, bg_val, bb
+                        br,
+                        bg_val,
+                        bb
                     ));
                 }
 
                 // Draw character
-                let (fr, fg_val, fb) = fg.as_ref().map(color_to_rgb).unwrap_or((255, 255, 255));
+                let (fr, fg_val, fb) = fg.as_ref().map(color_to_rgb).unwrap_or(default_fg);
                 let escaped = match ch {
                     '<' => "&lt;".to_string(),
                     '>' => "&gt;".to_string(),
@@ -820,8 +1445,7 @@ pub fn export_to_svg(content: &str, path: &str) -> Result<()> {
     }
 
     svg.push_str("</svg>");
-    std::fs::write(path, svg).map_err(|e| anyhow::anyhow!("Failed to save SVG: {}", e))?;
-    Ok(())
+    svg
 }
 
 /// Convert ratatui Color to RGB tuple
@@ -836,6 +1460,13 @@ fn color_to_rgb(color: &ratatui::style::Color) -> (u8, u8, u8) {
         ratatui::style::Color::Magenta => (255, 0, 255),
         ratatui::style::Color::Cyan => (0, 255, 255),
         ratatui::style::Color::White => (255, 255, 255),
+        ratatui::style::Color::DarkGray => indexed_color_to_rgb(8),
+        ratatui::style::Color::LightRed => indexed_color_to_rgb(9),
+        ratatui::style::Color::LightGreen => indexed_color_to_rgb(10),
+        ratatui::style::Color::LightYellow => indexed_color_to_rgb(11),
+        ratatui::style::Color::LightBlue => indexed_color_to_rgb(12),
+        ratatui::style::Color::LightMagenta => indexed_color_to_rgb(13),
+        ratatui::style::Color::LightCyan => indexed_color_to_rgb(14),
         ratatui::style::Color::Indexed(n) => indexed_color_to_rgb(*n),
         _ => (255, 255, 255),
     }
@@ -864,7 +1495,7 @@ fn indexed_color_to_rgb(n: u8) -> (u8, u8, u8) {
             15 => (255, 255, 255),
             _ => (0, 0, 0),
         }
-    } else if n >= 16 && n <= 231 {
+    } else if (16..=231).contains(&n) {
         let idx = n - 16;
         let b = (idx % 6) as u8;
         let g = ((idx / 6) % 6) as u8;
@@ -879,69 +1510,209 @@ fn indexed_color_to_rgb(n: u8) -> (u8, u8, u8) {
     }
 }
 
-/// Strip ANSI escape codes from text
-pub(crate) fn strip_ansi_codes(text: &str) -> String {
-    let mut result = String::with_capacity(text.len());
-    let mut chars = text.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        if c == '\x1b' {
-            // Skip CSI-like escape sequences (ESC [ ... letter)
-            if chars.peek() == Some(&'[') {
-                chars.next(); // consume '['
-                while let Some(&next) = chars.peek() {
-                    chars.next();
-                    if next.is_ascii_alphabetic() {
-                        break;
-                    }
-                }
-            } else {
-                // For other ESC sequences (OSC etc.), attempt to skip until BEL or 'c' or alphabetic terminator
-                while let Some(&next) = chars.peek() {
-                    chars.next();
-                    if next == '\x07' || next.is_ascii_alphabetic() {
-                        break;
-                    }
-                }
-            }
-        } else {
-            result.push(c);
-        }
-    }
-
-    result
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
-    fn test_strip_ansi_codes() {
+    fn test_strip_ansi_removes_osc8_hyperlinks() {
+        let linked = crate::render_engines::text_stylizer::hyperlink(
+            "\x1b[38;2;255;0;0mClick\x1b[0m me",
+            "https://example.com/path",
+        );
+        assert_eq!(strip_ansi_codes(&linked), "Click me");
+
+        // BEL-terminated form
+        let bel = "\x1b]8;;https://example.com\x07here\x1b]8;;\x07";
+        assert_eq!(strip_ansi_codes(bel), "here");
+
+        let spans = parse_ansi_to_spans(&linked);
+        let text: String = spans.iter().map(|(text, _)| text.as_str()).collect();
+        assert_eq!(text, "Click me");
+    }
+
+    #[test]
+    fn test_convert_ansi_to_html_basic() {
         let input = "\x1b[38;2;255;0;0mRed\x1b[0m";
-        let output = strip_ansi_codes(input);
-        assert_eq!(output, "Red");
+        let html = convert_ansi_to_html(input, true);
+        assert!(html.contains("rgb(255,0,0)"));
+        assert!(html.contains("Red"));
     }
 
     #[test]
-    fn test_strip_ansi_preserves_text() {
-        let input = "Hello, World!";
-        let output = strip_ansi_codes(input);
-        assert_eq!(output, "Hello, World!");
+    fn test_convert_to_markdown_fences_stripped_art() {
+        let md = convert_to_markdown("\x1b[31m@#\x1b[0m\n.:\n");
+        assert!(md.starts_with("```text\n"));
+        assert!(md.ends_with("\n```\n"));
+        assert!(md.contains("@#\n.:"));
+        assert!(!md.contains('\x1b'));
+
+        let md = convert_to_markdown("a```b\n````\n");
+        assert_eq!(md, "`````text\na```b\n````\n`````\n");
     }
 
     #[test]
-    fn test_strip_ansi_complex() {
-        let input = "\x1b[38;5;196mR\x1b[38;5;208mA\x1b[38;5;226mI\x1b[38;5;46mN\x1b[38;5;21mB\x1b[38;5;129mO\x1b[38;5;196mW\x1b[0m";
-        let output = strip_ansi_codes(input);
-        assert_eq!(output, "RAINBOW");
+    fn test_export_to_png_bytes_is_png() {
+        let bytes = export_to_png_bytes("\x1b[31mAB\x1b[0m\nCD").unwrap();
+        assert!(bytes.starts_with(b"\x89PNG\r\n\x1a\n"));
     }
 
     #[test]
-    fn test_convert_ansi_to_html_basic() {
-        let input = "\x1b[38;2;255;0;0mRed\x1b[0m";
-        let html = convert_ansi_to_html(input);
-        assert!(html.contains("rgb(255,0,0)"));
-        assert!(html.contains("Red"));
+    fn test_render_svg_escapes_text() {
+        let svg = render_svg("a<b", true);
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains("&lt;"));
+        assert!(svg.ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_clipboard_html_only_for_html_format() {
+        use crate::state::OutputFormat;
+
+        let content = "\x1b[38;2;255;0;0m@\x1b[0m";
+        let background = DEFAULT_HTML_BACKGROUND;
+        let html = clipboard_html(content, OutputFormat::Html, background).unwrap();
+        assert!(html.contains("<pre>"));
+        assert!(html.contains("color:rgb(255,0,0)"));
+
+        assert!(clipboard_html(content, OutputFormat::Ansi, background).is_none());
+        assert!(clipboard_html(content, OutputFormat::Txt, background).is_none());
+        assert!(clipboard_html(content, OutputFormat::Markdown, background).is_none());
+    }
+
+    #[test]
+    fn test_clipboard_text_raw_keeps_escapes() {
+        let content = "\x1b[38;2;255;0;0m@\x1b[0m#\n\x1b[1m%\x1b[0m";
+
+        let raw = clipboard_text(content, true);
+        assert_eq!(raw, content);
+        assert!(raw.contains("\x1b["));
+
+        let plain = clipboard_text(content, false);
+        assert_eq!(plain, "@#\n%");
+        assert!(!plain.contains('\x1b'));
+    }
+
+    #[test]
+    fn test_parse_sgr_text_attributes() {
+        let spans = parse_ansi_to_spans("\x1b[1;4mX\x1b[0mY");
+        assert_eq!(spans.len(), 2);
+
+        let (text, style) = &spans[0];
+        assert_eq!(text, "X");
+        assert!(style.bold && style.underline && !style.italic);
+        let modifiers = style.modifiers();
+        assert!(modifiers.contains(ratatui::style::Modifier::BOLD));
+        assert!(modifiers.contains(ratatui::style::Modifier::UNDERLINED));
+
+        assert_eq!(spans[1], ("Y".to_string(), AnsiStyle::default()));
+    }
+
+    #[test]
+    fn test_sgr_attributes_toggle_off_individually() {
+        let spans = parse_ansi_to_spans("\x1b[1;3;31mA\x1b[22mB\x1b[23mC");
+        assert!(spans[0].1.bold && spans[0].1.italic);
+        assert!(!spans[1].1.bold && spans[1].1.italic);
+        assert!(!spans[2].1.italic);
+        // Attribute codes leave colors alone
+        assert_eq!(spans[2].1.fg, Some(ratatui::style::Color::Red));
+    }
+
+    #[test]
+    fn test_convert_ansi_to_html_background() {
+        let html = convert_ansi_to_html("@", true);
+        assert!(html.contains("<div style=\"background:#0c0c0c;color:#cccccc;padding:1em"));
+        assert!(html.contains("<pre>@\n</pre></div>"));
+
+        let light = convert_ansi_to_html_with_background("@", Rgb::new(255, 255, 255));
+        assert!(light.contains("background:#ffffff;color:#1e1e1e;"));
+    }
+
+    #[test]
+    fn test_convert_ansi_to_html_attributes() {
+        let html = convert_ansi_to_html("\x1b[1;3;4mX\x1b[0m", true);
+        assert!(html.contains("font-weight:bold"));
+        assert!(html.contains("font-style:italic"));
+        assert!(html.contains("text-decoration:underline"));
+    }
+
+    #[test]
+    fn test_bright_colors_parsed() {
+        let spans = parse_ansi_to_spans("\x1b[91mX\x1b[0m");
+        assert_eq!(spans[0].1.fg, Some(ratatui::style::Color::LightRed));
+
+        let spans = parse_ansi_to_spans("\x1b[90;104mX");
+        assert_eq!(spans[0].1.fg, Some(ratatui::style::Color::DarkGray));
+        assert_eq!(spans[0].1.bg, Some(ratatui::style::Color::LightBlue));
+
+        let html = convert_ansi_to_html("\x1b[91mX\x1b[0m", true);
+        assert!(html.contains("<span style=\"color:#ff0000\">X</span>"));
+    }
+
+    #[test]
+    fn test_inverted_export_uses_light_background() {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(
+            crate::config::Config::default(),
+            crate::terminal_capabilities::TerminalCapabilities::default(),
+            tx,
+        );
+        let export = |state: &AppState| {
+            let background = state
+                .config
+                .ui
+                .html_background(state.output_for_dark_background());
+            convert_ansi_to_html_with_background("@", background)
+        };
+
+        assert!(export(&state).contains("background:#0c0c0c;color:#cccccc;"));
+        state.ascii_state.invert = true;
+        assert!(export(&state).contains("background:#f5f5f5;color:#1e1e1e;"));
+
+        assert!(convert_ansi_to_html("@", false).contains("background:#f5f5f5;"));
+        assert!(render_svg("@", true).contains("style=\"background:#000\""));
+        let light = render_svg("@", false);
+        assert!(light.contains("style=\"background:#fff\""));
+        assert!(light.contains("fill=\"rgb(0,0,0)\""));
+    }
+
+    #[test]
+    fn test_focus_events_toggle_focused() {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(
+            crate::config::Config::default(),
+            crate::terminal_capabilities::TerminalCapabilities::default(),
+            tx,
+        );
+        assert!(state.focused);
+
+        handle_event(Event::FocusLost, &mut state).unwrap();
+        assert!(!state.focused);
+        handle_event(Event::FocusGained, &mut state).unwrap();
+        assert!(state.focused);
+    }
+
+    #[test]
+    fn test_remapped_quit_key() {
+        use crossterm::event::KeyModifiers;
+
+        let mut config = crate::config::Config::default();
+        config
+            .keybindings
+            .insert("quit".to_string(), vec!["ctrl+x".to_string()]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(
+            config,
+            crate::terminal_capabilities::TerminalCapabilities::default(),
+            tx,
+        );
+
+        let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+        handle_event(Event::Key(q), &mut state).unwrap();
+        assert!(!state.should_quit);
+
+        let ctrl_x = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::CONTROL);
+        handle_event(Event::Key(ctrl_x), &mut state).unwrap();
+        assert!(state.should_quit);
     }
 }
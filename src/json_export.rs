@@ -0,0 +1,93 @@
+//! JSON export
+//!
+//! Wraps rendered output with the settings that produced it, for scripts that want
+//! to parse both the art and how it was made.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+use crate::state::RenderMode;
+use crate::terminal_capabilities::ColorSupport;
+use crate::unicode_handler::strip_ansi_codes;
+
+/// Rendered output plus render metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonExport {
+    /// Render mode name, e.g. "ASCII Art"
+    pub mode: String,
+    /// Target width in columns; `None` for the text stylizer
+    pub width: Option<usize>,
+    /// Charset, Unicode mode or text style name
+    pub style: String,
+    /// Color level the output was rendered with
+    pub color_support: String,
+    /// Widest line of the output, in terminal columns
+    pub columns: usize,
+    /// Number of output lines
+    pub rows: usize,
+    pub render_time_ms: u64,
+    /// Output exactly as rendered, ANSI codes included
+    pub content: String,
+}
+
+impl JsonExport {
+    pub fn new(
+        mode: RenderMode,
+        width: Option<usize>,
+        style: &str,
+        color_support: ColorSupport,
+        render_time_ms: u64,
+        content: &str,
+    ) -> Self {
+        let clean = strip_ansi_codes(content);
+        Self {
+            mode: mode.name().to_string(),
+            width,
+            style: style.to_string(),
+            color_support: color_support.name().to_string(),
+            columns: clean.lines().map(|line| line.width()).max().unwrap_or(0),
+            rows: clean.lines().count(),
+            render_time_ms,
+            content: content.to_string(),
+        }
+    }
+
+    /// Serialize as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to serialize JSON export")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_export_round_trips() {
+        let content = "\x1b[38;2;255;0;0m@@\x1b[0m#\n.:\n";
+        let export = JsonExport::new(
+            RenderMode::ColorAscii,
+            Some(3),
+            "Standard",
+            ColorSupport::TrueColor,
+            12,
+            content,
+        );
+        let json = export.to_json().unwrap();
+
+        let parsed: JsonExport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, export);
+        assert_eq!(parsed.mode, "Color ASCII");
+        assert_eq!(parsed.width, Some(3));
+        assert_eq!(parsed.style, "Standard");
+        assert_eq!(parsed.color_support, "True Color");
+        assert_eq!((parsed.columns, parsed.rows), (3, 2));
+        assert_eq!(parsed.render_time_ms, 12);
+        assert_eq!(parsed.content, content);
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["width"], 3);
+        assert!(value["content"].as_str().unwrap().contains('\x1b'));
+    }
+}
@@ -0,0 +1,454 @@
+//! Remappable keybindings
+//!
+//! Resolves the `[keybindings]` config section into a key → action lookup.
+//! Actions left out of the section keep their default keys.
+
+use anyhow::{bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::state::FocusedWidget;
+
+/// A user-facing command that can be bound to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Help,
+    CycleFormat,
+    NextWidget,
+    PrevWidget,
+    Load,
+    ReloadLast,
+    Paste,
+    Save,
+    Copy,
+    CopyRaw,
+    Render,
+    WordWrap,
+    LineNumbers,
+    MemoryUsage,
+    Compare,
+    CompareLock,
+    Presets,
+    AutoFit,
+    FontAspectUp,
+    FontAspectDown,
+    CycleColor,
+    PreserveScroll,
+    Undo,
+    Redo,
+    History,
+    Crop,
+    RotateLeft,
+    RotateRight,
+    FlipHorizontal,
+    FlipVertical,
+    ResetTransforms,
+}
+
+impl Action {
+    pub fn all() -> &'static [Action] {
+        &[
+            Action::Quit,
+            Action::Help,
+            Action::CycleFormat,
+            Action::NextWidget,
+            Action::PrevWidget,
+            Action::Load,
+            Action::ReloadLast,
+            Action::Paste,
+            Action::Save,
+            Action::Copy,
+            Action::CopyRaw,
+            Action::Render,
+            Action::WordWrap,
+            Action::LineNumbers,
+            Action::MemoryUsage,
+            Action::Compare,
+            Action::CompareLock,
+            Action::Presets,
+            Action::AutoFit,
+            Action::FontAspectUp,
+            Action::FontAspectDown,
+            Action::CycleColor,
+            Action::PreserveScroll,
+            Action::Undo,
+            Action::Redo,
+            Action::History,
+            Action::Crop,
+            Action::RotateLeft,
+            Action::RotateRight,
+            Action::FlipHorizontal,
+            Action::FlipVertical,
+            Action::ResetTransforms,
+        ]
+    }
+
+    /// Name used in the `[keybindings]` config section
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::CycleFormat => "cycle_format",
+            Action::NextWidget => "next_widget",
+            Action::PrevWidget => "prev_widget",
+            Action::Load => "load",
+            Action::ReloadLast => "reload_last",
+            Action::Paste => "paste",
+            Action::Save => "save",
+            Action::Copy => "copy",
+            Action::CopyRaw => "copy_raw",
+            Action::Render => "render",
+            Action::WordWrap => "word_wrap",
+            Action::LineNumbers => "line_numbers",
+            Action::MemoryUsage => "memory_usage",
+            Action::Compare => "compare",
+            Action::CompareLock => "compare_lock",
+            Action::Presets => "presets",
+            Action::AutoFit => "auto_fit",
+            Action::FontAspectUp => "font_aspect_up",
+            Action::FontAspectDown => "font_aspect_down",
+            Action::CycleColor => "cycle_color",
+            Action::PreserveScroll => "preserve_scroll",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::History => "history",
+            Action::Crop => "crop",
+            Action::RotateLeft => "rotate_left",
+            Action::RotateRight => "rotate_right",
+            Action::FlipHorizontal => "flip_horizontal",
+            Action::FlipVertical => "flip_vertical",
+            Action::ResetTransforms => "reset_transforms",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::all().iter().copied().find(|a| a.name() == name)
+    }
+
+    /// Keys bound when the config doesn't mention this action
+    fn default_keys(&self) -> &'static [&'static str] {
+        match self {
+            Action::Quit => &["q", "Q"],
+            Action::Help => &["?"],
+            Action::CycleFormat => &["o", "O"],
+            Action::NextWidget => &["tab"],
+            Action::PrevWidget => &["shift+tab"],
+            Action::Load => &["L"],
+            Action::ReloadLast => &["r"],
+            Action::Paste => &["V"],
+            Action::Save => &["S"],
+            Action::Copy => &["c"],
+            Action::CopyRaw => &["C"],
+            Action::Render => &["space"],
+            Action::WordWrap => &["w", "W"],
+            Action::LineNumbers => &["n", "N"],
+            Action::MemoryUsage => &["m", "M"],
+            Action::Compare => &["b", "B"],
+            Action::CompareLock => &["x", "X"],
+            Action::Presets => &["p", "P"],
+            Action::AutoFit => &["f", "F"],
+            Action::FontAspectUp => &["]"],
+            Action::FontAspectDown => &["["],
+            Action::CycleColor => &["g"],
+            Action::PreserveScroll => &["t", "T"],
+            Action::Undo => &["u"],
+            Action::Redo => &["ctrl+r"],
+            Action::History => &["H"],
+            Action::Crop => &["R"],
+            Action::RotateLeft => &["<"],
+            Action::RotateRight => &[">"],
+            Action::FlipHorizontal => &["|"],
+            Action::FlipVertical => &["\\"],
+            Action::ResetTransforms => &["ctrl+t"],
+        }
+    }
+
+    /// Whether the action does anything while `focus` has the keyboard
+    pub fn available_in(&self, focus: FocusedWidget) -> bool {
+        match self {
+            Action::Copy
+            | Action::CopyRaw
+            | Action::WordWrap
+            | Action::LineNumbers
+            | Action::MemoryUsage
+            | Action::Compare
+            | Action::CompareLock => focus == FocusedWidget::Preview,
+            Action::Undo | Action::Redo => focus == FocusedWidget::ControlPanel,
+            Action::CycleColor => focus != FocusedWidget::ModeSelector,
+            _ => true,
+        }
+    }
+}
+
+/// A key plus modifiers, normalized so it can be compared against key events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeySpec {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeySpec {
+    /// Parse a spec like `q`, `Q`, `space`, `shift+tab`, `ctrl+s` or `f5`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            bail!("Empty key spec");
+        }
+
+        // A lone "+" is a key, not a separator
+        let mut parts: Vec<&str> = if spec == "+" {
+            vec!["+"]
+        } else {
+            spec.split('+').collect()
+        };
+        let key = parts.pop().unwrap_or_default();
+
+        let mut modifiers = KeyModifiers::NONE;
+        for part in parts {
+            modifiers |= match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => bail!("Unknown modifier '{}' in '{}'", other, spec),
+            };
+        }
+
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "tab" => KeyCode::Tab,
+                "backtab" => KeyCode::BackTab,
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "backspace" => KeyCode::Backspace,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                name => match name.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => bail!("Unknown key '{}' in '{}'", key, spec),
+                },
+            },
+        };
+
+        Ok(Self::normalize(code, modifiers))
+    }
+
+    pub fn from_event(key: &KeyEvent) -> Self {
+        Self::normalize(key.code, key.modifiers)
+    }
+
+    /// Shift is implied by the character itself and Shift+Tab arrives as either form
+    fn normalize(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        match code {
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => Self {
+                code: KeyCode::BackTab,
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            KeyCode::BackTab | KeyCode::Char(_) => Self {
+                code,
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            _ => Self { code, modifiers },
+        }
+    }
+}
+
+/// Written the way the help overlay shows it, e.g. `Ctrl+R`, `Shift+Tab`, `Space`
+impl fmt::Display for KeySpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::ALT, "Alt+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::BackTab => f.write_str("Shift+Tab"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Key → action lookup consulted before each widget's own key handling
+#[derive(Debug, Clone)]
+pub struct Keybindings {
+    bindings: HashMap<KeySpec, Action>,
+    /// Each action's keys in the order they were configured, for display
+    keys: HashMap<Action, Vec<KeySpec>>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self::from_config(&BTreeMap::new()).expect("default keybindings are valid")
+    }
+}
+
+impl Keybindings {
+    /// Build the lookup from the `[keybindings]` section
+    ///
+    /// Unknown action names, unparseable keys and keys bound to two actions are errors.
+    pub fn from_config(config: &BTreeMap<String, Vec<String>>) -> Result<Self> {
+        if let Some(name) = config.keys().find(|name| Action::from_name(name).is_none()) {
+            bail!("Unknown keybinding action '{}'", name);
+        }
+
+        let mut bindings = HashMap::new();
+        let mut keys: HashMap<Action, Vec<KeySpec>> = HashMap::new();
+        for &action in Action::all() {
+            let specs: Vec<&str> = match config.get(action.name()) {
+                Some(keys) => keys.iter().map(String::as_str).collect(),
+                None => action.default_keys().to_vec(),
+            };
+            for spec in specs {
+                let key = KeySpec::parse(spec)?;
+                if let Some(existing) = bindings.insert(key, action) {
+                    if existing != action {
+                        bail!(
+                            "Key '{}' is bound to both '{}' and '{}'",
+                            spec,
+                            existing.name(),
+                            action.name()
+                        );
+                    }
+                }
+                let action_keys = keys.entry(action).or_default();
+                if !action_keys.contains(&key) {
+                    action_keys.push(key);
+                }
+            }
+        }
+
+        Ok(Self { bindings, keys })
+    }
+
+    /// The action bound to a key event, if any
+    pub fn action_for(&self, key: &KeyEvent) -> Option<Action> {
+        self.bindings.get(&KeySpec::from_event(key)).copied()
+    }
+
+    /// The keys bound to `action`, e.g. `q / Q`, or `unbound` if the config left it none
+    pub fn label(&self, action: Action) -> String {
+        match self.keys.get(&action) {
+            Some(keys) if !keys.is_empty() => keys
+                .iter()
+                .map(KeySpec::to_string)
+                .collect::<Vec<_>>()
+                .join(" / "),
+            _ => "unbound".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(entries: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        entries
+            .iter()
+            .map(|(action, keys)| {
+                (
+                    action.to_string(),
+                    keys.iter().map(|k| k.to_string()).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_key_specs() {
+        assert_eq!(KeySpec::parse("q").unwrap().code, KeyCode::Char('q'));
+        assert_eq!(KeySpec::parse("space").unwrap().code, KeyCode::Char(' '));
+        assert_eq!(KeySpec::parse("+").unwrap().code, KeyCode::Char('+'));
+        assert_eq!(KeySpec::parse("F5").unwrap().code, KeyCode::F(5));
+
+        let ctrl_s = KeySpec::parse("ctrl+s").unwrap();
+        assert_eq!(ctrl_s.code, KeyCode::Char('s'));
+        assert_eq!(ctrl_s.modifiers, KeyModifiers::CONTROL);
+
+        assert!(KeySpec::parse("hyper+q").is_err());
+        assert!(KeySpec::parse("nope").is_err());
+        assert!(KeySpec::parse("").is_err());
+    }
+
+    #[test]
+    fn test_shift_tab_matches_both_event_forms() {
+        let spec = KeySpec::parse("shift+tab").unwrap();
+        let tab = KeyEvent::new(KeyCode::Tab, KeyModifiers::SHIFT);
+        let backtab = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert_eq!(KeySpec::from_event(&tab), spec);
+        assert_eq!(KeySpec::from_event(&backtab), spec);
+    }
+
+    #[test]
+    fn test_defaults_and_remap() {
+        let defaults = Keybindings::default();
+        let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+        assert_eq!(defaults.action_for(&q), Some(Action::Quit));
+
+        let remapped = Keybindings::from_config(&config(&[("quit", &["ctrl+q"])])).unwrap();
+        assert_eq!(remapped.action_for(&q), None);
+        let ctrl_q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL);
+        assert_eq!(remapped.action_for(&ctrl_q), Some(Action::Quit));
+
+        // Shift+C copies the raw escapes, plain c strips them
+        let c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE);
+        let shift_c = KeyEvent::new(KeyCode::Char('C'), KeyModifiers::SHIFT);
+        assert_eq!(defaults.action_for(&c), Some(Action::Copy));
+        assert_eq!(defaults.action_for(&shift_c), Some(Action::CopyRaw));
+    }
+
+    #[test]
+    fn test_labels_follow_remapped_keys() {
+        let defaults = Keybindings::default();
+        assert_eq!(defaults.label(Action::Quit), "q / Q");
+        assert_eq!(defaults.label(Action::Render), "Space");
+        assert_eq!(defaults.label(Action::PrevWidget), "Shift+Tab");
+        assert_eq!(defaults.label(Action::Redo), "Ctrl+r");
+
+        let remapped =
+            Keybindings::from_config(&config(&[("save", &["ctrl+s", "f2"]), ("help", &[])]))
+                .unwrap();
+        assert_eq!(remapped.label(Action::Save), "Ctrl+s / F2");
+        assert_eq!(remapped.label(Action::Help), "unbound");
+    }
+
+    #[test]
+    fn test_conflicts_and_unknown_actions_rejected() {
+        let conflict = Keybindings::from_config(&config(&[("save", &["q"])]));
+        let message = conflict.unwrap_err().to_string();
+        assert!(message.contains("quit") && message.contains("save"));
+
+        assert!(Keybindings::from_config(&config(&[("launch", &["x"])])).is_err());
+    }
+}
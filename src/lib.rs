@@ -1,12 +1,41 @@
 //! Glyphgen - High-Performance Terminal Art Rendering Studio
 //!
 //! A TUI application for converting images to ASCII/Unicode art and stylizing text.
+//!
+//! The renderers can also be embedded directly; these calls run on the calling
+//! thread without spawning workers:
+//!
+//! ```
+//! use glyphgen::{render_image_to_ascii, AsciiConfig, CharacterSet};
+//! use image::{DynamicImage, RgbImage};
+//!
+//! let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(40, 20, image::Rgb([255, 255, 255])));
+//! let config = AsciiConfig {
+//!     target_width: 20,
+//!     charset: CharacterSet::Standard,
+//!     ..AsciiConfig::default()
+//! };
+//! let art = render_image_to_ascii(&img, &config)?;
+//! assert!(art.lines().all(|line| line == "@".repeat(20)));
+//! # Ok::<(), anyhow::Error>(())
+//! ```
 
+pub mod cli;
 pub mod color_space;
 pub mod config;
+pub mod crop;
+pub mod file_browser;
+pub mod file_watch;
+pub mod graphics;
 pub mod image_loader;
+pub mod image_transform;
 pub mod input;
+pub mod json_export;
+pub mod keybindings;
+pub mod path_completion;
 pub mod perf_monitor;
+pub mod redraw;
+pub mod render_cache;
 pub mod render_engines;
 pub mod state;
 pub mod terminal_capabilities;
@@ -16,4 +45,44 @@ pub mod worker;
 
 // Re-export commonly used types
 pub use config::Config;
+pub use render_engines::{
+    ascii::{AsciiConfig, CharacterSet},
+    text_stylizer::{GradientMode, TextAlignment, TextTransform, UnicodeStyle},
+    unicode::{UnicodeConfig, UnicodeMode},
+    ResizeFilter,
+};
 pub use state::{AppState, RenderMode};
+pub use terminal_capabilities::ColorSupport;
+
+use anyhow::Result;
+use image::DynamicImage;
+
+/// Render an image as ASCII art
+pub fn render_image_to_ascii(image: &DynamicImage, config: &AsciiConfig) -> Result<String> {
+    render_engines::ascii::render_ascii(image, config)
+}
+
+/// Render an image as Unicode block or Braille art, with ANSI colors per `config.color_mode`
+pub fn render_image_to_unicode(image: &DynamicImage, config: &UnicodeConfig) -> Result<String> {
+    render_engines::unicode::render_unicode(image, config)
+}
+
+/// Apply a text transform, a Unicode style and an optional color gradient to `text`
+pub fn stylize(
+    text: &str,
+    transform: TextTransform,
+    style: UnicodeStyle,
+    gradient: GradientMode,
+    start_color: (u8, u8, u8),
+    end_color: (u8, u8, u8),
+) -> Result<String> {
+    render_engines::text_stylizer::stylize_text(
+        text,
+        transform,
+        style,
+        gradient,
+        start_color,
+        end_color,
+        TextAlignment::Left,
+    )
+}
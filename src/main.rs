@@ -1,37 +1,64 @@
 //! Main entry point for Glyphgen Terminal Art Studio
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event},
+    cursor::MoveTo,
+    event::{
+        self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, Event,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::{
-    io::{self, Stdout},
+    io::{self, IsTerminal, Stdout, Write},
     panic,
+    sync::atomic::{AtomicUsize, Ordering},
     time::{Duration, Instant},
 };
 
 use glyphgen::{
+    cli::{decide_run_mode, LogLevel, RunMode},
     config::Config,
+    crop::CropRect,
+    file_watch::{FileWatcher, WatchEvent},
+    graphics::{GraphicsMode, KITTY_CLEAR},
+    image_transform::{apply_chain, ImageTransform},
     input::handle_event,
-    state::AppState,
-    terminal_capabilities::detect_capabilities,
+    redraw::{LoopEvent, RedrawScheduler},
+    state::{AppState, AsciiRenderState, ColorAsciiRenderState, UnicodeRenderState},
+    terminal_capabilities::{
+        detect_capabilities, probe_glyph_width, query_sixel_support, ColorSupport,
+        GraphicsProtocol, PROBE_GLYPH,
+    },
     ui,
-    worker::{spawn_workers, WorkerHandle},
+    worker::{
+        default_worker_count, spawn_workers, WorkerHandle, WorkerResponse, WORKER_THREAD_PREFIX,
+    },
 };
 
-/// Target frame time for 60 FPS
-const FRAME_TIME_MS: u64 = 16;
-
 fn main() -> Result<()> {
     // Set up panic hook to restore terminal on panic
     let original_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
+        // Render workers catch their panics and report them as render errors, so they
+        // leave the terminal (and the default report) alone
+        let thread = std::thread::current();
+        if thread
+            .name()
+            .is_some_and(|name| name.starts_with(WORKER_THREAD_PREFIX))
+        {
+            return;
+        }
+
         // Restore terminal
         let _ = disable_raw_mode();
-        let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture);
+        let _ = execute!(
+            io::stdout(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableFocusChange
+        );
         original_hook(panic_info);
     }));
 
@@ -41,8 +68,17 @@ fn main() -> Result<()> {
     // Simple CLI parsing for convenience
     let mut arg_image: Option<std::path::PathBuf> = None;
     let mut arg_render_once = false;
+    let mut arg_check = false;
     let mut arg_mode: Option<String> = None;
     let mut arg_output_format: Option<String> = None;
+    let mut arg_output: Option<String> = None;
+    let mut arg_batch: Option<std::path::PathBuf> = None;
+    let mut arg_from_list: Option<std::path::PathBuf> = None;
+    let mut arg_watch = false;
+    let mut arg_graphics = GraphicsMode::Off;
+    let mut arg_edits = ImageEdits::default();
+    let mut arg_overrides = glyphgen::cli::RenderOverrides::default();
+    let mut arg_log = LogLevel::default();
 
     let mut iter = std::env::args().skip(1);
     while let Some(a) = iter.next() {
@@ -53,6 +89,52 @@ fn main() -> Result<()> {
                 }
             }
             "--render-once" => arg_render_once = true,
+            "--check" => arg_check = true,
+            "--verbose" | "-v" => arg_log = LogLevel::Verbose,
+            "--quiet" | "-q" => arg_log = LogLevel::Quiet,
+            "--watch" => arg_watch = true,
+            "--graphics" => {
+                if let Some(g) = iter.next() {
+                    match GraphicsMode::from_arg(&g) {
+                        Some(mode) => arg_graphics = mode,
+                        None => eprintln!("Unknown graphics mode '{}', ignoring", g),
+                    }
+                }
+            }
+            "--crop" => {
+                if let Some(c) = iter.next() {
+                    match CropRect::parse(&c) {
+                        Ok(crop) => arg_edits.crop = Some(crop),
+                        Err(e) => eprintln!("{}, ignoring", e),
+                    }
+                }
+            }
+            "--rotate" => {
+                if let Some(r) = iter.next() {
+                    match ImageTransform::from_rotate_arg(&r) {
+                        Some(transform) => arg_edits.transforms.push(transform),
+                        None => eprintln!("Unknown rotation '{}', ignoring", r),
+                    }
+                }
+            }
+            "--flip" => {
+                if let Some(f) = iter.next() {
+                    match ImageTransform::from_flip_arg(&f) {
+                        Some(transform) => arg_edits.transforms.push(transform),
+                        None => eprintln!("Unknown flip '{}', ignoring", f),
+                    }
+                }
+            }
+            "--batch" => {
+                if let Some(d) = iter.next() {
+                    arg_batch = Some(std::path::PathBuf::from(d));
+                }
+            }
+            "--from-list" => {
+                if let Some(f) = iter.next() {
+                    arg_from_list = Some(std::path::PathBuf::from(f));
+                }
+            }
             "--mode" => {
                 if let Some(m) = iter.next() {
                     arg_mode = Some(m);
@@ -63,46 +145,235 @@ fn main() -> Result<()> {
                     arg_output_format = Some(f);
                 }
             }
+            "--output" => {
+                if let Some(o) = iter.next() {
+                    arg_output = Some(o);
+                }
+            }
+            "--width" => {
+                if let Some(w) = iter.next() {
+                    arg_overrides.width = Some(glyphgen::cli::parse_width(&w)?);
+                }
+            }
+            "--charset" => {
+                if let Some(c) = iter.next() {
+                    arg_overrides.charset = Some(glyphgen::cli::parse_charset(&c)?);
+                }
+            }
+            "--unicode-mode" => {
+                if let Some(m) = iter.next() {
+                    arg_overrides.unicode_mode = Some(glyphgen::cli::parse_unicode_mode(&m)?);
+                }
+            }
+            "--color" => {
+                if let Some(c) = iter.next() {
+                    arg_overrides.color = Some(glyphgen::cli::parse_color(&c)?);
+                }
+            }
+            "--text" => {
+                if let Some(t) = iter.next() {
+                    arg_overrides.text = Some(if t == "-" {
+                        let input = io::read_to_string(io::stdin())
+                            .context("Failed to read --text from stdin")?;
+                        input.trim_end_matches(['\n', '\r']).to_string()
+                    } else {
+                        t
+                    });
+                }
+            }
+            "--style" => {
+                if let Some(s) = iter.next() {
+                    arg_overrides.style = Some(glyphgen::cli::parse_style(&s)?);
+                }
+            }
+            "--gradient" => {
+                if let Some(g) = iter.next() {
+                    arg_overrides.gradient = Some(glyphgen::cli::parse_gradient(&g)?);
+                }
+            }
+            "--start-color" => {
+                if let Some(c) = iter.next() {
+                    arg_overrides.start_color = Some(glyphgen::cli::parse_hex_color(&c)?);
+                }
+            }
+            "--end-color" => {
+                if let Some(c) = iter.next() {
+                    arg_overrides.end_color = Some(glyphgen::cli::parse_hex_color(&c)?);
+                }
+            }
+            "--align" => {
+                if let Some(a) = iter.next() {
+                    arg_overrides.alignment = Some(glyphgen::cli::parse_alignment(&a)?);
+                }
+            }
             _ => {}
         }
     }
 
-    // Detect terminal capabilities
-    let capabilities = detect_capabilities();
+    // `--text` on its own renders the text stylizer, including for --check
+    if arg_mode.is_none() {
+        arg_mode = glyphgen::cli::infer_mode(arg_image.is_some(), arg_overrides.text.is_some())
+            .map(str::to_string);
+    }
 
-    // Load configuration
-    let config = Config::load().unwrap_or_default();
+    // Load configuration; on failure keep the error so the file isn't overwritten on exit.
+    // --check only reads it, reporting a pending migration instead of writing it
+    let loaded = if arg_check {
+        Config::read()
+    } else {
+        Config::load().map(|config| (config, false))
+    };
+    let (config, config_migrated, config_error) = match loaded {
+        Ok((config, migrated)) => (config, migrated, None),
+        Err(e) => (Config::default(), false, Some(e)),
+    };
+
+    // Detect terminal capabilities; an invalid force_color is reported once the TUI is up
+    let capabilities = detect_capabilities(config.terminal.force_color().ok().flatten());
+
+    // --check reports what a run would use and exits, before any worker or terminal setup
+    if arg_check {
+        let mut headless_config = config.clone();
+        arg_overrides.apply_to(&mut headless_config);
+        return run_check(
+            &config,
+            config_migrated,
+            config_error,
+            &capabilities,
+            &headless_config,
+            arg_mode.as_deref(),
+            arg_output_format.as_deref(),
+            arg_overrides.color.unwrap_or(capabilities.color_support),
+            arg_image.as_deref(),
+        );
+    }
+
+    // Redirected output can't host the TUI, so decide up front how to run
+    let stdout_is_tty = io::stdout().is_terminal();
+    let run_mode = decide_run_mode(
+        arg_batch.is_some() || arg_from_list.is_some(),
+        arg_render_once,
+        arg_image.is_some() || arg_overrides.text.is_some(),
+        stdout_is_tty,
+    )?;
+
+    // Headless renders take --width/--charset/--unicode-mode/--color over the config
+    let mut headless_config = config.clone();
+    arg_overrides.apply_to(&mut headless_config);
+    let headless_color = arg_overrides.color.unwrap_or(capabilities.color_support);
+    let text_options = arg_overrides.text_options(&config.text);
+
+    // Batch mode is headless as well: render a whole directory (then any listed files) and exit
+    if run_mode == RunMode::Batch {
+        let mut inputs = Vec::new();
+        if let Some(dir) = &arg_batch {
+            inputs.extend(glyphgen::cli::collect_batch_inputs(dir)?);
+        }
+        if let Some(list) = &arg_from_list {
+            inputs.extend(glyphgen::cli::read_path_list(list)?);
+        }
+        // One thread per worker feeds the shared pool
+        let workers = spawn_workers(default_worker_count());
+        return run_batch(
+            &inputs,
+            arg_mode.as_deref(),
+            arg_output_format.as_deref(),
+            arg_output.as_deref(),
+            &arg_edits,
+            &text_options,
+            &headless_config,
+            headless_color,
+            &workers,
+            arg_log,
+        );
+    }
 
-    // Spawn worker threads
-    let workers = spawn_workers();
+    // One worker completes renders in request order
+    let workers = spawn_workers(1);
 
     // If render-once was requested, do not start full TUI — perform a single render + save
-    if arg_render_once && arg_image.is_some() {
+    if run_mode == RunMode::RenderOnce {
+        // Piped without --render-once: the render itself is the output
+        if !arg_render_once && arg_output.is_none() {
+            arg_output = Some("-".to_string());
+        }
         return run_render_once(
-            arg_image.unwrap(),
+            arg_image.as_deref(),
             arg_mode.as_deref(),
             arg_output_format.as_deref(),
-            &config,
+            arg_output.as_deref(),
+            &arg_edits,
+            &text_options,
+            &headless_config,
+            headless_color,
             &workers,
+            arg_log,
         );
     }
 
     // Create application state
     let mut app_state = AppState::new(config, capabilities, workers.request_tx.clone());
+    if let Some(e) = &config_error {
+        // TOML errors quote the offending source below their first line
+        let message = e.to_string();
+        app_state.mark_config_load_failed(message.lines().next().unwrap_or_default());
+    }
+
+    // Watch the input image for external edits if requested
+    let watcher = match (&arg_image, arg_watch) {
+        (Some(path), true) if path.to_str().is_some_and(glyphgen::image_loader::is_url) => {
+            eprintln!("--watch only works with local files; ignoring it for a URL");
+            None
+        }
+        (Some(path), true) => Some(FileWatcher::new(path.clone())),
+        _ => None,
+    };
 
     // If an image path was provided, set it (this will auto-render)
+    app_state.transform_chain = arg_edits.transforms;
+    app_state.crop = arg_edits.crop;
     if let Some(path) = arg_image {
-        match glyphgen::image_loader::load_image(&path) {
+        match glyphgen::image_loader::load_image_from_source(&path) {
             Ok(img) => app_state.set_input_image(path, img),
             Err(e) => eprintln!("Failed to load image: {}", e),
         }
+    } else {
+        app_state.offer_last_image();
     }
 
     // Initialize terminal (only needed for interactive TUI)
     let mut terminal = setup_terminal()?;
 
+    // Experimental graphics preview: confirm support now that raw mode is on
+    let protocol = app_state.capabilities.graphics_protocol;
+    let supported = match arg_graphics {
+        GraphicsMode::Off => true,
+        GraphicsMode::Sixel => {
+            protocol == GraphicsProtocol::Sixel || query_sixel_support(Duration::from_millis(200))
+        }
+        GraphicsMode::Kitty => protocol == GraphicsProtocol::Kitty,
+    };
+    if supported {
+        app_state.graphics = arg_graphics;
+    } else {
+        app_state.set_status(
+            &format!(
+                "{} graphics not supported - using Unicode preview",
+                arg_graphics.name()
+            ),
+            false,
+        );
+    }
+
+    // Opt-in glyph width probe, also needs raw mode for the cursor report
+    if app_state.config.terminal.probe_glyph_width {
+        app_state.capabilities.braille_width =
+            probe_glyph_width(PROBE_GLYPH, Duration::from_millis(200));
+        app_state.warn_if_braille_misaligned();
+    }
+
     // Run main event loop
-    let result = run_event_loop(&mut terminal, &mut app_state, &workers);
+    let result = run_event_loop(&mut terminal, &mut app_state, &workers, watcher);
 
     // Cleanup
     cleanup_terminal(terminal)?;
@@ -117,7 +388,10 @@ fn main() -> Result<()> {
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+    execute!(stdout, EnterAlternateScreen)?;
+    if stdout.is_terminal() {
+        execute!(stdout, EnableMouseCapture, EnableFocusChange)?;
+    }
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -126,11 +400,14 @@ fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
 /// Restore terminal to normal state
 fn cleanup_terminal(mut terminal: Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
     disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    if io::stdout().is_terminal() {
+        execute!(
+            terminal.backend_mut(),
+            DisableMouseCapture,
+            DisableFocusChange
+        )?;
+    }
     terminal.show_cursor()?;
     Ok(())
 }
@@ -140,37 +417,127 @@ fn run_event_loop(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     app_state: &mut AppState,
     workers: &WorkerHandle,
+    mut watcher: Option<FileWatcher>,
 ) -> Result<()> {
-    let frame_duration = Duration::from_millis(FRAME_TIME_MS);
+    let mut redraw = RedrawScheduler::new(app_state.config.ui.max_fps);
+    let mut graphics_dirty = true;
+    let mut graphics_shown = false;
+    let mut graphics_area = None;
+    let mut graphics_rerender = false;
 
     loop {
         let frame_start = Instant::now();
 
-        // Render UI
-        terminal.draw(|frame| ui::render(frame, app_state))?;
+        // Render UI, only when something changed and the terminal has focus
+        if redraw.take_redraw_if_focused(app_state.focused) {
+            terminal.draw(|frame| ui::render(frame, app_state))?;
 
-        // Poll for events with timeout
-        let timeout = frame_duration.saturating_sub(frame_start.elapsed());
+            // The bitmap is sized to the preview area, so re-render when that area changes
+            if app_state.graphics != GraphicsMode::Off
+                && app_state.preview_area.get() != graphics_area
+            {
+                graphics_area = app_state.preview_area.get();
+                graphics_rerender = true;
+            }
+
+            // Overlay the graphics preview; only redrawn after something may have covered it
+            if app_state.graphics_visible() {
+                if graphics_dirty {
+                    draw_graphics(terminal, app_state)?;
+                    graphics_dirty = false;
+                    graphics_shown = true;
+                }
+            } else if graphics_shown {
+                // Kitty images live on their own layer and outlast the cells under them
+                if app_state.graphics == GraphicsMode::Kitty {
+                    let backend = terminal.backend_mut();
+                    backend.write_all(KITTY_CLEAR.as_bytes())?;
+                    backend.flush()?;
+                }
+                graphics_shown = false;
+            }
+
+            // Record frame time for performance monitoring; idle waits don't count
+            let frame_time = frame_start.elapsed().max(redraw.frame_interval());
+            app_state.perf_metrics.record_frame(frame_time);
+        }
+
+        if graphics_rerender
+            && !app_state.is_rendering
+            && app_state.input_image.is_some()
+            && app_state.current_mode == glyphgen::state::RenderMode::ImageToUnicode
+        {
+            app_state.trigger_render();
+            graphics_rerender = false;
+            redraw.mark_dirty();
+        }
+
+        // Poll for events with timeout, waiting longer while nothing is happening
+        let busy = app_state.is_rendering || app_state.is_fetching();
+        let timeout = redraw
+            .poll_timeout(busy, app_state.focused)
+            .saturating_sub(frame_start.elapsed());
         if event::poll(timeout)? {
             let event = event::read()?;
 
             // Handle terminal resize
             if let Event::Resize(width, height) = event {
-                app_state.set_terminal_size(width, height);
+                app_state.handle_resize(width, height);
+                redraw.record(LoopEvent::Resize);
+            } else if matches!(event, Event::FocusGained | Event::FocusLost) {
+                redraw.record(LoopEvent::Focus);
+            } else {
+                redraw.record(LoopEvent::Input);
             }
 
             // Handle input
             handle_event(event, app_state)?;
+            graphics_dirty = true;
+        } else {
+            redraw.record(LoopEvent::Idle);
         }
 
         // Process worker responses (non-blocking)
         while let Ok(response) = workers.response_rx.try_recv() {
+            // Progress only touches the status bar, so the image stays put
+            if !matches!(response, WorkerResponse::Progress { .. }) {
+                graphics_dirty = true;
+            }
             app_state.handle_worker_response(response);
+            redraw.record(LoopEvent::Worker);
         }
 
-        // Record frame time for performance monitoring
-        let frame_time = frame_start.elapsed();
-        app_state.perf_metrics.record_frame(frame_time);
+        // Show the image (or error) from a load-prompt URL fetch once it arrives
+        if app_state.poll_url_fetch() {
+            graphics_dirty = true;
+            redraw.record(LoopEvent::Worker);
+        }
+
+        // Ring the bell or notify after a slow render (`[ui] notify_after_ms`)
+        if let Some(notification) = app_state.pending_notification.take() {
+            let backend = terminal.backend_mut();
+            backend.write_all(notification.as_bytes())?;
+            backend.flush()?;
+        }
+
+        // Reload and re-render the watched image when it changes on disk
+        if let Some(watcher) = watcher.as_mut() {
+            match watcher.poll(Instant::now()) {
+                WatchEvent::Changed => {
+                    let path = watcher.path().to_path_buf();
+                    match glyphgen::image_loader::load_image(&path) {
+                        Ok(img) => app_state.set_input_image(path, img),
+                        Err(e) => app_state.set_status(&format!("Reload failed: {}", e), true),
+                    }
+                    redraw.record(LoopEvent::FileWatch);
+                }
+                WatchEvent::Missing(msg) => {
+                    app_state.set_status(&msg, true);
+                    redraw.record(LoopEvent::FileWatch);
+                }
+                WatchEvent::Unchanged => {}
+            }
+        }
 
         // Check for quit
         if app_state.should_quit {
@@ -178,130 +545,464 @@ fn run_event_loop(
         }
     }
 
-    // Save configuration on exit
-    if let Err(e) = app_state.config.save() {
+    // Save configuration on exit, unless that would replace an unparseable file with defaults
+    if !app_state.should_save_config() {
+        eprintln!(
+            "Warning: Config file could not be parsed; left it unchanged for manual repair ({})",
+            Config::config_path()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default()
+        );
+    } else if let Err(e) = app_state.config.save() {
         eprintln!("Warning: Failed to save config: {}", e);
     }
 
     Ok(())
 }
 
+/// Write the current graphics image at the top-left of the preview area
+fn draw_graphics(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    app_state: &AppState,
+) -> Result<()> {
+    if let (Some(graphics), Some(area)) =
+        (&app_state.preview_graphics, app_state.preview_area.get())
+    {
+        let backend = terminal.backend_mut();
+        if app_state.graphics == GraphicsMode::Kitty {
+            // Replace rather than stack the previous placement
+            backend.write_all(KITTY_CLEAR.as_bytes())?;
+        }
+        execute!(backend, MoveTo(area.x, area.y))?;
+        backend.write_all(graphics.as_bytes())?;
+        backend.flush()?;
+    }
+    Ok(())
+}
+
+/// Rotations, flips and a crop from the command line, applied to each image before rendering
+#[derive(Debug, Default)]
+struct ImageEdits {
+    transforms: Vec<ImageTransform>,
+    crop: Option<CropRect>,
+}
+
+impl ImageEdits {
+    /// Apply the transforms in order, then the crop (clamped to the transformed image)
+    fn apply(&self, image: image::DynamicImage) -> image::DynamicImage {
+        let image = if self.transforms.is_empty() {
+            image
+        } else {
+            apply_chain(&image, &self.transforms)
+        };
+        match self.crop {
+            Some(crop) => crop.apply(&image),
+            None => image,
+        }
+    }
+}
+
 /// Render once: load image, request worker, wait for response, save output to file
+///
+/// `output` sets the destination file (its extension picks the format when
+/// `output_format` is omitted); `-` writes to stdout instead. The text stylizer
+/// renders `text` and needs no image.
+#[allow(clippy::too_many_arguments)]
 fn run_render_once(
-    image_path: std::path::PathBuf,
+    image_path: Option<&std::path::Path>,
+    mode: Option<&str>,
+    output_format: Option<&str>,
+    output: Option<&str>,
+    edits: &ImageEdits,
+    text: &glyphgen::cli::TextOptions,
+    config: &Config,
+    color_mode: ColorSupport,
+    workers: &WorkerHandle,
+    log: LogLevel,
+) -> Result<()> {
+    let mode = glyphgen::cli::parse_mode(mode);
+
+    // An explicit --output-format wins; otherwise infer it from the --output extension
+    let output_format =
+        output_format.or_else(|| output.and_then(glyphgen::cli::infer_output_format));
+    let format = glyphgen::cli::resolve_output_format(mode, output_format);
+
+    render_file(
+        image_path, mode, format, output, edits, text, config, color_mode, workers, log,
+    )
+}
+
+/// `--check`: print the config location, detected capabilities and effective settings
+///
+/// Exits non-zero when the config file, `force_color` or `--image` is invalid, so a
+/// script can catch it without rendering anything. The config file is never written.
+#[allow(clippy::too_many_arguments)]
+fn run_check(
+    config: &Config,
+    config_migrated: bool,
+    config_error: Option<anyhow::Error>,
+    capabilities: &glyphgen::terminal_capabilities::TerminalCapabilities,
+    headless_config: &Config,
+    mode: Option<&str>,
+    output_format: Option<&str>,
+    color_mode: ColorSupport,
+    image: Option<&std::path::Path>,
+) -> Result<()> {
+    let mut problems = Vec::new();
+
+    match Config::config_path() {
+        Ok(path) => println!("Config: {}", path.display()),
+        Err(e) => problems.push(format!("Config path: {}", e)),
+    }
+    if config_migrated {
+        println!(
+            "Config: would be migrated to version {} on the next run",
+            glyphgen::config::CONFIG_VERSION
+        );
+    }
+    if let Some(e) = config_error {
+        problems.push(format!("Config: {:#}", e));
+    }
+    if let Err(e) = config.terminal.force_color() {
+        problems.push(format!("force_color: {}", e));
+    }
+    if let Some(image) = image {
+        let is_url = image.to_str().is_some_and(glyphgen::image_loader::is_url);
+        if is_url && !cfg!(feature = "network") {
+            problems.push("--image URLs need the `network` feature".to_string());
+        } else if !is_url && !image.is_file() {
+            problems.push(format!("--image {} does not exist", image.display()));
+        }
+    }
+
+    let mode = glyphgen::cli::parse_mode(mode);
+    let format = glyphgen::cli::resolve_output_format(mode, output_format);
+    print!(
+        "{}",
+        glyphgen::cli::capability_report(capabilities, |name| std::env::var(name).ok())
+    );
+    print!(
+        "{}",
+        glyphgen::cli::settings_report(headless_config, mode, format, color_mode)
+    );
+
+    if problems.is_empty() {
+        println!("OK");
+        return Ok(());
+    }
+    for problem in &problems {
+        eprintln!("error: {}", problem);
+    }
+    anyhow::bail!("--check found {} problem(s)", problems.len())
+}
+
+/// Batch mode: render each of `inputs` without entering the TUI
+///
+/// Outputs are written as `<stem>.<ext>` into `output_dir`, or next to each input, with a
+/// `-2`, `-3`, ... suffix for inputs that would otherwise share one.
+/// Files render at once on every worker of `workers`, each answered on a channel of its
+/// own, so a render that outlives `render_file`'s timeout is never saved as another file.
+/// Fails once every file has been tried if any of them couldn't be rendered.
+#[allow(clippy::too_many_arguments)]
+fn run_batch(
+    inputs: &[std::path::PathBuf],
     mode: Option<&str>,
     output_format: Option<&str>,
+    output_dir: Option<&str>,
+    edits: &ImageEdits,
+    text: &glyphgen::cli::TextOptions,
     config: &Config,
+    color_mode: ColorSupport,
     workers: &WorkerHandle,
+    log: LogLevel,
 ) -> Result<()> {
-    let img = glyphgen::image_loader::load_image(&image_path)?;
-
-    // Determine mode
-    let mode_enum = match mode.unwrap_or("ascii") {
-        "ascii" => glyphgen::state::RenderMode::ImageToAscii,
-        "unicode" => glyphgen::state::RenderMode::ImageToUnicode,
-        "text" => glyphgen::state::RenderMode::TextStylizer,
-        other => {
-            eprintln!("Unknown mode '{}', defaulting to ascii", other);
-            glyphgen::state::RenderMode::ImageToAscii
+    let mode = glyphgen::cli::parse_mode(mode);
+    if mode == glyphgen::state::RenderMode::TextStylizer {
+        anyhow::bail!("Batch mode requires an image mode (ascii, unicode or color-ascii)");
+    }
+
+    let format = glyphgen::cli::resolve_output_format(mode, output_format);
+    let output_dir = output_dir.map(std::path::Path::new);
+
+    let dests = glyphgen::cli::batch_output_paths(inputs, output_dir, format);
+    let jobs: Vec<_> = inputs.iter().zip(&dests).collect();
+
+    // Each thread takes the next unclaimed input until none are left
+    let next = AtomicUsize::new(0);
+    let rendered = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for _ in 0..workers.worker_count().min(inputs.len()) {
+            scope.spawn(|| {
+                while let Some(&(input, dest)) = jobs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    let dest = dest.to_string_lossy();
+                    match render_file(
+                        Some(input),
+                        mode,
+                        format,
+                        Some(&*dest),
+                        edits,
+                        text,
+                        config,
+                        color_mode,
+                        workers,
+                        log,
+                    ) {
+                        Ok(()) => {
+                            rendered.fetch_add(1, Ordering::Relaxed);
+                            if log != LogLevel::Quiet {
+                                println!("Rendered {} -> {}", input.display(), dest);
+                            }
+                        }
+                        Err(e) => eprintln!("Failed to render {}: {}", input.display(), e),
+                    }
+                }
+            });
         }
+    });
+
+    let rendered = rendered.into_inner();
+    let errors = inputs.len() - rendered;
+    if log != LogLevel::Quiet || errors > 0 {
+        println!("Rendered {}/{} ({} errors)", rendered, inputs.len(), errors);
+    }
+    anyhow::ensure!(
+        errors == 0,
+        "{} of {} files failed to render",
+        errors,
+        inputs.len()
+    );
+    Ok(())
+}
+
+/// Render a single image file (or `text`, for the text stylizer) and save it in `format`
+/// to `output` (or the mode's default filename when `output` is `None`)
+///
+/// Unicode and Color ASCII output use `color_mode`, so `NO_COLOR`/`FORCE_COLOR` apply to
+/// headless renders too.
+#[allow(clippy::too_many_arguments)]
+fn render_file(
+    image_path: Option<&std::path::Path>,
+    mode: glyphgen::state::RenderMode,
+    format: &str,
+    output: Option<&str>,
+    edits: &ImageEdits,
+    text: &glyphgen::cli::TextOptions,
+    config: &Config,
+    color_mode: ColorSupport,
+    workers: &WorkerHandle,
+    log: LogLevel,
+) -> Result<()> {
+    // Every mode but the text stylizer renders the input image
+    let mut dimensions = None;
+    let mut load_image = || -> Result<image::DynamicImage> {
+        let path = image_path.context("This mode needs an --image to render")?;
+        let image = edits.apply(glyphgen::image_loader::load_image_from_source(path)?);
+        dimensions = Some((image.width(), image.height()));
+        Ok(image)
     };
 
-    // Construct message based on mode
+    // Construct message based on mode, with the same settings the TUI starts from
     use glyphgen::worker::WorkerMessage;
-    let config_clone = config.clone();
-
-    match mode_enum {
-        glyphgen::state::RenderMode::ImageToAscii => {
-            let msg = WorkerMessage::AsciiRequest {
-                image: std::sync::Arc::new(img),
-                width: config_clone.ascii.default_width,
-                charset: glyphgen::render_engines::ascii::CharacterSet::Extended,
-                invert: false,
-                edge_enhance: config_clone.ascii.edge_enhance,
+    let font_aspect = config.ui.font_aspect;
+
+    let msg = match mode {
+        glyphgen::state::RenderMode::ImageToAscii => WorkerMessage::AsciiRequest {
+            image: std::sync::Arc::new(load_image()?),
+            config: AsciiRenderState::from_config(&config.ascii).render_config(font_aspect),
+        },
+        glyphgen::state::RenderMode::ImageToUnicode => {
+            let unicode = UnicodeRenderState {
+                color_mode,
+                palette: if config.unicode.use_palette {
+                    config.palette.parse()?
+                } else {
+                    Vec::new()
+                },
+                ..UnicodeRenderState::from_config(&config.unicode)
             };
-            let _ = workers.request_tx.send(msg);
+            WorkerMessage::UnicodeRequest {
+                image: std::sync::Arc::new(load_image()?),
+                config: unicode.render_config(font_aspect),
+            }
         }
-        glyphgen::state::RenderMode::ImageToUnicode => {
-            let msg = WorkerMessage::UnicodeRequest {
-                image: std::sync::Arc::new(img),
-                width: config_clone.unicode.default_width,
-                mode: glyphgen::render_engines::unicode::UnicodeMode::HalfBlocks,
-                color_mode: glyphgen::terminal_capabilities::ColorSupport::TrueColor,
+        glyphgen::state::RenderMode::ColorAscii => {
+            let color_ascii = ColorAsciiRenderState {
+                color_mode,
+                ..ColorAsciiRenderState::from_config(&config.ascii)
             };
-            let _ = workers.request_tx.send(msg);
+            WorkerMessage::AsciiRequest {
+                image: std::sync::Arc::new(load_image()?),
+                config: color_ascii.render_config(font_aspect),
+            }
         }
         glyphgen::state::RenderMode::TextStylizer => {
-            let msg = WorkerMessage::TextRequest {
-                text: String::from("Example Text"),
-                style: glyphgen::render_engines::text_stylizer::UnicodeStyle::Bold,
-                gradient: glyphgen::render_engines::text_stylizer::GradientMode::None,
-                start_color: (255, 0, 0),
-                end_color: (0, 0, 255),
-            };
-            let _ = workers.request_tx.send(msg);
+            WorkerMessage::TextRequest {
+                text: text.text.clone(),
+                transform: glyphgen::render_engines::text_stylizer::TextTransform::None,
+                style: text.style,
+                gradient: text.gradient,
+                start_color: text.start_color,
+                end_color: text.end_color,
+                alignment: text.alignment,
+                link: None,
+                // Files are read elsewhere, so keep the real glyphs
+                unicode_support: glyphgen::terminal_capabilities::UnicodeSupport::Full,
+            }
         }
-    }
+    };
+    let job = workers.submit(msg);
+
+    // Wait for result, skipping progress updates; giving up drops `job`, so a request
+    // still queued is never rendered
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let response = loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match job.response_rx.recv_timeout(remaining) {
+            Ok(WorkerResponse::Progress { .. } | WorkerResponse::AsciiChunk { .. }) => continue,
+            Ok(response) => break response,
+            Err(_) => anyhow::bail!("Timed out waiting for render response"),
+        }
+    };
 
-    // Wait for result
-    use std::time::Duration;
-    if let Ok(response) = workers.response_rx.recv_timeout(Duration::from_secs(10)) {
-        match response {
-            glyphgen::worker::WorkerResponse::AsciiComplete { output, render_time } => {
-                // ASCII mode: save based on format
-                let (out_file, content) = match output_format.unwrap_or("txt") {
-                    "html" => (
-                        "ascii_output.html",
-                        glyphgen::input::convert_ansi_to_html(&output),
-                    ),
-                    "ansi" => ("ascii_output.ansi", output),
-                    _ => ("ascii_output.txt", output),
-                };
-                std::fs::write(out_file, content)?;
-                println!("Saved ASCII output to {} ({}ms)", out_file, render_time);
-            }
-            glyphgen::worker::WorkerResponse::UnicodeComplete { output, render_time } => {
-                // Unicode mode: ANSI/HTML/PNG/SVG only (no TXT)
-                let (out_file, content) = match output_format.unwrap_or("ansi") {
-                    "html" => (
-                        "unicode_output.html",
-                        glyphgen::input::convert_ansi_to_html(&output),
-                    ),
-                    "png" => {
-                        glyphgen::input::export_to_png(&output, "unicode_output.png")?;
-                        println!("Saved Unicode PNG to unicode_output.png ({}ms)", render_time);
-                        return Ok(());
-                    }
-                    "svg" => {
-                        glyphgen::input::export_to_svg(&output, "unicode_output.svg")?;
-                        println!("Saved Unicode SVG to unicode_output.svg ({}ms)", render_time);
-                        return Ok(());
-                    }
-                    _ => ("unicode_output.ansi", output),
-                };
-                std::fs::write(out_file, content)?;
-                println!("Saved Unicode output to {} ({}ms)", out_file, render_time);
-            }
-            glyphgen::worker::WorkerResponse::TextComplete { output, render_time } => {
-                // Text stylizer: save based on format
-                let (out_file, content) = match output_format.unwrap_or("txt") {
-                    "html" => (
-                        "styled_text.html",
-                        glyphgen::input::convert_ansi_to_html(&output),
-                    ),
-                    "ansi" => ("styled_text.ansi", output),
-                    _ => ("styled_text.txt", output),
-                };
-                std::fs::write(out_file, content)?;
-                println!("Saved text output to {} ({}ms)", out_file, render_time);
-            }
-            glyphgen::worker::WorkerResponse::Error(err) => {
-                eprintln!("Render error: {}", err);
-            }
+    let (content, render_time, stem, label) = match response {
+        WorkerResponse::AsciiComplete {
+            output,
+            render_time,
+            ..
+        } if mode == glyphgen::state::RenderMode::ColorAscii => (
+            output,
+            render_time,
+            "color_ascii_output",
+            "Color ASCII output",
+        ),
+        WorkerResponse::AsciiComplete {
+            output,
+            render_time,
+            ..
+        } => (output, render_time, "ascii_output", "ASCII output"),
+        WorkerResponse::UnicodeComplete {
+            output,
+            render_time,
+            ..
         }
-    } else {
-        eprintln!("Timed out waiting for render response");
+        | WorkerResponse::GraphicsComplete {
+            output,
+            render_time,
+            ..
+        } => (output, render_time, "unicode_output", "Unicode output"),
+        WorkerResponse::TextComplete {
+            output,
+            render_time,
+        } => (output, render_time, "styled_text", "text output"),
+        WorkerResponse::Error(err) => anyhow::bail!("Render error: {}", err),
+        WorkerResponse::Progress { .. } | WorkerResponse::AsciiChunk { .. } => {
+            unreachable!("progress is skipped above")
+        }
+    };
+
+    let (width, style, color_support) = render_file_settings(mode, text, config, color_mode);
+    let data = match format {
+        // Headless renders are never inverted, so they are drawn for a dark background
+        "html" => glyphgen::input::convert_ansi_to_html_with_background(
+            &content,
+            config.ui.html_background(true),
+        )
+        .into_bytes(),
+        "png" => glyphgen::input::export_to_png_bytes(&content)?,
+        "svg" => glyphgen::input::render_svg(&content, true).into_bytes(),
+        "md" => glyphgen::input::convert_to_markdown(&content).into_bytes(),
+        "json" => glyphgen::json_export::JsonExport::new(
+            mode,
+            width,
+            &style,
+            color_support,
+            render_time,
+            &content,
+        )
+        .to_json()?
+        .into_bytes(),
+        _ => content.into_bytes(),
+    };
+
+    if log == LogLevel::Verbose {
+        let report = glyphgen::cli::RenderReport {
+            input: image_path.filter(|_| dimensions.is_some()).map(Into::into),
+            dimensions,
+            mode,
+            width,
+            style,
+            color: color_support,
+            format: format.to_string(),
+            output_bytes: data.len(),
+            render_time_ms: render_time,
+        };
+        eprintln!("{}", report.to_line());
     }
 
+    let default_file = format!("{}.{}", stem, format);
+    write_render_output(output, &default_file, &data, label, render_time, log)
+}
+
+/// Width, style name and color level `render_file` renders `mode` with
+fn render_file_settings(
+    mode: glyphgen::state::RenderMode,
+    text: &glyphgen::cli::TextOptions,
+    config: &Config,
+    color_mode: ColorSupport,
+) -> (Option<usize>, String, ColorSupport) {
+    use glyphgen::state::RenderMode;
+    match mode {
+        RenderMode::ImageToAscii => (
+            Some(config.ascii.default_width),
+            config.ascii.default_charset.name().to_string(),
+            ColorSupport::NoColor,
+        ),
+        RenderMode::ImageToUnicode => (
+            Some(config.unicode.default_width),
+            config.unicode.default_mode.name().to_string(),
+            color_mode,
+        ),
+        RenderMode::TextStylizer => (
+            None,
+            text.style.name().to_string(),
+            if text.gradient == glyphgen::GradientMode::None {
+                ColorSupport::NoColor
+            } else {
+                ColorSupport::TrueColor
+            },
+        ),
+        RenderMode::ColorAscii => (
+            Some(config.ascii.default_width),
+            config.ascii.default_charset.name().to_string(),
+            color_mode,
+        ),
+    }
+}
+
+/// Write rendered bytes to `output` (or `default_file`); `-` means stdout.
+///
+/// The "Saved ..." line is skipped for stdout so the output can be piped cleanly, and
+/// with `--quiet`.
+fn write_render_output(
+    output: Option<&str>,
+    default_file: &str,
+    data: &[u8],
+    label: &str,
+    render_time: u64,
+    log: LogLevel,
+) -> Result<()> {
+    if output == Some("-") {
+        let mut stdout = io::stdout().lock();
+        stdout.write_all(data)?;
+        stdout.flush()?;
+        return Ok(());
+    }
+
+    let path = output.unwrap_or(default_file);
+    glyphgen::cli::ensure_parent_dir(std::path::Path::new(path))?;
+    std::fs::write(path, data)?;
+    if log != LogLevel::Quiet {
+        println!("Saved {} to {} ({}ms)", label, path, render_time);
+    }
     Ok(())
 }
@@ -0,0 +1,158 @@
+//! Path completion for the load prompt
+//!
+//! Completes a partially typed path against the entries of its parent directory,
+//! like a shell's Tab completion.
+
+use std::path::{is_separator, Path, MAIN_SEPARATOR};
+
+/// A directory entry that a partial path can complete to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+impl Entry {
+    pub fn new(name: &str, is_dir: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            is_dir,
+        }
+    }
+}
+
+/// Split typed input into its directory part (kept as typed, with the trailing
+/// separator) and the partial file name after it
+pub fn split_input(input: &str) -> (&str, &str) {
+    match input.rfind(is_separator) {
+        Some(index) => input.split_at(index + 1),
+        None => ("", input),
+    }
+}
+
+/// Completions of `input` among `entries` of its directory, sorted
+///
+/// Each completion is the full input path; directories end with a separator so the
+/// next Tab descends into them. Hidden entries are only offered once a `.` is typed.
+pub fn matches(input: &str, entries: &[Entry]) -> Vec<String> {
+    let (dir, partial) = split_input(input);
+    let mut matches: Vec<String> = entries
+        .iter()
+        .filter(|entry| entry.name.starts_with(partial))
+        .filter(|entry| partial.starts_with('.') || !entry.name.starts_with('.'))
+        .map(|entry| {
+            let mut path = format!("{}{}", dir, entry.name);
+            if entry.is_dir {
+                path.push(MAIN_SEPARATOR);
+            }
+            path
+        })
+        .collect();
+    matches.sort();
+    matches
+}
+
+/// Longest prefix shared by every candidate (empty if there are none)
+pub fn common_prefix(candidates: &[String]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+    let mut prefix_len = first.len();
+    for candidate in &candidates[1..] {
+        prefix_len = first
+            .char_indices()
+            .zip(candidate.chars())
+            .take_while(|((_, a), b)| a == b)
+            .map(|((index, a), _)| index + a.len_utf8())
+            .last()
+            .unwrap_or(0)
+            .min(prefix_len);
+    }
+    first[..prefix_len].to_string()
+}
+
+/// Entries of `dir`, or none if it can't be read
+pub fn list_dir(dir: &Path) -> Vec<Entry> {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    read_dir
+        .flatten()
+        .map(|entry| Entry {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            // Follows symlinks, so a link to a folder completes like a folder
+            is_dir: entry.path().is_dir(),
+        })
+        .collect()
+}
+
+/// Completions of `input` against the filesystem; relative paths list the working directory
+pub fn complete(input: &str) -> Vec<String> {
+    let (dir, _) = split_input(input);
+    let dir = if dir.is_empty() { "." } else { dir };
+    matches(input, &list_dir(Path::new(dir)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing() -> Vec<Entry> {
+        vec![
+            Entry::new("cat.png", false),
+            Entry::new("cats", true),
+            Entry::new("dog.jpg", false),
+            Entry::new(".hidden.png", false),
+        ]
+    }
+
+    #[test]
+    fn test_split_input() {
+        assert_eq!(split_input("photos/ca"), ("photos/", "ca"));
+        assert_eq!(split_input("/tmp/"), ("/tmp/", ""));
+        assert_eq!(split_input("cat"), ("", "cat"));
+    }
+
+    #[test]
+    fn test_matches_filter_by_partial_name() {
+        let sep = MAIN_SEPARATOR;
+        assert_eq!(
+            matches("pics/ca", &listing()),
+            vec!["pics/cat.png".to_string(), format!("pics/cats{}", sep)]
+        );
+        assert_eq!(matches("d", &listing()), vec!["dog.jpg".to_string()]);
+        assert!(matches("pics/zebra", &listing()).is_empty());
+
+        // Dotfiles only show up once the name starts with a dot
+        assert_eq!(matches("", &listing()).len(), 3);
+        assert_eq!(matches(".", &listing()), vec![".hidden.png".to_string()]);
+    }
+
+    #[test]
+    fn test_common_prefix() {
+        let candidates = vec!["cat.png".to_string(), "cats/".to_string()];
+        assert_eq!(common_prefix(&candidates), "cat");
+        assert_eq!(common_prefix(&["dog.jpg".to_string()]), "dog.jpg");
+        assert_eq!(common_prefix(&[]), "");
+        assert_eq!(
+            common_prefix(&["café.png".to_string(), "cafè.png".to_string()]),
+            "caf"
+        );
+    }
+
+    #[test]
+    fn test_complete_lists_real_directory() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(dir.join("image.png"), b"").unwrap();
+
+        let input = format!("{}{}i", dir.display(), MAIN_SEPARATOR);
+        assert_eq!(
+            complete(&input),
+            vec![format!("{}{}image.png", dir.display(), MAIN_SEPARATOR)]
+        );
+        let input = format!("{}{}n", dir.display(), MAIN_SEPARATOR);
+        assert!(complete(&input)[0].ends_with(&format!("nested{}", MAIN_SEPARATOR)));
+    }
+}
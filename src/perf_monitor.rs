@@ -1,16 +1,20 @@
 //! Performance monitoring
 //!
-//! Track frame times, FPS, and render times.
+//! Track frame times, FPS, render times, and memory footprint.
 
 use std::collections::VecDeque;
 use std::time::Duration;
 
+use crate::render_engines::RenderTiming;
+
 /// Performance metrics
 pub struct PerfMetrics {
     frame_times: VecDeque<Duration>,
     pub fps: f32,
     pub avg_frame_time_ms: f32,
     pub last_render_time_ms: u64,
+    /// Resize/map breakdown of the last image render, when the engine reported one
+    pub last_render_timing: Option<RenderTiming>,
 }
 
 impl PerfMetrics {
@@ -23,6 +27,7 @@ impl PerfMetrics {
             fps: 0.0,
             avg_frame_time_ms: 0.0,
             last_render_time_ms: 0,
+            last_render_timing: None,
         }
     }
 
@@ -68,6 +73,27 @@ impl Default for PerfMetrics {
     }
 }
 
+/// Decoded RGBA footprint of an image, in bytes
+pub fn image_bytes(width: u32, height: u32) -> usize {
+    width as usize * height as usize * 4
+}
+
+/// Format a byte count as B, KiB, MiB or GiB with one decimal place
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,4 +129,22 @@ mod tests {
 
         assert!(metrics.is_degraded());
     }
+
+    #[test]
+    fn test_format_bytes_boundaries() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(1024 * 1024 - 1), "1024.0 KiB");
+        assert_eq!(format_bytes(1024 * 1024), "1.0 MiB");
+        assert_eq!(format_bytes(1024 * 1024 * 1024), "1.0 GiB");
+    }
+
+    #[test]
+    fn test_image_bytes() {
+        // A 12MP photo decodes to roughly 46 MiB of RGBA
+        assert_eq!(image_bytes(4000, 3000), 48_000_000);
+        assert_eq!(format_bytes(image_bytes(4000, 3000)), "45.8 MiB");
+    }
 }
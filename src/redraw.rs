@@ -0,0 +1,189 @@
+//! Redraw scheduling
+//!
+//! The event loop redraws only after something changed, and polls less often
+//! while idle so a static screen doesn't keep the CPU busy.
+
+use std::time::Duration;
+
+/// Default frame rate cap (`[ui] max_fps`)
+pub const DEFAULT_MAX_FPS: u32 = 60;
+
+/// Highest accepted `max_fps`; faster than any terminal can usefully repaint
+pub const MAX_FPS_LIMIT: u32 = 240;
+
+/// Poll timeout while nothing is dirty and no render is running
+///
+/// Input still wakes the loop immediately; this only paces file-watch checks.
+pub const IDLE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Something the event loop saw since the last frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoopEvent {
+    /// Key, mouse or paste input
+    Input,
+    /// The terminal was resized
+    Resize,
+    /// A worker finished, streamed rows or reported progress, or a URL fetch finished
+    Worker,
+    /// The watched image was reloaded or went missing
+    FileWatch,
+    /// The terminal gained or lost focus
+    Focus,
+    /// The poll timed out with nothing to do
+    Idle,
+}
+
+/// Dirty flag and poll pacing for the event loop
+#[derive(Debug, Clone)]
+pub struct RedrawScheduler {
+    needs_redraw: bool,
+    frame_interval: Duration,
+}
+
+impl RedrawScheduler {
+    /// Start dirty so the first frame is drawn
+    pub fn new(max_fps: u32) -> Self {
+        let fps = max_fps.clamp(1, MAX_FPS_LIMIT);
+        Self {
+            needs_redraw: true,
+            frame_interval: Duration::from_micros(1_000_000 / fps as u64),
+        }
+    }
+
+    /// Minimum time between frames
+    pub fn frame_interval(&self) -> Duration {
+        self.frame_interval
+    }
+
+    /// Note an event; anything but an idle timeout needs a redraw
+    pub fn record(&mut self, event: LoopEvent) {
+        if event != LoopEvent::Idle {
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Request a redraw for a state change made outside of an event
+    pub fn mark_dirty(&mut self) {
+        self.needs_redraw = true;
+    }
+
+    pub fn needs_redraw(&self) -> bool {
+        self.needs_redraw
+    }
+
+    /// Whether to draw this frame, clearing the flag
+    pub fn take_redraw(&mut self) -> bool {
+        std::mem::take(&mut self.needs_redraw)
+    }
+
+    /// Like [`take_redraw`](Self::take_redraw), but never draws while the
+    /// terminal is unfocused; the flag is kept so the screen catches up as
+    /// soon as focus returns
+    pub fn take_redraw_if_focused(&mut self, focused: bool) -> bool {
+        focused && self.take_redraw()
+    }
+
+    /// How long to wait for input
+    ///
+    /// `busy` (a render in flight) keeps the frame pace; an unfocused terminal
+    /// is never drawn, so it always polls at the idle rate.
+    pub fn poll_timeout(&self, busy: bool, focused: bool) -> Duration {
+        if focused && (self.needs_redraw || busy) {
+            self.frame_interval
+        } else {
+            self.frame_interval.max(IDLE_POLL_INTERVAL)
+        }
+    }
+}
+
+impl Default for RedrawScheduler {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_FPS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redraws_only_after_events() {
+        let mut scheduler = RedrawScheduler::default();
+
+        // First frame always draws, then nothing until something happens
+        assert!(scheduler.take_redraw());
+        assert!(!scheduler.take_redraw());
+
+        scheduler.record(LoopEvent::Idle);
+        assert!(!scheduler.take_redraw());
+
+        for event in [
+            LoopEvent::Input,
+            LoopEvent::Resize,
+            LoopEvent::Worker,
+            LoopEvent::FileWatch,
+            LoopEvent::Focus,
+        ] {
+            scheduler.record(event);
+            assert!(scheduler.needs_redraw());
+            assert!(scheduler.take_redraw());
+            assert!(!scheduler.needs_redraw());
+        }
+
+        // Several events between frames still draw once
+        scheduler.record(LoopEvent::Input);
+        scheduler.record(LoopEvent::Worker);
+        scheduler.record(LoopEvent::Idle);
+        assert!(scheduler.take_redraw());
+        assert!(!scheduler.take_redraw());
+
+        scheduler.mark_dirty();
+        assert!(scheduler.take_redraw());
+    }
+
+    #[test]
+    fn test_poll_timeout_slows_down_when_idle() {
+        let mut scheduler = RedrawScheduler::new(60);
+        let frame = scheduler.frame_interval();
+        assert_eq!(frame, Duration::from_micros(16_666));
+
+        assert_eq!(scheduler.poll_timeout(false, true), frame);
+        scheduler.take_redraw();
+        assert_eq!(scheduler.poll_timeout(false, true), IDLE_POLL_INTERVAL);
+        assert_eq!(scheduler.poll_timeout(true, true), frame);
+        assert_eq!(scheduler.poll_timeout(true, false), IDLE_POLL_INTERVAL);
+    }
+
+    #[test]
+    fn test_unfocused_defers_redraw_until_focus_returns() {
+        let mut scheduler = RedrawScheduler::default();
+        assert!(scheduler.take_redraw_if_focused(true));
+
+        // Focus lost: events pile up but nothing is drawn
+        scheduler.record(LoopEvent::Focus);
+        assert!(!scheduler.take_redraw_if_focused(false));
+        scheduler.record(LoopEvent::Worker);
+        assert!(!scheduler.take_redraw_if_focused(false));
+        assert!(scheduler.needs_redraw());
+
+        // Focus regained: the pending redraw happens on the next frame
+        scheduler.record(LoopEvent::Focus);
+        assert!(scheduler.take_redraw_if_focused(true));
+        assert!(!scheduler.take_redraw_if_focused(true));
+    }
+
+    #[test]
+    fn test_max_fps_clamped() {
+        assert_eq!(
+            RedrawScheduler::new(0).frame_interval(),
+            Duration::from_secs(1)
+        );
+        assert_eq!(
+            RedrawScheduler::new(10_000).frame_interval(),
+            RedrawScheduler::new(MAX_FPS_LIMIT).frame_interval()
+        );
+        // A cap below the idle rate keeps its own, longer interval
+        let slow = RedrawScheduler::new(2);
+        assert_eq!(slow.poll_timeout(true, true), Duration::from_millis(500));
+    }
+}
@@ -0,0 +1,149 @@
+//! Render result cache
+//!
+//! Keeps the last few finished renders so that returning to earlier settings
+//! (or switching modes back and forth) doesn't hit a worker again.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use image::DynamicImage;
+
+use crate::render_engines::{ascii::AsciiConfig, unicode::UnicodeConfig};
+
+/// Number of renders kept before the least recently used one is evicted
+pub const RENDER_CACHE_CAPACITY: usize = 16;
+
+/// Engine settings a cached render was produced with
+#[derive(Debug, Clone, PartialEq)]
+pub enum CachedConfig {
+    Ascii(AsciiConfig),
+    Unicode(UnicodeConfig),
+}
+
+/// Cache key: source image identity plus the full render settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderKey {
+    /// Address of the shared image; the cache is cleared whenever the image changes
+    image: usize,
+    config: CachedConfig,
+}
+
+impl RenderKey {
+    pub fn new(image: &Arc<DynamicImage>, config: CachedConfig) -> Self {
+        Self {
+            image: Arc::as_ptr(image) as usize,
+            config,
+        }
+    }
+
+    /// Settings the render was requested with
+    pub fn config(&self) -> &CachedConfig {
+        &self.config
+    }
+}
+
+/// Bounded least-recently-used cache of rendered output
+#[derive(Debug)]
+pub struct RenderCache {
+    /// Most recently used entry first
+    entries: VecDeque<(RenderKey, String)>,
+    capacity: usize,
+}
+
+impl Default for RenderCache {
+    fn default() -> Self {
+        Self::new(RENDER_CACHE_CAPACITY)
+    }
+}
+
+impl RenderCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Look up a render, marking it as most recently used
+    pub fn get(&mut self, key: &RenderKey) -> Option<String> {
+        let index = self.entries.iter().position(|(k, _)| k == key)?;
+        let entry = self.entries.remove(index)?;
+        let output = entry.1.clone();
+        self.entries.push_front(entry);
+        Some(output)
+    }
+
+    /// Store a render, evicting the least recently used entry when full
+    pub fn insert(&mut self, key: RenderKey, output: String) {
+        if let Some(index) = self.entries.iter().position(|(k, _)| *k == key) {
+            self.entries.remove(index);
+        }
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_back();
+        }
+        self.entries.push_front((key, output));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    fn ascii_key(image: &Arc<DynamicImage>, width: usize) -> RenderKey {
+        RenderKey::new(
+            image,
+            CachedConfig::Ascii(AsciiConfig {
+                target_width: width,
+                ..AsciiConfig::default()
+            }),
+        )
+    }
+
+    #[test]
+    fn test_hit_requires_same_image_and_settings() {
+        let image = Arc::new(DynamicImage::ImageRgb8(RgbImage::new(4, 4)));
+        let other = Arc::new(DynamicImage::ImageRgb8(RgbImage::new(4, 4)));
+        let mut cache = RenderCache::default();
+
+        cache.insert(ascii_key(&image, 80), "art".to_string());
+
+        assert_eq!(cache.get(&ascii_key(&image, 80)), Some("art".to_string()));
+        assert_eq!(cache.get(&ascii_key(&image, 81)), None);
+        assert_eq!(cache.get(&ascii_key(&other, 80)), None);
+        let unicode = RenderKey::new(&image, CachedConfig::Unicode(UnicodeConfig::default()));
+        assert_eq!(cache.get(&unicode), None);
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let image = Arc::new(DynamicImage::ImageRgb8(RgbImage::new(4, 4)));
+        let mut cache = RenderCache::new(2);
+
+        cache.insert(ascii_key(&image, 1), "one".to_string());
+        cache.insert(ascii_key(&image, 2), "two".to_string());
+        // Touch the first entry so the second becomes the oldest
+        assert!(cache.get(&ascii_key(&image, 1)).is_some());
+        cache.insert(ascii_key(&image, 3), "three".to_string());
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&ascii_key(&image, 1)).is_some());
+        assert!(cache.get(&ascii_key(&image, 2)).is_none());
+        assert!(cache.get(&ascii_key(&image, 3)).is_some());
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -5,13 +5,28 @@
 use anyhow::Result;
 use image::{DynamicImage, GenericImageView, GrayImage, Luma};
 use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::color_space::{
+    format_fg_color, luminance_to_gray, rgb_to_luminance, rgb_to_luminance_bt601, Rgb, ANSI_RESET,
+};
+use crate::render_engines::{
+    auto_levels, cell_aspect, elapsed_ms, render_rows_streaming, saturate_image, RenderTiming,
+    ResizeFilter, DEFAULT_FONT_ASPECT, DEFAULT_SATURATION,
+};
+use crate::terminal_capabilities::ColorSupport;
 
 /// Character set for ASCII rendering
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Serialized as a plain string: `"Standard"`, `"Extended"`, `"Blocks"`, or the
+/// custom ramp itself, so `default_charset` is easy to edit by hand.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(from = "CharacterSetRepr", into = "String")]
 pub enum CharacterSet {
     /// Simple 10-character set: " .:-=+*#%@"
     Standard,
     /// Extended 70-character set for more detail
+    #[default]
     Extended,
     /// Unicode block elements: " ░▒▓█"
     Blocks,
@@ -19,12 +34,6 @@ pub enum CharacterSet {
     Custom(String),
 }
 
-impl Default for CharacterSet {
-    fn default() -> Self {
-        CharacterSet::Extended
-    }
-}
-
 impl CharacterSet {
     pub fn name(&self) -> &str {
         match self {
@@ -42,38 +51,219 @@ impl CharacterSet {
                 " .'`^\",:;Il!i><~+_-?][}{1)(|/tfjrxnuvczXYUJCLQ0OZmwqpdbkhao*#MW&8%B@$"
             }
             CharacterSet::Blocks => " ░▒▓█",
+            // An empty ramp would leave nothing to index; fall back to a blank
+            CharacterSet::Custom(chars) if chars.is_empty() => " ",
             CharacterSet::Custom(chars) => chars,
         }
     }
 
+    /// Cycle forward; `Custom` comes back empty and must be filled in by the caller
     pub fn next(&self) -> Self {
         match self {
             CharacterSet::Standard => CharacterSet::Extended,
             CharacterSet::Extended => CharacterSet::Blocks,
-            CharacterSet::Blocks => CharacterSet::Standard,
+            CharacterSet::Blocks => CharacterSet::Custom(String::new()),
             CharacterSet::Custom(_) => CharacterSet::Standard,
         }
     }
 
+    /// Cycle backward; `Custom` comes back empty and must be filled in by the caller
     pub fn prev(&self) -> Self {
         match self {
-            CharacterSet::Standard => CharacterSet::Blocks,
+            CharacterSet::Standard => CharacterSet::Custom(String::new()),
             CharacterSet::Extended => CharacterSet::Standard,
             CharacterSet::Blocks => CharacterSet::Extended,
-            CharacterSet::Custom(_) => CharacterSet::Standard,
+            CharacterSet::Custom(_) => CharacterSet::Blocks,
+        }
+    }
+
+    /// Row scale on top of the font aspect; solid block glyphs read closer to square
+    /// than text, so `Blocks` gets slightly taller output
+    pub fn aspect_scale(&self) -> f32 {
+        match self {
+            CharacterSet::Blocks => BLOCKS_ASPECT_SCALE,
+            _ => 1.0,
+        }
+    }
+
+    /// The ramp as characters, with `empty_char` (if any) replacing the first
+    pub fn ramp(&self, empty_char: Option<char>) -> Vec<char> {
+        let mut chars: Vec<char> = self.chars().chars().collect();
+        if let Some(empty) = empty_char {
+            chars[0] = empty;
+        }
+        chars
+    }
+
+    /// Validate a user-supplied dark-to-light ramp for `Custom`
+    pub fn validate_custom(chars: &str) -> Result<()> {
+        match chars.chars().count() {
+            0 => anyhow::bail!("Charset is empty"),
+            1 => anyhow::bail!("Charset needs at least 2 characters"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// [`CharacterSet::aspect_scale`] of `Blocks` (0.55 rows per column at the default font aspect)
+pub const BLOCKS_ASPECT_SCALE: f32 = 1.1;
+
+/// Serialized forms a [`CharacterSet`] is read from
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CharacterSetRepr {
+    /// A built-in name, or any other string as a custom ramp
+    Name(String),
+    /// The older tagged form, `{ Custom = "..." }`
+    Tagged {
+        #[serde(rename = "Custom")]
+        custom: String,
+    },
+}
+
+impl From<CharacterSetRepr> for CharacterSet {
+    fn from(repr: CharacterSetRepr) -> Self {
+        match repr {
+            CharacterSetRepr::Name(name) => match name.as_str() {
+                "Standard" => CharacterSet::Standard,
+                "Extended" => CharacterSet::Extended,
+                "Blocks" => CharacterSet::Blocks,
+                _ => CharacterSet::Custom(name),
+            },
+            CharacterSetRepr::Tagged { custom } => CharacterSet::Custom(custom),
+        }
+    }
+}
+
+impl From<CharacterSet> for String {
+    fn from(charset: CharacterSet) -> Self {
+        match charset {
+            CharacterSet::Custom(chars) => chars,
+            builtin => builtin.name().to_string(),
+        }
+    }
+}
+
+/// Edge detection operator used by edge enhancement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EdgeMethod {
+    /// No edge detection (edge enhancement becomes a no-op)
+    None,
+    /// Sobel 3x3 gradient (weighted center row/column)
+    #[default]
+    Sobel,
+    /// Prewitt 3x3 gradient (uniform ±1 kernels)
+    Prewitt,
+    /// 4-neighbor Laplacian (second derivative)
+    Laplacian,
+}
+
+impl EdgeMethod {
+    pub fn name(&self) -> &str {
+        match self {
+            EdgeMethod::None => "None",
+            EdgeMethod::Sobel => "Sobel",
+            EdgeMethod::Prewitt => "Prewitt",
+            EdgeMethod::Laplacian => "Laplacian",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            EdgeMethod::None => EdgeMethod::Sobel,
+            EdgeMethod::Sobel => EdgeMethod::Prewitt,
+            EdgeMethod::Prewitt => EdgeMethod::Laplacian,
+            EdgeMethod::Laplacian => EdgeMethod::None,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        match self {
+            EdgeMethod::None => EdgeMethod::Laplacian,
+            EdgeMethod::Sobel => EdgeMethod::None,
+            EdgeMethod::Prewitt => EdgeMethod::Sobel,
+            EdgeMethod::Laplacian => EdgeMethod::Prewitt,
+        }
+    }
+}
+
+/// Luminance weighting used to convert the resized image to grayscale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LumaCoeffs {
+    /// ITU-R BT.601 (SDTV) weights
+    #[default]
+    Bt601,
+    /// ITU-R BT.709 (HDTV) weights, often better for film/photo content
+    Bt709,
+}
+
+impl LumaCoeffs {
+    pub fn name(&self) -> &str {
+        match self {
+            LumaCoeffs::Bt601 => "BT.601",
+            LumaCoeffs::Bt709 => "BT.709",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            LumaCoeffs::Bt601 => LumaCoeffs::Bt709,
+            LumaCoeffs::Bt709 => LumaCoeffs::Bt601,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        self.next()
+    }
+
+    /// Luminance (0.0 to 1.0) of an RGB pixel using these coefficients
+    pub fn luminance(&self, r: u8, g: u8, b: u8) -> f32 {
+        match self {
+            LumaCoeffs::Bt601 => rgb_to_luminance_bt601(r, g, b),
+            LumaCoeffs::Bt709 => rgb_to_luminance(r, g, b),
         }
     }
 }
 
 /// Configuration for ASCII rendering
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct AsciiConfig {
     pub target_width: usize,
     pub charset: CharacterSet,
     pub invert: bool,
     pub edge_enhance: bool,
+    /// Operator used when `edge_enhance` is on
+    pub edge_method: EdgeMethod,
+    /// Weight of the edge response in the blend (0.0 = original only, 1.0 = edges only)
+    pub edge_strength: f32,
+    /// Filter used to scale the image to the character grid
+    pub resize_filter: ResizeFilter,
+    /// Coefficients used to compute per-pixel luminance
+    pub luma: LumaCoeffs,
+    /// Width-to-height ratio of a terminal cell, used to correct vertical stretch
+    pub font_aspect: f32,
+    /// Skip the cell correction and keep the raw pixel aspect (one pixel per cell)
+    pub preserve_pixel_aspect: bool,
+    /// Render pixels below `TRANSPARENT_ALPHA` as the first (emptiest) charset character
+    pub alpha_as_space: bool,
+    /// Right-pad lines to equal display width (applied by the worker after rendering)
+    pub pad_lines: bool,
+    /// Saturation multiplier applied before luminance (0.0 = grayscale, up to 3.0)
+    pub saturation: f32,
+    /// Stretch the resized image's luminance to the full range before mapping
+    pub auto_levels: bool,
+    /// Stands in for the ramp's first (darkest, usually space) character, e.g. `.`
+    /// to make empty cells visible
+    pub empty_char: Option<char>,
+    /// Map rows on the rayon thread pool when the grid is large enough
+    pub parallel: bool,
+    /// Color each character with its source pixel (`NoColor` for plain ASCII)
+    pub color_mode: ColorSupport,
 }
 
+/// Alpha below which a pixel counts as transparent when `alpha_as_space` is on
+pub const TRANSPARENT_ALPHA: u8 = 128;
+
 impl Default for AsciiConfig {
     fn default() -> Self {
         Self {
@@ -81,41 +271,113 @@ impl Default for AsciiConfig {
             charset: CharacterSet::Extended,
             invert: false,
             edge_enhance: false,
+            edge_method: EdgeMethod::Sobel,
+            edge_strength: 0.3,
+            resize_filter: ResizeFilter::Lanczos3,
+            luma: LumaCoeffs::Bt601,
+            font_aspect: DEFAULT_FONT_ASPECT,
+            preserve_pixel_aspect: false,
+            alpha_as_space: true,
+            pad_lines: false,
+            saturation: DEFAULT_SATURATION,
+            auto_levels: false,
+            empty_char: None,
+            parallel: true,
+            color_mode: ColorSupport::NoColor,
         }
     }
 }
 
+/// Columns and rows `config` renders `image` to
+pub fn grid_size(image: &DynamicImage, config: &AsciiConfig) -> (usize, usize) {
+    calculate_dimensions(
+        image,
+        config.target_width,
+        config.font_aspect,
+        config.preserve_pixel_aspect,
+        &config.charset,
+    )
+}
+
 /// Render an image as ASCII art
 pub fn render_ascii(image: &DynamicImage, config: &AsciiConfig) -> Result<String> {
+    render_ascii_with_progress(image, config, &mut |_| {})
+}
+
+/// Render an image as ASCII art, reporting the fraction of rows done after each row
+pub fn render_ascii_with_progress(
+    image: &DynamicImage,
+    config: &AsciiConfig,
+    progress: &mut dyn FnMut(f32),
+) -> Result<String> {
+    render_ascii_timed(image, config, progress).map(|(output, _)| output)
+}
+
+/// Render an image as ASCII art, also returning how long resizing and mapping took
+pub fn render_ascii_timed(
+    image: &DynamicImage,
+    config: &AsciiConfig,
+    progress: &mut dyn FnMut(f32),
+) -> Result<(String, RenderTiming)> {
+    render_ascii_streaming(image, config, progress, &mut |_| {})
+}
+
+/// Render an image as ASCII art, passing finished rows (newline-terminated) to `on_rows`
+/// as they are produced
+pub fn render_ascii_streaming(
+    image: &DynamicImage,
+    config: &AsciiConfig,
+    progress: &mut dyn FnMut(f32),
+    on_rows: &mut dyn FnMut(&str),
+) -> Result<(String, RenderTiming)> {
+    if image.width() == 0 || image.height() == 0 {
+        anyhow::bail!("Image has no pixels");
+    }
+    let start = Instant::now();
+
     // Calculate target dimensions
     // Characters are approximately 2:1 aspect ratio (taller than wide)
-    let (width, height) = calculate_dimensions(image, config.target_width);
+    let (width, height) = grid_size(image, config);
 
     // Resize image
     let resized = image.resize_exact(
         width as u32,
         height as u32,
-        image::imageops::FilterType::Lanczos3,
+        config.resize_filter.filter_type(),
     );
+    let mut resized = saturate_image(resized, config.saturation);
+    if config.auto_levels {
+        resized = auto_levels(resized, config.luma);
+    }
+    let resize_ms = elapsed_ms(start);
+    let process_start = Instant::now();
 
-    // Convert to grayscale
-    let gray = resized.to_luma8();
+    // Convert to grayscale with the selected luminance weighting
+    let rgba = resized.to_rgba8();
+    let gray = GrayImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, _] = rgba.get_pixel(x, y).0;
+        Luma([luminance_to_gray(config.luma.luminance(r, g, b))])
+    });
 
     // Apply edge enhancement if requested
     let processed = if config.edge_enhance {
-        apply_edge_enhancement(&gray)
+        apply_edge_enhancement(&gray, config.edge_method, config.edge_strength)
     } else {
         gray
     };
 
     // Map pixels to characters
-    let charset_chars: Vec<char> = config.charset.chars().chars().collect();
+    let charset_chars = config.charset.ramp(config.empty_char);
     let num_chars = charset_chars.len();
 
-    let mut output = String::with_capacity((width + 1) * height);
-
-    for y in 0..height {
+    let output = render_rows_streaming(width, height, config.parallel, progress, on_rows, |y| {
+        let mut line = String::with_capacity(width);
         for x in 0..width {
+            if config.alpha_as_space && rgba.get_pixel(x as u32, y as u32)[3] < TRANSPARENT_ALPHA {
+                line.push(charset_chars[0]);
+                continue;
+            }
+
             let pixel = processed.get_pixel(x as u32, y as u32);
             let luminance = pixel.0[0] as f32 / 255.0;
 
@@ -127,24 +389,43 @@ pub fn render_ascii(image: &DynamicImage, config: &AsciiConfig) -> Result<String
             };
 
             // Map luminance to character index
-            let index = (luminance * (num_chars - 1) as f32).round() as usize;
-            let index = index.min(num_chars - 1);
-
-            output.push(charset_chars[index]);
+            let glyph = charset_chars[luminance_to_index(luminance, num_chars)];
+            if config.color_mode == ColorSupport::NoColor {
+                line.push(glyph);
+            } else {
+                let [r, g, b, _] = rgba.get_pixel(x as u32, y as u32).0;
+                line.push_str(&format_fg_color(Rgb::new(r, g, b), config.color_mode));
+                line.push(glyph);
+                line.push_str(ANSI_RESET);
+            }
         }
-        output.push('\n');
-    }
+        line
+    });
 
-    Ok(output)
+    let timing = RenderTiming {
+        resize_ms,
+        process_ms: elapsed_ms(process_start),
+        total_ms: elapsed_ms(start),
+    };
+    Ok((output, timing))
 }
 
 /// Calculate output dimensions maintaining aspect ratio
-fn calculate_dimensions(image: &DynamicImage, target_width: usize) -> (usize, usize) {
+fn calculate_dimensions(
+    image: &DynamicImage,
+    target_width: usize,
+    font_aspect: f32,
+    preserve_pixel_aspect: bool,
+    charset: &CharacterSet,
+) -> (usize, usize) {
     let (img_width, img_height) = image.dimensions();
     let aspect_ratio = img_width as f32 / img_height as f32;
 
-    // Terminal characters are roughly 2:1 aspect ratio
-    let char_aspect = 0.5;
+    // Terminal cells are taller than wide; squash rows by the font's width/height ratio
+    let mut char_aspect = cell_aspect(font_aspect, preserve_pixel_aspect);
+    if !preserve_pixel_aspect {
+        char_aspect *= charset.aspect_scale();
+    }
 
     let width = target_width;
     let height = ((target_width as f32 / aspect_ratio) * char_aspect).round() as usize;
@@ -153,34 +434,62 @@ fn calculate_dimensions(image: &DynamicImage, target_width: usize) -> (usize, us
     (width.max(1), height.max(1))
 }
 
-/// Apply simple edge enhancement using Sobel operator
-fn apply_edge_enhancement(image: &GrayImage) -> GrayImage {
+/// Sobel kernels
+const SOBEL_X: [[i32; 3]; 3] = [[-1, 0, 1], [-2, 0, 2], [-1, 0, 1]];
+const SOBEL_Y: [[i32; 3]; 3] = [[-1, -2, -1], [0, 0, 0], [1, 2, 1]];
+
+/// Prewitt kernels
+const PREWITT_X: [[i32; 3]; 3] = [[-1, 0, 1], [-1, 0, 1], [-1, 0, 1]];
+const PREWITT_Y: [[i32; 3]; 3] = [[-1, -1, -1], [0, 0, 0], [1, 1, 1]];
+
+/// 4-neighbor Laplacian kernel
+const LAPLACIAN: [[i32; 3]; 3] = [[0, 1, 0], [1, -4, 1], [0, 1, 0]];
+
+/// Apply edge enhancement using the selected operator
+///
+/// The edge response is blended with the original: `original * (1 - strength) + edge * strength`.
+/// Images under 3x3 have no interior for the kernels and are returned unchanged.
+fn apply_edge_enhancement(image: &GrayImage, method: EdgeMethod, strength: f32) -> GrayImage {
     let (width, height) = image.dimensions();
+    if method == EdgeMethod::None || width < 3 || height < 3 {
+        return image.clone();
+    }
+
     let mut output = GrayImage::new(width, height);
+    let strength = strength.clamp(0.0, 1.0);
 
-    // Sobel kernels
-    let sobel_x: [[i32; 3]; 3] = [[-1, 0, 1], [-2, 0, 2], [-1, 0, 1]];
-    let sobel_y: [[i32; 3]; 3] = [[-1, -2, -1], [0, 0, 0], [1, 2, 1]];
+    // Convolve the 3x3 neighbourhood around (x, y) with a kernel
+    let convolve = |x: u32, y: u32, kernel: &[[i32; 3]; 3]| -> i32 {
+        let mut sum = 0i32;
+        for ky in 0..3 {
+            for kx in 0..3 {
+                let px = image.get_pixel(x + kx - 1, y + ky - 1).0[0] as i32;
+                sum += px * kernel[ky as usize][kx as usize];
+            }
+        }
+        sum
+    };
 
     for y in 1..(height - 1) {
         for x in 1..(width - 1) {
-            let mut gx = 0i32;
-            let mut gy = 0i32;
-
-            for ky in 0..3 {
-                for kx in 0..3 {
-                    let px = image.get_pixel(x + kx - 1, y + ky - 1).0[0] as i32;
-                    gx += px * sobel_x[ky as usize][kx as usize];
-                    gy += px * sobel_y[ky as usize][kx as usize];
+            // Calculate edge response
+            let edge = match method {
+                EdgeMethod::Sobel => {
+                    let gx = convolve(x, y, &SOBEL_X);
+                    let gy = convolve(x, y, &SOBEL_Y);
+                    ((gx * gx + gy * gy) as f32).sqrt()
                 }
-            }
-
-            // Calculate gradient magnitude
-            let gradient = ((gx * gx + gy * gy) as f32).sqrt();
+                EdgeMethod::Prewitt => {
+                    let gx = convolve(x, y, &PREWITT_X);
+                    let gy = convolve(x, y, &PREWITT_Y);
+                    ((gx * gx + gy * gy) as f32).sqrt()
+                }
+                EdgeMethod::Laplacian => convolve(x, y, &LAPLACIAN).abs() as f32,
+                EdgeMethod::None => 0.0,
+            };
 
-            // Blend original with edge (70% original, 30% edge)
             let original = image.get_pixel(x, y).0[0] as f32;
-            let enhanced = (original * 0.7 + gradient * 0.3).min(255.0) as u8;
+            let enhanced = (original * (1.0 - strength) + edge * strength).min(255.0) as u8;
 
             output.put_pixel(x, y, Luma([enhanced]));
         }
@@ -199,23 +508,231 @@ fn apply_edge_enhancement(image: &GrayImage) -> GrayImage {
     output
 }
 
-/// Map a luminance value (0.0-1.0) to a character
-pub fn map_luminance_to_char(luminance: f32, charset: &CharacterSet) -> char {
-    let chars: Vec<char> = charset.chars().chars().collect();
-    let num_chars = chars.len();
-    let index = (luminance.clamp(0.0, 1.0) * (num_chars - 1) as f32).round() as usize;
-    chars[index.min(num_chars - 1)]
+/// Map a luminance value (0.0-1.0) to a character, with `empty_char` (if any)
+/// standing in for the darkest one
+pub fn map_luminance_to_char(
+    luminance: f32,
+    charset: &CharacterSet,
+    empty_char: Option<char>,
+) -> char {
+    let chars = charset.ramp(empty_char);
+    chars[luminance_to_index(luminance, chars.len())]
+}
+
+/// Index into a ramp of `num_chars` characters; a 1-char ramp always yields 0
+fn luminance_to_index(luminance: f32, num_chars: usize) -> usize {
+    let last = num_chars.saturating_sub(1);
+    if last == 0 {
+        return 0;
+    }
+    let index = (luminance.clamp(0.0, 1.0) * last as f32).round() as usize;
+    index.min(last)
+}
+
+/// Order `chars` from least to most ink, as `CharacterSet::Custom` expects
+///
+/// `coverage` gives the inked fraction of a character's cell. Duplicates are
+/// dropped and ties keep their input order.
+pub fn sort_by_coverage(chars: &str, coverage: impl Fn(char) -> f32) -> String {
+    let mut unique: Vec<char> = Vec::new();
+    for c in chars.chars() {
+        if !unique.contains(&c) {
+            unique.push(c);
+        }
+    }
+
+    let mut measured: Vec<(char, f32)> = unique.into_iter().map(|c| (c, coverage(c))).collect();
+    measured.sort_by(|a, b| a.1.total_cmp(&b.1));
+    measured.into_iter().map(|(c, _)| c).collect()
+}
+
+/// Build a luminance ramp from `chars` by rasterizing each with the bundled
+/// font and measuring its ink coverage
+#[cfg(feature = "coverage-ramp")]
+pub fn build_ramp_from_coverage(chars: &str) -> String {
+    use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+
+    const FONT_BYTES: &[u8] = include_bytes!("../../assets/fonts/DejaVuSansMono.ttf");
+    let Ok(font) = FontRef::try_from_slice(FONT_BYTES) else {
+        return chars.to_string();
+    };
+    let scaled = font.as_scaled(PxScale::from(32.0));
+
+    sort_by_coverage(chars, |c| {
+        let id = font.glyph_id(c);
+        let cell_area = scaled.h_advance(id) * scaled.height();
+        if cell_area <= 0.0 {
+            return 0.0;
+        }
+        let Some(outline) = font.outline_glyph(id.with_scale(scaled.scale())) else {
+            // Whitespace has no outline
+            return 0.0;
+        };
+        let mut ink = 0.0;
+        outline.draw(|_, _, alpha| ink += alpha);
+        ink / cell_area
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_charset_serializes_as_plain_string() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Wrapper {
+            charset: CharacterSet,
+        }
+
+        let cases = [
+            (CharacterSet::Standard, "charset = \"Standard\"\n"),
+            (CharacterSet::Extended, "charset = \"Extended\"\n"),
+            (CharacterSet::Blocks, "charset = \"Blocks\"\n"),
+            (
+                CharacterSet::Custom(" . o O @".to_string()),
+                "charset = \" . o O @\"\n",
+            ),
+        ];
+        for (charset, expected) in cases {
+            let wrapper = Wrapper { charset };
+            let toml_str = toml::to_string(&wrapper).unwrap();
+            assert_eq!(toml_str, expected);
+            assert_eq!(toml::from_str::<Wrapper>(&toml_str).unwrap(), wrapper);
+
+            let json = serde_json::to_string(&wrapper).unwrap();
+            assert_eq!(serde_json::from_str::<Wrapper>(&json).unwrap(), wrapper);
+        }
+
+        // Hand-edited ramps and the older tagged form both load as custom sets
+        let parsed: Wrapper = toml::from_str("charset = \"@%#*+=-:. \"").unwrap();
+        assert_eq!(
+            parsed.charset,
+            CharacterSet::Custom("@%#*+=-:. ".to_string())
+        );
+        let legacy: Wrapper = toml::from_str("charset = { Custom = \" .oO@\" }").unwrap();
+        assert_eq!(legacy.charset, CharacterSet::Custom(" .oO@".to_string()));
+    }
+
     #[test]
     fn test_charset_cycling() {
         let charset = CharacterSet::Standard;
         assert_eq!(charset.next(), CharacterSet::Extended);
-        assert_eq!(charset.prev(), CharacterSet::Blocks);
+        assert_eq!(charset.prev(), CharacterSet::Custom(String::new()));
+        assert_eq!(
+            CharacterSet::Blocks.next(),
+            CharacterSet::Custom(String::new())
+        );
+        assert_eq!(
+            CharacterSet::Custom("ab".into()).next(),
+            CharacterSet::Standard
+        );
+    }
+
+    #[test]
+    fn test_sort_by_coverage() {
+        let coverage = |c: char| match c {
+            ' ' => 0.0,
+            '.' => 0.05,
+            ':' => 0.1,
+            '+' => 0.2,
+            '#' => 0.5,
+            '@' => 0.7,
+            _ => 1.0,
+        };
+        assert_eq!(sort_by_coverage("@#+:. ", coverage), " .:+#@");
+        assert_eq!(sort_by_coverage(".@.#", coverage), ".#@");
+        assert_eq!(sort_by_coverage("", coverage), "");
+    }
+
+    #[cfg(feature = "coverage-ramp")]
+    #[test]
+    fn test_build_ramp_from_coverage() {
+        assert_eq!(build_ramp_from_coverage("@. "), " .@");
+    }
+
+    #[test]
+    fn test_custom_charset_validation() {
+        assert!(CharacterSet::validate_custom("").is_err());
+        assert!(CharacterSet::validate_custom("#").is_err());
+        assert!(CharacterSet::validate_custom(" #").is_ok());
+    }
+
+    #[test]
+    fn test_degenerate_custom_charsets_do_not_panic() {
+        let img = DynamicImage::ImageLuma8(hard_edge_image(8, 8));
+
+        let single = AsciiConfig {
+            target_width: 4,
+            charset: CharacterSet::Custom("#".into()),
+            ..AsciiConfig::default()
+        };
+        let output = render_ascii(&img, &single).unwrap();
+        assert!(output.chars().all(|c| c == '#' || c == '\n'));
+
+        let empty = AsciiConfig {
+            charset: CharacterSet::Custom(String::new()),
+            ..single
+        };
+        let output = render_ascii(&img, &empty).unwrap();
+        assert!(!output.is_empty());
+        assert!(output.chars().all(|c| c == ' ' || c == '\n'));
+    }
+
+    #[test]
+    fn test_timing_phases_add_up_to_total() {
+        // Wide enough that resizing takes measurable time; the hard-edge
+        // fixture's gradient would overflow u8 at this width.
+        let img = DynamicImage::ImageLuma8(GrayImage::from_fn(400, 300, |x, y| {
+            Luma([((x + y) % 256) as u8])
+        }));
+        let config = AsciiConfig {
+            target_width: 120,
+            edge_enhance: true,
+            ..AsciiConfig::default()
+        };
+
+        let (output, timing) = render_ascii_timed(&img, &config, &mut |_| {}).unwrap();
+        assert_eq!(output, render_ascii(&img, &config).unwrap());
+
+        let phases = timing.resize_ms + timing.process_ms;
+        assert!(timing.resize_ms > 0.0 && timing.process_ms > 0.0);
+        assert!(phases <= timing.total_ms, "{:?}", timing);
+        assert!(
+            timing.total_ms - phases < 1.0 + timing.total_ms * 0.1,
+            "{:?}",
+            timing
+        );
+    }
+
+    #[test]
+    fn test_tiny_and_thin_images_with_edge_enhance() {
+        let config = AsciiConfig {
+            edge_enhance: true,
+            ..AsciiConfig::default()
+        };
+        for (width, height) in [(1, 1), (1, 50), (50, 1)] {
+            let img = DynamicImage::ImageLuma8(GrayImage::from_pixel(width, height, Luma([200])));
+            let output = render_ascii(&img, &config).unwrap();
+            assert!(output.chars().any(|c| c != '\n'));
+        }
+
+        let empty = DynamicImage::ImageLuma8(GrayImage::new(0, 5));
+        assert!(render_ascii(&empty, &config).is_err());
+    }
+
+    #[test]
+    fn test_degenerate_charsets_map_luminance() {
+        let empty = CharacterSet::Custom(String::new());
+        assert_eq!(empty.chars(), " ");
+        for luminance in [0.0, 0.5, 1.0] {
+            assert_eq!(map_luminance_to_char(luminance, &empty, None), ' ');
+        }
+
+        let single = CharacterSet::Custom("#".into());
+        for luminance in [-1.0, 0.0, 0.5, 1.0, 2.0] {
+            assert_eq!(map_luminance_to_char(luminance, &single, None), '#');
+        }
     }
 
     #[test]
@@ -223,23 +740,91 @@ mod tests {
         let charset = CharacterSet::Standard;
 
         // Dark (low luminance) should map to first char (space)
-        assert_eq!(map_luminance_to_char(0.0, &charset), ' ');
+        assert_eq!(map_luminance_to_char(0.0, &charset, None), ' ');
 
         // Bright (high luminance) should map to last char (@)
-        assert_eq!(map_luminance_to_char(1.0, &charset), '@');
+        assert_eq!(map_luminance_to_char(1.0, &charset, None), '@');
+
+        // An empty-char override only replaces the darkest character
+        assert_eq!(map_luminance_to_char(0.0, &charset, Some('.')), '.');
+        assert_eq!(map_luminance_to_char(1.0, &charset, Some('.')), '@');
     }
 
     #[test]
     fn test_dimension_calculation() {
         use image::RgbImage;
+        let standard = CharacterSet::Standard;
 
         // Create a 100x100 test image
         let img = DynamicImage::ImageRgb8(RgbImage::new(100, 100));
-        let (width, height) = calculate_dimensions(&img, 80);
+        let (width, height) = calculate_dimensions(&img, 80, DEFAULT_FONT_ASPECT, false, &standard);
 
         assert_eq!(width, 80);
         // Height should be roughly half of width due to char aspect ratio
-        assert!(height >= 30 && height <= 50);
+        assert!((30..=50).contains(&height));
+    }
+
+    #[test]
+    fn test_font_aspect_scales_height() {
+        let standard = CharacterSet::Standard;
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(100, 100));
+        assert_eq!(
+            calculate_dimensions(&img, 80, 0.5, false, &standard),
+            (80, 40)
+        );
+        assert_eq!(
+            calculate_dimensions(&img, 80, 0.4, false, &standard),
+            (80, 32)
+        );
+        assert_eq!(
+            calculate_dimensions(&img, 80, 0.6, false, &standard),
+            (80, 48)
+        );
+        // Out-of-range ratios are clamped rather than producing extreme output
+        assert_eq!(
+            calculate_dimensions(&img, 80, 5.0, false, &standard),
+            (80, 64)
+        );
+    }
+
+    #[test]
+    fn test_blocks_charset_renders_taller() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(100, 100));
+        let blocks = calculate_dimensions(&img, 80, 0.5, false, &CharacterSet::Blocks);
+        let standard = calculate_dimensions(&img, 80, 0.5, false, &CharacterSet::Standard);
+        assert_eq!(standard, (80, 40));
+        assert_eq!(blocks, (80, 44));
+        assert_eq!(
+            calculate_dimensions(&img, 80, 0.5, false, &CharacterSet::Custom(" #".into())),
+            standard
+        );
+        // Square pixels stay square whatever the charset
+        assert_eq!(
+            calculate_dimensions(&img, 80, 0.5, true, &CharacterSet::Blocks),
+            (80, 80)
+        );
+
+        let config = AsciiConfig {
+            target_width: 20,
+            charset: CharacterSet::Blocks,
+            ..AsciiConfig::default()
+        };
+        assert_eq!(render_ascii(&img, &config).unwrap().lines().count(), 11);
+    }
+
+    #[test]
+    fn test_preserve_pixel_aspect_keeps_square_grid() {
+        let standard = CharacterSet::Standard;
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(100, 100));
+        assert_eq!(
+            calculate_dimensions(&img, 80, 0.5, true, &standard),
+            (80, 80)
+        );
+        let wide = DynamicImage::ImageRgb8(image::RgbImage::new(200, 100));
+        assert_eq!(
+            calculate_dimensions(&wide, 80, 0.5, true, &standard),
+            (80, 40)
+        );
     }
 
     #[test]
@@ -260,10 +845,255 @@ mod tests {
             charset: CharacterSet::Standard,
             invert: false,
             edge_enhance: false,
+            ..AsciiConfig::default()
         };
 
         let result = render_ascii(&DynamicImage::ImageRgb8(img), &config).unwrap();
         assert!(!result.is_empty());
         assert!(result.contains('\n'));
     }
+
+    #[test]
+    fn test_color_mode_wraps_each_glyph_in_truecolor() {
+        let img = image::RgbImage::from_pixel(8, 8, image::Rgb([200, 40, 10]));
+        let config = AsciiConfig {
+            target_width: 4,
+            color_mode: ColorSupport::TrueColor,
+            ..AsciiConfig::default()
+        };
+
+        let result = render_ascii(&DynamicImage::ImageRgb8(img), &config).unwrap();
+        let first_line = result.lines().next().unwrap();
+        assert_eq!(first_line.matches("\x1b[38;2;200;40;10m").count(), 4);
+        assert_eq!(first_line.matches(ANSI_RESET).count(), 4);
+
+        // Plain ASCII stays free of escapes
+        let plain = render_ascii(
+            &DynamicImage::ImageRgb8(image::RgbImage::from_pixel(8, 8, image::Rgb([200, 40, 10]))),
+            &AsciiConfig {
+                target_width: 4,
+                ..AsciiConfig::default()
+            },
+        )
+        .unwrap();
+        assert!(!plain.contains('\x1b'));
+    }
+
+    /// Gray image with a soft vertical gradient and a hard edge in the middle
+    fn hard_edge_image(width: u32, height: u32) -> GrayImage {
+        GrayImage::from_fn(width, height, |x, _| {
+            let base = if x < width / 2 { 60 } else { 120 };
+            Luma([base + x as u8])
+        })
+    }
+
+    #[test]
+    fn test_transparent_border_renders_as_spaces() {
+        // Opaque white center inside a fully transparent (but white) 2-pixel border
+        let img = image::RgbaImage::from_fn(8, 8, |x, y| {
+            let border = x < 2 || y < 2 || x >= 6 || y >= 6;
+            image::Rgba([255, 255, 255, if border { 0 } else { 255 }])
+        });
+        let config = AsciiConfig {
+            target_width: 8,
+            charset: CharacterSet::Standard,
+            resize_filter: ResizeFilter::Nearest,
+            font_aspect: 0.8,
+            ..AsciiConfig::default()
+        };
+
+        let output = render_ascii(&DynamicImage::ImageRgba8(img.clone()), &config).unwrap();
+        let rows: Vec<&str> = output.lines().collect();
+        assert!(rows[0].chars().all(|c| c == ' '));
+        assert!(rows.last().unwrap().chars().all(|c| c == ' '));
+        assert!(rows
+            .iter()
+            .all(|row| row.starts_with("  ") && row.ends_with("  ")));
+        assert!(rows.iter().any(|row| row.contains('@')));
+
+        // With the option off the transparent white border renders as solid
+        let opaque = AsciiConfig {
+            alpha_as_space: false,
+            ..config
+        };
+        let output = render_ascii(&DynamicImage::ImageRgba8(img), &opaque).unwrap();
+        assert!(output.lines().next().unwrap().chars().all(|c| c == '@'));
+    }
+
+    #[test]
+    fn test_edge_methods_differ() {
+        let gray = hard_edge_image(16, 8);
+        let methods = [
+            EdgeMethod::None,
+            EdgeMethod::Sobel,
+            EdgeMethod::Prewitt,
+            EdgeMethod::Laplacian,
+        ];
+        let outputs: Vec<GrayImage> = methods
+            .iter()
+            .map(|m| apply_edge_enhancement(&gray, *m, 0.3))
+            .collect();
+
+        for i in 0..outputs.len() {
+            for j in (i + 1)..outputs.len() {
+                assert_ne!(
+                    outputs[i], outputs[j],
+                    "{:?} and {:?} should differ",
+                    methods[i], methods[j]
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_methods_render_non_empty() {
+        let gray = hard_edge_image(40, 40);
+        let img = DynamicImage::ImageLuma8(gray);
+
+        for method in [
+            EdgeMethod::Sobel,
+            EdgeMethod::Prewitt,
+            EdgeMethod::Laplacian,
+        ] {
+            let config = AsciiConfig {
+                target_width: 20,
+                edge_enhance: true,
+                edge_method: method,
+                ..AsciiConfig::default()
+            };
+            let result = render_ascii(&img, &config).unwrap();
+            assert!(!result.trim().is_empty(), "Empty output for {:?}", method);
+        }
+    }
+
+    #[test]
+    fn test_edge_strength_zero_keeps_original() {
+        let gray = hard_edge_image(16, 8);
+        let result = apply_edge_enhancement(&gray, EdgeMethod::Sobel, 0.0);
+        assert_eq!(result, gray);
+    }
+
+    #[test]
+    fn test_nearest_filter_keeps_hard_edges() {
+        // Two-tone "pixel art" source upscaled onto the character grid
+        let gray = GrayImage::from_fn(4, 4, |x, _| Luma([if x < 2 { 0 } else { 255 }]));
+        let img = DynamicImage::ImageLuma8(gray);
+
+        let render_with = |resize_filter| {
+            let config = AsciiConfig {
+                target_width: 16,
+                charset: CharacterSet::Standard,
+                resize_filter,
+                ..AsciiConfig::default()
+            };
+            render_ascii(&img, &config).unwrap()
+        };
+
+        let nearest = render_with(ResizeFilter::Nearest);
+        assert!(nearest.chars().all(|c| c == ' ' || c == '@' || c == '\n'));
+
+        let lanczos = render_with(ResizeFilter::Lanczos3);
+        assert!(lanczos.chars().any(|c| c != ' ' && c != '@' && c != '\n'));
+    }
+
+    #[test]
+    fn test_auto_levels_spans_full_charset() {
+        // Low-contrast horizontal gradient covering gray levels 100..=150
+        let gray = GrayImage::from_fn(51, 8, |x, _| Luma([100 + x as u8]));
+        let img = DynamicImage::ImageLuma8(gray);
+        let charset = CharacterSet::Standard;
+        let ramp = charset.chars();
+        let (darkest, lightest) = (ramp.chars().next().unwrap(), ramp.chars().last().unwrap());
+
+        let render_with = |auto_levels| {
+            let config = AsciiConfig {
+                target_width: 51,
+                charset: charset.clone(),
+                resize_filter: ResizeFilter::Nearest,
+                auto_levels,
+                ..AsciiConfig::default()
+            };
+            render_ascii(&img, &config).unwrap()
+        };
+
+        let flat = render_with(false);
+        assert!(!flat.contains(darkest) && !flat.contains(lightest));
+
+        let stretched = render_with(true);
+        assert!(stretched.contains(darkest) && stretched.contains(lightest));
+    }
+
+    #[test]
+    fn test_empty_char_replaces_only_darkest_cells() {
+        // Black left half, then a gradient up to white
+        let gray = GrayImage::from_fn(20, 4, |x, _| {
+            Luma([if x < 10 { 0 } else { (x as u8 - 9) * 25 }])
+        });
+        let img = DynamicImage::ImageLuma8(gray);
+        let render_with = |empty_char| {
+            let config = AsciiConfig {
+                target_width: 20,
+                charset: CharacterSet::Standard,
+                resize_filter: ResizeFilter::Nearest,
+                preserve_pixel_aspect: true,
+                empty_char,
+                ..AsciiConfig::default()
+            };
+            render_ascii(&img, &config).unwrap()
+        };
+
+        let plain = render_with(None);
+        let dotted = render_with(Some('·'));
+        assert_eq!(plain.lines().count(), dotted.lines().count());
+        for (plain, dotted) in plain.lines().zip(dotted.lines()) {
+            assert_eq!(plain.chars().count(), dotted.chars().count());
+            for (a, b) in plain.chars().zip(dotted.chars()) {
+                if a == ' ' {
+                    assert_eq!(b, '·');
+                } else {
+                    assert_eq!(a, b);
+                }
+            }
+        }
+        assert!(dotted.starts_with("··········"));
+        assert!(dotted.contains('@'));
+    }
+
+    #[test]
+    fn test_bt709_brightens_saturated_green() {
+        let img =
+            DynamicImage::ImageRgb8(image::RgbImage::from_pixel(8, 8, image::Rgb([0, 255, 0])));
+        let charset = CharacterSet::Standard;
+        let chars: Vec<char> = charset.chars().chars().collect();
+
+        let render_with = |luma| {
+            let config = AsciiConfig {
+                target_width: 4,
+                charset: charset.clone(),
+                luma,
+                ..AsciiConfig::default()
+            };
+            let output = render_ascii(&img, &config).unwrap();
+            let c = output.chars().next().unwrap();
+            chars.iter().position(|&ch| ch == c).unwrap()
+        };
+
+        assert!(render_with(LumaCoeffs::Bt709) > render_with(LumaCoeffs::Bt601));
+    }
+
+    #[test]
+    fn test_progress_reported_per_row() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(40, 40));
+        let config = AsciiConfig {
+            target_width: 20,
+            ..AsciiConfig::default()
+        };
+
+        let mut reports = Vec::new();
+        let output = render_ascii_with_progress(&img, &config, &mut |f| reports.push(f)).unwrap();
+
+        assert_eq!(reports.len(), output.lines().count());
+        assert!(reports.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(*reports.last().unwrap(), 1.0);
+    }
 }
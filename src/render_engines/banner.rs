@@ -0,0 +1,119 @@
+//! Banner text engine
+//!
+//! Renders text as large multi-row ASCII letters, like `figlet`, from a small
+//! embedded font.
+
+/// Rows in every banner glyph
+pub const BANNER_HEIGHT: usize = 5;
+
+/// Blank columns between neighbouring glyphs
+pub const LETTER_SPACING: usize = 1;
+
+/// Font rows for `c`; letters are case-insensitive and unknown characters draw as `?`
+pub fn glyph(c: char) -> &'static [&'static str; BANNER_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        'A' => &[" ### ", "#   #", "#####", "#   #", "#   #"],
+        'B' => &["#### ", "#   #", "#### ", "#   #", "#### "],
+        'C' => &[" ####", "#    ", "#    ", "#    ", " ####"],
+        'D' => &["#### ", "#   #", "#   #", "#   #", "#### "],
+        'E' => &["#####", "#    ", "#### ", "#    ", "#####"],
+        'F' => &["#####", "#    ", "#### ", "#    ", "#    "],
+        'G' => &[" ####", "#    ", "#  ##", "#   #", " ####"],
+        'H' => &["#   #", "#   #", "#####", "#   #", "#   #"],
+        'I' => &["###", " # ", " # ", " # ", "###"],
+        'J' => &["  ###", "    #", "    #", "#   #", " ### "],
+        'K' => &["#   #", "#  # ", "###  ", "#  # ", "#   #"],
+        'L' => &["#    ", "#    ", "#    ", "#    ", "#####"],
+        'M' => &["#   #", "## ##", "# # #", "#   #", "#   #"],
+        'N' => &["#   #", "##  #", "# # #", "#  ##", "#   #"],
+        'O' => &[" ### ", "#   #", "#   #", "#   #", " ### "],
+        'P' => &["#### ", "#   #", "#### ", "#    ", "#    "],
+        'Q' => &[" ### ", "#   #", "# # #", "#  # ", " ## #"],
+        'R' => &["#### ", "#   #", "#### ", "#  # ", "#   #"],
+        'S' => &[" ####", "#    ", " ### ", "    #", "#### "],
+        'T' => &["#####", "  #  ", "  #  ", "  #  ", "  #  "],
+        'U' => &["#   #", "#   #", "#   #", "#   #", " ### "],
+        'V' => &["#   #", "#   #", "#   #", " # # ", "  #  "],
+        'W' => &["#   #", "#   #", "# # #", "## ##", "#   #"],
+        'X' => &["#   #", " # # ", "  #  ", " # # ", "#   #"],
+        'Y' => &["#   #", " # # ", "  #  ", "  #  ", "  #  "],
+        'Z' => &["#####", "   # ", "  #  ", " #   ", "#####"],
+        '0' => &[" ### ", "#  ##", "# # #", "##  #", " ### "],
+        '1' => &[" # ", "## ", " # ", " # ", "###"],
+        '2' => &[" ### ", "#   #", "  ## ", " #   ", "#####"],
+        '3' => &["#### ", "    #", " ### ", "    #", "#### "],
+        '4' => &["#   #", "#   #", "#####", "    #", "    #"],
+        '5' => &["#####", "#    ", "#### ", "    #", "#### "],
+        '6' => &[" ### ", "#    ", "#### ", "#   #", " ### "],
+        '7' => &["#####", "    #", "   # ", "  #  ", "  #  "],
+        '8' => &[" ### ", "#   #", " ### ", "#   #", " ### "],
+        '9' => &[" ### ", "#   #", " ####", "    #", " ### "],
+        ' ' => &["   ", "   ", "   ", "   ", "   "],
+        '!' => &["#", "#", "#", " ", "#"],
+        '.' => &[" ", " ", " ", " ", "#"],
+        '-' => &["   ", "   ", "###", "   ", "   "],
+        _ => &[" ### ", "#   #", "  ## ", "     ", "  #  "],
+    }
+}
+
+/// Columns `c` takes up, not counting [`LETTER_SPACING`]
+pub fn glyph_width(c: char) -> usize {
+    glyph(c)[0].len()
+}
+
+/// Render `text` as banner letters, [`BANNER_HEIGHT`] rows per input line
+pub fn render_banner(text: &str) -> String {
+    let spacing = " ".repeat(LETTER_SPACING);
+    text.lines()
+        .map(|line| {
+            (0..BANNER_HEIGHT)
+                .map(|row| {
+                    line.chars()
+                        .map(|c| glyph(c)[row])
+                        .collect::<Vec<_>>()
+                        .join(&spacing)
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_banner_rows_match_glyph_widths() {
+        let banner = render_banner("HI");
+        let rows: Vec<&str> = banner.split('\n').collect();
+        assert_eq!(rows.len(), BANNER_HEIGHT);
+
+        let expected = glyph_width('H') + LETTER_SPACING + glyph_width('I');
+        assert!(rows.iter().all(|row| row.chars().count() == expected));
+        assert_eq!(rows[2], "#####  # ");
+    }
+
+    #[test]
+    fn test_font_glyphs_are_rectangular() {
+        let chars = ('A'..='Z')
+            .chain('0'..='9')
+            .chain([' ', '!', '.', '-', '?']);
+        for c in chars {
+            let width = glyph_width(c);
+            assert!(width > 0);
+            assert!(glyph(c).iter().all(|row| row.len() == width), "{:?}", c);
+        }
+    }
+
+    #[test]
+    fn test_banner_lowercase_unknown_and_lines() {
+        assert_eq!(render_banner("hi"), render_banner("HI"));
+        assert_eq!(render_banner("~"), render_banner("?"));
+
+        let two_lines = render_banner("A\nB");
+        assert_eq!(two_lines.lines().count(), 2 * BANNER_HEIGHT);
+        assert!(render_banner("").is_empty());
+    }
+}
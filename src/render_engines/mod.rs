@@ -3,5 +3,371 @@
 //! Contains all rendering engines for different output modes.
 
 pub mod ascii;
+pub mod banner;
 pub mod text_stylizer;
 pub mod unicode;
+
+use crate::color_space::{luminance_to_gray, Rgb};
+use ascii::LumaCoeffs;
+use image::imageops::FilterType;
+use image::DynamicImage;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Wall-clock time spent in each phase of an image render, in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RenderTiming {
+    /// Scaling to the character grid, plus saturation and negation
+    pub resize_ms: f64,
+    /// Mapping pixels to characters and colors, including edge detection
+    pub process_ms: f64,
+    pub total_ms: f64,
+}
+
+impl RenderTiming {
+    /// Short breakdown for the status bar, e.g. "resize 3.2ms, map 11.0ms"
+    pub fn summary(&self) -> String {
+        format!(
+            "resize {:.1}ms, map {:.1}ms",
+            self.resize_ms, self.process_ms
+        )
+    }
+}
+
+/// Milliseconds since `start`, with sub-millisecond precision
+pub(crate) fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Output cells below which spreading rows across threads costs more than it saves
+pub const PARALLEL_MIN_CELLS: usize = 16_000;
+
+/// Build every row with `row` and join them with newlines, in row order
+///
+/// With `parallel` set and a large enough grid, chunks of rows are rendered on the
+/// rayon pool; progress is reported between chunks, on the calling thread.
+pub(crate) fn render_rows<F>(
+    width: usize,
+    height: usize,
+    parallel: bool,
+    progress: &mut dyn FnMut(f32),
+    row: F,
+) -> String
+where
+    F: Fn(usize) -> String + Sync,
+{
+    render_rows_streaming(width, height, parallel, progress, &mut |_| {}, row)
+}
+
+/// Like `render_rows`, also passing each batch of finished rows to `on_rows` in order
+///
+/// Every batch ends with a newline, so concatenating them gives the returned string.
+pub(crate) fn render_rows_streaming<F>(
+    width: usize,
+    height: usize,
+    parallel: bool,
+    progress: &mut dyn FnMut(f32),
+    on_rows: &mut dyn FnMut(&str),
+    row: F,
+) -> String
+where
+    F: Fn(usize) -> String + Sync,
+{
+    let mut output = String::new();
+    if !parallel || width * height < PARALLEL_MIN_CELLS {
+        for y in 0..height {
+            let mut line = row(y);
+            line.push('\n');
+            on_rows(&line);
+            output.push_str(&line);
+            progress((y + 1) as f32 / height as f32);
+        }
+        return output;
+    }
+
+    let chunk = (height / 20).max(rayon::current_num_threads() * 2);
+    let mut start = 0;
+    while start < height {
+        let end = (start + chunk).min(height);
+        let rows: Vec<String> = (start..end).into_par_iter().map(&row).collect();
+        let mut batch = String::new();
+        for line in rows {
+            batch.push_str(&line);
+            batch.push('\n');
+        }
+        on_rows(&batch);
+        output.push_str(&batch);
+        progress(end as f32 / height as f32);
+        start = end;
+    }
+    output
+}
+
+/// Width-to-height ratio of a terminal cell; 0.5 assumes cells twice as tall as wide
+pub const DEFAULT_FONT_ASPECT: f32 = 0.5;
+
+/// Keep a font aspect within the range real terminal fonts fall in
+pub fn clamp_font_aspect(font_aspect: f32) -> f32 {
+    font_aspect.clamp(0.3, 0.8)
+}
+
+/// Row scale for the character grid: the clamped font aspect, or 1.0 to keep
+/// one source pixel per cell (square pixels) when `preserve_pixel_aspect` is on
+pub fn cell_aspect(font_aspect: f32, preserve_pixel_aspect: bool) -> f32 {
+    if preserve_pixel_aspect {
+        1.0
+    } else {
+        clamp_font_aspect(font_aspect)
+    }
+}
+
+/// Widest width (1..=`max_width`) whose render is at most `rows` tall
+///
+/// Inverts a grid-height function such as `ascii::grid_size` by binary search,
+/// relying on height never shrinking as width grows. Falls back to 1 column
+/// when even that is too tall.
+pub fn width_for_rows(
+    rows: usize,
+    max_width: usize,
+    mut height_at: impl FnMut(usize) -> usize,
+) -> usize {
+    let (mut low, mut high) = (1, max_width.max(1));
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        if height_at(mid) <= rows {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+    low
+}
+
+/// Saturation multiplier that leaves colors untouched
+pub const DEFAULT_SATURATION: f32 = 1.0;
+
+/// Keep a saturation multiplier between grayscale (0.0) and 3x
+pub fn clamp_saturation(saturation: f32) -> f32 {
+    saturation.clamp(0.0, 3.0)
+}
+
+/// Scale the saturation of every pixel; returns the image unchanged at 1.0
+pub fn saturate_image(image: DynamicImage, saturation: f32) -> DynamicImage {
+    let saturation = clamp_saturation(saturation);
+    if saturation == DEFAULT_SATURATION {
+        return image;
+    }
+
+    let mut rgba = image.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        let [r, g, b, a] = pixel.0;
+        let rgb = Rgb::new(r, g, b).saturate(saturation);
+        pixel.0 = [rgb.r, rgb.g, rgb.b, a];
+    }
+    DynamicImage::ImageRgba8(rgba)
+}
+
+/// Share of pixels clipped at each end of the histogram by `auto_levels`
+const AUTO_LEVELS_CLIP: f32 = 0.01;
+
+/// Stretch contrast so the 1st..99th percentile luminance spans the full 0-255 range
+///
+/// Luminance is weighted with `luma`, so the percentiles match the grayscale the ASCII
+/// renderer maps. Fully transparent pixels are ignored when building the histogram.
+/// Returns the image unchanged when it is flat or already uses the full range.
+pub fn auto_levels(image: DynamicImage, luma: LumaCoeffs) -> DynamicImage {
+    let mut rgba = image.to_rgba8();
+    let mut histogram = [0usize; 256];
+    let mut total = 0usize;
+    for pixel in rgba.pixels() {
+        let [r, g, b, a] = pixel.0;
+        if a == 0 {
+            continue;
+        }
+        histogram[luminance_to_gray(luma.luminance(r, g, b)) as usize] += 1;
+        total += 1;
+    }
+    if total == 0 {
+        return image;
+    }
+
+    let clip = (total as f32 * AUTO_LEVELS_CLIP) as usize;
+    let percentile = |levels: Vec<usize>| {
+        let mut seen = 0;
+        levels
+            .into_iter()
+            .find(|&level| {
+                seen += histogram[level];
+                seen > clip
+            })
+            .unwrap_or(0)
+    };
+    let low = percentile((0..256).collect());
+    let high = percentile((0..256).rev().collect());
+    if high <= low || (low == 0 && high == 255) {
+        return image;
+    }
+
+    let scale = 255.0 / (high - low) as f32;
+    let stretch = |c: u8| ((c as f32 - low as f32) * scale).round().clamp(0.0, 255.0) as u8;
+    for pixel in rgba.pixels_mut() {
+        let [r, g, b, a] = pixel.0;
+        pixel.0 = [stretch(r), stretch(g), stretch(b), a];
+    }
+    DynamicImage::ImageRgba8(rgba)
+}
+
+/// Resampling filter used when scaling the source image to the output grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ResizeFilter {
+    /// Nearest neighbor - fastest, keeps pixel-art edges sharp
+    Nearest,
+    /// Bilinear
+    Triangle,
+    /// Bicubic (Catmull-Rom spline)
+    CatmullRom,
+    /// Lanczos with window 3 - sharpest, slowest
+    #[default]
+    Lanczos3,
+}
+
+impl ResizeFilter {
+    pub fn name(&self) -> &str {
+        match self {
+            ResizeFilter::Nearest => "Nearest",
+            ResizeFilter::Triangle => "Triangle",
+            ResizeFilter::CatmullRom => "CatmullRom",
+            ResizeFilter::Lanczos3 => "Lanczos3",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ResizeFilter::Nearest => ResizeFilter::Triangle,
+            ResizeFilter::Triangle => ResizeFilter::CatmullRom,
+            ResizeFilter::CatmullRom => ResizeFilter::Lanczos3,
+            ResizeFilter::Lanczos3 => ResizeFilter::Nearest,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        match self {
+            ResizeFilter::Nearest => ResizeFilter::Lanczos3,
+            ResizeFilter::Triangle => ResizeFilter::Nearest,
+            ResizeFilter::CatmullRom => ResizeFilter::Triangle,
+            ResizeFilter::Lanczos3 => ResizeFilter::CatmullRom,
+        }
+    }
+
+    /// Map to the `image` crate's filter type
+    pub fn filter_type(&self) -> FilterType {
+        match self {
+            ResizeFilter::Nearest => FilterType::Nearest,
+            ResizeFilter::Triangle => FilterType::Triangle,
+            ResizeFilter::CatmullRom => FilterType::CatmullRom,
+            ResizeFilter::Lanczos3 => FilterType::Lanczos3,
+        }
+    }
+}
+
+/// How an image mode picks its render width
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SizingMode {
+    /// Keep the width set in the control panel
+    #[default]
+    Fixed,
+    /// Use the preview's visible columns
+    FitWidth,
+    /// Pick the widest render whose rows fit the preview's visible rows
+    FitHeight,
+}
+
+impl SizingMode {
+    pub fn name(&self) -> &str {
+        match self {
+            SizingMode::Fixed => "Fixed",
+            SizingMode::FitWidth => "Fit width",
+            SizingMode::FitHeight => "Fit height",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            SizingMode::Fixed => SizingMode::FitWidth,
+            SizingMode::FitWidth => SizingMode::FitHeight,
+            SizingMode::FitHeight => SizingMode::Fixed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resize_filter_cycle() {
+        let mut filter = ResizeFilter::default();
+        for _ in 0..4 {
+            assert_eq!(filter.next().prev(), filter);
+            filter = filter.next();
+        }
+        assert_eq!(filter, ResizeFilter::Lanczos3);
+    }
+
+    #[test]
+    fn test_width_for_rows_inverts_height() {
+        // Height grows by half a row per column, like a square image at font aspect 0.5
+        let height = |width: usize| (width as f32 * 0.5).round() as usize;
+        assert_eq!(width_for_rows(40, 300, height), 80);
+        assert!(height(width_for_rows(40, 300, height)) <= 40);
+        // Capped by the widest allowed render, and never below one column
+        assert_eq!(width_for_rows(1000, 300, height), 300);
+        assert_eq!(width_for_rows(0, 300, |_| 1), 1);
+    }
+
+    #[test]
+    fn test_font_aspect_clamped() {
+        assert_eq!(clamp_font_aspect(0.1), 0.3);
+        assert_eq!(clamp_font_aspect(DEFAULT_FONT_ASPECT), 0.5);
+        assert_eq!(clamp_font_aspect(2.0), 0.8);
+        assert_eq!(cell_aspect(0.1, false), 0.3);
+        assert_eq!(cell_aspect(0.1, true), 1.0);
+    }
+
+    #[test]
+    fn test_saturation_clamped() {
+        assert_eq!(clamp_saturation(-1.0), 0.0);
+        assert_eq!(clamp_saturation(DEFAULT_SATURATION), 1.0);
+        assert_eq!(clamp_saturation(5.0), 3.0);
+    }
+
+    #[test]
+    fn test_auto_levels_stretches_low_contrast() {
+        let gradient = image::GrayImage::from_fn(51, 1, |x, _| image::Luma([100 + x as u8]));
+        let stretched =
+            auto_levels(DynamicImage::ImageLuma8(gradient), LumaCoeffs::Bt601).to_luma8();
+        let min = stretched.pixels().map(|p| p.0[0]).min().unwrap();
+        let max = stretched.pixels().map(|p| p.0[0]).max().unwrap();
+        assert_eq!((min, max), (0, 255));
+    }
+
+    #[test]
+    fn test_auto_levels_leaves_flat_image() {
+        let flat = DynamicImage::ImageLuma8(image::GrayImage::from_pixel(4, 4, image::Luma([120])));
+        let leveled = auto_levels(flat, LumaCoeffs::Bt601).to_luma8();
+        assert_eq!(leveled.get_pixel(0, 0).0[0], 120);
+    }
+
+    #[test]
+    fn test_auto_levels_uses_selected_luma() {
+        // Red and blue weigh less under BT.709, so a magenta ramp spans fewer levels there
+        let ramp = image::RgbImage::from_fn(41, 1, |x, _| {
+            image::Rgb([100 + x as u8, 100, 100 + x as u8])
+        });
+        let ramp = DynamicImage::ImageRgb8(ramp);
+        let bt601 = auto_levels(ramp.clone(), LumaCoeffs::Bt601).to_rgb8();
+        let bt709 = auto_levels(ramp, LumaCoeffs::Bt709).to_rgb8();
+        assert_ne!(bt601, bt709);
+    }
+}
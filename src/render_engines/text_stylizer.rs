@@ -3,13 +3,16 @@
 //! Converts plain text to Unicode stylized text with gradient coloring.
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use unicode_segmentation::UnicodeSegmentation;
 
-use crate::color_space::{interpolate_color, format_fg_color, Rgb, ANSI_RESET};
+use crate::color_space::{format_fg_color, interpolate_color, Rgb, ANSI_RESET};
+use crate::render_engines::banner::render_banner;
 use crate::terminal_capabilities::ColorSupport;
+use crate::unicode_handler::{display_width, strip_ansi_codes};
 
 /// Unicode text styles using Mathematical Alphanumeric Symbols
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum UnicodeStyle {
     /// 𝐀𝐁𝐂 (U+1D400)
     #[default]
@@ -32,6 +35,8 @@ pub enum UnicodeStyle {
     SansSerifBold,
     /// 𝙰𝙱𝙲 (U+1D670)
     Monospace,
+    /// Large multi-row ASCII letters, like `figlet` (see [`render_banner`])
+    Banner,
     /// ＡＢＣ (U+FF21)
     Fullwidth,
     /// ⒶⒷⒸ (U+24B6)
@@ -55,6 +60,7 @@ impl UnicodeStyle {
             UnicodeStyle::SansSerif => "Sans-Serif",
             UnicodeStyle::SansSerifBold => "Sans-Serif Bold",
             UnicodeStyle::Monospace => "Monospace",
+            UnicodeStyle::Banner => "Banner",
             UnicodeStyle::Fullwidth => "Fullwidth",
             UnicodeStyle::Circled => "Circled",
             UnicodeStyle::NegativeCircled => "Negative Circled",
@@ -62,7 +68,7 @@ impl UnicodeStyle {
         }
     }
 
-    const ALL_STYLES_ARRAY: [UnicodeStyle; 14] = [
+    const ALL_STYLES_ARRAY: [UnicodeStyle; 15] = [
         UnicodeStyle::Bold,
         UnicodeStyle::Italic,
         UnicodeStyle::BoldItalic,
@@ -73,6 +79,7 @@ impl UnicodeStyle {
         UnicodeStyle::SansSerif,
         UnicodeStyle::SansSerifBold,
         UnicodeStyle::Monospace,
+        UnicodeStyle::Banner,
         UnicodeStyle::Fullwidth,
         UnicodeStyle::Circled,
         UnicodeStyle::NegativeCircled,
@@ -83,6 +90,35 @@ impl UnicodeStyle {
         &Self::ALL_STYLES_ARRAY[..]
     }
 
+    /// SGR attributes approximating the style on terminals that can't show its glyphs
+    pub fn sgr_fallback(&self) -> Option<&'static str> {
+        match self {
+            UnicodeStyle::Bold | UnicodeStyle::BoldScript | UnicodeStyle::SansSerifBold => {
+                Some("1")
+            }
+            UnicodeStyle::Italic | UnicodeStyle::Script => Some("3"),
+            UnicodeStyle::BoldItalic => Some("1;3"),
+            UnicodeStyle::NegativeCircled => Some("7"),
+            UnicodeStyle::Fraktur
+            | UnicodeStyle::DoubleStruck
+            | UnicodeStyle::SansSerif
+            | UnicodeStyle::Monospace
+            | UnicodeStyle::Banner
+            | UnicodeStyle::Fullwidth
+            | UnicodeStyle::Circled
+            | UnicodeStyle::Squared => None,
+        }
+    }
+
+    /// Look up a style by name, ignoring case, spaces and dashes (`bold-italic`, `Bold Italic`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let key = name_key(name);
+        Self::all()
+            .iter()
+            .copied()
+            .find(|style| name_key(style.name()) == key)
+    }
+
     pub fn next(&self) -> Self {
         let all = Self::all();
         let idx = all.iter().position(|s| s == self).unwrap_or(0);
@@ -108,6 +144,7 @@ impl UnicodeStyle {
             UnicodeStyle::SansSerif => Some(0x1D5A0),
             UnicodeStyle::SansSerifBold => Some(0x1D5D4),
             UnicodeStyle::Monospace => Some(0x1D670),
+            UnicodeStyle::Banner => None,
             UnicodeStyle::Fullwidth => Some(0xFF21),
             UnicodeStyle::Circled => Some(0x24B6),
             UnicodeStyle::NegativeCircled => Some(0x1F150),
@@ -128,6 +165,7 @@ impl UnicodeStyle {
             UnicodeStyle::SansSerif => Some(0x1D5BA),
             UnicodeStyle::SansSerifBold => Some(0x1D5EE),
             UnicodeStyle::Monospace => Some(0x1D68A),
+            UnicodeStyle::Banner => None,
             UnicodeStyle::Fullwidth => Some(0xFF41),
             UnicodeStyle::Circled => Some(0x24D0),
             UnicodeStyle::NegativeCircled => None, // No lowercase
@@ -150,10 +188,8 @@ impl UnicodeStyle {
     }
 }
 
-
-
 /// Gradient coloring mode
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum GradientMode {
     #[default]
     None,
@@ -170,6 +206,18 @@ impl GradientMode {
         }
     }
 
+    /// Look up a gradient by name, ignoring case (`none`, `horizontal`, `rainbow`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let key = name_key(name);
+        [
+            GradientMode::None,
+            GradientMode::Horizontal,
+            GradientMode::Rainbow,
+        ]
+        .into_iter()
+        .find(|gradient| name_key(gradient.name()) == key)
+    }
+
     pub fn next(&self) -> Self {
         match self {
             GradientMode::None => GradientMode::Horizontal,
@@ -187,25 +235,344 @@ impl GradientMode {
     }
 }
 
-/// Stylize text with Unicode styles and optional gradient
+/// Horizontal alignment of multi-line output, relative to the widest line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextAlignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+impl TextAlignment {
+    pub fn name(&self) -> &str {
+        match self {
+            TextAlignment::Left => "Left",
+            TextAlignment::Center => "Center",
+            TextAlignment::Right => "Right",
+        }
+    }
+
+    /// Look up an alignment by name, ignoring case (`left`, `center`, `right`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let key = name_key(name);
+        [
+            TextAlignment::Left,
+            TextAlignment::Center,
+            TextAlignment::Right,
+        ]
+        .into_iter()
+        .find(|alignment| name_key(alignment.name()) == key)
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TextAlignment::Left => TextAlignment::Center,
+            TextAlignment::Center => TextAlignment::Right,
+            TextAlignment::Right => TextAlignment::Left,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        match self {
+            TextAlignment::Left => TextAlignment::Right,
+            TextAlignment::Center => TextAlignment::Left,
+            TextAlignment::Right => TextAlignment::Center,
+        }
+    }
+}
+
+/// Plain-text transform applied before Unicode styling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextTransform {
+    #[default]
+    None,
+    /// Rotate ASCII letters by 13 places (applying it twice restores the text)
+    Rot13,
+    /// Swap letters for look-alike digits (a→4, e→3, ...)
+    Leet,
+    /// Reverse the order of grapheme clusters
+    Reverse,
+    /// Flip to upside-down lookalikes (a→ɐ, e→ǝ, ...) and reverse
+    UpsideDown,
+}
+
+impl TextTransform {
+    pub fn name(&self) -> &str {
+        match self {
+            TextTransform::None => "None",
+            TextTransform::Rot13 => "ROT13",
+            TextTransform::Leet => "Leetspeak",
+            TextTransform::Reverse => "Reverse",
+            TextTransform::UpsideDown => "Upside Down",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TextTransform::None => TextTransform::Rot13,
+            TextTransform::Rot13 => TextTransform::Leet,
+            TextTransform::Leet => TextTransform::Reverse,
+            TextTransform::Reverse => TextTransform::UpsideDown,
+            TextTransform::UpsideDown => TextTransform::None,
+        }
+    }
+
+    pub fn prev(&self) -> Self {
+        match self {
+            TextTransform::None => TextTransform::UpsideDown,
+            TextTransform::Rot13 => TextTransform::None,
+            TextTransform::Leet => TextTransform::Rot13,
+            TextTransform::Reverse => TextTransform::Leet,
+            TextTransform::UpsideDown => TextTransform::Reverse,
+        }
+    }
+
+    /// Apply the transform to plain text
+    pub fn apply(&self, text: &str) -> String {
+        match self {
+            TextTransform::None => text.to_string(),
+            TextTransform::Rot13 => text.chars().map(rot13_char).collect(),
+            TextTransform::Leet => text.chars().map(leet_char).collect(),
+            // Work on graphemes so emoji sequences and combining marks stay intact
+            TextTransform::Reverse => text.graphemes(true).rev().collect(),
+            TextTransform::UpsideDown => text
+                .graphemes(true)
+                .rev()
+                .map(|g| {
+                    let mut chars = g.chars();
+                    match (chars.next().and_then(upside_down_char), chars.next()) {
+                        (Some(flipped), None) => flipped.to_string(),
+                        _ => g.to_string(),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+fn rot13_char(c: char) -> char {
+    match c {
+        'a'..='z' => (((c as u8 - b'a') + 13) % 26 + b'a') as char,
+        'A'..='Z' => (((c as u8 - b'A') + 13) % 26 + b'A') as char,
+        _ => c,
+    }
+}
+
+/// Upside-down lookalike for a character, if there is a common one
+fn upside_down_char(c: char) -> Option<char> {
+    let flipped = match c {
+        'a' => 'ɐ',
+        'b' => 'q',
+        'c' => 'ɔ',
+        'd' => 'p',
+        'e' => 'ǝ',
+        'f' => 'ɟ',
+        'g' => 'ƃ',
+        'h' => 'ɥ',
+        'i' => 'ᴉ',
+        'j' => 'ɾ',
+        'k' => 'ʞ',
+        'm' => 'ɯ',
+        'n' => 'u',
+        'p' => 'd',
+        'q' => 'b',
+        'r' => 'ɹ',
+        't' => 'ʇ',
+        'u' => 'n',
+        'v' => 'ʌ',
+        'w' => 'ʍ',
+        'y' => 'ʎ',
+        'A' => '∀',
+        'C' => 'Ɔ',
+        'E' => 'Ǝ',
+        'F' => 'Ⅎ',
+        'G' => '⅁',
+        'J' => 'ſ',
+        'L' => '˥',
+        'M' => 'W',
+        'P' => 'Ԁ',
+        'T' => '┴',
+        'U' => '∩',
+        'V' => 'Λ',
+        'W' => 'M',
+        'Y' => '⅄',
+        '1' => 'Ɩ',
+        '3' => 'Ɛ',
+        '6' => '9',
+        '7' => 'ㄥ',
+        '9' => '6',
+        '.' => '˙',
+        ',' => '\'',
+        '\'' => ',',
+        '?' => '¿',
+        '!' => '¡',
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '_' => '‾',
+        '&' => '⅋',
+        _ => return None,
+    };
+    Some(flipped)
+}
+
+fn leet_char(c: char) -> char {
+    match c.to_ascii_lowercase() {
+        'a' => '4',
+        'b' => '8',
+        'e' => '3',
+        'g' => '6',
+        'i' => '1',
+        'o' => '0',
+        's' => '5',
+        't' => '7',
+        'z' => '2',
+        _ => c,
+    }
+}
+
+/// Stylize text with a plain-text transform, Unicode styles and optional gradient
 pub fn stylize_text(
     text: &str,
+    transform: TextTransform,
     style: UnicodeStyle,
     gradient: GradientMode,
     start_color: (u8, u8, u8),
     end_color: (u8, u8, u8),
+    alignment: TextAlignment,
 ) -> Result<String> {
-    // First apply Unicode style
-    let styled = apply_unicode_style(text, style);
+    // First apply the plain-text transform
+    let transformed = transform.apply(text);
+
+    // Then apply Unicode style
+    let styled = apply_unicode_style(&transformed, style);
 
     // Then apply gradient if requested
     let colored = apply_gradient(&styled, gradient, start_color, end_color);
 
-    Ok(colored)
+    // Pad last, so the padding stays outside the colors
+    Ok(align_lines(&colored, alignment))
+}
+
+/// Like [`stylize_text`], for terminals without full Unicode support
+///
+/// The text stays plain and the style is approximated with SGR bold/italic/reverse
+/// (see [`UnicodeStyle::sgr_fallback`]) instead of Mathematical Alphanumeric glyphs,
+/// which those terminals draw as boxes.
+pub fn stylize_text_plain(
+    text: &str,
+    transform: TextTransform,
+    style: UnicodeStyle,
+    gradient: GradientMode,
+    start_color: (u8, u8, u8),
+    end_color: (u8, u8, u8),
+    alignment: TextAlignment,
+) -> Result<String> {
+    let transformed = transform.apply(text);
+    // Banner letters are plain ASCII, so they need no fallback
+    let transformed = if style == UnicodeStyle::Banner {
+        render_banner(&transformed)
+    } else {
+        transformed
+    };
+    let colored = apply_gradient(&transformed, gradient, start_color, end_color);
+
+    let attributed = match style.sgr_fallback() {
+        // Gradients reset after every character, so restore the attribute each time.
+        // Each line is wrapped on its own so alignment padding stays unstyled.
+        Some(sgr) => {
+            let attribute = format!("\x1b[{}m", sgr);
+            let restore = format!("{}{}", ANSI_RESET, attribute);
+            colored
+                .split('\n')
+                .map(|line| {
+                    format!(
+                        "{}{}{}",
+                        attribute,
+                        line.replace(ANSI_RESET, &restore),
+                        ANSI_RESET
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        None => colored,
+    };
+    Ok(align_lines(&attributed, alignment))
+}
+
+/// Pad each line of `text` to the width of the widest one
+///
+/// Widths ignore ANSI escapes and count wide (e.g. CJK) characters as two columns.
+/// Padding is added outside the line's escapes, so gradients leave it uncolored.
+fn align_lines(text: &str, alignment: TextAlignment) -> String {
+    if alignment == TextAlignment::Left || !text.contains('\n') {
+        return text.to_string();
+    }
+
+    let widths: Vec<usize> = text
+        .split('\n')
+        .map(|line| display_width(&strip_ansi_codes(line)))
+        .collect();
+    let widest = widths.iter().copied().max().unwrap_or(0);
+
+    text.split('\n')
+        .zip(widths)
+        .map(|(line, width)| {
+            let gap = widest - width;
+            let left = match alignment {
+                TextAlignment::Left => 0,
+                TextAlignment::Center => gap / 2,
+                TextAlignment::Right => gap,
+            };
+            // Right alignment doesn't need trailing spaces
+            let right = if alignment == TextAlignment::Center {
+                gap - left
+            } else {
+                0
+            };
+            format!("{}{}{}", " ".repeat(left), line, " ".repeat(right))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Wrap each non-empty line in an OSC 8 hyperlink to `url`
+///
+/// Lines are linked separately so terminals that end links at a newline still
+/// make every line clickable.
+pub fn hyperlink(text: &str, url: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// First `http://` or `https://` URL in `text`, without trailing punctuation
+pub fn find_url(text: &str) -> Option<&str> {
+    text.split_whitespace()
+        .find(|word| word.starts_with("https://") || word.starts_with("http://"))
+        .map(|url| url.trim_end_matches(['.', ',', ';', ':', '!', '?', ')']))
 }
 
 /// Apply Unicode style transformation to text
 fn apply_unicode_style(text: &str, style: UnicodeStyle) -> String {
+    if style == UnicodeStyle::Banner {
+        return render_banner(text);
+    }
+
     let mut result = String::with_capacity(text.len() * 4); // Unicode chars can be 4 bytes
 
     for c in text.chars() {
@@ -223,19 +590,19 @@ fn transform_char(c: char, style: UnicodeStyle) -> char {
             let offset = c as u32 - 'A' as u32;
             // Handle exceptions in some Unicode ranges
             let code = match style {
-                UnicodeStyle::Script if c == 'B' => 0x212C, // ℬ
-                UnicodeStyle::Script if c == 'E' => 0x2130, // ℰ
-                UnicodeStyle::Script if c == 'F' => 0x2131, // ℱ
-                UnicodeStyle::Script if c == 'H' => 0x210B, // ℋ
-                UnicodeStyle::Script if c == 'I' => 0x2110, // ℐ
-                UnicodeStyle::Script if c == 'L' => 0x2112, // ℒ
-                UnicodeStyle::Script if c == 'M' => 0x2133, // ℳ
-                UnicodeStyle::Script if c == 'R' => 0x211B, // ℛ
-                UnicodeStyle::Fraktur if c == 'C' => 0x212D, // ℭ
-                UnicodeStyle::Fraktur if c == 'H' => 0x210C, // ℌ
-                UnicodeStyle::Fraktur if c == 'I' => 0x2111, // ℑ
-                UnicodeStyle::Fraktur if c == 'R' => 0x211C, // ℜ
-                UnicodeStyle::Fraktur if c == 'Z' => 0x2128, // ℨ
+                UnicodeStyle::Script if c == 'B' => 0x212C,       // ℬ
+                UnicodeStyle::Script if c == 'E' => 0x2130,       // ℰ
+                UnicodeStyle::Script if c == 'F' => 0x2131,       // ℱ
+                UnicodeStyle::Script if c == 'H' => 0x210B,       // ℋ
+                UnicodeStyle::Script if c == 'I' => 0x2110,       // ℐ
+                UnicodeStyle::Script if c == 'L' => 0x2112,       // ℒ
+                UnicodeStyle::Script if c == 'M' => 0x2133,       // ℳ
+                UnicodeStyle::Script if c == 'R' => 0x211B,       // ℛ
+                UnicodeStyle::Fraktur if c == 'C' => 0x212D,      // ℭ
+                UnicodeStyle::Fraktur if c == 'H' => 0x210C,      // ℌ
+                UnicodeStyle::Fraktur if c == 'I' => 0x2111,      // ℑ
+                UnicodeStyle::Fraktur if c == 'R' => 0x211C,      // ℜ
+                UnicodeStyle::Fraktur if c == 'Z' => 0x2128,      // ℨ
                 UnicodeStyle::DoubleStruck if c == 'C' => 0x2102, // ℂ
                 UnicodeStyle::DoubleStruck if c == 'H' => 0x210D, // ℍ
                 UnicodeStyle::DoubleStruck if c == 'N' => 0x2115, // ℕ
@@ -305,11 +672,7 @@ fn apply_gradient(
 }
 
 /// Apply horizontal gradient (interpolate from start to end color)
-fn apply_horizontal_gradient(
-    text: &str,
-    start: (u8, u8, u8),
-    end: (u8, u8, u8),
-) -> String {
+fn apply_horizontal_gradient(text: &str, start: (u8, u8, u8), end: (u8, u8, u8)) -> String {
     let graphemes: Vec<&str> = text.graphemes(true).collect();
     let len = graphemes.len();
 
@@ -388,17 +751,199 @@ fn hue_to_rgb(hue: f32) -> Rgb {
         _ => (c, 0.0, x),
     };
 
-    Rgb::new(
-        (r * 255.0) as u8,
-        (g * 255.0) as u8,
-        (b * 255.0) as u8,
-    )
+    Rgb::new((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
+}
+
+/// Lowercase `name` and drop everything but letters and digits, so names match loosely
+fn name_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_plain_fallback_keeps_ascii() {
+        let plain = stylize_text_plain(
+            "AB",
+            TextTransform::None,
+            UnicodeStyle::Bold,
+            GradientMode::None,
+            (0, 0, 0),
+            (0, 0, 0),
+            TextAlignment::Left,
+        )
+        .unwrap();
+        assert_eq!(plain, "\x1b[1mAB\x1b[0m");
+
+        let fraktur = stylize_text_plain(
+            "AB",
+            TextTransform::None,
+            UnicodeStyle::Fraktur,
+            GradientMode::None,
+            (0, 0, 0),
+            (0, 0, 0),
+            TextAlignment::Left,
+        )
+        .unwrap();
+        assert_eq!(fraktur, "AB");
+    }
+
+    #[test]
+    fn test_plain_fallback_keeps_attribute_through_gradient() {
+        let plain = stylize_text_plain(
+            "AB",
+            TextTransform::None,
+            UnicodeStyle::Italic,
+            GradientMode::Horizontal,
+            (255, 0, 0),
+            (0, 0, 255),
+            TextAlignment::Left,
+        )
+        .unwrap();
+        assert!(plain.starts_with("\x1b[3m"));
+        // Each per-character reset is followed by the italic attribute again
+        assert_eq!(plain.matches("\x1b[0m\x1b[3m").count(), 2);
+        assert_eq!(strip_ansi_codes(&plain), "AB");
+    }
+
+    #[test]
+    fn test_center_alignment_pads_short_lines_evenly() {
+        let centered = stylize_text(
+            "Hi\nHello world",
+            TextTransform::None,
+            UnicodeStyle::Bold,
+            GradientMode::None,
+            (0, 0, 0),
+            (0, 0, 0),
+            TextAlignment::Center,
+        )
+        .unwrap();
+        let lines: Vec<&str> = centered.split('\n').collect();
+        // "Hello world" is 11 columns, so "Hi" gets 9 spaces split 4/5
+        assert_eq!(lines[0], "    𝐇𝐢     ");
+        assert_eq!(lines[1], "𝐇𝐞𝐥𝐥𝐨 𝐰𝐨𝐫𝐥𝐝");
+
+        let right = align_lines("ab\nabcde", TextAlignment::Right);
+        assert_eq!(right, "   ab\nabcde");
+        let left = align_lines("ab\nabcde", TextAlignment::Left);
+        assert_eq!(left, "ab\nabcde");
+    }
+
+    #[test]
+    fn test_alignment_uses_display_width_and_skips_colors() {
+        // Each CJK character is two columns wide
+        let centered = align_lines("日本\nabcdefgh", TextAlignment::Center);
+        assert_eq!(centered.split('\n').next(), Some("  日本  "));
+
+        // Padding sits outside the gradient's escapes, so it stays uncolored
+        let colored = stylize_text(
+            "a\nabc",
+            TextTransform::None,
+            UnicodeStyle::Monospace,
+            GradientMode::Rainbow,
+            (0, 0, 0),
+            (0, 0, 0),
+            TextAlignment::Right,
+        )
+        .unwrap();
+        let first = colored.split('\n').next().unwrap();
+        assert!(first.starts_with("  \x1b["));
+        assert_eq!(strip_ansi_codes(&colored), "  𝚊\n𝚊𝚋𝚌");
+
+        // SGR fallback attributes are closed before the padding
+        let plain = stylize_text_plain(
+            "a\nabc",
+            TextTransform::None,
+            UnicodeStyle::NegativeCircled,
+            GradientMode::None,
+            (0, 0, 0),
+            (0, 0, 0),
+            TextAlignment::Center,
+        )
+        .unwrap();
+        assert_eq!(plain, " \x1b[7ma\x1b[0m \n\x1b[7mabc\x1b[0m");
+    }
+
+    #[test]
+    fn test_banner_style_renders_large_letters() {
+        let banner = stylize_text(
+            "Hi",
+            TextTransform::None,
+            UnicodeStyle::Banner,
+            GradientMode::None,
+            (0, 0, 0),
+            (0, 0, 0),
+            TextAlignment::Left,
+        )
+        .unwrap();
+        assert_eq!(banner, render_banner("HI"));
+
+        // Plain ASCII already, so terminals without Unicode get the same letters
+        let plain = stylize_text_plain(
+            "Hi",
+            TextTransform::None,
+            UnicodeStyle::Banner,
+            GradientMode::None,
+            (0, 0, 0),
+            (0, 0, 0),
+            TextAlignment::Left,
+        )
+        .unwrap();
+        assert_eq!(plain, banner);
+        assert_eq!(
+            UnicodeStyle::from_name("banner"),
+            Some(UnicodeStyle::Banner)
+        );
+    }
+
+    #[test]
+    fn test_alignment_lookup_and_cycle() {
+        assert_eq!(
+            TextAlignment::from_name("center"),
+            Some(TextAlignment::Center)
+        );
+        assert_eq!(
+            TextAlignment::from_name("RIGHT"),
+            Some(TextAlignment::Right)
+        );
+        assert_eq!(TextAlignment::from_name("justify"), None);
+        assert_eq!(TextAlignment::Right.next(), TextAlignment::Left);
+        assert_eq!(TextAlignment::Left.prev(), TextAlignment::Right);
+    }
+
+    #[test]
+    fn test_style_and_gradient_lookup_by_name() {
+        assert_eq!(UnicodeStyle::from_name("bold"), Some(UnicodeStyle::Bold));
+        assert_eq!(
+            UnicodeStyle::from_name("Bold Italic"),
+            Some(UnicodeStyle::BoldItalic)
+        );
+        assert_eq!(
+            UnicodeStyle::from_name("double-struck"),
+            Some(UnicodeStyle::DoubleStruck)
+        );
+        assert_eq!(
+            UnicodeStyle::from_name("sans_serif_bold"),
+            Some(UnicodeStyle::SansSerifBold)
+        );
+        assert_eq!(UnicodeStyle::from_name("gothic"), None);
+        for style in UnicodeStyle::all() {
+            assert_eq!(UnicodeStyle::from_name(style.name()), Some(*style));
+        }
+
+        assert_eq!(
+            GradientMode::from_name("Rainbow"),
+            Some(GradientMode::Rainbow)
+        );
+        assert_eq!(GradientMode::from_name("none"), Some(GradientMode::None));
+        assert_eq!(GradientMode::from_name("diagonal"), None);
+    }
+
     #[test]
     fn test_style_cycling() {
         let style = UnicodeStyle::Bold;
@@ -456,10 +1001,12 @@ mod tests {
     fn test_stylize_with_gradient() {
         let result = stylize_text(
             "Test",
+            TextTransform::None,
             UnicodeStyle::Bold,
             GradientMode::Horizontal,
             (255, 0, 0),
             (0, 0, 255),
+            TextAlignment::Left,
         )
         .unwrap();
 
@@ -480,4 +1027,64 @@ mod tests {
         let blue = hue_to_rgb(240.0);
         assert_eq!(blue.b, 255);
     }
+
+    #[test]
+    fn test_rot13_round_trip() {
+        let text = "Hello, World! 123";
+        let once = TextTransform::Rot13.apply(text);
+        assert_eq!(once, "Uryyb, Jbeyq! 123");
+        assert_eq!(TextTransform::Rot13.apply(&once), text);
+    }
+
+    #[test]
+    fn test_leet_substitutions() {
+        assert_eq!(TextTransform::Leet.apply("leet"), "l337");
+        assert_eq!(TextTransform::Leet.apply("GAME OVER"), "64M3 0V3R");
+        assert_eq!(TextTransform::None.apply("leet"), "leet");
+    }
+
+    #[test]
+    fn test_transform_applied_before_style() {
+        let result = stylize_text(
+            "Nop",
+            TextTransform::Rot13,
+            UnicodeStyle::Bold,
+            GradientMode::None,
+            (0, 0, 0),
+            (0, 0, 0),
+            TextAlignment::Left,
+        )
+        .unwrap();
+        assert_eq!(result, apply_unicode_style("Abc", UnicodeStyle::Bold));
+    }
+
+    #[test]
+    fn test_reverse_keeps_graphemes_intact() {
+        let text = "a👍🏽b🇺🇸e\u{301}";
+        assert_eq!(TextTransform::Reverse.apply(text), "e\u{301}🇺🇸b👍🏽a");
+    }
+
+    #[test]
+    fn test_hyperlink_wraps_each_line() {
+        assert_eq!(
+            hyperlink("ab\n\ncd", "https://example.com"),
+            "\x1b]8;;https://example.com\x1b\\ab\x1b]8;;\x1b\\\n\n\x1b]8;;https://example.com\x1b\\cd\x1b]8;;\x1b\\"
+        );
+    }
+
+    #[test]
+    fn test_find_url() {
+        assert_eq!(
+            find_url("see https://example.com/x."),
+            Some("https://example.com/x")
+        );
+        assert_eq!(find_url("(http://a.b)"), None);
+        assert_eq!(find_url("no links here"), None);
+    }
+
+    #[test]
+    fn test_upside_down_mapping() {
+        assert_eq!(TextTransform::UpsideDown.apply("hello"), "ollǝɥ");
+        assert_eq!(TextTransform::UpsideDown.apply("Hi!"), "¡ᴉH");
+    }
 }
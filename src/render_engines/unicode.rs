@@ -5,8 +5,16 @@
 use anyhow::Result;
 use image::{DynamicImage, GenericImageView};
 use serde::{Deserialize, Serialize};
-
-use crate::color_space::{format_bg_color, format_fg_color, Rgb, ANSI_RESET};
+use std::time::Instant;
+
+use crate::color_space::{
+    ansi16_to_bg, ansi16_to_fg, ansi256_to_bg, ansi256_to_fg, format_bg_color, format_fg_color,
+    interpolate_color, quantize_to_palette, rgb_to_ansi_bg, rgb_to_ansi_fg, Rgb, ANSI_RESET,
+};
+use crate::render_engines::{
+    ascii::LumaCoeffs, auto_levels, cell_aspect, elapsed_ms, render_rows, saturate_image,
+    RenderTiming, ResizeFilter, DEFAULT_FONT_ASPECT, DEFAULT_SATURATION,
+};
 use crate::terminal_capabilities::ColorSupport;
 
 /// Unicode rendering mode
@@ -48,11 +56,38 @@ impl UnicodeMode {
 }
 
 /// Configuration for Unicode rendering
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct UnicodeConfig {
     pub target_width: usize,
     pub mode: UnicodeMode,
     pub color_mode: ColorSupport,
+    /// Filter used to scale the image to the character grid
+    pub resize_filter: ResizeFilter,
+    /// Width-to-height ratio of a terminal cell, used to correct vertical stretch
+    pub font_aspect: f32,
+    /// Skip the cell correction and keep the raw pixel aspect (one pixel per cell row)
+    pub preserve_pixel_aspect: bool,
+    /// Swap light and dark: denser blocks and raised Braille dots mark dark pixels
+    ///
+    /// Half-blocks draw the pixel colors directly and ignore this.
+    pub invert: bool,
+    /// Color that transparent pixels are blended onto in Half-Blocks mode
+    pub background: Rgb,
+    /// Half-Blocks cells whose top and bottom colors differ by at most this much
+    /// per channel are drawn as a single-color full block instead of `▀`
+    pub merge_threshold: u8,
+    /// Custom palette that Blocks and Half-Blocks snap colors to; empty uses the full ramp
+    pub palette: Vec<Rgb>,
+    /// Replace each color with its negative (255 - channel) before quantization
+    pub negate: bool,
+    /// Saturation multiplier (0.0 = grayscale, 1.0 = unchanged, up to 3.0)
+    pub saturation: f32,
+    /// Stretch the resized image's luminance to the full range before quantization
+    pub auto_levels: bool,
+    /// Ordered (Bayer) dithering between block levels; only Blocks mode uses this
+    pub dither: bool,
+    /// Map rows on the rayon thread pool when the grid is large enough
+    pub parallel: bool,
 }
 
 impl Default for UnicodeConfig {
@@ -61,131 +96,346 @@ impl Default for UnicodeConfig {
             target_width: 80,
             mode: UnicodeMode::HalfBlocks,
             color_mode: ColorSupport::TrueColor,
+            resize_filter: ResizeFilter::Lanczos3,
+            font_aspect: DEFAULT_FONT_ASPECT,
+            preserve_pixel_aspect: false,
+            invert: false,
+            background: Rgb::new(0, 0, 0),
+            merge_threshold: 0,
+            palette: Vec::new(),
+            negate: false,
+            saturation: DEFAULT_SATURATION,
+            auto_levels: false,
+            dither: false,
+            parallel: true,
         }
     }
 }
 
+/// Columns and rows `config` renders `image` to
+pub fn grid_size(image: &DynamicImage, config: &UnicodeConfig) -> (usize, usize) {
+    match config.mode {
+        UnicodeMode::Blocks => calculate_dimensions(
+            image,
+            config.target_width,
+            1,
+            config.font_aspect,
+            config.preserve_pixel_aspect,
+        ),
+        UnicodeMode::HalfBlocks => calculate_dimensions(
+            image,
+            config.target_width,
+            2,
+            config.font_aspect,
+            config.preserve_pixel_aspect,
+        ),
+        UnicodeMode::Braille => (config.target_width, braille_rows(image, config)),
+    }
+}
+
 /// Render an image as Unicode art
 pub fn render_unicode(image: &DynamicImage, config: &UnicodeConfig) -> Result<String> {
-    match config.mode {
-        UnicodeMode::Blocks => render_blocks(image, config),
-        UnicodeMode::HalfBlocks => render_half_blocks(image, config),
-        UnicodeMode::Braille => render_braille(image, config),
+    render_unicode_with_progress(image, config, &mut |_| {})
+}
+
+/// Render an image as Unicode art, reporting the fraction of rows done after each row
+pub fn render_unicode_with_progress(
+    image: &DynamicImage,
+    config: &UnicodeConfig,
+    progress: &mut dyn FnMut(f32),
+) -> Result<String> {
+    render_unicode_timed(image, config, progress).map(|(output, _)| output)
+}
+
+/// Render an image as Unicode art, also returning how long resizing and mapping took
+pub fn render_unicode_timed(
+    image: &DynamicImage,
+    config: &UnicodeConfig,
+    progress: &mut dyn FnMut(f32),
+) -> Result<(String, RenderTiming)> {
+    if image.width() == 0 || image.height() == 0 {
+        anyhow::bail!("Image has no pixels");
     }
+
+    let start = Instant::now();
+    let mut timing = RenderTiming::default();
+    let output = match config.mode {
+        UnicodeMode::Blocks => render_blocks(image, config, progress, &mut timing),
+        UnicodeMode::HalfBlocks => render_half_blocks(image, config, progress, &mut timing),
+        UnicodeMode::Braille => render_braille(image, config, progress, &mut timing),
+    }?;
+    timing.total_ms = elapsed_ms(start);
+    Ok((output, timing))
 }
 
-/// Render using simple block characters with color
-fn render_blocks(image: &DynamicImage, config: &UnicodeConfig) -> Result<String> {
-    let (width, height) = calculate_dimensions(image, config.target_width, 1);
+/// 4x4 Bayer threshold map; each cell's rank decides when it steps up a level
+const BAYER_4X4: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
 
-    let resized = image.resize_exact(
-        width as u32,
-        height as u32,
-        image::imageops::FilterType::Lanczos3,
+/// Ordered dithering offset for the cell at (x, y), within ±0.5 of a block level
+///
+/// Depends only on position, so rows can still be rendered independently.
+fn bayer_offset(x: usize, y: usize) -> f32 {
+    (BAYER_4X4[y % 4][x % 4] as f32 + 0.5) / 16.0 - 0.5
+}
+
+/// Render using simple block characters with color
+fn render_blocks(
+    image: &DynamicImage,
+    config: &UnicodeConfig,
+    progress: &mut dyn FnMut(f32),
+    timing: &mut RenderTiming,
+) -> Result<String> {
+    let (width, height) = calculate_dimensions(
+        image,
+        config.target_width,
+        1,
+        config.font_aspect,
+        config.preserve_pixel_aspect,
     );
 
+    let resized = resize_for_render(image, width, height, config, timing);
+    let process_start = Instant::now();
+
     let block_chars = [' ', '░', '▒', '▓', '█'];
-    let mut output = String::with_capacity((width * 20 + 1) * height); // Extra space for ANSI codes
 
-    for y in 0..height {
+    let output = render_rows(width, height, config.parallel, progress, |y| {
+        let mut line = String::with_capacity(width * 20); // Extra space for ANSI codes
+        let mut colors = ColorRun::default();
         for x in 0..width {
             let pixel = resized.get_pixel(x as u32, y as u32);
             let rgb = Rgb::new(pixel[0], pixel[1], pixel[2]);
 
             // Calculate luminance for block selection
-            let luminance =
+            let mut luminance =
                 (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32)
                     / 255.0;
-            let block_idx = (luminance * (block_chars.len() - 1) as f32).round() as usize;
+            if config.invert {
+                luminance = 1.0 - luminance;
+            }
+            let mut level = luminance * (block_chars.len() - 1) as f32;
+            if config.dither {
+                level += bayer_offset(x, y);
+            }
+            let block_idx = level.round().max(0.0) as usize;
             let block_char = block_chars[block_idx.min(block_chars.len() - 1)];
 
             // Add color if supported
             if config.color_mode != ColorSupport::NoColor {
-                output.push_str(&format_fg_color(rgb, config.color_mode));
+                colors.set(&mut line, palette_fg_color(rgb, config), None);
             }
 
-            output.push(block_char);
-
-            if config.color_mode != ColorSupport::NoColor {
-                output.push_str(ANSI_RESET);
-            }
+            line.push(block_char);
         }
-        output.push('\n');
-    }
+        colors.finish(&mut line);
+        line
+    });
 
+    timing.process_ms = elapsed_ms(process_start);
     Ok(output)
 }
 
 /// Render using half-block characters for 2x vertical resolution
-fn render_half_blocks(image: &DynamicImage, config: &UnicodeConfig) -> Result<String> {
+fn render_half_blocks(
+    image: &DynamicImage,
+    config: &UnicodeConfig,
+    progress: &mut dyn FnMut(f32),
+    timing: &mut RenderTiming,
+) -> Result<String> {
     // Double the vertical resolution since each character cell represents 2 rows
-    let (width, height) = calculate_dimensions(image, config.target_width, 2);
-    let actual_height = height * 2;
-
-    let resized = image.resize_exact(
-        width as u32,
-        actual_height as u32,
-        image::imageops::FilterType::Lanczos3,
+    let (width, height) = calculate_dimensions(
+        image,
+        config.target_width,
+        2,
+        config.font_aspect,
+        config.preserve_pixel_aspect,
     );
+    let actual_height = height * 2;
 
-    let mut output = String::with_capacity((width * 30 + 1) * height);
+    let resized = resize_for_render(image, width, actual_height, config, timing).to_rgba8();
+    let process_start = Instant::now();
 
-    // Process 2 rows at a time
-    for y in (0..actual_height).step_by(2) {
+    // Each output row covers 2 pixel rows
+    let output = render_rows(width, height, config.parallel, progress, |row| {
+        let y = row * 2;
+        let mut line = String::with_capacity(width * 30);
+        let mut colors = ColorRun::default();
         for x in 0..width {
-            let top_pixel = resized.get_pixel(x as u32, y as u32);
-            let top_rgb = Rgb::new(top_pixel[0], top_pixel[1], top_pixel[2]);
+            let top_pixel = *resized.get_pixel(x as u32, y as u32);
+            let top_rgb = composite(top_pixel, config.background);
 
             let bottom_pixel = if y + 1 < actual_height {
-                resized.get_pixel(x as u32, (y + 1) as u32)
+                *resized.get_pixel(x as u32, (y + 1) as u32)
             } else {
                 top_pixel
             };
-            let bottom_rgb = Rgb::new(bottom_pixel[0], bottom_pixel[1], bottom_pixel[2]);
+            let bottom_rgb = composite(bottom_pixel, config.background);
 
-            // Use upper half block (▀) with top color as foreground, bottom as background
-            if config.color_mode != ColorSupport::NoColor {
-                output.push_str(&format_fg_color(top_rgb, config.color_mode));
-                output.push_str(&format_bg_color(bottom_rgb, config.color_mode));
+            if config.color_mode == ColorSupport::NoColor {
+                line.push('▀');
+                continue;
             }
 
-            output.push('▀');
+            // Matching halves carry one color; a full block needs half the escapes
+            if colors_within(top_rgb, bottom_rgb, config.merge_threshold) {
+                let rgb = interpolate_color(top_rgb, bottom_rgb, 0.5);
+                colors.set(&mut line, palette_fg_color(rgb, config), None);
+                line.push('█');
+                continue;
+            }
 
-            if config.color_mode != ColorSupport::NoColor {
-                output.push_str(ANSI_RESET);
+            // Use upper half block (▀) with top color as foreground, bottom as background
+            colors.set(
+                &mut line,
+                palette_fg_color(top_rgb, config),
+                Some(palette_bg_color(bottom_rgb, config)),
+            );
+            line.push('▀');
+        }
+        colors.finish(&mut line);
+        line
+    });
+
+    timing.process_ms = elapsed_ms(process_start);
+    Ok(output)
+}
+
+/// Colors in effect on an output line, so escapes are only emitted when they change
+#[derive(Default)]
+struct ColorRun {
+    fg: Option<String>,
+    bg: Option<String>,
+}
+
+impl ColorRun {
+    /// Append the escapes needed to draw the next cell with `fg` and `bg`
+    ///
+    /// Dropping a background needs a reset, which also clears the foreground.
+    fn set(&mut self, line: &mut String, fg: String, bg: Option<String>) {
+        if bg.is_none() && self.bg.is_some() {
+            line.push_str(ANSI_RESET);
+            *self = Self::default();
+        }
+        if self.fg.as_ref() != Some(&fg) {
+            line.push_str(&fg);
+            self.fg = Some(fg);
+        }
+        if let Some(bg) = bg {
+            if self.bg.as_ref() != Some(&bg) {
+                line.push_str(&bg);
+                self.bg = Some(bg);
             }
         }
-        output.push('\n');
     }
 
-    Ok(output)
+    /// Close the line with a single reset if any color was emitted
+    fn finish(self, line: &mut String) {
+        if self.fg.is_some() || self.bg.is_some() {
+            line.push_str(ANSI_RESET);
+        }
+    }
+}
+
+/// Scale the image to the render grid, then apply saturation, auto-levels and negation
+fn resize_for_render(
+    image: &DynamicImage,
+    width: usize,
+    height: usize,
+    config: &UnicodeConfig,
+    timing: &mut RenderTiming,
+) -> DynamicImage {
+    let start = Instant::now();
+    let resized = image.resize_exact(
+        width as u32,
+        height as u32,
+        config.resize_filter.filter_type(),
+    );
+    let mut resized = saturate_image(resized, config.saturation);
+    if config.auto_levels {
+        // Unicode modes have no luma setting of their own
+        resized = auto_levels(resized, LumaCoeffs::default());
+    }
+    if config.negate {
+        resized.invert();
+    }
+    timing.resize_ms = elapsed_ms(start);
+    resized
+}
+
+/// Foreground escape for a pixel, snapped to the custom palette when one is set
+///
+/// TrueColor emits the palette entry's RGB; 16/256-color modes emit the entry's
+/// index so the terminal's own palette (listed in slot order) supplies the color.
+fn palette_fg_color(rgb: Rgb, config: &UnicodeConfig) -> String {
+    if config.palette.is_empty() {
+        return format_fg_color(rgb, config.color_mode);
+    }
+    let index = quantize_to_palette(rgb, &config.palette);
+    match config.color_mode {
+        ColorSupport::TrueColor => rgb_to_ansi_fg(config.palette[index]),
+        ColorSupport::Color256 if index < 256 => ansi256_to_fg(index as u8),
+        ColorSupport::Color16 if index < 16 => ansi16_to_fg(index as u8),
+        support => format_fg_color(config.palette[index], support),
+    }
+}
+
+/// Background counterpart of [`palette_fg_color`]
+fn palette_bg_color(rgb: Rgb, config: &UnicodeConfig) -> String {
+    if config.palette.is_empty() {
+        return format_bg_color(rgb, config.color_mode);
+    }
+    let index = quantize_to_palette(rgb, &config.palette);
+    match config.color_mode {
+        ColorSupport::TrueColor => rgb_to_ansi_bg(config.palette[index]),
+        ColorSupport::Color256 if index < 256 => ansi256_to_bg(index as u8),
+        ColorSupport::Color16 if index < 16 => ansi16_to_bg(index as u8),
+        support => format_bg_color(config.palette[index], support),
+    }
+}
+
+/// Alpha-blend a pixel onto the background color
+fn composite(pixel: image::Rgba<u8>, background: Rgb) -> Rgb {
+    let alpha = pixel[3] as f32 / 255.0;
+    let blend = |fg: u8, bg: u8| (fg as f32 * alpha + bg as f32 * (1.0 - alpha)).round() as u8;
+    Rgb::new(
+        blend(pixel[0], background.r),
+        blend(pixel[1], background.g),
+        blend(pixel[2], background.b),
+    )
+}
+
+/// Whether every channel of `a` and `b` differs by at most `threshold`
+fn colors_within(a: Rgb, b: Rgb, threshold: u8) -> bool {
+    a.r.abs_diff(b.r) <= threshold
+        && a.g.abs_diff(b.g) <= threshold
+        && a.b.abs_diff(b.b) <= threshold
 }
 
 /// Render using Braille patterns for 2x4 resolution
-fn render_braille(image: &DynamicImage, config: &UnicodeConfig) -> Result<String> {
+fn render_braille(
+    image: &DynamicImage,
+    config: &UnicodeConfig,
+    progress: &mut dyn FnMut(f32),
+    timing: &mut RenderTiming,
+) -> Result<String> {
     // Braille: each character is 2 wide × 4 tall dots
     let char_width = 2;
     let char_height = 4;
 
     let output_width = config.target_width;
     let pixel_width = output_width * char_width;
-
-    // Calculate height maintaining aspect ratio
-    let (img_width, img_height) = image.dimensions();
-    let aspect = img_width as f32 / img_height as f32;
-    let pixel_height = (pixel_width as f32 / aspect).round() as usize;
-    let output_height = (pixel_height + char_height - 1) / char_height;
-
-    let resized = image.resize_exact(
-        pixel_width as u32,
-        (output_height * char_height) as u32,
-        image::imageops::FilterType::Lanczos3,
+    let output_height = braille_rows(image, config);
+
+    let resized = resize_for_render(
+        image,
+        pixel_width,
+        output_height * char_height,
+        config,
+        timing,
     );
+    let process_start = Instant::now();
 
     let gray = resized.to_luma8();
 
-    let mut output = String::with_capacity((output_width + 1) * output_height);
-
     // Braille dot positions (Unicode Braille starts at U+2800)
     // Dot pattern:
     // 1 4
@@ -201,44 +451,66 @@ fn render_braille(image: &DynamicImage, config: &UnicodeConfig) -> Result<String
 
     let threshold = 128u8;
 
-    for cy in 0..output_height {
-        for cx in 0..output_width {
-            let mut braille = 0u8;
-
-            for dy in 0..char_height {
-                for dx in 0..char_width {
-                    let px = cx * char_width + dx;
-                    let py = cy * char_height + dy;
-
-                    if px < pixel_width && py < output_height * char_height {
-                        let pixel = gray.get_pixel(px as u32, py as u32);
-                        if pixel.0[0] > threshold {
-                            braille |= dot_values[dy][dx];
+    let output = render_rows(
+        output_width,
+        output_height,
+        config.parallel,
+        progress,
+        |cy| {
+            let mut line = String::with_capacity(output_width * 3);
+            for cx in 0..output_width {
+                let mut braille = 0u8;
+
+                for (dy, dots) in dot_values.iter().enumerate() {
+                    for (dx, &dot) in dots.iter().enumerate() {
+                        let px = cx * char_width + dx;
+                        let py = cy * char_height + dy;
+
+                        if px < pixel_width && py < output_height * char_height {
+                            let pixel = gray.get_pixel(px as u32, py as u32);
+                            if (pixel.0[0] > threshold) != config.invert {
+                                braille |= dot;
+                            }
                         }
                     }
                 }
-            }
 
-            let braille_char = char::from_u32(0x2800 + braille as u32).unwrap_or(' ');
-            output.push(braille_char);
-        }
-        output.push('\n');
-    }
+                let braille_char = char::from_u32(0x2800 + braille as u32).unwrap_or(' ');
+                line.push(braille_char);
+            }
+            line
+        },
+    );
 
+    timing.process_ms = elapsed_ms(process_start);
     Ok(output)
 }
 
+/// Rows of 2x4-dot Braille cells for `config.target_width` columns
+fn braille_rows(image: &DynamicImage, config: &UnicodeConfig) -> usize {
+    let pixel_width = config.target_width * 2;
+
+    // Calculate height maintaining aspect ratio; dots are square when the font aspect is 0.5
+    let (img_width, img_height) = image.dimensions();
+    let aspect = img_width as f32 / img_height as f32;
+    let dot_aspect = cell_aspect(config.font_aspect, config.preserve_pixel_aspect) * 2.0;
+    let pixel_height = (pixel_width as f32 / aspect * dot_aspect).round() as usize;
+    pixel_height.div_ceil(4).max(1)
+}
+
 /// Calculate output dimensions
 fn calculate_dimensions(
     image: &DynamicImage,
     target_width: usize,
     vertical_multiplier: usize,
+    font_aspect: f32,
+    preserve_pixel_aspect: bool,
 ) -> (usize, usize) {
     let (img_width, img_height) = image.dimensions();
     let aspect_ratio = img_width as f32 / img_height as f32;
 
-    // Terminal characters are roughly 2:1 aspect ratio
-    let char_aspect = 0.5 * vertical_multiplier as f32;
+    // Terminal cells are taller than wide; squash rows by the font's width/height ratio
+    let char_aspect = cell_aspect(font_aspect, preserve_pixel_aspect) * vertical_multiplier as f32;
 
     let width = target_width;
     let height = ((target_width as f32 / aspect_ratio) * char_aspect).round() as usize;
@@ -250,6 +522,7 @@ fn calculate_dimensions(
 mod tests {
     use super::*;
     use image::RgbImage;
+    use ratatui::style::Color;
 
     #[test]
     fn test_mode_cycling() {
@@ -258,6 +531,23 @@ mod tests {
         assert_eq!(mode.prev(), UnicodeMode::Braille);
     }
 
+    #[test]
+    fn test_font_aspect_scales_height() {
+        let img = DynamicImage::ImageRgb8(RgbImage::new(100, 100));
+        assert_eq!(calculate_dimensions(&img, 80, 1, 0.5, false), (80, 40));
+        assert_eq!(calculate_dimensions(&img, 80, 1, 0.6, false), (80, 48));
+        // Half-blocks pack two pixel rows per cell, so the row count doubles
+        assert_eq!(calculate_dimensions(&img, 80, 2, 0.4, false), (80, 64));
+    }
+
+    #[test]
+    fn test_preserve_pixel_aspect_keeps_square_grid() {
+        let img = DynamicImage::ImageRgb8(RgbImage::new(100, 100));
+        assert_eq!(calculate_dimensions(&img, 80, 1, 0.5, true), (80, 80));
+        // Half-blocks still pack two pixel rows per cell
+        assert_eq!(calculate_dimensions(&img, 80, 2, 0.5, true), (80, 160));
+    }
+
     #[test]
     fn test_render_blocks() {
         let img = DynamicImage::ImageRgb8(RgbImage::new(10, 10));
@@ -265,12 +555,49 @@ mod tests {
             target_width: 10,
             mode: UnicodeMode::Blocks,
             color_mode: ColorSupport::NoColor,
+            ..UnicodeConfig::default()
         };
 
         let result = render_unicode(&img, &config).unwrap();
         assert!(!result.is_empty());
     }
 
+    #[test]
+    fn test_dither_mixes_block_levels_across_gradient() {
+        // A shallow gradient that sits inside a single undithered block level
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(32, 32, |x, _| {
+            let v = 64 + (x * 12 / 31) as u8;
+            image::Rgb([v, v, v])
+        }));
+        let config = UnicodeConfig {
+            target_width: 32,
+            mode: UnicodeMode::Blocks,
+            color_mode: ColorSupport::NoColor,
+            resize_filter: ResizeFilter::Nearest,
+            ..UnicodeConfig::default()
+        };
+        let distinct = |output: String| {
+            let mut chars: Vec<char> = output.chars().filter(|c| *c != '\n').collect();
+            chars.sort();
+            chars.dedup();
+            chars.len()
+        };
+
+        let plain = distinct(render_unicode(&img, &config).unwrap());
+        let dithered = distinct(
+            render_unicode(
+                &img,
+                &UnicodeConfig {
+                    dither: true,
+                    ..config.clone()
+                },
+            )
+            .unwrap(),
+        );
+        assert_eq!(plain, 1);
+        assert!(dithered > plain);
+    }
+
     #[test]
     fn test_render_half_blocks() {
         let img = DynamicImage::ImageRgb8(RgbImage::new(10, 10));
@@ -278,6 +605,7 @@ mod tests {
             target_width: 10,
             mode: UnicodeMode::HalfBlocks,
             color_mode: ColorSupport::NoColor,
+            ..UnicodeConfig::default()
         };
 
         let result = render_unicode(&img, &config).unwrap();
@@ -285,6 +613,124 @@ mod tests {
         assert!(result.contains('▀') || result.chars().any(|c| c == ' ' || c == '\n'));
     }
 
+    #[test]
+    fn test_half_blocks_merge_matching_halves() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(6, 6, image::Rgb([200, 40, 40])));
+        let config = UnicodeConfig {
+            target_width: 6,
+            mode: UnicodeMode::HalfBlocks,
+            color_mode: ColorSupport::TrueColor,
+            ..UnicodeConfig::default()
+        };
+
+        let result = render_unicode(&img, &config).unwrap();
+        assert!(!result.contains('▀'));
+        assert!(result.contains('█'));
+        assert!(!result.contains("\x1b[48;"));
+    }
+
+    /// Each visible character with the colors the ANSI parser assigns to it
+    fn parsed_cells(output: &str) -> Vec<(char, Option<Color>, Option<Color>)> {
+        output
+            .lines()
+            .flat_map(crate::input::parse_ansi_to_spans)
+            .flat_map(|(text, style)| {
+                text.chars()
+                    .map(|c| (c, style.fg, style.bg))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_color_runs_emit_escapes_only_on_change() {
+        // Left half red, right half blue
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(8, 8, |x, _| {
+            if x < 4 {
+                image::Rgb([255, 0, 0])
+            } else {
+                image::Rgb([0, 0, 255])
+            }
+        }));
+        let config = UnicodeConfig {
+            target_width: 8,
+            mode: UnicodeMode::Blocks,
+            color_mode: ColorSupport::TrueColor,
+            resize_filter: ResizeFilter::Nearest,
+            ..UnicodeConfig::default()
+        };
+
+        let result = render_unicode(&img, &config).unwrap();
+        for line in result.lines() {
+            // Two color changes and one closing reset instead of two escapes per cell
+            assert_eq!(line.matches('\x1b').count(), 3);
+            assert!(line.ends_with(ANSI_RESET));
+        }
+
+        let cells = parsed_cells(&result);
+        assert_eq!(cells.len(), 8 * result.lines().count());
+        for (i, &(_, fg, bg)) in cells.iter().enumerate() {
+            let expected = if i % 8 < 4 {
+                Color::Rgb(255, 0, 0)
+            } else {
+                Color::Rgb(0, 0, 255)
+            };
+            assert_eq!(fg, Some(expected));
+            assert_eq!(bg, None);
+        }
+    }
+
+    #[test]
+    fn test_color_run_resets_dropped_background() {
+        let red = format_fg_color(Rgb::new(255, 0, 0), ColorSupport::TrueColor);
+        let blue = format_bg_color(Rgb::new(0, 0, 255), ColorSupport::TrueColor);
+        let mut line = String::new();
+        let mut colors = ColorRun::default();
+        colors.set(&mut line, red.clone(), Some(blue.clone()));
+        line.push('▀');
+        colors.set(&mut line, red.clone(), Some(blue));
+        line.push('▀');
+        colors.set(&mut line, red, None);
+        line.push('█');
+        colors.finish(&mut line);
+
+        assert_eq!(line.matches('\x1b').count(), 5);
+        let cells = parsed_cells(&line);
+        let red = Some(Color::Rgb(255, 0, 0));
+        let blue = Some(Color::Rgb(0, 0, 255));
+        assert_eq!(
+            cells,
+            vec![('▀', red, blue), ('▀', red, blue), ('█', red, None)]
+        );
+    }
+
+    #[test]
+    fn test_half_blocks_merge_threshold() {
+        // Upscaling two rows 40 levels apart leaves each cell's halves about 10 apart
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(4, 2, |_, y| {
+            image::Rgb([100 + y as u8 * 40, 100, 100])
+        }));
+        let render = |merge_threshold| {
+            render_unicode(
+                &img,
+                &UnicodeConfig {
+                    target_width: 4,
+                    mode: UnicodeMode::HalfBlocks,
+                    color_mode: ColorSupport::TrueColor,
+                    resize_filter: ResizeFilter::Triangle,
+                    merge_threshold,
+                    ..UnicodeConfig::default()
+                },
+            )
+            .unwrap()
+        };
+
+        assert!(render(0).contains('▀'));
+        let merged = render(20);
+        assert!(!merged.contains('▀'));
+        assert!(merged.contains('█'));
+    }
+
     #[test]
     fn test_render_braille() {
         let img = DynamicImage::ImageRgb8(RgbImage::new(10, 10));
@@ -292,12 +738,186 @@ mod tests {
             target_width: 10,
             mode: UnicodeMode::Braille,
             color_mode: ColorSupport::NoColor,
+            ..UnicodeConfig::default()
         };
 
         let result = render_unicode(&img, &config).unwrap();
         assert!(!result.is_empty());
     }
 
+    #[test]
+    fn test_invert_flips_block_choice() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(8, 8, image::Rgb([80, 80, 80])));
+        let render = |mode, invert| {
+            let config = UnicodeConfig {
+                target_width: 4,
+                mode,
+                color_mode: ColorSupport::NoColor,
+                invert,
+                ..UnicodeConfig::default()
+            };
+            render_unicode(&img, &config).unwrap()
+        };
+
+        assert!(render(UnicodeMode::Blocks, false).starts_with('░'));
+        assert!(render(UnicodeMode::Blocks, true).starts_with('▓'));
+        // Dark pixels stay unset normally and raise every dot when inverted
+        assert!(render(UnicodeMode::Braille, false).starts_with('\u{2800}'));
+        assert!(render(UnicodeMode::Braille, true).starts_with('\u{28FF}'));
+    }
+
+    #[test]
+    fn test_half_blocks_blend_transparency_onto_background() {
+        let img = image::RgbaImage::from_pixel(4, 4, image::Rgba([0, 0, 255, 128]));
+        let config = UnicodeConfig {
+            target_width: 4,
+            mode: UnicodeMode::HalfBlocks,
+            color_mode: ColorSupport::TrueColor,
+            resize_filter: ResizeFilter::Nearest,
+            background: Rgb::new(255, 0, 0),
+            ..UnicodeConfig::default()
+        };
+
+        let result = render_unicode(&DynamicImage::ImageRgba8(img), &config).unwrap();
+        // Half-transparent blue over red blends to purple in both halves, merged into one block
+        let blended = Rgb::new(127, 0, 128);
+        assert!(result.starts_with(&format!(
+            "{}█",
+            format_fg_color(blended, ColorSupport::TrueColor)
+        )));
+    }
+
+    #[test]
+    fn test_palette_snaps_colors() {
+        let img = RgbImage::from_pixel(4, 4, image::Rgb([250, 10, 10]));
+        let palette = vec![
+            Rgb::new(40, 40, 40),
+            Rgb::new(204, 36, 29),
+            Rgb::new(152, 151, 26),
+        ];
+        let render = |color_mode| {
+            let config = UnicodeConfig {
+                target_width: 4,
+                mode: UnicodeMode::Blocks,
+                color_mode,
+                palette: palette.clone(),
+                ..UnicodeConfig::default()
+            };
+            render_unicode(&DynamicImage::ImageRgb8(img.clone()), &config).unwrap()
+        };
+
+        assert!(render(ColorSupport::TrueColor).starts_with("\x1b[38;2;204;36;29m"));
+        assert!(render(ColorSupport::Color256).starts_with("\x1b[38;5;1m"));
+        assert!(render(ColorSupport::Color16).starts_with("\x1b[31m"));
+    }
+
+    #[test]
+    fn test_thin_images_render_every_mode() {
+        for (width, height) in [(1, 1), (1, 50), (400, 1)] {
+            let img = DynamicImage::ImageRgb8(RgbImage::new(width, height));
+            for mode in [
+                UnicodeMode::Blocks,
+                UnicodeMode::HalfBlocks,
+                UnicodeMode::Braille,
+            ] {
+                let config = UnicodeConfig {
+                    target_width: 10,
+                    mode,
+                    ..UnicodeConfig::default()
+                };
+                assert!(!render_unicode(&img, &config).unwrap().is_empty());
+            }
+        }
+    }
+
+    #[test]
+    fn test_negate_turns_red_cyan() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 4, image::Rgb([255, 0, 0])));
+        let config = UnicodeConfig {
+            target_width: 4,
+            mode: UnicodeMode::HalfBlocks,
+            color_mode: ColorSupport::TrueColor,
+            resize_filter: ResizeFilter::Nearest,
+            negate: true,
+            ..UnicodeConfig::default()
+        };
+
+        let result = render_unicode(&img, &config).unwrap();
+        assert!(result.starts_with("\x1b[38;2;0;255;255m"));
+        assert!(!result.contains("255;0;0"));
+    }
+
+    #[test]
+    fn test_saturation_zero_is_grayscale_and_one_is_no_op() {
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(8, 8, |x, y| {
+            image::Rgb([(x * 32) as u8, (y * 32) as u8, 200])
+        }));
+        let config = UnicodeConfig {
+            target_width: 8,
+            mode: UnicodeMode::HalfBlocks,
+            color_mode: ColorSupport::Color256,
+            resize_filter: ResizeFilter::Nearest,
+            saturation: 0.0,
+            ..UnicodeConfig::default()
+        };
+
+        let gray = render_unicode(&img, &config).unwrap();
+        let codes: Vec<u8> = gray
+            .split("\x1b[")
+            .filter_map(|seq| seq.strip_prefix("38;5;").or(seq.strip_prefix("48;5;")))
+            .map(|rest| rest.split('m').next().unwrap().parse().unwrap())
+            .collect();
+        assert!(!codes.is_empty());
+        assert!(codes.iter().all(|&code| code >= 232), "{:?}", codes);
+
+        let unchanged = render_unicode(
+            &img,
+            &UnicodeConfig {
+                saturation: 1.0,
+                color_mode: ColorSupport::TrueColor,
+                ..config.clone()
+            },
+        )
+        .unwrap();
+        assert!(unchanged.starts_with("\x1b[38;2;0;0;200m"));
+        assert_eq!(
+            unchanged,
+            render_unicode(
+                &img,
+                &UnicodeConfig {
+                    color_mode: ColorSupport::TrueColor,
+                    resize_filter: ResizeFilter::Nearest,
+                    target_width: 8,
+                    ..UnicodeConfig::default()
+                }
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_progress_reaches_completion() {
+        let img = DynamicImage::ImageRgb8(RgbImage::new(40, 30));
+        for mode in [
+            UnicodeMode::Blocks,
+            UnicodeMode::HalfBlocks,
+            UnicodeMode::Braille,
+        ] {
+            let config = UnicodeConfig {
+                target_width: 20,
+                mode,
+                color_mode: ColorSupport::NoColor,
+                ..UnicodeConfig::default()
+            };
+            let mut reports = Vec::new();
+            render_unicode_with_progress(&img, &config, &mut |f| reports.push(f)).unwrap();
+
+            assert!(!reports.is_empty());
+            assert!(reports.windows(2).all(|w| w[0] <= w[1]));
+            assert_eq!(*reports.last().unwrap(), 1.0);
+        }
+    }
+
     #[test]
     fn test_with_color() {
         let mut img = RgbImage::new(4, 4);
@@ -312,6 +932,7 @@ mod tests {
             target_width: 4,
             mode: UnicodeMode::HalfBlocks,
             color_mode: ColorSupport::TrueColor,
+            ..UnicodeConfig::default()
         };
 
         let result = render_unicode(&DynamicImage::ImageRgb8(img), &config).unwrap();
@@ -2,19 +2,39 @@
 //!
 //! Single source of truth for application state with mode-specific substates.
 
+use std::cell::Cell;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use crossbeam_channel::Sender;
+use anyhow::Result;
+use crossbeam_channel::{Receiver, Sender, TryRecvError};
 use image::DynamicImage;
+use ratatui::layout::Rect;
 
-use crate::config::Config;
-use crate::perf_monitor::PerfMetrics;
+use crate::color_space::{parse_hex_color, Rgb};
+use crate::config::{AsciiPreferences, Config, Preset, UiPreferences, UnicodePreferences};
+use crate::crop::CropRect;
+use crate::file_browser::{BrowserAction, FileBrowser};
+use crate::graphics::GraphicsMode;
+use crate::image_transform::{apply_chain, ImageTransform};
+use crate::json_export::JsonExport;
+use crate::keybindings::Keybindings;
+use crate::perf_monitor::{image_bytes, PerfMetrics};
+use crate::render_cache::{CachedConfig, RenderCache, RenderKey};
 use crate::render_engines::{
-    ascii::CharacterSet, text_stylizer::GradientMode, text_stylizer::UnicodeStyle,
-    unicode::UnicodeMode,
+    ascii::{self, render_ascii, AsciiConfig, CharacterSet, EdgeMethod, LumaCoeffs},
+    clamp_font_aspect, clamp_saturation,
+    text_stylizer::find_url,
+    text_stylizer::GradientMode,
+    text_stylizer::TextAlignment,
+    text_stylizer::TextTransform,
+    text_stylizer::UnicodeStyle,
+    unicode::{self, UnicodeConfig, UnicodeMode},
+    width_for_rows, ResizeFilter, SizingMode, DEFAULT_SATURATION,
 };
 use crate::terminal_capabilities::{ColorSupport, TerminalCapabilities};
+use crate::ui::theme::Theme;
+use crate::unicode_handler::{display_width, strip_ansi_codes, truncate_to_width};
 use crate::worker::{WorkerMessage, WorkerResponse};
 
 /// Main render mode selection
@@ -24,6 +44,7 @@ pub enum RenderMode {
     ImageToAscii,
     ImageToUnicode,
     TextStylizer,
+    ColorAscii,
 }
 
 impl RenderMode {
@@ -32,6 +53,7 @@ impl RenderMode {
             RenderMode::ImageToAscii => "ASCII Art",
             RenderMode::ImageToUnicode => "Unicode Art",
             RenderMode::TextStylizer => "Text Stylizer",
+            RenderMode::ColorAscii => "Color ASCII",
         }
     }
 
@@ -41,10 +63,12 @@ impl RenderMode {
 }
 
 // Module-level static array used by `RenderMode::all()` to ensure a &'static slice
-static ALL_RENDER_MODES: [RenderMode; 3] = [
+// Order matches the numbered shortcuts in the mode selector
+static ALL_RENDER_MODES: [RenderMode; 4] = [
     RenderMode::ImageToAscii,
     RenderMode::ImageToUnicode,
     RenderMode::TextStylizer,
+    RenderMode::ColorAscii,
 ];
 
 /// Which widget is currently focused
@@ -75,12 +99,32 @@ impl FocusedWidget {
 }
 
 /// ASCII rendering state
-#[derive(Debug, Clone)]
+///
+/// `Default` uses fixed defaults (width 80); [`AsciiRenderState::from_config`] starts
+/// from the `[ascii]` config section instead.
+#[derive(Debug, Clone, PartialEq)]
 pub struct AsciiRenderState {
     pub charset: CharacterSet,
     pub width: usize,
     pub invert: bool,
     pub edge_enhance: bool,
+    pub edge_method: EdgeMethod,
+    pub edge_strength: f32,
+    pub resize_filter: ResizeFilter,
+    pub luma: LumaCoeffs,
+    /// Render transparent pixels as spaces (from `config.ascii.alpha_as_space`)
+    pub alpha_as_space: bool,
+    /// Pad lines to equal display width (from `config.ascii.pad_lines`)
+    pub pad_lines: bool,
+    pub saturation: f32,
+    /// Stretch contrast to the full range (from `config.ascii.auto_levels`)
+    pub auto_levels: bool,
+    /// Stand-in for the darkest ramp character (from `config.ascii.empty_char`)
+    pub empty_char: Option<char>,
+    /// Keep the raw pixel aspect instead of correcting for tall cells
+    pub preserve_pixel_aspect: bool,
+    /// How `width` is chosen (from `config.ui.sizing`)
+    pub sizing: SizingMode,
     pub selected_setting: usize,
 }
 
@@ -91,14 +135,45 @@ impl Default for AsciiRenderState {
             width: 80,
             invert: false,
             edge_enhance: false,
+            edge_method: EdgeMethod::Sobel,
+            edge_strength: 0.3,
+            resize_filter: ResizeFilter::Lanczos3,
+            luma: LumaCoeffs::Bt601,
+            alpha_as_space: true,
+            pad_lines: false,
+            saturation: DEFAULT_SATURATION,
+            auto_levels: false,
+            empty_char: None,
+            preserve_pixel_aspect: false,
+            sizing: SizingMode::Fixed,
             selected_setting: 0,
         }
     }
 }
 
 impl AsciiRenderState {
+    /// Settings from the `[ascii]` config section, with everything else at its default
+    pub fn from_config(prefs: &AsciiPreferences) -> Self {
+        Self {
+            charset: prefs.default_charset.clone(),
+            width: prefs.default_width,
+            edge_enhance: prefs.edge_enhance,
+            edge_strength: prefs.edge_strength,
+            edge_method: prefs.edge_method,
+            resize_filter: prefs.resize_filter,
+            luma: prefs.luma,
+            alpha_as_space: prefs.alpha_as_space,
+            pad_lines: prefs.pad_lines,
+            saturation: clamp_saturation(prefs.saturation),
+            auto_levels: prefs.auto_levels,
+            empty_char: prefs.empty_char,
+            preserve_pixel_aspect: prefs.preserve_pixel_aspect,
+            ..Self::default()
+        }
+    }
+
     pub fn settings_count() -> usize {
-        5 // width, charset, invert, edge_enhance, output_format
+        10 // width, charset, invert, edge_enhance, edge_method, resize_filter, luma, saturation, preserve_aspect, output_format
     }
 
     pub fn setting_name(&self, index: usize) -> &'static str {
@@ -107,6 +182,11 @@ impl AsciiRenderState {
             1 => "Charset",
             2 => "Invert",
             3 => "Edge Enhance",
+            4 => "Edge Method",
+            5 => "Resize Filter",
+            6 => "Luma",
+            7 => "Saturation",
+            8 => "Preserve Aspect",
             _ => "Unknown",
         }
     }
@@ -117,17 +197,86 @@ impl AsciiRenderState {
             1 => self.charset.name().to_string(),
             2 => if self.invert { "On" } else { "Off" }.to_string(),
             3 => if self.edge_enhance { "On" } else { "Off" }.to_string(),
+            4 => self.edge_method.name().to_string(),
+            5 => self.resize_filter.name().to_string(),
+            6 => self.luma.name().to_string(),
+            7 => format!("{:.1}x", self.saturation),
+            8 => if self.preserve_pixel_aspect {
+                "On"
+            } else {
+                "Off"
+            }
+            .to_string(),
             _ => String::new(),
         }
     }
+
+    /// Build the engine configuration for the current settings
+    pub fn render_config(&self, font_aspect: f32) -> AsciiConfig {
+        AsciiConfig {
+            target_width: self.width,
+            charset: self.charset.clone(),
+            invert: self.invert,
+            edge_enhance: self.edge_enhance,
+            edge_method: self.edge_method,
+            edge_strength: self.edge_strength,
+            resize_filter: self.resize_filter,
+            luma: self.luma,
+            font_aspect,
+            preserve_pixel_aspect: self.preserve_pixel_aspect,
+            alpha_as_space: self.alpha_as_space,
+            pad_lines: self.pad_lines,
+            saturation: self.saturation,
+            auto_levels: self.auto_levels,
+            empty_char: self.empty_char,
+            parallel: true,
+            color_mode: ColorSupport::NoColor,
+        }
+    }
+
+    /// Widest width (up to `max_width`) whose render of `image` is at most `rows` tall
+    pub fn fit_height_width(
+        &self,
+        image: &DynamicImage,
+        rows: usize,
+        max_width: usize,
+        font_aspect: f32,
+    ) -> usize {
+        let mut config = self.render_config(font_aspect);
+        width_for_rows(rows, max_width, |width| {
+            config.target_width = width;
+            ascii::grid_size(image, &config).1
+        })
+    }
 }
 
 /// Unicode rendering state
-#[derive(Debug, Clone)]
+///
+/// `Default` uses fixed defaults (width 80); [`UnicodeRenderState::from_config`] starts
+/// from the `[unicode]` config section instead.
+#[derive(Debug, Clone, PartialEq)]
 pub struct UnicodeRenderState {
     pub mode: UnicodeMode,
     pub width: usize,
     pub color_mode: ColorSupport,
+    pub resize_filter: ResizeFilter,
+    pub invert: bool,
+    pub negate: bool,
+    /// Bayer dithering between block levels (Blocks mode only)
+    pub dither: bool,
+    pub saturation: f32,
+    /// Stretch contrast to the full range (from `config.unicode.auto_levels`)
+    pub auto_levels: bool,
+    /// Color transparent pixels are blended onto (from `config.unicode.background`)
+    pub background: Rgb,
+    /// Half-Blocks merge tolerance (from `config.unicode.merge_threshold`)
+    pub merge_threshold: u8,
+    /// Colors to snap to when `config.unicode.use_palette` is on; empty otherwise
+    pub palette: Vec<Rgb>,
+    /// Keep the raw pixel aspect instead of correcting for tall cells
+    pub preserve_pixel_aspect: bool,
+    /// How `width` is chosen (from `config.ui.sizing`)
+    pub sizing: SizingMode,
     pub selected_setting: usize,
 }
 
@@ -137,14 +286,42 @@ impl Default for UnicodeRenderState {
             mode: UnicodeMode::HalfBlocks,
             width: 80,
             color_mode: ColorSupport::TrueColor,
+            resize_filter: ResizeFilter::Lanczos3,
+            invert: false,
+            negate: false,
+            dither: false,
+            saturation: DEFAULT_SATURATION,
+            auto_levels: false,
+            background: Rgb::new(0, 0, 0),
+            merge_threshold: 0,
+            palette: Vec::new(),
+            preserve_pixel_aspect: false,
+            sizing: SizingMode::Fixed,
             selected_setting: 0,
         }
     }
 }
 
 impl UnicodeRenderState {
+    /// Settings from the `[unicode]` config section, with everything else at its default
+    ///
+    /// The palette is left empty, since it lives in its own `[palette]` section.
+    pub fn from_config(prefs: &UnicodePreferences) -> Self {
+        Self {
+            mode: prefs.default_mode,
+            width: prefs.default_width,
+            resize_filter: prefs.resize_filter,
+            saturation: clamp_saturation(prefs.saturation),
+            auto_levels: prefs.auto_levels,
+            background: Rgb::from_tuple(prefs.background),
+            merge_threshold: prefs.merge_threshold,
+            preserve_pixel_aspect: prefs.preserve_pixel_aspect,
+            ..Self::default()
+        }
+    }
+
     pub fn settings_count() -> usize {
-        4 // width, mode, color, output_format
+        10 // width, mode, color, resize_filter, invert, negate, dither, saturation, preserve_aspect, output_format
     }
 
     pub fn setting_name(&self, index: usize) -> &'static str {
@@ -152,6 +329,12 @@ impl UnicodeRenderState {
             0 => "Width",
             1 => "Mode",
             2 => "Color",
+            3 => "Resize Filter",
+            4 => "Invert",
+            5 => "Negate Colors",
+            6 => "Dither",
+            7 => "Saturation",
+            8 => "Preserve Aspect",
             _ => "Unknown",
         }
     }
@@ -161,15 +344,181 @@ impl UnicodeRenderState {
             0 => format!("{}", self.width),
             1 => self.mode.name().to_string(),
             2 => self.color_mode.name().to_string(),
+            3 => self.resize_filter.name().to_string(),
+            4 => if self.invert { "On" } else { "Off" }.to_string(),
+            5 => if self.negate { "On" } else { "Off" }.to_string(),
+            6 => if self.dither { "On" } else { "Off" }.to_string(),
+            7 => format!("{:.1}x", self.saturation),
+            8 => if self.preserve_pixel_aspect {
+                "On"
+            } else {
+                "Off"
+            }
+            .to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Build the engine configuration for the current settings
+    pub fn render_config(&self, font_aspect: f32) -> UnicodeConfig {
+        UnicodeConfig {
+            target_width: self.width,
+            mode: self.mode,
+            color_mode: self.color_mode,
+            resize_filter: self.resize_filter,
+            font_aspect,
+            preserve_pixel_aspect: self.preserve_pixel_aspect,
+            invert: self.invert,
+            background: self.background,
+            merge_threshold: self.merge_threshold,
+            palette: self.palette.clone(),
+            negate: self.negate,
+            saturation: self.saturation,
+            auto_levels: self.auto_levels,
+            dither: self.dither,
+            parallel: true,
+        }
+    }
+
+    /// Widest width (up to `max_width`) whose render of `image` is at most `rows` tall
+    pub fn fit_height_width(
+        &self,
+        image: &DynamicImage,
+        rows: usize,
+        max_width: usize,
+        font_aspect: f32,
+    ) -> usize {
+        let mut config = self.render_config(font_aspect);
+        width_for_rows(rows, max_width, |width| {
+            config.target_width = width;
+            unicode::grid_size(image, &config).1
+        })
+    }
+}
+
+/// Color ASCII rendering state: ASCII glyphs, each colored with its source pixel
+///
+/// `Default` uses fixed defaults (width 80); [`ColorAsciiRenderState::from_config`]
+/// starts from the `[ascii]` config section instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorAsciiRenderState {
+    pub charset: CharacterSet,
+    pub width: usize,
+    pub invert: bool,
+    pub color_mode: ColorSupport,
+    pub resize_filter: ResizeFilter,
+    pub saturation: f32,
+    /// Render transparent pixels as spaces (from `config.ascii.alpha_as_space`)
+    pub alpha_as_space: bool,
+    /// Stretch contrast to the full range (from `config.ascii.auto_levels`)
+    pub auto_levels: bool,
+    /// Stand-in for the darkest ramp character (from `config.ascii.empty_char`)
+    pub empty_char: Option<char>,
+    /// How `width` is chosen (from `config.ui.sizing`)
+    pub sizing: SizingMode,
+    pub selected_setting: usize,
+}
+
+impl Default for ColorAsciiRenderState {
+    fn default() -> Self {
+        Self {
+            charset: CharacterSet::Extended,
+            width: 80,
+            invert: false,
+            color_mode: ColorSupport::TrueColor,
+            resize_filter: ResizeFilter::Lanczos3,
+            saturation: DEFAULT_SATURATION,
+            alpha_as_space: true,
+            auto_levels: false,
+            empty_char: None,
+            sizing: SizingMode::Fixed,
+            selected_setting: 0,
+        }
+    }
+}
+
+impl ColorAsciiRenderState {
+    /// Settings from the `[ascii]` config section, with everything else at its default
+    pub fn from_config(prefs: &AsciiPreferences) -> Self {
+        Self {
+            charset: prefs.default_charset.clone(),
+            width: prefs.default_width,
+            resize_filter: prefs.resize_filter,
+            saturation: clamp_saturation(prefs.saturation),
+            alpha_as_space: prefs.alpha_as_space,
+            auto_levels: prefs.auto_levels,
+            empty_char: prefs.empty_char,
+            ..Self::default()
+        }
+    }
+
+    pub fn settings_count() -> usize {
+        7 // width, charset, invert, color, resize_filter, saturation, output_format
+    }
+
+    pub fn setting_name(&self, index: usize) -> &'static str {
+        match index {
+            0 => "Width",
+            1 => "Charset",
+            2 => "Invert",
+            3 => "Color",
+            4 => "Resize Filter",
+            5 => "Saturation",
+            _ => "Unknown",
+        }
+    }
+
+    pub fn setting_value(&self, index: usize) -> String {
+        match index {
+            0 => format!("{}", self.width),
+            1 => self.charset.name().to_string(),
+            2 => if self.invert { "On" } else { "Off" }.to_string(),
+            3 => self.color_mode.name().to_string(),
+            4 => self.resize_filter.name().to_string(),
+            5 => format!("{:.1}x", self.saturation),
             _ => String::new(),
         }
     }
+
+    /// Build the engine configuration for the current settings
+    pub fn render_config(&self, font_aspect: f32) -> AsciiConfig {
+        AsciiConfig {
+            target_width: self.width,
+            charset: self.charset.clone(),
+            invert: self.invert,
+            resize_filter: self.resize_filter,
+            font_aspect,
+            alpha_as_space: self.alpha_as_space,
+            saturation: self.saturation,
+            auto_levels: self.auto_levels,
+            empty_char: self.empty_char,
+            color_mode: self.color_mode,
+            ..AsciiConfig::default()
+        }
+    }
+
+    /// Widest width (up to `max_width`) whose render of `image` is at most `rows` tall
+    pub fn fit_height_width(
+        &self,
+        image: &DynamicImage,
+        rows: usize,
+        max_width: usize,
+        font_aspect: f32,
+    ) -> usize {
+        let mut config = self.render_config(font_aspect);
+        width_for_rows(rows, max_width, |width| {
+            config.target_width = width;
+            ascii::grid_size(image, &config).1
+        })
+    }
 }
 
 /// Text stylizer state
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TextStylizeState {
     pub style: UnicodeStyle,
+    /// Plain-text transform applied before the Unicode style
+    pub transform: TextTransform,
     pub gradient: GradientMode,
     pub start_color: (u8, u8, u8),
     pub end_color: (u8, u8, u8),
@@ -177,12 +526,19 @@ pub struct TextStylizeState {
     pub cursor_position: usize,
     pub selected_setting: usize,
     pub editing_text: bool,
+    /// Make the output clickable, linking to the first URL in the input
+    pub hyperlink: bool,
+    /// Alignment of multi-line input, relative to the widest line
+    pub alignment: TextAlignment,
+    /// Channel of the selected color that +/- adjusts (0 = R, 1 = G, 2 = B)
+    pub color_channel: usize,
 }
 
 impl Default for TextStylizeState {
     fn default() -> Self {
         Self {
             style: UnicodeStyle::Bold,
+            transform: TextTransform::None,
             gradient: GradientMode::None,
             start_color: (255, 0, 0),
             end_color: (0, 0, 255),
@@ -190,22 +546,59 @@ impl Default for TextStylizeState {
             cursor_position: 0,
             selected_setting: 0,
             editing_text: false,
+            hyperlink: false,
+            alignment: TextAlignment::Left,
+            color_channel: 0,
         }
     }
 }
 
+/// How far +/- moves a color channel in the text stylizer
+pub const COLOR_CHANNEL_STEP: u8 = 16;
+
 impl TextStylizeState {
+    /// Move the active color channel left (R ← G ← B) or right, wrapping around
+    pub fn cycle_color_channel(&mut self, forward: bool) {
+        self.color_channel = if forward {
+            (self.color_channel + 1) % 3
+        } else {
+            (self.color_channel + 2) % 3
+        };
+    }
+
+    /// Step the active channel of the start (or end) color up or down, clamped to 0-255
+    pub fn adjust_color_channel(&mut self, end: bool, increase: bool) {
+        let color = if end {
+            &mut self.end_color
+        } else {
+            &mut self.start_color
+        };
+        let channel = match self.color_channel {
+            0 => &mut color.0,
+            1 => &mut color.1,
+            _ => &mut color.2,
+        };
+        *channel = if increase {
+            channel.saturating_add(COLOR_CHANNEL_STEP)
+        } else {
+            channel.saturating_sub(COLOR_CHANNEL_STEP)
+        };
+    }
+
     pub fn settings_count() -> usize {
-        6 // style, gradient, start_color, end_color, input, output_format
+        9 // style, transform, gradient, start_color, end_color, input, hyperlink, alignment, output_format
     }
 
     pub fn setting_name(&self, index: usize) -> &'static str {
         match index {
             0 => "Style",
-            1 => "Gradient",
-            2 => "Start Color",
-            3 => "End Color",
-            4 => "Input Text",
+            1 => "Transform",
+            2 => "Gradient",
+            3 => "Start Color",
+            4 => "End Color",
+            5 => "Input Text",
+            6 => "Hyperlink",
+            7 => "Alignment",
             _ => "Unknown",
         }
     }
@@ -213,27 +606,167 @@ impl TextStylizeState {
     pub fn setting_value(&self, index: usize) -> String {
         match index {
             0 => self.style.name().to_string(),
-            1 => self.gradient.name().to_string(),
-            2 => format!(
+            1 => self.transform.name().to_string(),
+            2 => self.gradient.name().to_string(),
+            3 => format!(
                 "#{:02X}{:02X}{:02X}",
                 self.start_color.0, self.start_color.1, self.start_color.2
             ),
-            3 => format!(
+            4 => format!(
                 "#{:02X}{:02X}{:02X}",
                 self.end_color.0, self.end_color.1, self.end_color.2
             ),
-            4 => {
+            5 => {
                 if self.input_text.is_empty() {
                     "[Type here...]".to_string()
-                } else if self.input_text.len() > 20 {
-                    format!("{}...", &self.input_text[..20])
+                } else if display_width(&self.input_text) > 20 {
+                    format!("{}...", truncate_to_width(&self.input_text, 20))
                 } else {
                     self.input_text.clone()
                 }
             }
+            6 => if self.hyperlink { "On" } else { "Off" }.to_string(),
+            7 => self.alignment.name().to_string(),
             _ => String::new(),
         }
     }
+
+    /// URL the output links to: the first URL in the input, when hyperlinks are on
+    pub fn link_target(&self) -> Option<&str> {
+        if self.hyperlink {
+            find_url(&self.input_text)
+        } else {
+            None
+        }
+    }
+}
+
+/// Maximum number of settings changes kept for undo
+pub const UNDO_DEPTH: usize = 50;
+
+/// One mode's control-panel settings, saved for undo/redo
+///
+/// The selected row and text-editing flag are cleared so cursor movement
+/// never counts as a change.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SettingsSnapshot {
+    Ascii(AsciiRenderState),
+    Unicode(UnicodeRenderState),
+    Text(TextStylizeState),
+    ColorAscii(ColorAsciiRenderState),
+}
+
+/// Maximum number of completed renders kept for the history overlay
+pub const HISTORY_LIMIT: usize = 30;
+
+/// A completed render the history overlay can jump back to
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub mode: RenderMode,
+    pub settings: SettingsSnapshot,
+    pub output: String,
+}
+
+impl HistoryEntry {
+    /// Short description of the settings, e.g. "ASCII Art · Extended · 80 cols"
+    pub fn label(&self) -> String {
+        let detail = match &self.settings {
+            SettingsSnapshot::Ascii(ascii) => {
+                format!("{} · {} cols", ascii.charset.name(), ascii.width)
+            }
+            SettingsSnapshot::Unicode(unicode) => {
+                format!("{} · {} cols", unicode.mode.name(), unicode.width)
+            }
+            SettingsSnapshot::ColorAscii(color) => {
+                format!("{} · {} cols", color.charset.name(), color.width)
+            }
+            SettingsSnapshot::Text(text) => {
+                let input: String = text.input_text.chars().take(16).collect();
+                format!("{} · \"{}\"", text.style.name(), input)
+            }
+        };
+        format!("{} · {}", self.mode.name(), detail)
+    }
+}
+
+/// Columns of the ASCII thumbnail shown in the crop overlay
+pub const CROP_THUMBNAIL_WIDTH: usize = 48;
+
+/// Fraction of the image size moved or resized per key press in the crop overlay
+const CROP_STEP_DIVISOR: u32 = 20;
+
+/// Columns of the quick preview shown while a newly loaded image renders at full size
+pub const QUICK_PREVIEW_WIDTH: usize = 40;
+
+/// Images with at least this many pixels get a quick preview when loaded
+pub const QUICK_PREVIEW_MIN_PIXELS: u64 = 1_000_000;
+
+/// A low-width, nearest-filter render of `image`, or `None` when the full render
+/// is no wider than the preview would be
+fn quick_preview_request(
+    image: &Arc<DynamicImage>,
+    config: &CachedConfig,
+) -> Option<WorkerMessage> {
+    match config {
+        CachedConfig::Ascii(config) if config.target_width > QUICK_PREVIEW_WIDTH => {
+            Some(WorkerMessage::AsciiRequest {
+                image: Arc::clone(image),
+                config: AsciiConfig {
+                    target_width: QUICK_PREVIEW_WIDTH,
+                    resize_filter: ResizeFilter::Nearest,
+                    ..config.clone()
+                },
+            })
+        }
+        CachedConfig::Unicode(config) if config.target_width > QUICK_PREVIEW_WIDTH => {
+            Some(WorkerMessage::UnicodeRequest {
+                image: Arc::clone(image),
+                config: UnicodeConfig {
+                    target_width: QUICK_PREVIEW_WIDTH,
+                    resize_filter: ResizeFilter::Nearest,
+                    ..config.clone()
+                },
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Color level to re-render at when `output_len` bytes is over `budget`
+///
+/// Only TrueColor output is downgraded, to 256 colors; a `budget` of 0 never downgrades.
+pub fn downgraded_color(
+    color: ColorSupport,
+    output_len: usize,
+    budget: usize,
+) -> Option<ColorSupport> {
+    (color == ColorSupport::TrueColor && budget > 0 && output_len > budget)
+        .then_some(ColorSupport::Color256)
+}
+
+/// The same render at 256 colors, when TrueColor output overran `budget`
+fn downgraded_request(
+    image: Arc<DynamicImage>,
+    config: &CachedConfig,
+    output_len: usize,
+    budget: usize,
+) -> Option<WorkerMessage> {
+    match config {
+        CachedConfig::Ascii(config) => Some(WorkerMessage::AsciiRequest {
+            image,
+            config: AsciiConfig {
+                color_mode: downgraded_color(config.color_mode, output_len, budget)?,
+                ..config.clone()
+            },
+        }),
+        CachedConfig::Unicode(config) => Some(WorkerMessage::UnicodeRequest {
+            image,
+            config: UnicodeConfig {
+                color_mode: downgraded_color(config.color_mode, output_len, budget)?,
+                ..config.clone()
+            },
+        }),
+    }
 }
 
 /// Main application state
@@ -242,59 +775,156 @@ pub struct AppState {
     pub current_mode: RenderMode,
     pub focus: FocusedWidget,
     pub show_help: bool,
+    /// First help line shown; the overlay is shorter than the help on most terminals
+    pub help_scroll: usize,
+    /// Inner help overlay rect from the last frame, used to clamp `help_scroll`
+    pub help_area: Cell<Option<Rect>>,
     pub should_quit: bool,
 
     // Mode-specific state
     pub ascii_state: AsciiRenderState,
     pub unicode_state: UnicodeRenderState,
     pub text_state: TextStylizeState,
+    pub color_ascii_state: ColorAsciiRenderState,
 
     // Shared state
     pub input_file: Option<PathBuf>,
     pub input_image: Option<Arc<DynamicImage>>,
+    /// Decoded RGBA size of the input image
+    pub input_image_bytes: usize,
+    /// Rotations and flips applied to the loaded image, oldest first
+    pub transform_chain: Vec<ImageTransform>,
+    // The image as loaded; `input_image` is this with `transform_chain` applied
+    source_image: Option<Arc<DynamicImage>>,
+    /// Region of the input image to render instead of the whole picture
+    pub crop: Option<CropRect>,
+    // Input image with `crop` applied, kept so cached renders stay keyed to one image
+    cropped_image: Option<Arc<DynamicImage>>,
     pub preview_content: Option<String>,
+    /// Columns x rows of the last finished render
+    pub preview_grid: (usize, usize),
     pub preview_scroll: usize,
-    pub preview_scroll_x: usize,  // Horizontal scroll position
+    pub preview_scroll_x: usize, // Horizontal scroll position
+    /// Keep the scroll offset across re-renders instead of jumping back to the top
+    pub preserve_scroll_on_render: bool,
+    pub previous_preview_content: Option<String>, // Last committed render, for compare view
+    pub compare_mode: bool,
+    pub compare_scroll_locked: bool,
+    pub compare_scroll: (usize, usize), // Independent (y, x) scroll of the "before" pane
     pub status_message: String,
     pub status_is_error: bool,
 
+    // Settings history, most recent last
+    undo_stack: Vec<SettingsSnapshot>,
+    redo_stack: Vec<SettingsSnapshot>,
+
+    /// Completed renders, oldest first
+    pub history: Vec<HistoryEntry>,
+    // Render history overlay (when pressing [H])
+    pub history_overlay_active: bool,
+    pub history_selected: usize,
+
+    // Crop overlay (when pressing [R]): the selection being edited over an ASCII thumbnail
+    pub crop_overlay_active: bool,
+    pub crop_selection: CropRect,
+    pub crop_thumbnail: String,
+
     // Terminal info
     pub terminal_size: (u16, u16),
+    /// Whether the terminal window has focus; drawing pauses while it doesn't
+    pub focused: bool,
+    /// Columns inside the preview borders for the current terminal size
+    pub preview_width: u16,
     pub capabilities: TerminalCapabilities,
+    /// UI colors resolved from `config.theme`
+    pub theme: Theme,
+    /// Key lookup resolved from `config.keybindings`
+    pub keybindings: Keybindings,
 
     // Performance
     pub perf_metrics: PerfMetrics,
     pub is_rendering: bool,
+    /// Fraction of the in-flight render completed, if the worker has reported any
+    pub render_progress: Option<f32>,
+    /// Recent image renders, so unchanged settings don't hit a worker again
+    pub render_cache: RenderCache,
+    /// Cache key for the in-flight image render
+    pending_cache_key: Option<RenderKey>,
+    // Chunks of the in-flight ASCII render already shown in the preview
+    streamed_chunks: usize,
+    /// Requests sent to the worker so far; each request's generation is its position
+    render_generation: u64,
+    /// Requests the worker has finished (answered or failed), in order
+    completed_generation: u64,
+    /// Generation of the in-flight quick preview, superseded by the next request
+    quick_preview_generation: Option<u64>,
+    /// Render asked for while another was in flight, with its `quick_preview` flag;
+    /// started as soon as the in-flight one finishes, whose output is then stale
+    rerender_pending: Option<bool>,
+    /// The preview holds a quick preview, which the full render replaces in place
+    quick_preview_shown: bool,
+    /// The in-flight render is already a 256-color retry of an oversized one
+    output_budget_retry: bool,
+    /// Bell or OSC 9 sequence for the event loop to write after a slow render
+    pub pending_notification: Option<String>,
 
     // Configuration
     pub config: Config,
+    /// False when the config file existed but couldn't be parsed; it is then left untouched on exit
+    pub config_was_loaded_ok: bool,
 
     // Interactive load prompt (when pressing [L])
     pub load_prompt_active: bool,
     pub load_prompt_input: String,
     pub load_prompt_error: Option<String>,
+    /// Candidates from the last Tab press; repeated Tabs cycle through them
+    pub load_prompt_completions: Vec<String>,
+    /// File browser overlay, opened from the load prompt
+    pub file_browser: Option<FileBrowser>,
+    /// URL fetch from the load prompt still running on a background thread
+    url_fetch: Option<Receiver<(PathBuf, Result<DynamicImage>)>>,
+
+    // Custom charset prompt (when cycling the ASCII charset onto Custom)
+    pub charset_prompt_active: bool,
+    pub charset_prompt_input: String,
+    pub charset_prompt_error: Option<String>,
+
+    // Hex color prompt for the text stylizer; sets the end color when `color_prompt_end`
+    pub color_prompt_active: bool,
+    pub color_prompt_end: bool,
+    pub color_prompt_input: String,
+    pub color_prompt_error: Option<String>,
+
+    // Preset prompt (when pressing [P]); saves instead of loading when `preset_prompt_saving`
+    pub preset_prompt_active: bool,
+    pub preset_prompt_saving: bool,
+    pub preset_prompt_input: String,
+    pub preset_prompt_error: Option<String>,
 
     // Preview control
     pub preview_output_format: OutputFormat,
 
+    // Experimental pixel graphics preview (Unicode mode only)
+    pub graphics: GraphicsMode,
+    pub preview_graphics: Option<String>,
+    /// Inner preview rect from the last frame, used to size and place graphics
+    pub preview_area: Cell<Option<Rect>>,
+
     // Worker communication
     worker_tx: Sender<WorkerMessage>,
 }
 
 /// Output format used when saving previews
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum OutputFormat {
+    #[default]
     Ansi,
     Html,
     Txt,
     Png,
     Svg,
-}
-
-impl Default for OutputFormat {
-    fn default() -> Self {
-        OutputFormat::Ansi
-    }
+    Markdown,
+    Json,
 }
 
 impl OutputFormat {
@@ -305,6 +935,8 @@ impl OutputFormat {
             OutputFormat::Txt => "TXT",
             OutputFormat::Png => "PNG",
             OutputFormat::Svg => "SVG",
+            OutputFormat::Markdown => "MD",
+            OutputFormat::Json => "JSON",
         }
     }
 
@@ -316,16 +948,19 @@ impl OutputFormat {
             OutputFormat::Txt,
             OutputFormat::Png,
             OutputFormat::Svg,
+            OutputFormat::Markdown,
+            OutputFormat::Json,
         ]
     }
 
-    /// Get available formats for Unicode mode (no TXT - breaks display)
+    /// Get available formats for Unicode mode (no TXT/MD - breaks display)
     pub fn unicode_formats() -> &'static [OutputFormat] {
         &[
             OutputFormat::Ansi,
             OutputFormat::Html,
             OutputFormat::Png,
             OutputFormat::Svg,
+            OutputFormat::Json,
         ]
     }
 
@@ -353,6 +988,8 @@ impl OutputFormat {
 }
 
 impl AppState {
+    /// Build the app state from `config`; each image mode starts from its
+    /// `from_config` settings rather than `Default`
     pub fn new(
         config: Config,
         capabilities: TerminalCapabilities,
@@ -360,58 +997,166 @@ impl AppState {
     ) -> Self {
         let (width, height) = capabilities.size;
 
-        // Initialize from config
+        // Initialize from config, plus the detected color support and shared sizing
         let ascii_state = AsciiRenderState {
-            charset: config.ascii.default_charset.clone(),
-            width: config.ascii.default_width,
-            invert: false,
-            edge_enhance: config.ascii.edge_enhance,
-            selected_setting: 0,
+            sizing: config.ui.sizing,
+            ..AsciiRenderState::from_config(&config.ascii)
         };
 
-        let unicode_state = UnicodeRenderState {
-            mode: config.unicode.default_mode,
-            width: config.unicode.default_width,
+        let mut unicode_state = UnicodeRenderState {
             color_mode: capabilities.color_support,
-            selected_setting: 0,
+            sizing: config.ui.sizing,
+            ..UnicodeRenderState::from_config(&config.unicode)
         };
 
-        Self {
+        let color_ascii_state = ColorAsciiRenderState {
+            color_mode: capabilities.color_support,
+            sizing: config.ui.sizing,
+            ..ColorAsciiRenderState::from_config(&config.ascii)
+        };
+
+        // Config keys that failed to parse were replaced by defaults while loading
+        let config_error = if config.defaulted_keys.is_empty() {
+            None
+        } else {
+            Some(format!(
+                "Config: invalid {} (using defaults)",
+                config.defaulted_keys.join(", ")
+            ))
+        };
+
+        // A bad [keybindings] section falls back to the defaults rather than failing startup
+        let (keybindings, keybindings_error) = match Keybindings::from_config(&config.keybindings) {
+            Ok(keybindings) => (keybindings, None),
+            Err(e) => (
+                Keybindings::default(),
+                Some(format!("Keybindings: {} (using defaults)", e)),
+            ),
+        };
+
+        // Likewise an invalid [palette] renders with the full color ramp
+        let mut palette_error = None;
+        if config.unicode.use_palette {
+            match config.palette.parse() {
+                Ok(palette) => unicode_state.palette = palette,
+                Err(e) => palette_error = Some(format!("Palette: {} (not applied)", e)),
+            }
+        }
+
+        // An invalid force_color falls back to auto-detection (see main); report it here
+        let terminal_error = config
+            .terminal
+            .force_color()
+            .err()
+            .map(|e| format!("Terminal: {} (auto-detecting)", e));
+
+        let mut state = Self {
             current_mode: RenderMode::default(),
             focus: FocusedWidget::default(),
             show_help: false,
+            help_scroll: 0,
+            help_area: Cell::new(None),
             should_quit: false,
 
             ascii_state,
             unicode_state,
             text_state: TextStylizeState::default(),
+            color_ascii_state,
 
             input_file: None,
             input_image: None,
+            input_image_bytes: 0,
+            transform_chain: Vec::new(),
+            source_image: None,
+            crop: None,
+            cropped_image: None,
             preview_content: None,
+            preview_grid: (0, 0),
             preview_scroll: 0,
             preview_scroll_x: 0,
+            preserve_scroll_on_render: false,
+            previous_preview_content: None,
+            compare_mode: false,
+            compare_scroll_locked: true,
+            compare_scroll: (0, 0),
             status_message: "Ready - Press [?] for help".to_string(),
             status_is_error: false,
 
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+
+            history: Vec::new(),
+            history_overlay_active: false,
+            history_selected: 0,
+
+            crop_overlay_active: false,
+            crop_selection: CropRect::new(0, 0, 1, 1),
+            crop_thumbnail: String::new(),
+
             terminal_size: (width, height),
+            focused: true,
+            preview_width: crate::ui::preview_inner_width(width),
             capabilities,
+            theme: Theme::from_preferences(&config.theme),
+            keybindings,
 
             perf_metrics: PerfMetrics::new(),
             is_rendering: false,
+            render_progress: None,
+            render_cache: RenderCache::default(),
+            pending_cache_key: None,
+            streamed_chunks: 0,
+            render_generation: 0,
+            completed_generation: 0,
+            quick_preview_generation: None,
+            rerender_pending: None,
+            quick_preview_shown: false,
+            output_budget_retry: false,
+            pending_notification: None,
 
             config,
+            config_was_loaded_ok: true,
 
             // Load prompt defaults
             load_prompt_active: false,
             load_prompt_input: String::new(),
             load_prompt_error: None,
+            load_prompt_completions: Vec::new(),
+            file_browser: None,
+            url_fetch: None,
+
+            charset_prompt_active: false,
+            charset_prompt_input: String::new(),
+            charset_prompt_error: None,
+            color_prompt_active: false,
+            color_prompt_end: false,
+            color_prompt_input: String::new(),
+            color_prompt_error: None,
+
+            preset_prompt_active: false,
+            preset_prompt_saving: false,
+            preset_prompt_input: String::new(),
+            preset_prompt_error: None,
 
             // Default to ANSI output for terminal-first usage
             preview_output_format: OutputFormat::default(),
 
+            graphics: GraphicsMode::Off,
+            preview_graphics: None,
+            preview_area: Cell::new(None),
+
             worker_tx,
+        };
+
+        if let Some(message) = config_error
+            .or(keybindings_error)
+            .or(palette_error)
+            .or(terminal_error)
+        {
+            state.set_status(&message, true);
         }
+        state.apply_auto_fit();
+        state
     }
 
     /// Set current render mode
@@ -419,6 +1164,8 @@ impl AppState {
         if self.current_mode != mode {
             self.current_mode = mode;
             self.preview_content = None;
+            self.previous_preview_content = None;
+            self.preview_graphics = None;
             self.preview_scroll = 0;
             self.set_status(&format!("Switched to {} mode", mode.name()), false);
         }
@@ -427,6 +1174,150 @@ impl AppState {
     /// Update terminal size on resize
     pub fn set_terminal_size(&mut self, width: u16, height: u16) {
         self.terminal_size = (width, height);
+        self.preview_width = crate::ui::preview_inner_width(width);
+    }
+
+    /// Track a terminal resize, re-rendering if a fitted width changed
+    ///
+    /// A render already running finishes first; the re-fitted one follows it.
+    pub fn handle_resize(&mut self, width: u16, height: u16) {
+        self.set_terminal_size(width, height);
+        if self.apply_auto_fit() && self.showing_image() {
+            self.trigger_render();
+        }
+    }
+
+    /// Re-fit the width of each image mode whose sizing follows the preview
+    ///
+    /// Fit height needs the loaded image, so those modes keep their width until one
+    /// is loaded. Returns true if any width changed.
+    pub fn apply_auto_fit(&mut self) -> bool {
+        let columns = (self.preview_width as usize).clamp(20, 300);
+        let (width, height) = self.terminal_size;
+        let rows = crate::ui::preview_inner_height(width, height) as usize;
+        let font_aspect = self.config.ui.font_aspect;
+        let image = self.render_image();
+        let image = image.as_deref();
+
+        let mut changed = false;
+        if let Some(width) = fitted_width(self.ascii_state.sizing, columns, || {
+            image.map(|image| {
+                self.ascii_state
+                    .fit_height_width(image, rows, columns, font_aspect)
+            })
+        }) {
+            changed |= std::mem::replace(&mut self.ascii_state.width, width) != width;
+        }
+        if let Some(width) = fitted_width(self.unicode_state.sizing, columns, || {
+            image.map(|image| {
+                self.unicode_state
+                    .fit_height_width(image, rows, columns, font_aspect)
+            })
+        }) {
+            changed |= std::mem::replace(&mut self.unicode_state.width, width) != width;
+        }
+        if let Some(width) = fitted_width(self.color_ascii_state.sizing, columns, || {
+            image.map(|image| {
+                self.color_ascii_state
+                    .fit_height_width(image, rows, columns, font_aspect)
+            })
+        }) {
+            changed |= std::mem::replace(&mut self.color_ascii_state.width, width) != width;
+        }
+        changed
+    }
+
+    /// Render width of the current mode's image settings
+    fn current_width(&self) -> usize {
+        match self.current_mode {
+            RenderMode::ImageToUnicode => self.unicode_state.width,
+            RenderMode::ColorAscii => self.color_ascii_state.width,
+            _ => self.ascii_state.width,
+        }
+    }
+
+    /// Whether the current mode renders the loaded image
+    pub fn showing_image(&self) -> bool {
+        self.input_image.is_some() && self.current_mode != RenderMode::TextStylizer
+    }
+
+    /// Whether the current output is drawn for a dark background; inverted renders
+    /// put dense glyphs on dark pixels, so they read correctly on light ones
+    pub fn output_for_dark_background(&self) -> bool {
+        match self.current_mode {
+            RenderMode::ImageToAscii => !self.ascii_state.invert,
+            RenderMode::ColorAscii => !self.color_ascii_state.invert,
+            // Half-Blocks ignores invert
+            RenderMode::ImageToUnicode => {
+                !self.unicode_state.invert || self.unicode_state.mode == UnicodeMode::HalfBlocks
+            }
+            RenderMode::TextStylizer => true,
+        }
+    }
+
+    /// Cycle how image modes pick their width: fixed, fit width, fit height
+    /// (persisted via `config.ui.sizing`)
+    pub fn cycle_sizing(&mut self) {
+        let sizing = self.config.ui.sizing.next();
+        self.config.ui.sizing = sizing;
+        self.ascii_state.sizing = sizing;
+        self.unicode_state.sizing = sizing;
+        self.color_ascii_state.sizing = sizing;
+
+        let changed = self.apply_auto_fit();
+        let status = match sizing {
+            SizingMode::Fixed => "Sizing: Fixed".to_string(),
+            SizingMode::FitHeight if self.input_image.is_none() => {
+                "Sizing: Fit height (applies once an image is loaded)".to_string()
+            }
+            _ => format!(
+                "Sizing: {} ({} columns)",
+                sizing.name(),
+                self.current_width()
+            ),
+        };
+        self.set_status(&status, false);
+        if changed && self.showing_image() {
+            self.trigger_render();
+        }
+    }
+
+    /// Nudge the terminal font aspect used for image renders (persisted via `config.ui.font_aspect`)
+    pub fn adjust_font_aspect(&mut self, increase: bool) {
+        let step = if increase { 0.05 } else { -0.05 };
+        let aspect = clamp_font_aspect(self.config.ui.font_aspect + step);
+        self.config.ui.font_aspect = (aspect * 100.0).round() / 100.0;
+        self.set_status(
+            &format!("Font aspect: {:.2}", self.config.ui.font_aspect),
+            false,
+        );
+        self.apply_auto_fit();
+        if self.showing_image() {
+            self.trigger_render();
+        }
+    }
+
+    /// Step the color level of the colored modes to the next of the four levels
+    ///
+    /// Color ASCII cycles its own level; every other mode cycles the Unicode level.
+    /// Re-renders when the current mode draws with the changed level.
+    pub fn cycle_color_mode(&mut self) {
+        let (label, color_mode) = match self.current_mode {
+            RenderMode::ColorAscii => ("Color ASCII", &mut self.color_ascii_state.color_mode),
+            _ => ("Unicode", &mut self.unicode_state.color_mode),
+        };
+        *color_mode = color_mode.next();
+        let status = format!("{} color: {}", label, color_mode.name());
+
+        let uses_color = matches!(
+            self.current_mode,
+            RenderMode::ImageToUnicode | RenderMode::ColorAscii
+        );
+        if uses_color && self.showing_image() {
+            self.trigger_render();
+        }
+        // Set after the render so "Rendering..." doesn't replace the note
+        self.set_status(&status, false);
     }
 
     /// Set status message
@@ -435,6 +1326,44 @@ impl AppState {
         self.status_is_error = is_error;
     }
 
+    /// Warn when Braille mode is selected but the startup probe measured
+    /// Braille glyphs at a width other than one cell
+    pub fn warn_if_braille_misaligned(&mut self) {
+        if self.current_mode != RenderMode::ImageToUnicode
+            || self.unicode_state.mode != UnicodeMode::Braille
+        {
+            return;
+        }
+        if let Some(width) = self.capabilities.braille_width.filter(|&w| w != 1) {
+            self.set_status(
+                &format!(
+                    "Terminal draws Braille {} cells wide - output may misalign",
+                    width
+                ),
+                true,
+            );
+        }
+    }
+
+    /// Record that the config file couldn't be parsed and defaults are in use
+    pub fn mark_config_load_failed(&mut self, error: &str) {
+        self.config_was_loaded_ok = false;
+        self.set_status(
+            &format!(
+                "Config: {} (using defaults; file won't be overwritten)",
+                error
+            ),
+            true,
+        );
+    }
+
+    /// Whether exiting should write the config back to disk
+    ///
+    /// Saving defaults over a file that failed to parse would lose the user's settings.
+    pub fn should_save_config(&self) -> bool {
+        self.config_was_loaded_ok
+    }
+
     /// Set the input image
     pub fn set_input_image(&mut self, path: PathBuf, image: DynamicImage) {
         let filename = path
@@ -442,57 +1371,130 @@ impl AppState {
             .map(|s| s.to_string_lossy().to_string())
             .unwrap_or_else(|| "unknown".to_string());
 
+        // Remember files on disk (not pasted images) so the next session can reload them
+        if let Ok(absolute) = std::fs::canonicalize(&path) {
+            if absolute.is_file() {
+                self.config.last_image = Some(absolute);
+            }
+        }
         self.input_file = Some(path);
-        self.input_image = Some(Arc::new(image));
+        self.input_image_bytes = image_bytes(image.width(), image.height());
+        // Rotations and flips carry over to the new image (e.g. when --watch reloads it)
+        let source = Arc::new(image);
+        let image = if self.transform_chain.is_empty() {
+            Arc::clone(&source)
+        } else {
+            Arc::new(apply_chain(&source, &self.transform_chain))
+        };
+        self.source_image = Some(source);
+        // A crop set for an earlier image (or from --crop) is trimmed to fit this one
+        self.crop = self
+            .crop
+            .map(|crop| crop.clamp_to(image.width(), image.height()));
+        self.cropped_image = None;
+        self.input_image = Some(image);
         self.set_status(&format!("Loaded: {}", filename), false);
         self.preview_content = None;
+        self.render_cache.clear();
+        self.pending_cache_key = None;
 
         // Clear any load prompt state
         self.load_prompt_active = false;
         self.load_prompt_input.clear();
         self.load_prompt_error = None;
+        self.file_browser = None;
 
-        // Auto-render after loading
-        self.trigger_render();
+        // Auto-render after loading; large images show a quick preview first
+        self.apply_auto_fit();
+        self.start_render(true);
     }
 
     /// Trigger a render operation based on current mode
     pub fn trigger_render(&mut self) {
+        self.start_render(false);
+    }
+
+    /// Send a request to the worker, returning its generation
+    ///
+    /// The TUI's single worker answers in request order, so responses are matched to
+    /// generations by counting them.
+    fn send_request(&mut self, msg: WorkerMessage) -> u64 {
+        self.render_generation += 1;
+        let _ = self.worker_tx.send(msg);
+        self.render_generation
+    }
+
+    /// Render in the current mode; with `quick_preview`, a large image is first rendered
+    /// at [`QUICK_PREVIEW_WIDTH`] so something shows while the full render runs
+    fn start_render(&mut self, quick_preview: bool) {
         if self.is_rendering {
+            let pending = self.rerender_pending.unwrap_or(false);
+            self.rerender_pending = Some(pending || quick_preview);
             return;
         }
 
         match self.current_mode {
-            RenderMode::ImageToAscii | RenderMode::ImageToUnicode => {
-                // Clone the Arc first to avoid borrow conflicts
-                let image = if let Some(img) = self.input_image.as_ref() {
-                    Arc::clone(img)
+            RenderMode::ImageToAscii | RenderMode::ImageToUnicode | RenderMode::ColorAscii => {
+                let image = if let Some(img) = self.render_image() {
+                    img
                 } else {
                     self.set_status("No image loaded - Press [L] to load", false);
                     return;
                 };
 
+                let font_aspect = self.config.ui.font_aspect;
+                let config = match self.current_mode {
+                    RenderMode::ImageToAscii => {
+                        CachedConfig::Ascii(self.ascii_state.render_config(font_aspect))
+                    }
+                    RenderMode::ColorAscii => {
+                        CachedConfig::Ascii(self.color_ascii_state.render_config(font_aspect))
+                    }
+                    _ => CachedConfig::Unicode(self.unicode_state.render_config(font_aspect)),
+                };
+                let graphics_bounds = self.graphics_bounds();
+
+                // Graphics previews depend on the pane size, so only character art is cached
+                let key = RenderKey::new(&image, config.clone());
+                if graphics_bounds.is_none() || matches!(config, CachedConfig::Ascii(_)) {
+                    if let Some(output) = self.render_cache.get(&key) {
+                        self.commit_preview(output);
+                        self.set_status("Rendered (cached)", false);
+                        return;
+                    }
+                    self.pending_cache_key = Some(key);
+                }
+
                 self.is_rendering = true;
+                self.streamed_chunks = 0;
                 self.set_status("Rendering...", false);
 
-                let msg = match self.current_mode {
-                    RenderMode::ImageToAscii => WorkerMessage::AsciiRequest {
-                        image,
-                        width: self.ascii_state.width,
-                        charset: self.ascii_state.charset.clone(),
-                        invert: self.ascii_state.invert,
-                        edge_enhance: self.ascii_state.edge_enhance,
-                    },
-                    RenderMode::ImageToUnicode => WorkerMessage::UnicodeRequest {
-                        image,
-                        width: self.unicode_state.width,
-                        mode: self.unicode_state.mode,
-                        color_mode: self.unicode_state.color_mode,
-                    },
-                    _ => return,
+                let large = u64::from(image.width()) * u64::from(image.height())
+                    >= QUICK_PREVIEW_MIN_PIXELS;
+                if quick_preview && large && graphics_bounds.is_none() {
+                    if let Some(preview) = quick_preview_request(&image, &config) {
+                        self.quick_preview_generation = Some(self.send_request(preview));
+                    }
+                }
+
+                let msg = match (config, graphics_bounds) {
+                    (CachedConfig::Ascii(config), _) => {
+                        WorkerMessage::AsciiRequest { image, config }
+                    }
+                    (CachedConfig::Unicode(config), Some(max_size)) => {
+                        WorkerMessage::GraphicsRequest {
+                            image,
+                            config,
+                            mode: self.graphics,
+                            max_size,
+                        }
+                    }
+                    (CachedConfig::Unicode(config), None) => {
+                        WorkerMessage::UnicodeRequest { image, config }
+                    }
                 };
 
-                let _ = self.worker_tx.send(msg);
+                self.send_request(msg);
             }
             RenderMode::TextStylizer => {
                 if self.text_state.input_text.is_empty() {
@@ -505,57 +1507,553 @@ impl AppState {
 
                 let msg = WorkerMessage::TextRequest {
                     text: self.text_state.input_text.clone(),
+                    transform: self.text_state.transform,
                     style: self.text_state.style,
                     gradient: self.text_state.gradient,
                     start_color: self.text_state.start_color,
                     end_color: self.text_state.end_color,
+                    alignment: self.text_state.alignment,
+                    link: self.text_state.link_target().map(str::to_string),
+                    unicode_support: self.capabilities.unicode_support,
                 };
 
-                let _ = self.worker_tx.send(msg);
+                self.send_request(msg);
             }
         }
     }
 
-    /// Handle response from worker thread
-    pub fn handle_worker_response(&mut self, response: WorkerResponse) {
-        self.is_rendering = false;
-
-        match response {
-            WorkerResponse::AsciiComplete { output, render_time } => {
-                self.preview_content = Some(output);
-                self.reset_scroll();
-                self.perf_metrics.last_render_time_ms = render_time;
-                self.set_status(&format!("Rendered in {}ms", render_time), false);
-            }
-            WorkerResponse::UnicodeComplete { output, render_time } => {
-                self.preview_content = Some(output);
-                self.reset_scroll();
-                self.perf_metrics.last_render_time_ms = render_time;
-                self.set_status(&format!("Rendered in {}ms", render_time), false);
+    /// Pixel size of the image that gets rendered: the input image, or its crop
+    pub fn render_dimensions(&self) -> Option<(u32, u32)> {
+        let input = self.input_image.as_ref()?;
+        Some(match self.crop {
+            Some(crop) => {
+                let crop = crop.clamp_to(input.width(), input.height());
+                (crop.width, crop.height)
             }
-            WorkerResponse::TextComplete { output, render_time } => {
-                self.preview_content = Some(output);
-                self.reset_scroll();
-                self.perf_metrics.last_render_time_ms = render_time;
-                self.set_status(&format!("Stylized in {}ms", render_time), false);
-            }
-            WorkerResponse::Error(err) => {
-                self.set_status(&format!("Error: {}", err), true);
+            None => (input.width(), input.height()),
+        })
+    }
+
+    /// The image to render: the input image with any crop applied
+    fn render_image(&mut self) -> Option<Arc<DynamicImage>> {
+        let input = self.input_image.as_ref()?;
+        let Some(crop) = self.crop else {
+            return Some(Arc::clone(input));
+        };
+        let cropped = self
+            .cropped_image
+            .get_or_insert_with(|| Arc::new(crop.apply(input)));
+        Some(Arc::clone(cropped))
+    }
+
+    /// Set (or clear) the crop region, clamped to the loaded image
+    ///
+    /// A region covering the whole image is treated as no crop.
+    pub fn set_crop(&mut self, crop: Option<CropRect>) {
+        let crop = match (crop, self.input_image.as_ref()) {
+            (Some(crop), Some(image)) => {
+                let crop = crop.clamp_to(image.width(), image.height());
+                (!crop.is_full(image.width(), image.height())).then_some(crop)
             }
+            (crop, _) => crop,
+        };
+        if crop == self.crop {
+            return;
         }
+        self.crop = crop;
+        self.cropped_image = None;
+        self.render_cache.clear();
+        self.apply_auto_fit();
+        match crop {
+            Some(crop) => self.set_status(&format!("Crop: {}", crop.describe()), false),
+            None => self.set_status("Crop cleared", false),
+        }
+    }
 
-        // If a load prompt was active and a worker returned an error, show the error message
-        if let Some(err) = self.load_prompt_error.clone() {
-            self.set_status(&err, true);
+    /// Rotate or flip the loaded image and re-render; any crop follows the same pixels
+    pub fn apply_transform(&mut self, transform: ImageTransform) {
+        let Some(image) = self.input_image.as_ref() else {
+            self.set_status("No image loaded - Press [L] to load", false);
+            return;
+        };
+        let (width, height) = (image.width(), image.height());
+        let transformed = Arc::new(transform.apply(image));
+        self.crop = self
+            .crop
+            .map(|crop| transform.apply_to_rect(crop, width, height));
+        self.transform_chain.push(transform);
+        self.replace_input_image(transformed);
+        let count = self.transform_chain.len();
+        let plural = if count == 1 { "" } else { "s" };
+        self.set_status(
+            &format!("{} ({} transform{})", transform.name(), count, plural),
+            false,
+        );
+        if self.showing_image() {
+            self.trigger_render();
         }
     }
 
-    /// Get current mode's selected setting index
-    pub fn current_selected_setting(&self) -> usize {
-        match self.current_mode {
-            RenderMode::ImageToAscii => self.ascii_state.selected_setting,
-            RenderMode::ImageToUnicode => self.unicode_state.selected_setting,
-            RenderMode::TextStylizer => self.text_state.selected_setting,
+    /// Undo every rotation and flip, going back to the image as loaded
+    pub fn reset_transforms(&mut self) {
+        if self.transform_chain.is_empty() {
+            self.set_status("No transforms to reset", false);
+            return;
+        }
+        let Some(source) = self.source_image.as_ref().map(Arc::clone) else {
+            return;
+        };
+        // Walk the crop back through the inverse transforms
+        if let (Some(mut crop), Some(image)) = (self.crop, self.input_image.as_ref()) {
+            let (mut width, mut height) = (image.width(), image.height());
+            for transform in self.transform_chain.iter().rev() {
+                crop = transform.inverse().apply_to_rect(crop, width, height);
+                if matches!(
+                    transform,
+                    ImageTransform::RotateLeft | ImageTransform::RotateRight
+                ) {
+                    std::mem::swap(&mut width, &mut height);
+                }
+            }
+            self.crop = Some(crop);
+        }
+        self.transform_chain.clear();
+        self.replace_input_image(source);
+        self.set_status("Transforms reset", false);
+        if self.showing_image() {
+            self.trigger_render();
+        }
+    }
+
+    /// Swap in a transformed input image, dropping renders of the old one
+    fn replace_input_image(&mut self, image: Arc<DynamicImage>) {
+        self.input_image = Some(image);
+        self.cropped_image = None;
+        self.render_cache.clear();
+        self.pending_cache_key = None;
+        self.apply_auto_fit();
+    }
+
+    /// Open the crop overlay, starting from the current crop or the central half of the image
+    pub fn open_crop(&mut self) {
+        let Some(image) = self.input_image.as_ref().map(Arc::clone) else {
+            self.set_status("No image loaded - Press [L] to load", false);
+            return;
+        };
+        let (width, height) = (image.width(), image.height());
+        self.crop_selection = self.crop.unwrap_or_else(|| {
+            CropRect::new(
+                width / 4,
+                height / 4,
+                (width / 2).max(1),
+                (height / 2).max(1),
+            )
+        });
+
+        // A small plain thumbnail is enough to aim the selection
+        let config = AsciiConfig {
+            target_width: CROP_THUMBNAIL_WIDTH,
+            charset: CharacterSet::Standard,
+            resize_filter: ResizeFilter::Triangle,
+            font_aspect: self.config.ui.font_aspect,
+            parallel: false,
+            ..AsciiConfig::default()
+        };
+        self.crop_thumbnail = render_ascii(&image, &config).unwrap_or_default();
+        self.crop_overlay_active = true;
+        self.set_status("Crop: arrows move, Shift+arrows resize, Enter apply", false);
+    }
+
+    /// Close the crop overlay without changing the crop
+    pub fn close_crop(&mut self) {
+        self.crop_overlay_active = false;
+    }
+
+    /// Step size in pixels for moving or resizing the crop selection
+    fn crop_step(&self) -> (i64, i64) {
+        let (width, height) = self
+            .input_image
+            .as_ref()
+            .map_or((1, 1), |image| (image.width(), image.height()));
+        (
+            (width / CROP_STEP_DIVISOR).max(1) as i64,
+            (height / CROP_STEP_DIVISOR).max(1) as i64,
+        )
+    }
+
+    /// Move the crop selection by whole steps; `resize` grows or shrinks it instead
+    pub fn nudge_crop_selection(&mut self, dx: i64, dy: i64, resize: bool) {
+        let Some((width, height)) = self
+            .input_image
+            .as_ref()
+            .map(|image| (image.width(), image.height()))
+        else {
+            return;
+        };
+        let (step_x, step_y) = self.crop_step();
+        let selection = self.crop_selection;
+        self.crop_selection = if resize {
+            selection.resize(dx * step_x, dy * step_y, width, height)
+        } else {
+            selection.translate(dx * step_x, dy * step_y, width, height)
+        };
+    }
+
+    /// Crop to the overlay's selection and re-render
+    pub fn apply_crop_selection(&mut self) {
+        self.crop_overlay_active = false;
+        self.set_crop(Some(self.crop_selection));
+        if self.showing_image() {
+            self.trigger_render();
+        }
+    }
+
+    /// Render the whole image again
+    pub fn clear_crop(&mut self) {
+        self.crop_overlay_active = false;
+        self.set_crop(None);
+        if self.showing_image() {
+            self.trigger_render();
+        }
+    }
+
+    /// Handle response from worker thread
+    pub fn handle_worker_response(&mut self, response: WorkerResponse) {
+        if let WorkerResponse::Progress { fraction } = response {
+            if self.is_rendering {
+                self.render_progress = Some(fraction);
+            }
+            return;
+        }
+        if let WorkerResponse::AsciiChunk { seq, rows } = response {
+            self.append_chunk(seq, rows);
+            return;
+        }
+
+        self.completed_generation += 1;
+        if self.quick_preview_generation == Some(self.completed_generation) {
+            // The full render is still queued behind the quick preview
+            self.quick_preview_generation = None;
+            self.render_progress = None;
+            if let WorkerResponse::AsciiComplete { output, .. }
+            | WorkerResponse::UnicodeComplete { output, .. } = response
+            {
+                if self.rerender_pending.is_some() {
+                    self.streamed_chunks = 0;
+                    return;
+                }
+                self.show_render(output);
+                self.quick_preview_shown = true;
+                self.set_status("Quick preview - rendering full size...", false);
+            }
+            self.streamed_chunks = 0;
+            return;
+        }
+
+        self.is_rendering = false;
+        self.render_progress = None;
+        let cache_key = self.pending_cache_key.take();
+
+        // The image or settings changed mid-render: keep the output for its own settings,
+        // but show the render they now call for instead
+        if let Some(quick_preview) = self.rerender_pending.take() {
+            if let (
+                WorkerResponse::AsciiComplete { output, .. }
+                | WorkerResponse::UnicodeComplete { output, .. },
+                Some(key),
+            ) = (response, cache_key)
+            {
+                self.render_cache.insert(key, output);
+            }
+            self.output_budget_retry = false;
+            self.streamed_chunks = 0;
+            self.start_render(quick_preview);
+            return;
+        }
+
+        // Over the output budget: render once more with shorter 256-color escapes
+        let retried = std::mem::take(&mut self.output_budget_retry);
+        if let (
+            WorkerResponse::AsciiComplete { output, .. }
+            | WorkerResponse::UnicodeComplete { output, .. },
+            Some(key),
+        ) = (&response, &cache_key)
+        {
+            let budget = self.config.terminal.max_output_bytes;
+            let retry = self
+                .render_image()
+                .filter(|_| !retried)
+                .and_then(|image| downgraded_request(image, key.config(), output.len(), budget));
+            if let Some(msg) = retry {
+                // Cached under the original settings, so a cache hit skips the oversized render
+                self.pending_cache_key = cache_key;
+                self.output_budget_retry = true;
+                self.is_rendering = true;
+                // Streamed rows are already showing; the retry replaces them in place
+                self.quick_preview_shown = self.streamed_chunks > 0;
+                self.streamed_chunks = 0;
+                self.set_status(
+                    &format!(
+                        "Output over {} bytes - re-rendering with 256 colors...",
+                        budget
+                    ),
+                    false,
+                );
+                self.send_request(msg);
+                return;
+            }
+        }
+        self.pending_notification = match &response {
+            WorkerResponse::AsciiComplete { render_time, .. }
+            | WorkerResponse::UnicodeComplete { render_time, .. }
+            | WorkerResponse::GraphicsComplete { render_time, .. }
+            | WorkerResponse::TextComplete { render_time, .. } => {
+                render_notification(&self.config.ui, *render_time)
+            }
+            _ => None,
+        };
+
+        match response {
+            WorkerResponse::AsciiComplete {
+                output,
+                render_time,
+                timing,
+            }
+            | WorkerResponse::UnicodeComplete {
+                output,
+                render_time,
+                timing,
+            } => {
+                if let Some(key) = cache_key {
+                    self.render_cache.insert(key, output.clone());
+                }
+                self.push_history(&output);
+                if self.streamed_chunks > 0 {
+                    // The preview already holds the streamed rows; swap in the final text
+                    self.preview_grid = grid_size(&output);
+                    self.preview_content = Some(output);
+                    self.streamed_chunks = 0;
+                } else {
+                    self.show_render(output);
+                }
+                self.perf_metrics.last_render_time_ms = render_time;
+                self.perf_metrics.last_render_timing = Some(timing);
+                self.set_status(
+                    &format!("Rendered in {}ms ({})", render_time, timing.summary()),
+                    false,
+                );
+            }
+            WorkerResponse::GraphicsComplete {
+                output,
+                graphics,
+                render_time,
+            } => {
+                self.push_history(&output);
+                self.commit_preview(output);
+                self.preview_graphics = Some(graphics);
+                self.perf_metrics.last_render_time_ms = render_time;
+                self.set_status(
+                    &format!("Rendered in {}ms ({})", render_time, self.graphics.name()),
+                    false,
+                );
+            }
+            WorkerResponse::TextComplete {
+                output,
+                render_time,
+            } => {
+                self.push_history(&output);
+                self.commit_preview(output);
+                self.perf_metrics.last_render_time_ms = render_time;
+                self.set_status(&format!("Stylized in {}ms", render_time), false);
+            }
+            WorkerResponse::Error(err) => {
+                self.set_status(&format!("Error: {}", err), true);
+            }
+            // Handled before the render is marked finished
+            WorkerResponse::Progress { .. } | WorkerResponse::AsciiChunk { .. } => {}
+        }
+        self.quick_preview_shown = false;
+
+        // If a load prompt was active and a worker returned an error, show the error message
+        if let Some(err) = self.load_prompt_error.clone() {
+            self.set_status(&err, true);
+        }
+    }
+
+    /// Append a completed render to the history, skipping a repeat of the last entry
+    fn push_history(&mut self, output: &str) {
+        let entry = HistoryEntry {
+            mode: self.current_mode,
+            settings: self.settings_snapshot(),
+            output: output.to_string(),
+        };
+        if self.history.last() == Some(&entry) {
+            return;
+        }
+        if self.history.len() == HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+        self.history.push(entry);
+    }
+
+    /// Open the render history overlay with the newest entry selected
+    pub fn open_history(&mut self) {
+        if self.history.is_empty() {
+            self.set_status("No renders in history yet", false);
+            return;
+        }
+        self.history_overlay_active = true;
+        self.history_selected = self.history.len() - 1;
+    }
+
+    /// Close the render history overlay without restoring anything
+    pub fn close_history(&mut self) {
+        self.history_overlay_active = false;
+    }
+
+    /// Move the history selection up (older) or down (newer)
+    pub fn move_history_selection(&mut self, newer: bool) {
+        if newer {
+            self.history_selected =
+                (self.history_selected + 1).min(self.history.len().saturating_sub(1));
+        } else {
+            self.history_selected = self.history_selected.saturating_sub(1);
+        }
+    }
+
+    /// Restore a history entry's settings and output into the preview
+    ///
+    /// The settings it replaces can be brought back with undo.
+    pub fn restore_history(&mut self, index: usize) {
+        let Some(entry) = self.history.get(index).cloned() else {
+            return;
+        };
+        let previous = self.restore_settings(entry.settings);
+        if previous != self.settings_snapshot() {
+            self.record_undo(previous);
+        }
+        self.commit_preview(entry.output);
+        self.history_overlay_active = false;
+        self.set_status(
+            &format!("Restored render {} of {}", index + 1, self.history.len()),
+            false,
+        );
+    }
+
+    /// Show the next streamed chunk of the render in flight
+    ///
+    /// The first chunk replaces the preview like a finished render; chunks that arrive
+    /// out of order or after the render ended are dropped.
+    fn append_chunk(&mut self, seq: usize, rows: String) {
+        // Rows of a render that is already stale never reach the preview
+        if !self.is_rendering || seq != self.streamed_chunks || self.rerender_pending.is_some() {
+            return;
+        }
+        if seq == 0 {
+            self.show_render(rows);
+        } else if let Some(preview) = self.preview_content.as_mut() {
+            preview.push_str(&rows);
+        }
+        self.streamed_chunks += 1;
+    }
+
+    /// Show a render in the preview; a quick preview is replaced in place so compare view
+    /// keeps the render before it
+    fn show_render(&mut self, output: String) {
+        if std::mem::take(&mut self.quick_preview_shown) {
+            self.preview_grid = grid_size(&output);
+            self.preview_content = Some(output);
+            self.preview_scroll = self.preview_scroll.min(self.max_preview_scroll());
+        } else {
+            self.commit_preview(output);
+        }
+    }
+
+    /// Replace the preview with a finished render, keeping the old one for compare view
+    fn commit_preview(&mut self, output: String) {
+        if self.preview_content.is_some() {
+            self.previous_preview_content = self.preview_content.take();
+        }
+        self.preview_grid = grid_size(&output);
+        self.preview_content = Some(output);
+        self.preview_graphics = None;
+        if self.preserve_scroll_on_render {
+            self.preview_scroll = self.preview_scroll.min(self.max_preview_scroll());
+        } else {
+            self.reset_scroll();
+        }
+    }
+
+    /// Pixel bounds for a graphics preview, or `None` to render character art
+    fn graphics_bounds(&self) -> Option<(u32, u32)> {
+        if self.graphics == GraphicsMode::Off {
+            return None;
+        }
+        let area = self.preview_area.get()?;
+        let (cell_width, cell_height) = cell_pixel_size();
+        Some((
+            area.width as u32 * cell_width,
+            area.height as u32 * cell_height,
+        ))
+    }
+
+    /// Whether the graphics preview should be drawn over the preview area this frame
+    pub fn graphics_visible(&self) -> bool {
+        self.preview_graphics.is_some()
+            && self.current_mode == RenderMode::ImageToUnicode
+            && !self.compare_mode
+            && !self.show_help
+            && !self.load_prompt_active
+            && !self.charset_prompt_active
+            && !self.color_prompt_active
+            && !self.preset_prompt_active
+            && !self.history_overlay_active
+            && !self.crop_overlay_active
+            && self.file_browser.is_none()
+    }
+
+    /// Toggle the side-by-side before/after compare view
+    pub fn toggle_compare_mode(&mut self) {
+        self.compare_mode = !self.compare_mode;
+        self.compare_scroll = (self.preview_scroll, self.preview_scroll_x);
+        if self.compare_mode && self.previous_preview_content.is_none() {
+            self.set_status("Compare: On (no previous render yet)", false);
+        } else {
+            let label = if self.compare_mode { "On" } else { "Off" };
+            self.set_status(&format!("Compare: {}", label), false);
+        }
+    }
+
+    /// Toggle whether both compare panes scroll together
+    ///
+    /// When unlocked, the "before" pane stays at the offset it had when the lock was released.
+    pub fn toggle_compare_scroll_lock(&mut self) {
+        self.compare_scroll_locked = !self.compare_scroll_locked;
+        self.compare_scroll = (self.preview_scroll, self.preview_scroll_x);
+        let label = if self.compare_scroll_locked {
+            "Locked"
+        } else {
+            "Unlocked"
+        };
+        self.set_status(&format!("Compare scroll: {}", label), false);
+    }
+
+    /// Scroll offsets (y, x) for the "before" pane of the compare view
+    pub fn compare_pane_scroll(&self) -> (usize, usize) {
+        if self.compare_scroll_locked {
+            (self.preview_scroll, self.preview_scroll_x)
+        } else {
+            self.compare_scroll
+        }
+    }
+
+    /// Get current mode's selected setting index
+    pub fn current_selected_setting(&self) -> usize {
+        match self.current_mode {
+            RenderMode::ImageToAscii => self.ascii_state.selected_setting,
+            RenderMode::ImageToUnicode => self.unicode_state.selected_setting,
+            RenderMode::TextStylizer => self.text_state.selected_setting,
+            RenderMode::ColorAscii => self.color_ascii_state.selected_setting,
         }
     }
 
@@ -564,6 +2062,8 @@ impl AppState {
         self.load_prompt_active = true;
         self.load_prompt_input.clear();
         self.load_prompt_error = None;
+        self.load_prompt_completions.clear();
+        self.file_browser = None;
         self.set_status("Enter image path and press Enter", false);
     }
 
@@ -572,12 +2072,126 @@ impl AppState {
         self.load_prompt_active = false;
         self.load_prompt_input.clear();
         self.load_prompt_error = None;
+        self.load_prompt_completions.clear();
+        // A fetch still running finishes unseen
+        self.url_fetch = None;
+        self.file_browser = None;
         self.set_status("Load cancelled", false);
     }
 
+    /// Open the file browser in the folder typed so far, or the working directory
+    pub fn open_file_browser(&mut self) {
+        let input = unquote_path(&self.load_prompt_input);
+        let (typed_dir, _) = crate::path_completion::split_input(input);
+        let dir = if typed_dir.is_empty() { "." } else { typed_dir };
+        match FileBrowser::open(std::path::Path::new(dir)) {
+            Ok(browser) => {
+                self.set_status(&format!("Browsing {}", browser.dir.display()), false);
+                self.file_browser = Some(browser);
+            }
+            Err(e) => self.set_status(&format!("{:#}", e), true),
+        }
+    }
+
+    /// Close the file browser, returning to the load prompt
+    pub fn close_file_browser(&mut self) {
+        self.file_browser = None;
+    }
+
+    /// Move the file browser cursor down (or up)
+    pub fn move_browser_selection(&mut self, down: bool) {
+        if let Some(browser) = self.file_browser.as_mut() {
+            browser.move_selection(down);
+        }
+    }
+
+    /// Go up one folder in the file browser
+    pub fn browser_ascend(&mut self) {
+        let Some(browser) = self.file_browser.as_mut() else {
+            return;
+        };
+        if let Err(e) = browser.ascend() {
+            self.set_status(&format!("{:#}", e), true);
+        }
+    }
+
+    /// Enter the selected folder, or load the selected image and close the load flow
+    pub fn browser_activate(&mut self) {
+        let Some(browser) = self.file_browser.as_mut() else {
+            return;
+        };
+        match browser.activate() {
+            Ok(BrowserAction::Selected(path)) => match crate::image_loader::load_image(&path) {
+                Ok(img) => self.set_input_image(path, img),
+                Err(e) => {
+                    let message = load_failure_message(&path, &e);
+                    self.set_status(&message, true);
+                }
+            },
+            Ok(BrowserAction::Entered) => {
+                let dir = browser.dir.display().to_string();
+                self.set_status(&format!("Browsing {}", dir), false);
+            }
+            Ok(BrowserAction::None) => {}
+            Err(e) => self.set_status(&format!("{:#}", e), true),
+        }
+    }
+
+    /// Tab-complete the load prompt path against the filesystem
+    pub fn complete_load_prompt(&mut self) {
+        let candidates = crate::path_completion::complete(&self.load_prompt_input);
+        self.apply_load_completion(candidates);
+    }
+
+    /// Complete the load prompt from `candidates` (the matches for its current input)
+    ///
+    /// A single match is taken outright and several are narrowed to their common
+    /// prefix. Once nothing more is shared, repeated calls cycle through the matches.
+    pub fn apply_load_completion(&mut self, candidates: Vec<String>) {
+        // Still on a candidate offered by the previous Tab: move to the next one
+        let cycling = self
+            .load_prompt_completions
+            .iter()
+            .position(|candidate| *candidate == self.load_prompt_input);
+        if let Some(index) = cycling.filter(|_| self.load_prompt_completions.len() > 1) {
+            let next = (index + 1) % self.load_prompt_completions.len();
+            self.load_prompt_input = self.load_prompt_completions[next].clone();
+            let status = format!(
+                "Match {} of {}",
+                next + 1,
+                self.load_prompt_completions.len()
+            );
+            self.set_status(&status, false);
+            return;
+        }
+
+        self.load_prompt_error = None;
+        match candidates.len() {
+            0 => {
+                self.load_prompt_completions.clear();
+                self.load_prompt_error = Some("No matches".to_string());
+            }
+            1 => {
+                self.load_prompt_input = candidates[0].clone();
+                self.load_prompt_completions.clear();
+                self.set_status("1 match", false);
+            }
+            count => {
+                let prefix = crate::path_completion::common_prefix(&candidates);
+                if prefix.len() > self.load_prompt_input.len() {
+                    self.load_prompt_input = prefix;
+                } else {
+                    self.load_prompt_input = candidates[0].clone();
+                }
+                self.load_prompt_completions = candidates;
+                self.set_status(&format!("{} matches - Tab cycles", count), false);
+            }
+        }
+    }
+
     /// Attempt to load the image from the prompt input
     pub fn submit_load_prompt(&mut self) {
-        let input = self.load_prompt_input.trim();
+        let input = unquote_path(&self.load_prompt_input);
         if input.is_empty() {
             self.load_prompt_error = Some("Path is empty".to_string());
             self.set_status("Path is empty", true);
@@ -585,121 +2199,799 @@ impl AppState {
         }
 
         let path = PathBuf::from(input);
-        if !path.exists() {
-            self.load_prompt_error = Some("File not found".to_string());
-            self.set_status("File not found", true);
+        if crate::image_loader::is_url(input) {
+            self.start_url_fetch(path);
             return;
         }
-
         match crate::image_loader::load_image(&path) {
             Ok(img) => {
                 self.set_input_image(path, img);
             }
             Err(e) => {
-                self.load_prompt_error = Some(format!("Failed to load: {}", e));
-                self.set_status(&format!("Failed to load: {}", e), true);
+                let message = load_failure_message(&path, &e);
+                self.set_status(&message, true);
+                self.load_prompt_error = Some(message);
             }
         }
     }
 
-    /// Get current mode's settings count
-    pub fn current_settings_count(&self) -> usize {
-        match self.current_mode {
-            RenderMode::ImageToAscii => AsciiRenderState::settings_count(),
-            RenderMode::ImageToUnicode => UnicodeRenderState::settings_count(),
-            RenderMode::TextStylizer => TextStylizeState::settings_count(),
-        }
+    /// Fetch a URL on a background thread so the UI stays responsive
+    fn start_url_fetch(&mut self, url: PathBuf) {
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        self.load_prompt_error = None;
+        self.set_status(&format!("Fetching {}...", url.display()), false);
+        self.url_fetch = Some(rx);
+        std::thread::spawn(move || {
+            let result = crate::image_loader::load_image_from_source(&url);
+            // The receiver is gone if the prompt was cancelled meanwhile
+            let _ = tx.send((url, result));
+        });
     }
 
-    /// Navigate to next setting in control panel
-    pub fn next_setting(&mut self) {
-        let count = self.current_settings_count();
-        match self.current_mode {
-            RenderMode::ImageToAscii => {
-                self.ascii_state.selected_setting =
-                    (self.ascii_state.selected_setting + 1) % count;
-            }
-            RenderMode::ImageToUnicode => {
-                self.unicode_state.selected_setting =
-                    (self.unicode_state.selected_setting + 1) % count;
-            }
-            RenderMode::TextStylizer => {
-                self.text_state.selected_setting = (self.text_state.selected_setting + 1) % count;
-            }
-        }
+    /// Whether a URL from the load prompt is still being fetched
+    pub fn is_fetching(&self) -> bool {
+        self.url_fetch.is_some()
     }
 
-    /// Navigate to previous setting in control panel
-    pub fn prev_setting(&mut self) {
-        let count = self.current_settings_count();
-        match self.current_mode {
-            RenderMode::ImageToAscii => {
-                self.ascii_state.selected_setting = if self.ascii_state.selected_setting == 0 {
-                    count - 1
-                } else {
-                    self.ascii_state.selected_setting - 1
-                };
-            }
-            RenderMode::ImageToUnicode => {
-                self.unicode_state.selected_setting = if self.unicode_state.selected_setting == 0 {
-                    count - 1
-                } else {
-                    self.unicode_state.selected_setting - 1
-                };
+    /// Load the image from a finished URL fetch; returns true if one finished
+    pub fn poll_url_fetch(&mut self) -> bool {
+        let Some(rx) = self.url_fetch.as_ref() else {
+            return false;
+        };
+        let finished = match rx.try_recv() {
+            Ok(finished) => finished,
+            Err(TryRecvError::Empty) => return false,
+            Err(TryRecvError::Disconnected) => {
+                self.url_fetch = None;
+                return false;
             }
-            RenderMode::TextStylizer => {
-                self.text_state.selected_setting = if self.text_state.selected_setting == 0 {
-                    count - 1
-                } else {
-                    self.text_state.selected_setting - 1
-                };
+        };
+        self.url_fetch = None;
+        match finished {
+            (url, Ok(img)) => self.set_input_image(url, img),
+            (_, Err(e)) => {
+                let message = e.to_string();
+                self.set_status(&message, true);
+                self.load_prompt_error = Some(message);
             }
         }
+        true
     }
 
-    /// Scroll preview up
-    pub fn scroll_up(&mut self, amount: usize) {
-        self.preview_scroll = self.preview_scroll.saturating_sub(amount);
+    /// Offer to reload the previous session's image, if it still exists
+    pub fn offer_last_image(&mut self) {
+        if let Some(name) = self
+            .config
+            .reloadable_last_image()
+            .and_then(|path| path.file_name())
+        {
+            let hint = format!("Press [r] to reload {}", name.to_string_lossy());
+            self.set_status(&hint, false);
+        }
     }
 
-    /// Scroll preview down
-    pub fn scroll_down(&mut self, amount: usize) {
-        if let Some(ref content) = self.preview_content {
-            let line_count = content.lines().count();
-            self.preview_scroll = (self.preview_scroll + amount).min(line_count.saturating_sub(1));
+    /// Load `config.last_image` again
+    pub fn reload_last_image(&mut self) {
+        let Some(path) = self.config.reloadable_last_image().map(PathBuf::from) else {
+            self.set_status("No previous image to reload", false);
+            return;
+        };
+        match crate::image_loader::load_image(&path) {
+            Ok(img) => self.set_input_image(path, img),
+            Err(e) => self.set_status(&format!("Failed to load: {}", e), true),
         }
     }
 
-    /// Scroll preview left (horizontal)
-    pub fn scroll_left(&mut self, amount: usize) {
-        self.preview_scroll_x = self.preview_scroll_x.saturating_sub(amount);
+    /// Start the custom charset prompt, pre-filled with the last custom ramp
+    pub fn start_charset_prompt(&mut self) {
+        self.charset_prompt_active = true;
+        self.charset_prompt_input = self.config.ascii.custom_charset.clone();
+        self.charset_prompt_error = None;
+        self.set_status("Enter a dark-to-light charset and press Enter", false);
     }
 
-    /// Scroll preview right (horizontal)
-    pub fn scroll_right(&mut self, amount: usize) {
-        if let Some(ref content) = self.preview_content {
-            // Find the maximum line width
-            let max_width = content
-                .lines()
-                .map(|line| crate::input::strip_ansi_codes(line).chars().count())
-                .max()
-                .unwrap_or(0);
-            self.preview_scroll_x = (self.preview_scroll_x + amount).min(max_width.saturating_sub(1));
+    /// Cancel the custom charset prompt, keeping the current charset
+    pub fn cancel_charset_prompt(&mut self) {
+        self.charset_prompt_active = false;
+        self.charset_prompt_input.clear();
+        self.charset_prompt_error = None;
+        self.set_status("Custom charset cancelled", false);
+    }
+
+    /// Validate the prompt input and switch the current mode's charset to it
+    pub fn submit_charset_prompt(&mut self) {
+        let input = self.charset_prompt_input.clone();
+        if let Err(e) = CharacterSet::validate_custom(&input) {
+            self.charset_prompt_error = Some(e.to_string());
+            self.set_status(&e.to_string(), true);
+            return;
+        }
+
+        if self.current_mode == RenderMode::ColorAscii {
+            self.color_ascii_state.charset = CharacterSet::Custom(input.clone());
+        } else {
+            self.ascii_state.charset = CharacterSet::Custom(input.clone());
         }
+        self.config.ascii.custom_charset = input;
+        self.charset_prompt_active = false;
+        self.charset_prompt_input.clear();
+        self.charset_prompt_error = None;
+
+        self.trigger_render();
     }
 
-    /// Reset horizontal scroll when content changes
-    pub fn reset_scroll(&mut self) {
-        self.preview_scroll = 0;
-        self.preview_scroll_x = 0;
+    /// Start the hex color prompt for the text start (or end) color, pre-filled with its value
+    pub fn start_color_prompt(&mut self, end: bool) {
+        let (r, g, b) = if end {
+            self.text_state.end_color
+        } else {
+            self.text_state.start_color
+        };
+        self.color_prompt_active = true;
+        self.color_prompt_end = end;
+        self.color_prompt_input = format!("#{:02X}{:02X}{:02X}", r, g, b);
+        self.color_prompt_error = None;
+        self.set_status("Enter a color as #RRGGBB or #RGB and press Enter", false);
     }
 
-    /// Adjust zoom (increase width when zooming out = more columns, or decrease width when zooming in = fewer columns)
-    /// When `zoom_in` is true we *magnify* (reduce columns) else we *zoom out* (increase columns)
-    pub fn adjust_zoom(&mut self, zoom_in: bool) {
-        match self.current_mode {
-            RenderMode::ImageToAscii => {
-                let width = self.ascii_state.width;
+    /// Cancel the hex color prompt, keeping the current color
+    pub fn cancel_color_prompt(&mut self) {
+        self.color_prompt_active = false;
+        self.color_prompt_input.clear();
+        self.color_prompt_error = None;
+        self.set_status("Color entry cancelled", false);
+    }
+
+    /// Parse the prompt input and set the chosen text color; the change can be undone
+    pub fn submit_color_prompt(&mut self) {
+        let Some(color) = parse_hex_color(&self.color_prompt_input) else {
+            let message = format!(
+                "Invalid color '{}' (use #RRGGBB or #RGB)",
+                self.color_prompt_input.trim()
+            );
+            self.color_prompt_error = Some(message.clone());
+            self.set_status(&message, true);
+            return;
+        };
+
+        let before = self.settings_snapshot();
+        if self.color_prompt_end {
+            self.text_state.end_color = color;
+        } else {
+            self.text_state.start_color = color;
+        }
+        if self.settings_snapshot() != before {
+            self.record_undo(before);
+        }
+        self.color_prompt_active = false;
+        self.color_prompt_input.clear();
+        self.color_prompt_error = None;
+
+        self.trigger_render();
+    }
+
+    /// Store the current mode's settings under `name` in `config.presets`
+    pub fn save_preset(&mut self, name: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            anyhow::bail!("Preset name is empty");
+        }
+
+        let preset = match self.current_mode {
+            RenderMode::ImageToAscii => {
+                let ascii = &self.ascii_state;
+                Preset::Ascii {
+                    charset: ascii.charset.clone(),
+                    width: ascii.width,
+                    invert: ascii.invert,
+                    edge_enhance: ascii.edge_enhance,
+                    edge_method: ascii.edge_method,
+                    edge_strength: ascii.edge_strength,
+                    resize_filter: ascii.resize_filter,
+                    luma: ascii.luma,
+                    saturation: ascii.saturation,
+                    preserve_pixel_aspect: ascii.preserve_pixel_aspect,
+                }
+            }
+            RenderMode::ImageToUnicode => {
+                let unicode = &self.unicode_state;
+                Preset::Unicode {
+                    unicode_mode: unicode.mode,
+                    width: unicode.width,
+                    color_mode: unicode.color_mode,
+                    resize_filter: unicode.resize_filter,
+                    invert: unicode.invert,
+                    negate: unicode.negate,
+                    dither: unicode.dither,
+                    saturation: unicode.saturation,
+                    preserve_pixel_aspect: unicode.preserve_pixel_aspect,
+                }
+            }
+            RenderMode::ColorAscii => {
+                let color = &self.color_ascii_state;
+                Preset::ColorAscii {
+                    charset: color.charset.clone(),
+                    width: color.width,
+                    invert: color.invert,
+                    color_mode: color.color_mode,
+                    resize_filter: color.resize_filter,
+                    saturation: color.saturation,
+                    alpha_as_space: color.alpha_as_space,
+                    auto_levels: color.auto_levels,
+                    empty_char: color.empty_char,
+                }
+            }
+            RenderMode::TextStylizer => {
+                let text = &self.text_state;
+                Preset::Text {
+                    style: text.style,
+                    transform: text.transform,
+                    gradient: text.gradient,
+                    start_color: text.start_color,
+                    end_color: text.end_color,
+                    alignment: text.alignment,
+                }
+            }
+        };
+
+        self.config.presets.insert(name.to_string(), preset);
+        self.set_status(&format!("Saved preset '{}'", name), false);
+        Ok(())
+    }
+
+    /// Restore a saved preset, switching to its mode, and re-render
+    pub fn load_preset(&mut self, name: &str) -> Result<()> {
+        let name = name.trim();
+        let preset = match self.config.presets.get(name) {
+            Some(preset) => preset.clone(),
+            None => anyhow::bail!("No preset named '{}'", name),
+        };
+
+        match preset {
+            Preset::Ascii {
+                charset,
+                width,
+                invert,
+                edge_enhance,
+                edge_method,
+                edge_strength,
+                resize_filter,
+                luma,
+                saturation,
+                preserve_pixel_aspect,
+            } => {
+                self.set_mode(RenderMode::ImageToAscii);
+                let ascii = &mut self.ascii_state;
+                ascii.charset = charset;
+                ascii.width = width;
+                ascii.invert = invert;
+                ascii.edge_enhance = edge_enhance;
+                ascii.edge_method = edge_method;
+                ascii.edge_strength = edge_strength;
+                ascii.resize_filter = resize_filter;
+                ascii.luma = luma;
+                ascii.saturation = clamp_saturation(saturation);
+                ascii.preserve_pixel_aspect = preserve_pixel_aspect;
+            }
+            Preset::Unicode {
+                unicode_mode,
+                width,
+                color_mode,
+                resize_filter,
+                invert,
+                negate,
+                dither,
+                saturation,
+                preserve_pixel_aspect,
+            } => {
+                self.set_mode(RenderMode::ImageToUnicode);
+                let unicode = &mut self.unicode_state;
+                unicode.mode = unicode_mode;
+                unicode.width = width;
+                unicode.color_mode = color_mode;
+                unicode.resize_filter = resize_filter;
+                unicode.invert = invert;
+                unicode.negate = negate;
+                unicode.dither = dither;
+                unicode.saturation = clamp_saturation(saturation);
+                unicode.preserve_pixel_aspect = preserve_pixel_aspect;
+            }
+            Preset::ColorAscii {
+                charset,
+                width,
+                invert,
+                color_mode,
+                resize_filter,
+                saturation,
+                alpha_as_space,
+                auto_levels,
+                empty_char,
+            } => {
+                self.set_mode(RenderMode::ColorAscii);
+                // Listed in full so a new render setting can't be left out of presets
+                self.color_ascii_state = ColorAsciiRenderState {
+                    charset,
+                    width,
+                    invert,
+                    color_mode,
+                    resize_filter,
+                    saturation: clamp_saturation(saturation),
+                    alpha_as_space,
+                    auto_levels,
+                    empty_char,
+                    sizing: self.color_ascii_state.sizing,
+                    selected_setting: self.color_ascii_state.selected_setting,
+                };
+            }
+            Preset::Text {
+                style,
+                transform,
+                gradient,
+                start_color,
+                end_color,
+                alignment,
+            } => {
+                self.set_mode(RenderMode::TextStylizer);
+                let text = &mut self.text_state;
+                text.style = style;
+                text.transform = transform;
+                text.gradient = gradient;
+                text.start_color = start_color;
+                text.end_color = end_color;
+                text.alignment = alignment;
+            }
+        }
+
+        // A fitted sizing mode owns the width; the preset's width only applies to Fixed
+        self.apply_auto_fit();
+        self.set_status(&format!("Loaded preset '{}'", name), false);
+        self.trigger_render();
+        Ok(())
+    }
+
+    /// Snapshot the current mode's settings
+    pub fn settings_snapshot(&self) -> SettingsSnapshot {
+        match self.current_mode {
+            RenderMode::ImageToAscii => SettingsSnapshot::Ascii(AsciiRenderState {
+                selected_setting: 0,
+                ..self.ascii_state.clone()
+            }),
+            RenderMode::ImageToUnicode => SettingsSnapshot::Unicode(UnicodeRenderState {
+                selected_setting: 0,
+                ..self.unicode_state.clone()
+            }),
+            RenderMode::TextStylizer => SettingsSnapshot::Text(TextStylizeState {
+                selected_setting: 0,
+                editing_text: false,
+                color_channel: 0,
+                ..self.text_state.clone()
+            }),
+            RenderMode::ColorAscii => SettingsSnapshot::ColorAscii(ColorAsciiRenderState {
+                selected_setting: 0,
+                ..self.color_ascii_state.clone()
+            }),
+        }
+    }
+
+    /// Wrap `content` with the current mode's settings and last render time for JSON export
+    pub fn json_export(&self, content: &str) -> JsonExport {
+        let (width, style, color_support) = match self.current_mode {
+            RenderMode::ImageToAscii => (
+                Some(self.ascii_state.width),
+                self.ascii_state.charset.name().to_string(),
+                ColorSupport::NoColor,
+            ),
+            RenderMode::ImageToUnicode => (
+                Some(self.unicode_state.width),
+                self.unicode_state.mode.name().to_string(),
+                self.unicode_state.color_mode,
+            ),
+            RenderMode::TextStylizer => (
+                None,
+                self.text_state.style.name().to_string(),
+                // Gradients are always written as TrueColor escapes
+                if self.text_state.gradient == GradientMode::None {
+                    ColorSupport::NoColor
+                } else {
+                    ColorSupport::TrueColor
+                },
+            ),
+            RenderMode::ColorAscii => (
+                Some(self.color_ascii_state.width),
+                self.color_ascii_state.charset.name().to_string(),
+                self.color_ascii_state.color_mode,
+            ),
+        };
+        JsonExport::new(
+            self.current_mode,
+            width,
+            &style,
+            color_support,
+            self.perf_metrics.last_render_time_ms,
+            content,
+        )
+    }
+
+    /// Record settings as they were before a change; a new change clears redo
+    pub fn record_undo(&mut self, before: SettingsSnapshot) {
+        if self.undo_stack.len() == UNDO_DEPTH {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(before);
+        self.redo_stack.clear();
+    }
+
+    /// Record the current settings before a change that is made over several steps
+    pub fn push_undo_snapshot(&mut self) {
+        self.record_undo(self.settings_snapshot());
+    }
+
+    /// Restore the settings before the last change and re-render
+    pub fn undo(&mut self) {
+        match self.undo_stack.pop() {
+            Some(snapshot) => {
+                let current = self.restore_settings(snapshot);
+                self.redo_stack.push(current);
+                self.set_status("Undid settings change", false);
+                self.refresh_after_history();
+            }
+            None => self.set_status("Nothing to undo", false),
+        }
+    }
+
+    /// Reapply the last undone change and re-render
+    pub fn redo(&mut self) {
+        match self.redo_stack.pop() {
+            Some(snapshot) => {
+                let current = self.restore_settings(snapshot);
+                self.undo_stack.push(current);
+                self.set_status("Redid settings change", false);
+                self.refresh_after_history();
+            }
+            None => self.set_status("Nothing to redo", false),
+        }
+    }
+
+    /// Apply a snapshot, switching to its mode; returns the settings it replaced
+    ///
+    /// The selected row is kept and remembered defaults in `config` follow the restore.
+    fn restore_settings(&mut self, snapshot: SettingsSnapshot) -> SettingsSnapshot {
+        match snapshot {
+            SettingsSnapshot::Ascii(ascii) => {
+                self.set_mode(RenderMode::ImageToAscii);
+                let previous = self.settings_snapshot();
+                self.ascii_state = AsciiRenderState {
+                    selected_setting: self.ascii_state.selected_setting,
+                    ..ascii
+                };
+                self.config.ascii.edge_method = self.ascii_state.edge_method;
+                self.config.ascii.resize_filter = self.ascii_state.resize_filter;
+                self.config.ascii.luma = self.ascii_state.luma;
+                self.config.ascii.saturation = self.ascii_state.saturation;
+                previous
+            }
+            SettingsSnapshot::Unicode(unicode) => {
+                self.set_mode(RenderMode::ImageToUnicode);
+                let previous = self.settings_snapshot();
+                self.unicode_state = UnicodeRenderState {
+                    selected_setting: self.unicode_state.selected_setting,
+                    ..unicode
+                };
+                self.config.unicode.resize_filter = self.unicode_state.resize_filter;
+                self.config.unicode.saturation = self.unicode_state.saturation;
+                previous
+            }
+            SettingsSnapshot::Text(text) => {
+                self.set_mode(RenderMode::TextStylizer);
+                let previous = self.settings_snapshot();
+                self.text_state = TextStylizeState {
+                    selected_setting: self.text_state.selected_setting,
+                    color_channel: self.text_state.color_channel,
+                    ..text
+                };
+                previous
+            }
+            SettingsSnapshot::ColorAscii(color) => {
+                self.set_mode(RenderMode::ColorAscii);
+                let previous = self.settings_snapshot();
+                self.color_ascii_state = ColorAsciiRenderState {
+                    selected_setting: self.color_ascii_state.selected_setting,
+                    ..color
+                };
+                previous
+            }
+        }
+    }
+
+    /// Re-render after undo/redo if there is a preview to update
+    fn refresh_after_history(&mut self) {
+        if self.preview_content.is_some() {
+            self.trigger_render();
+        }
+    }
+
+    /// Start the preset prompt in load mode
+    pub fn start_preset_prompt(&mut self) {
+        self.preset_prompt_active = true;
+        self.preset_prompt_saving = false;
+        self.preset_prompt_input.clear();
+        self.preset_prompt_error = None;
+        self.set_status("Enter a preset name (Tab: load/save, ↑/↓: saved)", false);
+    }
+
+    /// Cancel the preset prompt
+    pub fn cancel_preset_prompt(&mut self) {
+        self.preset_prompt_active = false;
+        self.preset_prompt_input.clear();
+        self.preset_prompt_error = None;
+        self.set_status("Preset cancelled", false);
+    }
+
+    /// Switch the preset prompt between loading and saving
+    pub fn toggle_preset_prompt_saving(&mut self) {
+        self.preset_prompt_saving = !self.preset_prompt_saving;
+        self.preset_prompt_error = None;
+    }
+
+    /// Fill the prompt with the next (or previous) saved preset name
+    pub fn cycle_preset_name(&mut self, forward: bool) {
+        let names: Vec<&String> = self.config.presets.keys().collect();
+        if names.is_empty() {
+            return;
+        }
+        let current = names.iter().position(|n| **n == self.preset_prompt_input);
+        let idx = match (current, forward) {
+            (Some(i), true) => (i + 1) % names.len(),
+            (Some(i), false) => (i + names.len() - 1) % names.len(),
+            (None, true) => 0,
+            (None, false) => names.len() - 1,
+        };
+        self.preset_prompt_input = names[idx].clone();
+    }
+
+    /// Save or load the preset named in the prompt
+    pub fn submit_preset_prompt(&mut self) {
+        let name = self.preset_prompt_input.clone();
+        let result = if self.preset_prompt_saving {
+            self.save_preset(&name)
+        } else {
+            self.load_preset(&name)
+        };
+
+        match result {
+            Ok(()) => {
+                self.preset_prompt_active = false;
+                self.preset_prompt_input.clear();
+                self.preset_prompt_error = None;
+            }
+            Err(e) => {
+                self.preset_prompt_error = Some(e.to_string());
+                self.set_status(&e.to_string(), true);
+            }
+        }
+    }
+
+    /// Get current mode's settings count
+    pub fn current_settings_count(&self) -> usize {
+        match self.current_mode {
+            RenderMode::ImageToAscii => AsciiRenderState::settings_count(),
+            RenderMode::ImageToUnicode => UnicodeRenderState::settings_count(),
+            RenderMode::TextStylizer => TextStylizeState::settings_count(),
+            RenderMode::ColorAscii => ColorAsciiRenderState::settings_count(),
+        }
+    }
+
+    /// Navigate to next setting in control panel
+    pub fn next_setting(&mut self) {
+        let count = self.current_settings_count();
+        match self.current_mode {
+            RenderMode::ImageToAscii => {
+                self.ascii_state.selected_setting = (self.ascii_state.selected_setting + 1) % count;
+            }
+            RenderMode::ImageToUnicode => {
+                self.unicode_state.selected_setting =
+                    (self.unicode_state.selected_setting + 1) % count;
+            }
+            RenderMode::TextStylizer => {
+                self.text_state.selected_setting = (self.text_state.selected_setting + 1) % count;
+            }
+            RenderMode::ColorAscii => {
+                self.color_ascii_state.selected_setting =
+                    (self.color_ascii_state.selected_setting + 1) % count;
+            }
+        }
+    }
+
+    /// Navigate to previous setting in control panel
+    pub fn prev_setting(&mut self) {
+        let count = self.current_settings_count();
+        match self.current_mode {
+            RenderMode::ImageToAscii => {
+                self.ascii_state.selected_setting = if self.ascii_state.selected_setting == 0 {
+                    count - 1
+                } else {
+                    self.ascii_state.selected_setting - 1
+                };
+            }
+            RenderMode::ImageToUnicode => {
+                self.unicode_state.selected_setting = if self.unicode_state.selected_setting == 0 {
+                    count - 1
+                } else {
+                    self.unicode_state.selected_setting - 1
+                };
+            }
+            RenderMode::TextStylizer => {
+                self.text_state.selected_setting = if self.text_state.selected_setting == 0 {
+                    count - 1
+                } else {
+                    self.text_state.selected_setting - 1
+                };
+            }
+            RenderMode::ColorAscii => {
+                let selected = self.color_ascii_state.selected_setting;
+                self.color_ascii_state.selected_setting = if selected == 0 {
+                    count - 1
+                } else {
+                    selected - 1
+                };
+            }
+        }
+    }
+
+    /// Scroll preview up
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.preview_scroll = self.preview_scroll.saturating_sub(amount);
+    }
+
+    /// Scroll preview down, stopping once the last line reaches the bottom of the pane
+    pub fn scroll_down(&mut self, amount: usize) {
+        self.preview_scroll = (self.preview_scroll + amount).min(self.max_preview_scroll());
+    }
+
+    /// Jump so the final page of the preview fills the pane
+    pub fn scroll_to_end(&mut self) {
+        self.preview_scroll = self.max_preview_scroll();
+    }
+
+    /// Largest vertical offset that still keeps a full page of content visible
+    ///
+    /// With word wrap on, a line can take several rows, so this is the first
+    /// line from which the rest of the content fits in the pane.
+    fn max_preview_scroll(&self) -> usize {
+        let Some(content) = self.preview_content.as_ref() else {
+            return 0;
+        };
+        // Before the first frame the pane size is unknown; allow one row
+        let (width, visible_height) = self
+            .preview_area
+            .get()
+            .map_or((u16::MAX, 1), |area| (area.width, area.height as usize));
+        let rows: Vec<usize> = crate::ui::line_rows(content, width, &self.config.ui).collect();
+        let mut rows_below = 0;
+        let mut first = rows.len();
+        while first > 0 && rows_below + rows[first - 1] <= visible_height.max(1) {
+            first -= 1;
+            rows_below += rows[first];
+        }
+        // A single line taller than the pane is still scrolled to
+        first.min(rows.len().saturating_sub(1))
+    }
+
+    /// Open the help overlay at its first line
+    pub fn open_help(&mut self) {
+        self.show_help = true;
+        self.help_scroll = 0;
+    }
+
+    /// Scroll the help overlay up
+    pub fn scroll_help_up(&mut self, amount: usize) {
+        self.help_scroll = self.help_scroll.saturating_sub(amount);
+    }
+
+    /// Scroll the help overlay down, stopping once its last line is visible
+    pub fn scroll_help_down(&mut self, amount: usize) {
+        self.help_scroll = (self.help_scroll + amount).min(self.max_help_scroll());
+    }
+
+    /// Jump to the last page of the help overlay
+    pub fn scroll_help_to_end(&mut self) {
+        self.help_scroll = self.max_help_scroll();
+    }
+
+    /// Number of rows the help overlay shows per page
+    pub fn help_page_rows(&self) -> usize {
+        self.help_area
+            .get()
+            .map_or(1, |area| (area.height as usize).max(1))
+    }
+
+    fn max_help_scroll(&self) -> usize {
+        crate::ui::help_line_count(&self.keybindings).saturating_sub(self.help_page_rows())
+    }
+
+    /// Scroll preview left (horizontal)
+    pub fn scroll_left(&mut self, amount: usize) {
+        if self.config.ui.word_wrap {
+            return;
+        }
+        self.preview_scroll_x = self.preview_scroll_x.saturating_sub(amount);
+    }
+
+    /// Scroll preview right (horizontal)
+    pub fn scroll_right(&mut self, amount: usize) {
+        if self.config.ui.word_wrap {
+            return;
+        }
+        if let Some(ref content) = self.preview_content {
+            // Find the maximum line width in terminal cells
+            let max_width = content
+                .lines()
+                .map(|line| display_width(&strip_ansi_codes(line)))
+                .max()
+                .unwrap_or(0);
+            self.preview_scroll_x =
+                (self.preview_scroll_x + amount).min(max_width.saturating_sub(1));
+        }
+    }
+
+    /// Toggle word wrap in the preview (persisted via `config.ui.word_wrap`)
+    pub fn toggle_word_wrap(&mut self) {
+        self.config.ui.word_wrap = !self.config.ui.word_wrap;
+        // Horizontal scrolling is meaningless while lines wrap
+        self.preview_scroll_x = 0;
+        let label = if self.config.ui.word_wrap {
+            "On"
+        } else {
+            "Off"
+        };
+        self.set_status(&format!("Word wrap: {}", label), false);
+    }
+
+    /// Toggle the line-number gutter in the preview (persisted via `config.ui.show_line_numbers`)
+    pub fn toggle_line_numbers(&mut self) {
+        self.config.ui.show_line_numbers = !self.config.ui.show_line_numbers;
+        let label = if self.config.ui.show_line_numbers {
+            "On"
+        } else {
+            "Off"
+        };
+        self.set_status(&format!("Line numbers: {}", label), false);
+    }
+
+    /// Toggle the image/preview memory readout in the status bar
+    pub fn toggle_memory_usage(&mut self) {
+        self.config.ui.show_memory_usage = !self.config.ui.show_memory_usage;
+        let label = if self.config.ui.show_memory_usage {
+            "On"
+        } else {
+            "Off"
+        };
+        self.set_status(&format!("Memory usage: {}", label), false);
+    }
+
+    /// Toggle keeping the preview scroll offset when a new render arrives
+    pub fn toggle_preserve_scroll(&mut self) {
+        self.preserve_scroll_on_render = !self.preserve_scroll_on_render;
+        let label = if self.preserve_scroll_on_render {
+            "On"
+        } else {
+            "Off"
+        };
+        self.set_status(&format!("Keep scroll on render: {}", label), false);
+    }
+
+    /// Reset horizontal scroll when content changes
+    pub fn reset_scroll(&mut self) {
+        self.preview_scroll = 0;
+        self.preview_scroll_x = 0;
+    }
+
+    /// Adjust zoom (increase width when zooming out = more columns, or decrease width when zooming in = fewer columns)
+    /// When `zoom_in` is true we *magnify* (reduce columns) else we *zoom out* (increase columns)
+    pub fn adjust_zoom(&mut self, zoom_in: bool) {
+        match self.current_mode {
+            RenderMode::ImageToAscii => {
+                let width = self.ascii_state.width;
                 let new = zoom_step(width, zoom_in);
                 self.ascii_state.width = new;
                 self.set_status(&format!("ASCII width: {}", new), false);
@@ -712,6 +3004,13 @@ impl AppState {
                 self.set_status(&format!("Unicode width: {}", new), false);
                 self.trigger_render();
             }
+            RenderMode::ColorAscii => {
+                let width = self.color_ascii_state.width;
+                let new = zoom_step(width, zoom_in);
+                self.color_ascii_state.width = new;
+                self.set_status(&format!("Color ASCII width: {}", new), false);
+                self.trigger_render();
+            }
             RenderMode::TextStylizer => {
                 // Text stylizer width not applicable
                 self.set_status("Zoom not applicable for Text Stylizer", true);
@@ -720,6 +3019,16 @@ impl AppState {
     }
 }
 
+/// Columns (widest line in terminal cells) and rows of rendered output
+fn grid_size(content: &str) -> (usize, usize) {
+    let columns = content
+        .lines()
+        .map(|line| display_width(&strip_ansi_codes(line)))
+        .max()
+        .unwrap_or(0);
+    (columns, content.lines().count())
+}
+
 /// Compute a zoom step size for given width. Zoom in reduces width, zoom out increases width.
 fn zoom_step(width: usize, zoom_in: bool) -> usize {
     if zoom_in {
@@ -733,9 +3042,599 @@ fn zoom_step(width: usize, zoom_in: bool) -> usize {
     }
 }
 
+/// Width `sizing` asks for: `None` when fixed (or when fit height has no image yet)
+fn fitted_width(
+    sizing: SizingMode,
+    columns: usize,
+    fit_height: impl FnOnce() -> Option<usize>,
+) -> Option<usize> {
+    match sizing {
+        SizingMode::Fixed => None,
+        SizingMode::FitWidth => Some(columns),
+        SizingMode::FitHeight => fit_height(),
+    }
+}
+
+/// Size of one terminal cell in pixels, falling back to a typical 10x20
+fn cell_pixel_size() -> (u32, u32) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns) as u32,
+            (size.height / size.rows) as u32,
+        ),
+        _ => (10, 20),
+    }
+}
+
+/// Trim whitespace and one pair of surrounding quotes, as pasted from file managers
+fn unquote_path(input: &str) -> &str {
+    let input = input.trim();
+    ['"', '\'']
+        .iter()
+        .find_map(|&quote| {
+            input
+                .strip_prefix(quote)
+                .and_then(|rest| rest.strip_suffix(quote))
+        })
+        .unwrap_or(input)
+}
+
+/// Explain why `path` failed to load: missing, unreadable, unsupported or undecodable
+fn load_failure_message(path: &std::path::Path, error: &anyhow::Error) -> String {
+    use std::io::ErrorKind;
+
+    let io_error = match std::fs::metadata(path) {
+        Ok(meta) if meta.is_dir() => return "Path is a directory, not an image".to_string(),
+        Ok(_) => std::fs::File::open(path).err(),
+        Err(e) => Some(e),
+    };
+    match io_error {
+        Some(e) => match e.kind() {
+            ErrorKind::NotFound => "File not found".to_string(),
+            ErrorKind::PermissionDenied => "Permission denied".to_string(),
+            ErrorKind::InvalidInput => "Invalid path".to_string(),
+            _ => format!("Can't read file: {}", e),
+        },
+        None if !crate::image_loader::is_supported_format(path) => {
+            let extension = path
+                .extension()
+                .map(|ext| format!(".{}", ext.to_string_lossy()))
+                .unwrap_or_else(|| "no extension".to_string());
+            format!(
+                "Unsupported format ({}); use {}",
+                extension,
+                crate::image_loader::supported_extensions().join(", ")
+            )
+        }
+        None => format!("Failed to decode image: {}", error.root_cause()),
+    }
+}
+
+/// Terminal sequence announcing a render that took `render_time_ms`, if it was slow enough
+fn render_notification(ui: &UiPreferences, render_time_ms: u64) -> Option<String> {
+    if !ui.should_notify(render_time_ms) {
+        return None;
+    }
+    Some(if ui.notify_desktop {
+        format!("\x1b]9;Glyphgen: rendered in {}ms\x07", render_time_ms)
+    } else {
+        "\x07".to_string()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::render_engines::RenderTiming;
+
+    fn test_state() -> AppState {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        AppState::new(Config::default(), TerminalCapabilities::default(), tx)
+    }
+
+    #[test]
+    fn test_long_text_input_truncates_on_grapheme_boundary() {
+        // 21 characters with a 4-byte emoji at index 19: byte 20 falls inside it
+        let mut text = TextStylizeState {
+            input_text: "abcdefghijklmnopqrs😀t".to_string(),
+            ..TextStylizeState::default()
+        };
+        assert_eq!(text.input_text.chars().count(), 21);
+        assert!(!text.input_text.is_char_boundary(20));
+
+        assert_eq!(text.setting_value(5), "abcdefghijklmnopqrs...");
+
+        text.input_text = "😀".repeat(10);
+        assert_eq!(text.setting_value(5), "😀".repeat(10));
+        text.input_text = "é".repeat(25);
+        assert_eq!(text.setting_value(5), format!("{}...", "é".repeat(20)));
+    }
+
+    #[test]
+    fn test_unquote_path() {
+        assert_eq!(unquote_path("  '/tmp/cat.png' "), "/tmp/cat.png");
+        assert_eq!(unquote_path("\"my photo.jpg\"\n"), "my photo.jpg");
+        assert_eq!(unquote_path("'mismatched.png\""), "'mismatched.png\"");
+        assert_eq!(unquote_path("plain.png"), "plain.png");
+    }
+
+    #[test]
+    fn test_load_failure_messages() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        let message = |path: &std::path::Path| {
+            let error = crate::image_loader::load_image(path).unwrap_err();
+            load_failure_message(path, &error)
+        };
+
+        assert_eq!(message(&dir.join("missing.png")), "File not found");
+        assert_eq!(message(dir), "Path is a directory, not an image");
+
+        let notes = dir.join("notes.txt");
+        std::fs::write(&notes, "not an image").unwrap();
+        assert!(message(&notes).starts_with("Unsupported format (.txt); use png, jpg"));
+        let bare = dir.join("README");
+        std::fs::write(&bare, "not an image").unwrap();
+        assert!(message(&bare).starts_with("Unsupported format (no extension)"));
+
+        let corrupt = dir.join("corrupt.png");
+        std::fs::write(&corrupt, "not an image").unwrap();
+        assert!(message(&corrupt).starts_with("Failed to decode image: "));
+    }
+
+    #[test]
+    fn test_load_prompt_completion_extends_then_cycles() {
+        let mut state = test_state();
+        state.start_load_prompt();
+        state.load_prompt_input = "pics/c".to_string();
+        let candidates = vec!["pics/cat.png".to_string(), "pics/cats/".to_string()];
+
+        // The shared prefix comes first...
+        state.apply_load_completion(candidates.clone());
+        assert_eq!(state.load_prompt_input, "pics/cat");
+        assert_eq!(state.status_message, "2 matches - Tab cycles");
+
+        // ...then each Tab steps through the matches, wrapping around
+        state.apply_load_completion(candidates.clone());
+        assert_eq!(state.load_prompt_input, "pics/cat.png");
+        state.apply_load_completion(candidates.clone());
+        assert_eq!(state.load_prompt_input, "pics/cats/");
+        assert_eq!(state.status_message, "Match 2 of 2");
+        state.apply_load_completion(candidates);
+        assert_eq!(state.load_prompt_input, "pics/cat.png");
+
+        // Typing starts a fresh completion
+        state.load_prompt_input = "pics/cats/x".to_string();
+        state.apply_load_completion(vec!["pics/cats/x.png".to_string()]);
+        assert_eq!(state.load_prompt_input, "pics/cats/x.png");
+        state.apply_load_completion(Vec::new());
+        assert_eq!(state.load_prompt_error.as_deref(), Some("No matches"));
+    }
+
+    #[test]
+    fn test_file_browser_loads_selected_image() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+        image::RgbImage::new(4, 4)
+            .save(dir.join("pic.png"))
+            .unwrap();
+
+        let mut state = test_state();
+        state.start_load_prompt();
+        state.load_prompt_input = format!("{}{}", dir.display(), std::path::MAIN_SEPARATOR);
+        state.open_file_browser();
+        let browser = state.file_browser.as_ref().unwrap();
+        assert_eq!(browser.entries[1].name, "pic.png");
+
+        state.move_browser_selection(true);
+        state.browser_activate();
+        assert!(state.file_browser.is_none());
+        assert!(!state.load_prompt_active);
+        assert!(state.input_image.is_some());
+        assert_eq!(
+            state.input_file,
+            Some(dir.join("pic.png").canonicalize().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_load_prompt_fetches_url_in_background() {
+        let mut state = test_state();
+        // Nothing listens on port 1, so the fetch fails fast with or without `network`
+        state.load_prompt_input = "http://127.0.0.1:1/pic.png".to_string();
+        state.submit_load_prompt();
+        assert!(state.is_fetching());
+        assert!(!state.status_is_error);
+
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while !state.poll_url_fetch() {
+            assert!(std::time::Instant::now() < deadline, "fetch never finished");
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert!(!state.is_fetching());
+        assert!(state.load_prompt_error.is_some());
+        assert!(state.status_is_error);
+    }
+
+    #[test]
+    fn test_load_prompt_reports_missing_file() {
+        let mut state = test_state();
+        state.load_prompt_input = "'/nonexistent/glyphgen.png'".to_string();
+        state.submit_load_prompt();
+        assert_eq!(state.load_prompt_error.as_deref(), Some("File not found"));
+        assert!(state.status_is_error);
+    }
+
+    #[test]
+    fn test_downgraded_color_decision() {
+        let truecolor = ColorSupport::TrueColor;
+        assert_eq!(
+            downgraded_color(truecolor, 5000, 4096),
+            Some(ColorSupport::Color256)
+        );
+        assert_eq!(downgraded_color(truecolor, 4096, 4096), None);
+        // No budget configured
+        assert_eq!(downgraded_color(truecolor, 5000, 0), None);
+        // Already short escapes, or none at all
+        assert_eq!(downgraded_color(ColorSupport::Color256, 5000, 4096), None);
+        assert_eq!(downgraded_color(ColorSupport::NoColor, 5000, 4096), None);
+    }
+
+    #[test]
+    fn test_oversized_truecolor_output_rerenders_at_256_colors() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(Config::default(), TerminalCapabilities::default(), tx);
+        state.config.terminal.max_output_bytes = 10;
+        state.current_mode = RenderMode::ImageToUnicode;
+        state.unicode_state.color_mode = ColorSupport::TrueColor;
+        state.set_input_image(
+            PathBuf::from("small.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(4, 4)),
+        );
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(WorkerMessage::UnicodeRequest { ref config, .. })
+                if config.color_mode == ColorSupport::TrueColor
+        ));
+
+        state.handle_worker_response(WorkerResponse::UnicodeComplete {
+            output: "x".repeat(11),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        assert!(state.is_rendering);
+        assert!(state.preview_content.is_none());
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(WorkerMessage::UnicodeRequest { ref config, .. })
+                if config.color_mode == ColorSupport::Color256
+        ));
+
+        // The smaller retry is shown and cached without another downgrade
+        state.handle_worker_response(WorkerResponse::UnicodeComplete {
+            output: "y".repeat(11),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        assert!(!state.is_rendering);
+        assert_eq!(state.preview_content, Some("y".repeat(11)));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_slow_render_queues_notification() {
+        let mut state = test_state();
+        state.config.ui.notify_after_ms = 1000;
+        state.handle_worker_response(WorkerResponse::TextComplete {
+            output: "fast".to_string(),
+            render_time: 20,
+        });
+        assert!(state.pending_notification.is_none());
+
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "slow".to_string(),
+            render_time: 2500,
+            timing: RenderTiming::default(),
+        });
+        assert_eq!(state.pending_notification.as_deref(), Some("\x07"));
+
+        state.config.ui.notify_desktop = true;
+        assert_eq!(
+            render_notification(&state.config.ui, 2500).as_deref(),
+            Some("\x1b]9;Glyphgen: rendered in 2500ms\x07")
+        );
+        state.config.ui.do_not_disturb = true;
+        assert_eq!(render_notification(&state.config.ui, 2500), None);
+    }
+
+    #[test]
+    fn test_completed_render_keeps_previous_for_compare() {
+        let mut state = test_state();
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "first".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        assert!(state.previous_preview_content.is_none());
+
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "second".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        assert_eq!(state.previous_preview_content.as_deref(), Some("first"));
+        assert_eq!(state.preview_content.as_deref(), Some("second"));
+    }
+
+    #[test]
+    fn test_undo_charset_changes() {
+        let mut state = test_state();
+        let original = state.ascii_state.charset.clone();
+
+        for charset in [
+            CharacterSet::Blocks,
+            CharacterSet::Standard,
+            CharacterSet::Custom(" .:#".to_string()),
+        ] {
+            let before = state.settings_snapshot();
+            state.ascii_state.charset = charset;
+            state.record_undo(before);
+        }
+        let changed = state.ascii_state.charset.clone();
+
+        for _ in 0..3 {
+            state.undo();
+        }
+        assert_eq!(state.ascii_state.charset, original);
+        state.undo();
+        assert_eq!(state.status_message, "Nothing to undo");
+
+        for _ in 0..3 {
+            state.redo();
+        }
+        assert_eq!(state.ascii_state.charset, changed);
+    }
+
+    #[test]
+    fn test_undo_stack_is_bounded_and_new_change_clears_redo() {
+        let mut state = test_state();
+        for width in 0..UNDO_DEPTH + 10 {
+            let before = state.settings_snapshot();
+            state.ascii_state.width = 100 + width;
+            state.record_undo(before);
+        }
+        assert_eq!(state.undo_stack.len(), UNDO_DEPTH);
+
+        state.undo();
+        assert_eq!(state.redo_stack.len(), 1);
+        state.push_undo_snapshot();
+        assert!(state.redo_stack.is_empty());
+    }
+
+    #[test]
+    fn test_streamed_chunks_assemble_into_final_render() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::from_fn(120, 90, |x, y| {
+            image::Rgb([(x * 2) as u8, (y * 2) as u8, 90])
+        }));
+        let config = AsciiConfig {
+            target_width: 60,
+            ..AsciiConfig::default()
+        };
+        let expected = crate::render_engines::ascii::render_ascii(&img, &config).unwrap();
+        let lines: Vec<&str> = expected.split_inclusive('\n').collect();
+        let third = lines.len() / 3;
+
+        let mut state = test_state();
+        state.preview_content = Some("old".to_string());
+        state.is_rendering = true;
+        let chunks = [
+            &lines[..third],
+            &lines[third..2 * third],
+            &lines[2 * third..],
+        ];
+        for (seq, chunk) in chunks.iter().enumerate() {
+            state.handle_worker_response(WorkerResponse::AsciiChunk {
+                seq,
+                rows: chunk.concat(),
+            });
+            assert!(state.is_rendering);
+        }
+        // A chunk out of sequence is ignored
+        state.handle_worker_response(WorkerResponse::AsciiChunk {
+            seq: 7,
+            rows: "stray".to_string(),
+        });
+        assert_eq!(state.preview_content.as_deref(), Some(expected.as_str()));
+
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: expected.clone(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        assert!(!state.is_rendering);
+        assert_eq!(state.preview_content.as_deref(), Some(expected.as_str()));
+        assert_eq!(state.previous_preview_content.as_deref(), Some("old"));
+    }
+
+    #[test]
+    fn test_defaulted_config_keys_reported_in_status() {
+        let (config, _) = Config::parse("[ascii]\ndefault_width = \"wide\"\n").unwrap();
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let state = AppState::new(config, TerminalCapabilities::default(), tx);
+        assert!(state.status_is_error);
+        assert!(state.status_message.contains("ascii.default_width"));
+    }
+
+    #[test]
+    fn test_config_not_saved_after_failed_load() {
+        let mut state = test_state();
+        assert!(state.should_save_config());
+
+        state.mark_config_load_failed("expected `]`");
+        assert!(!state.should_save_config());
+        assert!(state.status_is_error);
+    }
+
+    #[test]
+    fn test_history_records_and_restores_renders() {
+        let mut state = test_state();
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "first".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        state.ascii_state.width = 120;
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "second".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        // A repeat of the last render is not recorded again
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "second".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        assert_eq!(state.history.len(), 2);
+
+        state.open_history();
+        assert_eq!(state.history_selected, 1);
+        state.move_history_selection(false);
+        state.restore_history(state.history_selected);
+
+        assert!(!state.history_overlay_active);
+        assert_eq!(state.preview_content.as_deref(), Some("first"));
+        assert_eq!(state.ascii_state.width, 80);
+        state.undo();
+        assert_eq!(state.ascii_state.width, 120);
+    }
+
+    #[test]
+    fn test_compare_scroll_lock() {
+        let mut state = test_state();
+        state.toggle_compare_mode();
+        state.preview_scroll = 3;
+        assert_eq!(state.compare_pane_scroll(), (3, 0));
+
+        state.toggle_compare_scroll_lock();
+        state.preview_scroll = 8;
+        assert_eq!(state.compare_pane_scroll(), (3, 0));
+    }
+
+    #[test]
+    fn test_word_wrap_disables_horizontal_scroll() {
+        let mut state = test_state();
+        state.preview_content = Some("x".repeat(200));
+
+        state.scroll_right(10);
+        assert_eq!(state.preview_scroll_x, 10);
+
+        state.toggle_word_wrap();
+        assert!(state.config.ui.word_wrap);
+        assert_eq!(state.preview_scroll_x, 0);
+
+        state.scroll_right(10);
+        assert_eq!(state.preview_scroll_x, 0);
+    }
+
+    #[test]
+    fn test_scroll_right_clamps_to_display_width() {
+        let mut state = test_state();
+        // Two wide chars plus three ASCII cells: seven cells, five chars
+        state.preview_content = Some("\x1b[31m你好\x1b[0mabc".to_string());
+
+        state.scroll_right(100);
+        assert_eq!(state.preview_scroll_x, 6);
+    }
+
+    #[test]
+    fn test_end_leaves_full_page_visible() {
+        let mut state = test_state();
+        let lines: Vec<String> = (0..50).map(|i| format!("line {}", i)).collect();
+        state.preview_content = Some(lines.join("\n"));
+        state.preview_area.set(Some(Rect::new(0, 0, 40, 10)));
+
+        state.scroll_to_end();
+        assert_eq!(state.preview_scroll, 40);
+
+        state.preview_scroll = 0;
+        state.scroll_down(1000);
+        assert_eq!(state.preview_scroll, 40);
+
+        // Content shorter than the pane never scrolls
+        state.preview_content = Some("a\nb".to_string());
+        state.preview_scroll = 0;
+        state.scroll_down(5);
+        assert_eq!(state.preview_scroll, 0);
+    }
+
+    #[test]
+    fn test_end_reaches_last_wrapped_rows() {
+        let mut state = test_state();
+        // 20 lines that each wrap onto three rows of a 10x6 pane
+        let lines: Vec<String> = (0..20)
+            .map(|i| format!("{:02}{}", i, "x".repeat(23)))
+            .collect();
+        state.preview_content = Some(lines.join("\n"));
+        state.preview_area.set(Some(Rect::new(0, 0, 10, 6)));
+        state.scroll_to_end();
+        assert_eq!(state.preview_scroll, 14);
+
+        state.toggle_word_wrap();
+        state.scroll_to_end();
+        assert_eq!(state.preview_scroll, 18);
+        state.scroll_up(5);
+        state.scroll_down(100);
+        assert_eq!(state.preview_scroll, 18);
+    }
+
+    #[test]
+    fn test_help_scroll_stops_at_last_page() {
+        let mut state = test_state();
+        state.help_area.set(Some(Rect::new(0, 0, 60, 20)));
+        let last_page = crate::ui::help_line_count(&state.keybindings) - 20;
+
+        state.open_help();
+        state.scroll_help_down(state.help_page_rows());
+        assert_eq!(state.help_scroll, 20);
+        state.scroll_help_down(1000);
+        assert_eq!(state.help_scroll, last_page);
+        state.scroll_help_up(3);
+        assert_eq!(state.help_scroll, last_page - 3);
+
+        // Reopening starts from the top again
+        state.show_help = false;
+        state.open_help();
+        assert_eq!(state.help_scroll, 0);
+    }
+
+    #[test]
+    fn test_preserve_scroll_across_renders() {
+        let mut state = test_state();
+        state.preview_area.set(Some(Rect::new(0, 0, 40, 10)));
+        let render = |state: &mut AppState, lines: usize| {
+            let output: Vec<String> = (0..lines).map(|i| format!("line {}", i)).collect();
+            state.handle_worker_response(WorkerResponse::AsciiComplete {
+                output: output.join("\n"),
+                render_time: 1,
+                timing: RenderTiming::default(),
+            });
+        };
+
+        render(&mut state, 50);
+        state.scroll_down(25);
+        render(&mut state, 50);
+        assert_eq!(state.preview_scroll, 0);
+
+        state.toggle_preserve_scroll();
+        state.scroll_down(25);
+        render(&mut state, 50);
+        assert_eq!(state.preview_scroll, 25);
+
+        // A shorter render clamps the offset to its last full page
+        render(&mut state, 30);
+        assert_eq!(state.preview_scroll, 20);
+    }
 
     #[test]
     fn test_zoom_step_behaviour() {
@@ -744,4 +3643,737 @@ mod tests {
         assert_eq!(zoom_step(12, true), 10); // min clamp
         assert!(zoom_step(4000, false) <= 4000);
     }
+
+    #[test]
+    fn test_charset_prompt_rejects_short_ramp() {
+        let mut state = test_state();
+        state.start_charset_prompt();
+        state.charset_prompt_input = "#".to_string();
+        state.submit_charset_prompt();
+
+        assert!(state.charset_prompt_active);
+        assert!(state.charset_prompt_error.is_some());
+        assert_eq!(state.ascii_state.charset, CharacterSet::Extended);
+    }
+
+    #[test]
+    fn test_color_channel_cycles_and_wraps() {
+        let mut text = TextStylizeState::default();
+        assert_eq!(text.color_channel, 0);
+        text.cycle_color_channel(true);
+        text.cycle_color_channel(true);
+        assert_eq!(text.color_channel, 2);
+        text.cycle_color_channel(true);
+        assert_eq!(text.color_channel, 0);
+        text.cycle_color_channel(false);
+        assert_eq!(text.color_channel, 2);
+    }
+
+    #[test]
+    fn test_color_channel_adjust_clamps() {
+        let mut text = TextStylizeState::default();
+        // Start is (255, 0, 0): red can't go higher, green can't go lower
+        text.adjust_color_channel(false, true);
+        assert_eq!(text.start_color, (255, 0, 0));
+        text.adjust_color_channel(false, false);
+        assert_eq!(text.start_color, (239, 0, 0));
+
+        text.cycle_color_channel(true);
+        text.adjust_color_channel(false, false);
+        assert_eq!(text.start_color, (239, 0, 0));
+        for _ in 0..20 {
+            text.adjust_color_channel(false, true);
+        }
+        assert_eq!(text.start_color, (239, 255, 0));
+
+        // The end color is adjusted separately, on the same channel
+        text.adjust_color_channel(true, true);
+        assert_eq!(text.end_color, (0, 16, 255));
+    }
+
+    #[test]
+    fn test_color_prompt_sets_text_colors() {
+        let mut state = test_state();
+        state.set_mode(RenderMode::TextStylizer);
+
+        state.start_color_prompt(true);
+        assert_eq!(state.color_prompt_input, "#0000FF");
+        state.color_prompt_input = "#0f0".to_string();
+        state.submit_color_prompt();
+        assert!(!state.color_prompt_active);
+        assert_eq!(state.text_state.end_color, (0, 255, 0));
+
+        // Invalid input keeps the prompt open and the color unchanged
+        state.start_color_prompt(false);
+        state.color_prompt_input = "#12".to_string();
+        state.submit_color_prompt();
+        assert!(state.color_prompt_active);
+        assert!(state.color_prompt_error.is_some());
+        assert_eq!(state.text_state.start_color, (255, 0, 0));
+
+        state.cancel_color_prompt();
+        state.undo();
+        assert_eq!(state.text_state.end_color, (0, 0, 255));
+    }
+
+    #[test]
+    fn test_charset_prompt_sets_and_persists_custom() {
+        let mut state = test_state();
+        state.start_charset_prompt();
+        state.charset_prompt_input = " .oO@".to_string();
+        state.submit_charset_prompt();
+
+        assert!(!state.charset_prompt_active);
+        assert_eq!(
+            state.ascii_state.charset,
+            CharacterSet::Custom(" .oO@".to_string())
+        );
+        assert_eq!(state.config.ascii.custom_charset, " .oO@");
+
+        // Re-opening the prompt starts from the last custom ramp
+        state.start_charset_prompt();
+        assert_eq!(state.charset_prompt_input, " .oO@");
+    }
+
+    #[test]
+    fn test_graphics_preview_cleared_by_text_render() {
+        let mut state = test_state();
+        state.current_mode = RenderMode::ImageToUnicode;
+        state.handle_worker_response(WorkerResponse::GraphicsComplete {
+            output: "fallback".to_string(),
+            graphics: "\x1bPq\x1b\\".to_string(),
+            render_time: 1,
+        });
+        assert_eq!(state.preview_content.as_deref(), Some("fallback"));
+        assert!(state.graphics_visible());
+
+        state.handle_worker_response(WorkerResponse::UnicodeComplete {
+            output: "plain".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        assert!(state.preview_graphics.is_none());
+        assert!(!state.graphics_visible());
+    }
+
+    #[test]
+    fn test_progress_tracked_until_render_completes() {
+        let mut state = test_state();
+
+        // Stray progress with nothing in flight is ignored
+        state.handle_worker_response(WorkerResponse::Progress { fraction: 0.5 });
+        assert_eq!(state.render_progress, None);
+
+        state.is_rendering = true;
+        state.handle_worker_response(WorkerResponse::Progress { fraction: 0.25 });
+        assert!(state.is_rendering);
+        assert_eq!(state.render_progress, Some(0.25));
+
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "done".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        assert!(!state.is_rendering);
+        assert_eq!(state.render_progress, None);
+    }
+
+    #[test]
+    fn test_identical_renders_hit_cache() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(Config::default(), TerminalCapabilities::default(), tx);
+        state.set_input_image(
+            PathBuf::from("test.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(8, 8)),
+        );
+        assert_eq!(rx.try_iter().count(), 1);
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "art".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+
+        // Same settings again: served from the cache without a worker request
+        state.trigger_render();
+        assert_eq!(rx.try_iter().count(), 0);
+        assert_eq!(state.preview_content.as_deref(), Some("art"));
+        assert!(!state.is_rendering);
+
+        // Changed settings miss the cache
+        state.ascii_state.width += 1;
+        state.trigger_render();
+        assert_eq!(rx.try_iter().count(), 1);
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "wider art".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        assert_eq!(state.render_cache.len(), 2);
+
+        // Loading a new image invalidates everything
+        state.set_input_image(
+            PathBuf::from("test.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(8, 8)),
+        );
+        assert_eq!(state.render_cache.len(), 0);
+        assert_eq!(rx.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_reload_mid_render_renders_new_image() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(Config::default(), TerminalCapabilities::default(), tx);
+        state.set_input_image(
+            PathBuf::from("test.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(8, 8)),
+        );
+        assert_eq!(rx.try_iter().count(), 1);
+
+        // The file changes while its first render is still running
+        state.set_input_image(
+            PathBuf::from("test.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(16, 16)),
+        );
+        assert!(state.is_rendering);
+        assert_eq!(rx.try_iter().count(), 0);
+
+        // The old image's rows and result don't reach the preview; the new image renders
+        state.handle_worker_response(WorkerResponse::AsciiChunk {
+            seq: 0,
+            rows: "old rows\n".to_string(),
+        });
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "old art".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        assert_eq!(state.preview_content, None);
+        assert!(state.is_rendering);
+        let requests: Vec<_> = rx.try_iter().collect();
+        assert!(matches!(
+            requests[..],
+            [WorkerMessage::AsciiRequest { ref image, .. }] if image.width() == 16
+        ));
+
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "new art".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        assert_eq!(state.preview_content.as_deref(), Some("new art"));
+        assert!(!state.is_rendering);
+    }
+
+    #[test]
+    fn test_conflicting_keybindings_reported_at_startup() {
+        let mut config = Config::default();
+        config
+            .keybindings
+            .insert("save".to_string(), vec!["q".to_string()]);
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let state = AppState::new(config, TerminalCapabilities::default(), tx);
+
+        assert!(state.status_is_error);
+        assert!(state.status_message.contains("bound to both"));
+    }
+
+    #[test]
+    fn test_preset_restores_settings_and_mode() {
+        let mut state = test_state();
+        state.ascii_state.width = 120;
+        state.ascii_state.invert = true;
+        state.save_preset("wide").unwrap();
+
+        state.ascii_state.width = 40;
+        state.ascii_state.invert = false;
+        state.set_mode(RenderMode::TextStylizer);
+
+        state.load_preset("wide").unwrap();
+        assert_eq!(state.current_mode, RenderMode::ImageToAscii);
+        assert_eq!(state.ascii_state.width, 120);
+        assert!(state.ascii_state.invert);
+
+        assert!(state.save_preset("  ").is_err());
+        assert!(state.load_preset("missing").is_err());
+    }
+
+    #[test]
+    fn test_preset_width_yields_to_fitted_sizing() {
+        let mut state = test_state();
+        state.set_terminal_size(200, 50);
+        state.ascii_state.width = 60;
+        state.save_preset("narrow").unwrap();
+
+        state.cycle_sizing();
+        assert_eq!(state.ascii_state.width, 173);
+        state.load_preset("narrow").unwrap();
+        assert_eq!(state.ascii_state.width, 173);
+
+        // Fixed sizing takes the preset's width as saved
+        state.config.ui.sizing = SizingMode::Fixed;
+        state.ascii_state.sizing = SizingMode::Fixed;
+        state.load_preset("narrow").unwrap();
+        assert_eq!(state.ascii_state.width, 60);
+    }
+
+    #[test]
+    fn test_color_ascii_preset_restores_every_render_setting() {
+        let mut state = test_state();
+        state.set_mode(RenderMode::ColorAscii);
+        let saved = ColorAsciiRenderState {
+            charset: CharacterSet::Blocks,
+            width: 64,
+            invert: true,
+            color_mode: ColorSupport::Color256,
+            resize_filter: ResizeFilter::Nearest,
+            saturation: 1.5,
+            alpha_as_space: false,
+            auto_levels: true,
+            empty_char: Some('.'),
+            ..state.color_ascii_state.clone()
+        };
+        state.color_ascii_state = saved.clone();
+        state.save_preset("poster").unwrap();
+
+        state.color_ascii_state = ColorAsciiRenderState {
+            sizing: saved.sizing,
+            ..ColorAsciiRenderState::default()
+        };
+        state.load_preset("poster").unwrap();
+        assert_eq!(state.color_ascii_state, saved);
+    }
+
+    #[test]
+    fn test_auto_fit_tracks_terminal_width() {
+        let mut state = test_state();
+        state.set_terminal_size(200, 50);
+        assert!(!state.apply_auto_fit());
+        assert_eq!(state.ascii_state.width, 80);
+
+        state.cycle_sizing();
+        assert_eq!(state.config.ui.sizing, SizingMode::FitWidth);
+        assert_eq!(state.ascii_state.width, 173);
+        assert_eq!(state.unicode_state.width, 173);
+
+        // Resizing changes the width once, and very wide terminals are clamped
+        state.set_terminal_size(120, 50);
+        assert!(state.apply_auto_fit());
+        assert!(!state.apply_auto_fit());
+        assert_eq!(state.ascii_state.width, 93);
+        state.set_terminal_size(1000, 50);
+        state.apply_auto_fit();
+        assert_eq!(state.unicode_state.width, 300);
+    }
+
+    #[test]
+    fn test_resize_mid_render_renders_fitted_width() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(Config::default(), TerminalCapabilities::default(), tx);
+        state.set_terminal_size(200, 50);
+        state.cycle_sizing();
+        state.set_input_image(
+            PathBuf::from("test.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(8, 8)),
+        );
+        assert_eq!(rx.try_iter().count(), 1);
+
+        state.handle_resize(120, 50);
+        assert_eq!(rx.try_iter().count(), 0);
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "wide art".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        let requests: Vec<_> = rx.try_iter().collect();
+        assert!(matches!(
+            requests[..],
+            [WorkerMessage::AsciiRequest { ref config, .. }] if config.target_width == 93
+        ));
+    }
+
+    #[test]
+    fn test_fit_height_keeps_rows_within_preview() {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(Config::default(), TerminalCapabilities::default(), tx);
+        state.set_terminal_size(200, 50);
+        state.cycle_sizing();
+        state.cycle_sizing();
+        assert_eq!(state.unicode_state.sizing, SizingMode::FitHeight);
+        assert!(state.status_message.contains("once an image is loaded"));
+
+        // Fitting this tall image to the preview width would overflow its rows
+        let image = DynamicImage::ImageRgb8(image::RgbImage::new(100, 400));
+        state.set_input_image(PathBuf::from("tall.png"), image.clone());
+        let font_aspect = state.config.ui.font_aspect;
+        let rows = crate::ui::preview_inner_height(200, 50) as usize;
+
+        let mut config = state.ascii_state.render_config(font_aspect);
+        assert!(ascii::grid_size(&image, &config).1 <= rows);
+        // The widest such width: one more column no longer fits
+        config.target_width += 1;
+        assert!(ascii::grid_size(&image, &config).1 > rows);
+        let config = state.unicode_state.render_config(font_aspect);
+        assert!(unicode::grid_size(&image, &config).1 <= rows);
+
+        // Shrinking the terminal re-fits
+        let width = state.ascii_state.width;
+        state.set_terminal_size(200, 30);
+        assert!(state.apply_auto_fit());
+        assert!(state.ascii_state.width < width);
+        let rows = crate::ui::preview_inner_height(200, 30) as usize;
+        let config = state.ascii_state.render_config(font_aspect);
+        assert!(ascii::grid_size(&image, &config).1 <= rows);
+    }
+
+    #[test]
+    fn test_fit_height_refits_after_image_changes() {
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(Config::default(), TerminalCapabilities::default(), tx);
+        state.set_terminal_size(200, 50);
+        state.cycle_sizing();
+        state.cycle_sizing();
+        let columns = state.preview_width as usize;
+
+        // A wide image would fit the rows at more columns than the preview has
+        let image = DynamicImage::ImageRgb8(image::RgbImage::new(1000, 100));
+        state.set_input_image(PathBuf::from("wide.png"), image);
+        assert_eq!(state.ascii_state.width, columns);
+
+        // Cropping to a tall region narrows the fitted width
+        state.set_crop(Some(CropRect::new(0, 0, 100, 100)));
+        let cropped = state.ascii_state.width;
+        assert!(cropped < columns);
+
+        // So does a larger font aspect, and rotating turns the crop on its side
+        state.adjust_font_aspect(true);
+        assert!(state.ascii_state.width < cropped);
+        state.set_crop(Some(CropRect::new(0, 0, 200, 100)));
+        let landscape = state.ascii_state.width;
+        state.apply_transform(ImageTransform::RotateRight);
+        assert!(state.ascii_state.width < landscape);
+        state.reset_transforms();
+        assert_eq!(state.ascii_state.width, landscape);
+    }
+
+    #[test]
+    fn test_all_modes_listed() {
+        let modes = RenderMode::all();
+        assert_eq!(
+            modes,
+            &[
+                RenderMode::ImageToAscii,
+                RenderMode::ImageToUnicode,
+                RenderMode::TextStylizer,
+                RenderMode::ColorAscii,
+            ]
+        );
+        assert_eq!(RenderMode::ColorAscii.name(), "Color ASCII");
+    }
+
+    #[test]
+    fn test_color_ascii_renders_with_its_own_settings() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(Config::default(), TerminalCapabilities::default(), tx);
+        state.set_mode(RenderMode::ColorAscii);
+        state.color_ascii_state.color_mode = ColorSupport::TrueColor;
+        state.color_ascii_state.width = 60;
+        state.set_input_image(
+            PathBuf::from("test.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(8, 8)),
+        );
+
+        match rx.try_recv().unwrap() {
+            WorkerMessage::AsciiRequest { config, .. } => {
+                assert_eq!(config.color_mode, ColorSupport::TrueColor);
+                assert_eq!(config.target_width, 60);
+            }
+            other => panic!("unexpected request: {:?}", other),
+        }
+        // The plain ASCII settings are untouched
+        assert_eq!(state.ascii_state.width, 80);
+    }
+
+    #[test]
+    fn test_large_image_gets_quick_preview_first() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(Config::default(), TerminalCapabilities::default(), tx);
+        state.set_input_image(
+            PathBuf::from("big.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(1200, 900)),
+        );
+
+        let requests: Vec<_> = rx
+            .try_iter()
+            .map(|msg| match msg {
+                WorkerMessage::AsciiRequest { config, .. } => {
+                    (config.target_width, config.resize_filter)
+                }
+                other => panic!("unexpected request: {:?}", other),
+            })
+            .collect();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0], (QUICK_PREVIEW_WIDTH, ResizeFilter::Nearest));
+        assert!(requests[1].0 > requests[0].0);
+
+        // The quick preview shows without ending the render...
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "quick".to_string(),
+            render_time: 1,
+            timing: RenderTiming::default(),
+        });
+        assert!(state.is_rendering);
+        assert_eq!(state.preview_content.as_deref(), Some("quick"));
+        assert!(state.history.is_empty());
+
+        // ...and the full render replaces it in place
+        state.handle_worker_response(WorkerResponse::AsciiComplete {
+            output: "full".to_string(),
+            render_time: 5,
+            timing: RenderTiming::default(),
+        });
+        assert!(!state.is_rendering);
+        assert_eq!(state.preview_content.as_deref(), Some("full"));
+        assert_eq!(state.previous_preview_content, None);
+        assert_eq!(state.history.len(), 1);
+
+        // Later renders of the same image skip the preview
+        state.ascii_state.width = 100;
+        state.trigger_render();
+        assert_eq!(rx.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_crop_applied_before_render() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(Config::default(), TerminalCapabilities::default(), tx);
+        state.set_input_image(
+            PathBuf::from("test.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(40, 30)),
+        );
+        rx.try_iter().for_each(drop);
+        state.handle_worker_response(WorkerResponse::Error("stop".to_string()));
+
+        // Overhanging regions are clamped to the image
+        state.set_crop(Some(CropRect::new(10, 10, 100, 100)));
+        assert_eq!(state.crop, Some(CropRect::new(10, 10, 30, 20)));
+        state.trigger_render();
+        match rx.try_recv().unwrap() {
+            WorkerMessage::AsciiRequest { image, .. } => {
+                assert_eq!((image.width(), image.height()), (30, 20));
+            }
+            other => panic!("unexpected request: {:?}", other),
+        }
+        state.handle_worker_response(WorkerResponse::Error("stop".to_string()));
+
+        // A crop covering the whole image is the same as none
+        state.set_crop(Some(CropRect::new(0, 0, 40, 30)));
+        assert_eq!(state.crop, None);
+    }
+
+    #[test]
+    fn test_render_and_grid_dimensions() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(Config::default(), TerminalCapabilities::default(), tx);
+        assert_eq!(state.render_dimensions(), None);
+        state.set_input_image(
+            PathBuf::from("test.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(40, 30)),
+        );
+        rx.try_iter().for_each(drop);
+        assert_eq!(state.render_dimensions(), Some((40, 30)));
+        state.set_crop(Some(CropRect::new(0, 0, 25, 10)));
+        assert_eq!(state.render_dimensions(), Some((25, 10)));
+
+        // Color codes don't count towards the width
+        state.handle_worker_response(WorkerResponse::TextComplete {
+            output: "\x1b[31mabc\x1b[0m\nde\n".to_string(),
+            render_time: 1,
+        });
+        assert_eq!(state.preview_grid, (3, 2));
+    }
+
+    #[test]
+    fn test_braille_width_warning() {
+        let mut state = test_state();
+        state.current_mode = RenderMode::ImageToUnicode;
+        state.unicode_state.mode = UnicodeMode::Braille;
+        state.set_status("Ready", false);
+
+        // Not probed, or probed as expected: no warning
+        state.warn_if_braille_misaligned();
+        state.capabilities.braille_width = Some(1);
+        state.warn_if_braille_misaligned();
+        assert!(!state.status_is_error);
+
+        state.capabilities.braille_width = Some(2);
+        state.unicode_state.mode = UnicodeMode::Blocks;
+        state.warn_if_braille_misaligned();
+        assert!(!state.status_is_error);
+
+        state.unicode_state.mode = UnicodeMode::Braille;
+        state.warn_if_braille_misaligned();
+        assert!(state.status_is_error);
+        assert!(state.status_message.contains("2 cells"));
+    }
+
+    #[test]
+    fn test_crop_overlay_moves_selection_within_image() {
+        let mut state = test_state();
+        state.open_crop();
+        assert!(!state.crop_overlay_active);
+
+        state.set_input_image(
+            PathBuf::from("test.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(100, 100)),
+        );
+        state.open_crop();
+        assert!(state.crop_overlay_active);
+        assert!(!state.crop_thumbnail.is_empty());
+        assert_eq!(state.crop_selection, CropRect::new(25, 25, 50, 50));
+
+        for _ in 0..20 {
+            state.nudge_crop_selection(1, 0, false);
+        }
+        assert_eq!(state.crop_selection, CropRect::new(50, 25, 50, 50));
+        state.nudge_crop_selection(0, -1, true);
+        assert_eq!(state.crop_selection, CropRect::new(50, 25, 50, 45));
+
+        state.apply_crop_selection();
+        assert!(!state.crop_overlay_active);
+        assert_eq!(state.crop, Some(CropRect::new(50, 25, 50, 45)));
+    }
+
+    #[test]
+    fn test_transforms_compose_and_reset() {
+        let mut state = test_state();
+        state.set_input_image(
+            PathBuf::from("test.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(40, 30)),
+        );
+        state.handle_worker_response(WorkerResponse::Error("stop".to_string()));
+        state.set_crop(Some(CropRect::new(0, 0, 10, 30)));
+
+        state.apply_transform(ImageTransform::RotateRight);
+        state.handle_worker_response(WorkerResponse::Error("stop".to_string()));
+        state.apply_transform(ImageTransform::FlipVertical);
+        state.handle_worker_response(WorkerResponse::Error("stop".to_string()));
+        assert_eq!(
+            state.transform_chain,
+            vec![ImageTransform::RotateRight, ImageTransform::FlipVertical]
+        );
+        let image = state.input_image.as_ref().unwrap();
+        assert_eq!((image.width(), image.height()), (30, 40));
+        // The left strip turns into the top strip, then flips to the bottom
+        assert_eq!(state.crop, Some(CropRect::new(0, 30, 30, 10)));
+
+        state.reset_transforms();
+        assert!(state.transform_chain.is_empty());
+        let image = state.input_image.as_ref().unwrap();
+        assert_eq!((image.width(), image.height()), (40, 30));
+        assert_eq!(state.crop, Some(CropRect::new(0, 0, 10, 30)));
+    }
+
+    #[test]
+    fn test_from_config_honors_configured_width() {
+        let mut config = Config::default();
+        config.ascii.default_width = 120;
+        config.ascii.default_charset = CharacterSet::Blocks;
+        config.unicode.default_width = 64;
+        config.unicode.default_mode = UnicodeMode::Braille;
+
+        let ascii = AsciiRenderState::from_config(&config.ascii);
+        assert_eq!(ascii.width, 120);
+        assert_eq!(ascii.charset, CharacterSet::Blocks);
+        let unicode = UnicodeRenderState::from_config(&config.unicode);
+        assert_eq!(unicode.width, 64);
+        assert_eq!(unicode.mode, UnicodeMode::Braille);
+        assert_eq!(ColorAsciiRenderState::from_config(&config.ascii).width, 120);
+
+        // `Default` stays independent of config
+        assert_eq!(AsciiRenderState::default().width, 80);
+        assert_eq!(UnicodeRenderState::default().width, 80);
+
+        // AppState::new builds each mode the same way
+        let (tx, _rx) = crossbeam_channel::unbounded();
+        let state = AppState::new(config, TerminalCapabilities::default(), tx);
+        assert_eq!(state.ascii_state, ascii);
+        assert_eq!(state.color_ascii_state.width, 120);
+        assert_eq!(state.unicode_state.width, 64);
+    }
+
+    #[test]
+    fn test_output_for_dark_background_follows_invert() {
+        let mut state = test_state();
+        assert!(state.output_for_dark_background());
+        state.ascii_state.invert = true;
+        assert!(!state.output_for_dark_background());
+
+        // Inverting only changes glyph choice in Blocks and Braille
+        state.set_mode(RenderMode::ImageToUnicode);
+        state.unicode_state.invert = true;
+        state.unicode_state.mode = UnicodeMode::HalfBlocks;
+        assert!(state.output_for_dark_background());
+        state.unicode_state.mode = UnicodeMode::Braille;
+        assert!(!state.output_for_dark_background());
+
+        state.set_mode(RenderMode::TextStylizer);
+        assert!(state.output_for_dark_background());
+    }
+
+    #[test]
+    fn test_cycle_color_mode_wraps_and_renders() {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let mut state = AppState::new(Config::default(), TerminalCapabilities::default(), tx);
+        state.current_mode = RenderMode::ImageToUnicode;
+        state.unicode_state.color_mode = ColorSupport::Color256;
+        state.set_input_image(
+            PathBuf::from("small.png"),
+            DynamicImage::ImageRgb8(image::RgbImage::new(4, 4)),
+        );
+        while rx.try_recv().is_ok() {}
+        state.is_rendering = false;
+
+        let expected = [
+            ColorSupport::TrueColor,
+            ColorSupport::NoColor,
+            ColorSupport::Color16,
+            ColorSupport::Color256,
+        ];
+        for color in expected {
+            state.cycle_color_mode();
+            assert_eq!(state.unicode_state.color_mode, color);
+            assert!(matches!(
+                rx.try_recv(),
+                Ok(WorkerMessage::UnicodeRequest { ref config, .. }) if config.color_mode == color
+            ));
+            state.is_rendering = false;
+        }
+        assert_eq!(state.status_message, "Unicode color: 256 Colors");
+
+        // Color ASCII cycles its own level
+        state.set_mode(RenderMode::ColorAscii);
+        while rx.try_recv().is_ok() {}
+        let before = state.color_ascii_state.color_mode;
+        state.cycle_color_mode();
+        assert_eq!(state.color_ascii_state.color_mode, before.next());
+        assert_eq!(state.unicode_state.color_mode, ColorSupport::Color256);
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(WorkerMessage::AsciiRequest { ref config, .. }) if config.color_mode == before.next()
+        ));
+    }
+
+    #[test]
+    fn test_font_aspect_adjustment_clamped() {
+        let mut state = test_state();
+        state.adjust_font_aspect(true);
+        assert_eq!(state.config.ui.font_aspect, 0.55);
+        assert_eq!(state.status_message, "Font aspect: 0.55");
+
+        for _ in 0..20 {
+            state.adjust_font_aspect(false);
+        }
+        assert_eq!(state.config.ui.font_aspect, 0.3);
+        assert_eq!(state.ascii_state.render_config(0.3).font_aspect, 0.3);
+    }
 }
@@ -1,12 +1,14 @@
 //! Terminal capability detection
 //!
-//! Detects color support, Unicode support, and terminal size.
+//! Detects color support, Unicode support, graphics protocols, and terminal size.
 
 use crossterm::terminal;
+use serde::{Deserialize, Serialize};
 use std::env;
+use std::time::Duration;
 
 /// Level of color support in the terminal
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum ColorSupport {
     /// No color support
     NoColor,
@@ -46,6 +48,17 @@ impl ColorSupport {
             ColorSupport::TrueColor => ColorSupport::Color256,
         }
     }
+
+    /// Parse a color level as written in config: `none`, `16`, `256` or `truecolor`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "none" | "off" => Some(ColorSupport::NoColor),
+            "16" => Some(ColorSupport::Color16),
+            "256" => Some(ColorSupport::Color256),
+            "truecolor" | "24bit" => Some(ColorSupport::TrueColor),
+            _ => None,
+        }
+    }
 }
 
 /// Level of Unicode support
@@ -59,12 +72,28 @@ pub enum UnicodeSupport {
     Full,
 }
 
+/// Pixel graphics protocol understood by the terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphicsProtocol {
+    /// Character cells only
+    #[default]
+    None,
+    /// DEC Sixel bitmaps
+    Sixel,
+    /// Kitty graphics protocol
+    Kitty,
+}
+
 /// Terminal capabilities
 #[derive(Debug, Clone)]
 pub struct TerminalCapabilities {
     pub color_support: ColorSupport,
     pub unicode_support: UnicodeSupport,
     pub mouse_support: bool,
+    /// Graphics protocol the terminal is known to accept
+    pub graphics_protocol: GraphicsProtocol,
+    /// Cells the terminal advanced for [`PROBE_GLYPH`], if probed at startup
+    pub braille_width: Option<u16>,
     pub size: (u16, u16),
 }
 
@@ -74,14 +103,19 @@ impl Default for TerminalCapabilities {
             color_support: ColorSupport::TrueColor,
             unicode_support: UnicodeSupport::Full,
             mouse_support: true,
+            graphics_protocol: GraphicsProtocol::None,
+            braille_width: None,
             size: (80, 24),
         }
     }
 }
 
 /// Detect terminal capabilities
-pub fn detect_capabilities() -> TerminalCapabilities {
-    let color_support = detect_color_support();
+///
+/// `force_color` (from `[terminal] force_color`) replaces the TERM/COLORTERM
+/// guess; `NO_COLOR` and `FORCE_COLOR` still take precedence over it.
+pub fn detect_capabilities(force_color: Option<ColorSupport>) -> TerminalCapabilities {
+    let color_support = detect_color_support(force_color);
     let unicode_support = detect_unicode_support();
     let size = terminal::size().unwrap_or((80, 24));
 
@@ -89,19 +123,43 @@ pub fn detect_capabilities() -> TerminalCapabilities {
         color_support,
         unicode_support,
         mouse_support: true, // Crossterm always supports mouse
+        graphics_protocol: detect_graphics_protocol(),
+        braille_width: None,
         size,
     }
 }
 
 /// Detect the level of color support
-fn detect_color_support() -> ColorSupport {
+fn detect_color_support(force_color: Option<ColorSupport>) -> ColorSupport {
+    color_support_from_env(|name| env::var(name).ok(), force_color, terminfo_has_flag)
+}
+
+/// Color level from environment variables read through `var`
+///
+/// Precedence: `NO_COLOR`, then `FORCE_COLOR`, then the configured `force_color`,
+/// then `COLORTERM`, `TERM` (consulting `terminfo_flag(term, cap)` inside tmux/screen)
+/// and `WT_SESSION`.
+fn color_support_from_env(
+    var: impl Fn(&str) -> Option<String>,
+    force_color: Option<ColorSupport>,
+    terminfo_flag: impl Fn(&str, &str) -> bool,
+) -> ColorSupport {
     // Check NO_COLOR environment variable (standard for disabling colors)
-    if env::var("NO_COLOR").is_ok() {
+    if var("NO_COLOR").is_some() {
         return ColorSupport::NoColor;
     }
 
-    // Check COLORTERM for TrueColor support
-    if let Ok(colorterm) = env::var("COLORTERM") {
+    // FORCE_COLOR picks a level outright, even when output isn't a terminal
+    if let Some(level) = var("FORCE_COLOR").and_then(|v| forced_color_level(&v)) {
+        return level;
+    }
+
+    if let Some(level) = force_color {
+        return level;
+    }
+
+    // Check COLORTERM for TrueColor support (tmux passes it through from the outer terminal)
+    if let Some(colorterm) = var("COLORTERM") {
         let colorterm = colorterm.to_lowercase();
         if colorterm.contains("truecolor") || colorterm.contains("24bit") {
             return ColorSupport::TrueColor;
@@ -109,9 +167,17 @@ fn detect_color_support() -> ColorSupport {
     }
 
     // Check TERM for color hints
-    if let Ok(term) = env::var("TERM") {
+    if let Some(term) = var("TERM") {
         let term = term.to_lowercase();
 
+        // tmux/screen report 256 colors, but tmux with the Tc or RGB capability does TrueColor
+        if term.starts_with("screen") || term.starts_with("tmux") || var("TMUX").is_some() {
+            if terminfo_flag(&term, "Tc") || terminfo_flag(&term, "RGB") {
+                return ColorSupport::TrueColor;
+            }
+            return ColorSupport::Color256;
+        }
+
         // Modern terminals with TrueColor
         if term.contains("kitty")
             || term.contains("alacritty")
@@ -120,7 +186,7 @@ fn detect_color_support() -> ColorSupport {
             || term.contains("256color")
         {
             // Many 256color terminals also support TrueColor
-            if env::var("COLORTERM").is_ok() {
+            if var("COLORTERM").is_some() {
                 return ColorSupport::TrueColor;
             }
             return ColorSupport::Color256;
@@ -134,11 +200,6 @@ fn detect_color_support() -> ColorSupport {
             return ColorSupport::Color16;
         }
 
-        // Check for screen/tmux
-        if term.contains("screen") || term.contains("tmux") {
-            return ColorSupport::Color256;
-        }
-
         // Basic terminal
         if term.contains("linux") || term.contains("console") {
             return ColorSupport::Color16;
@@ -146,7 +207,7 @@ fn detect_color_support() -> ColorSupport {
     }
 
     // Windows Terminal detection
-    if env::var("WT_SESSION").is_ok() {
+    if var("WT_SESSION").is_some() {
         return ColorSupport::TrueColor;
     }
 
@@ -154,6 +215,200 @@ fn detect_color_support() -> ColorSupport {
     ColorSupport::Color256
 }
 
+/// Whether the terminfo entry for `term` sets the boolean capability `cap`
+///
+/// Asks `tput`, which exits 0 for a set flag; false if `tput` is missing.
+fn terminfo_has_flag(term: &str, cap: &str) -> bool {
+    std::process::Command::new("tput")
+        .args(["-T", term, cap])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Level requested by a `FORCE_COLOR` value: `0`/`false` off, `2` 256 colors,
+/// `3` TrueColor, anything else 16 colors; empty means "not set"
+fn forced_color_level(value: &str) -> Option<ColorSupport> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "" => None,
+        "0" | "false" => Some(ColorSupport::NoColor),
+        "2" => Some(ColorSupport::Color256),
+        "3" => Some(ColorSupport::TrueColor),
+        _ => Some(ColorSupport::Color16),
+    }
+}
+
+/// Detect the graphics protocol from the environment
+///
+/// Kitty is preferred where both are available. Sixel detection only
+/// recognises terminals that always ship it; others (e.g. xterm, which needs
+/// `-ti vt340`) have to be asked with [`query_sixel_support`].
+fn detect_graphics_protocol() -> GraphicsProtocol {
+    graphics_protocol_from_env(
+        &env::var("TERM").unwrap_or_default(),
+        &env::var("TERM_PROGRAM").unwrap_or_default(),
+        env::var("KITTY_WINDOW_ID").is_ok(),
+    )
+}
+
+fn graphics_protocol_from_env(
+    term: &str,
+    term_program: &str,
+    kitty_window: bool,
+) -> GraphicsProtocol {
+    let term = term.to_lowercase();
+    if kitty_window
+        || term == "xterm-kitty"
+        || term.contains("ghostty")
+        || matches!(term_program, "ghostty" | "WezTerm")
+    {
+        return GraphicsProtocol::Kitty;
+    }
+
+    if term.contains("mlterm")
+        || term.contains("foot")
+        || term.contains("yaft")
+        || matches!(term_program, "mintty" | "contour")
+    {
+        return GraphicsProtocol::Sixel;
+    }
+
+    GraphicsProtocol::None
+}
+
+/// Ask the terminal for its Primary Device Attributes (DA1) and check for Sixel
+///
+/// Must be called with raw mode enabled so the reply isn't echoed. Returns
+/// `false` if the terminal does not answer within `timeout`.
+#[cfg(unix)]
+pub fn query_sixel_support(timeout: Duration) -> bool {
+    use std::io::Write;
+
+    let Ok(mut tty) = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+    else {
+        return false;
+    };
+    if tty.write_all(b"\x1b[c").and_then(|_| tty.flush()).is_err() {
+        return false;
+    }
+
+    read_tty_reply(&mut tty, b'c', 64, timeout)
+        .map(|reply| da1_reports_sixel(&String::from_utf8_lossy(&reply)))
+        .unwrap_or(false)
+}
+
+/// Ask the terminal for its Primary Device Attributes (DA1) and check for Sixel
+#[cfg(not(unix))]
+pub fn query_sixel_support(_timeout: Duration) -> bool {
+    false
+}
+
+/// Read a terminal reply up to `terminator` (or more than `max_len` bytes)
+///
+/// Polls before every read, so a terminal that never answers leaves nothing
+/// blocked behind. Returns `None` if the reply isn't complete by `timeout`.
+#[cfg(unix)]
+fn read_tty_reply<T>(
+    tty: &mut T,
+    terminator: u8,
+    max_len: usize,
+    timeout: Duration,
+) -> Option<Vec<u8>>
+where
+    T: std::io::Read + std::os::unix::io::AsRawFd,
+{
+    let deadline = std::time::Instant::now() + timeout;
+    let mut reply = Vec::new();
+    let mut byte = [0u8; 1];
+    loop {
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        let mut fd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let wait_ms = remaining.as_millis().min(i32::MAX as u128) as i32;
+        // SAFETY: `fd` is a single valid pollfd that outlives the call
+        let ready = unsafe { libc::poll(&mut fd, 1, wait_ms) };
+        if ready < 0 && std::io::Error::last_os_error().kind() == std::io::ErrorKind::Interrupted {
+            continue;
+        }
+        if ready <= 0 {
+            return None;
+        }
+        // Poll said readable, so this read returns without blocking
+        if tty.read(&mut byte).ok()? != 1 {
+            return None;
+        }
+        reply.push(byte[0]);
+        if byte[0] == terminator || reply.len() > max_len {
+            return Some(reply);
+        }
+    }
+}
+
+/// Check a DA1 reply such as `ESC [ ? 62 ; 4 ; 22 c` for attribute 4 (Sixel)
+pub fn da1_reports_sixel(reply: &str) -> bool {
+    let Some(start) = reply.find("[?") else {
+        return false;
+    };
+    let attributes = reply[start + 2..].trim_end_matches('c');
+    attributes.split(';').any(|attr| attr == "4")
+}
+
+/// Glyph drawn by [`probe_glyph_width`]; Braille mode assumes it is one cell wide
+pub const PROBE_GLYPH: char = '⣿';
+
+/// Print `glyph` at the start of the line and ask where the cursor ended up
+///
+/// Sends a Cursor Position Report request (`ESC [ 6 n`) after the glyph and
+/// infers its width from the reported column, then clears the line again.
+/// Must be called with raw mode enabled so the reply isn't echoed. Returns
+/// `None` if the terminal does not answer within `timeout`.
+#[cfg(unix)]
+pub fn probe_glyph_width(glyph: char, timeout: Duration) -> Option<u16> {
+    use crossterm::{
+        cursor::MoveToColumn,
+        queue,
+        style::Print,
+        terminal::{Clear, ClearType},
+    };
+    use std::io::Write;
+
+    let mut tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    queue!(tty, MoveToColumn(0), Print(glyph), Print("\x1b[6n")).ok()?;
+    tty.flush().ok()?;
+
+    let width = read_tty_reply(&mut tty, b'R', 32, timeout)
+        .and_then(|reply| glyph_width_from_report(&String::from_utf8_lossy(&reply)));
+    let _ = queue!(tty, MoveToColumn(0), Clear(ClearType::CurrentLine));
+    let _ = tty.flush();
+    width
+}
+
+/// Print `glyph` at the start of the line and ask where the cursor ended up
+#[cfg(not(unix))]
+pub fn probe_glyph_width(_glyph: char, _timeout: Duration) -> Option<u16> {
+    None
+}
+
+/// Width of a glyph printed at column 1, from a Cursor Position Report such
+/// as `ESC [ 12 ; 2 R` (row 12, column 2 means one cell wide)
+pub fn glyph_width_from_report(reply: &str) -> Option<u16> {
+    let start = reply.rfind("\x1b[")?;
+    let (_row, column) = reply[start + 2..].strip_suffix('R')?.split_once(';')?;
+    column.parse::<u16>().ok()?.checked_sub(1)
+}
+
 /// Detect Unicode support level
 fn detect_unicode_support() -> UnicodeSupport {
     // Check LANG and LC_ALL for UTF-8
@@ -211,5 +466,177 @@ mod tests {
         assert_eq!(caps.color_support, ColorSupport::TrueColor);
         assert_eq!(caps.unicode_support, UnicodeSupport::Full);
         assert!(caps.mouse_support);
+        assert_eq!(caps.graphics_protocol, GraphicsProtocol::None);
+    }
+
+    fn color_support_with(vars: &[(&str, &str)]) -> ColorSupport {
+        color_support_with_terminfo(vars, &[])
+    }
+
+    fn color_support_with_terminfo(vars: &[(&str, &str)], flags: &[&str]) -> ColorSupport {
+        color_support_from_env(
+            |name| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            },
+            None,
+            |_, cap| flags.contains(&cap),
+        )
+    }
+
+    #[test]
+    fn test_color_env_precedence() {
+        let truecolor = ("COLORTERM", "truecolor");
+        assert_eq!(color_support_with(&[truecolor]), ColorSupport::TrueColor);
+
+        // FORCE_COLOR beats COLORTERM
+        assert_eq!(
+            color_support_with(&[truecolor, ("FORCE_COLOR", "1")]),
+            ColorSupport::Color16
+        );
+        assert_eq!(
+            color_support_with(&[("FORCE_COLOR", "2"), ("TERM", "dumb")]),
+            ColorSupport::Color256
+        );
+        assert_eq!(
+            color_support_with(&[("FORCE_COLOR", "0"), truecolor]),
+            ColorSupport::NoColor
+        );
+        // An empty FORCE_COLOR is ignored
+        assert_eq!(
+            color_support_with(&[("FORCE_COLOR", ""), truecolor]),
+            ColorSupport::TrueColor
+        );
+
+        // NO_COLOR beats both
+        assert_eq!(
+            color_support_with(&[("NO_COLOR", "1"), ("FORCE_COLOR", "3"), truecolor]),
+            ColorSupport::NoColor
+        );
+    }
+
+    #[test]
+    fn test_tmux_truecolor_detection() {
+        // tmux forwarding COLORTERM from a truecolor terminal
+        assert_eq!(
+            color_support_with(&[("TERM", "tmux-256color"), ("COLORTERM", "truecolor")]),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            color_support_with(&[("TERM", "screen-256color"), ("COLORTERM", "24bit")]),
+            ColorSupport::TrueColor
+        );
+
+        // No COLORTERM: fall back to the Tc/RGB terminfo flags
+        assert_eq!(
+            color_support_with_terminfo(&[("TERM", "tmux-256color")], &["Tc"]),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            color_support_with_terminfo(
+                &[("TERM", "xterm-256color"), ("TMUX", "/tmp/s")],
+                &["RGB"]
+            ),
+            ColorSupport::TrueColor
+        );
+        assert_eq!(
+            color_support_with(&[("TERM", "tmux-256color")]),
+            ColorSupport::Color256
+        );
+    }
+
+    #[test]
+    fn test_configured_force_color() {
+        let detect = |vars: &[(&str, &str)]| {
+            color_support_from_env(
+                |name| {
+                    vars.iter()
+                        .find(|(key, _)| *key == name)
+                        .map(|(_, value)| value.to_string())
+                },
+                Some(ColorSupport::TrueColor),
+                |_, _| false,
+            )
+        };
+        assert_eq!(detect(&[("TERM", "screen")]), ColorSupport::TrueColor);
+        // Environment overrides still win
+        assert_eq!(detect(&[("FORCE_COLOR", "2")]), ColorSupport::Color256);
+        assert_eq!(detect(&[("NO_COLOR", "1")]), ColorSupport::NoColor);
+    }
+
+    #[test]
+    fn test_graphics_protocol_from_env() {
+        assert_eq!(
+            graphics_protocol_from_env("xterm-kitty", "", false),
+            GraphicsProtocol::Kitty
+        );
+        assert_eq!(
+            graphics_protocol_from_env("xterm-256color", "", true),
+            GraphicsProtocol::Kitty
+        );
+        assert_eq!(
+            graphics_protocol_from_env("xterm-ghostty", "ghostty", false),
+            GraphicsProtocol::Kitty
+        );
+        assert_eq!(
+            graphics_protocol_from_env("xterm-256color", "WezTerm", false),
+            GraphicsProtocol::Kitty
+        );
+        assert_eq!(
+            graphics_protocol_from_env("foot", "", false),
+            GraphicsProtocol::Sixel
+        );
+        assert_eq!(
+            graphics_protocol_from_env("xterm-256color", "", false),
+            GraphicsProtocol::None
+        );
+    }
+
+    #[test]
+    fn test_da1_reports_sixel() {
+        assert!(da1_reports_sixel("\x1b[?62;4;6;22c"));
+        assert!(da1_reports_sixel("\x1b[?63;1;2;4c"));
+        assert!(!da1_reports_sixel("\x1b[?62;1;6;22c"));
+        assert!(!da1_reports_sixel("\x1b[?64c"));
+        assert!(!da1_reports_sixel(""));
+    }
+
+    #[test]
+    fn test_glyph_width_from_report() {
+        assert_eq!(glyph_width_from_report("\x1b[12;2R"), Some(1));
+        assert_eq!(glyph_width_from_report("\x1b[1;3R"), Some(2));
+        // Stray input ahead of the report is ignored
+        assert_eq!(glyph_width_from_report("x\x1b[5;2R"), Some(1));
+        assert_eq!(glyph_width_from_report("\x1b[5;0R"), None);
+        assert_eq!(glyph_width_from_report("\x1b[5;2"), None);
+        assert_eq!(glyph_width_from_report("\x1b[?62;4c"), None);
+        assert_eq!(glyph_width_from_report(""), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_tty_reply_stops_at_terminator() {
+        use std::io::Write;
+
+        let (mut terminal, mut tty) = std::os::unix::net::UnixStream::pair().unwrap();
+        terminal.write_all(b"\x1b[?62;4cjk").unwrap();
+        let reply = read_tty_reply(&mut tty, b'c', 64, Duration::from_secs(5));
+        assert_eq!(reply.as_deref(), Some(&b"\x1b[?62;4c"[..]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_read_tty_reply_gives_up_at_deadline() {
+        use std::io::Write;
+
+        let (mut terminal, mut tty) = std::os::unix::net::UnixStream::pair().unwrap();
+        terminal.write_all(b"\x1b[12;").unwrap();
+        let start = std::time::Instant::now();
+        assert_eq!(
+            read_tty_reply(&mut tty, b'R', 32, Duration::from_millis(50)),
+            None
+        );
+        assert!(start.elapsed() < Duration::from_secs(5));
     }
 }
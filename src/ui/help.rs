@@ -2,14 +2,20 @@
 
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 
-/// Render the help overlay
-pub fn render_help_overlay(frame: &mut Frame, area: Rect) {
+use super::theme::Theme;
+use crate::keybindings::{Action, Keybindings};
+use crate::state::AppState;
+
+/// Render the help overlay, scrolled to `state.help_scroll`
+pub fn render_help_overlay(frame: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
+
     // Calculate overlay size (60% width, 70% height, centered)
     let overlay_width = (area.width as f32 * 0.7).min(70.0) as u16;
     let overlay_height = (area.height as f32 * 0.8).min(30.0) as u16;
@@ -19,141 +25,150 @@ pub fn render_help_overlay(frame: &mut Frame, area: Rect) {
     // Clear background
     frame.render_widget(Clear, overlay_area);
 
+    let help_text = create_help_text(theme, &state.keybindings);
+    let inner_height = overlay_area.height.saturating_sub(2) as usize;
+    let title = if help_text.len() > inner_height {
+        " Keyboard Shortcuts (↑↓ PgUp PgDn to scroll) "
+    } else {
+        " Keyboard Shortcuts "
+    };
+
     // Render help content
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Cyan))
+        .border_style(Style::default().fg(theme.accent))
         .title(Span::styled(
-            " Keyboard Shortcuts ",
+            title,
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ))
         .title_alignment(Alignment::Center);
 
     let inner = block.inner(overlay_area);
     frame.render_widget(block, overlay_area);
+    state.help_area.set(Some(inner));
 
-    let help_text = create_help_text();
-    let widget = Paragraph::new(help_text).style(Style::default().fg(Color::White));
+    // The terminal may have shrunk since the offset was last clamped
+    let max_scroll = help_text.len().saturating_sub(inner.height as usize);
+    let scroll = state.help_scroll.min(max_scroll) as u16;
+    let widget = Paragraph::new(help_text)
+        .style(Style::default().fg(theme.text))
+        .scroll((scroll, 0));
 
     frame.render_widget(widget, inner);
 }
 
-/// Create help text content
-fn create_help_text() -> Vec<Line<'static>> {
+/// Number of lines in the help text, for clamping its scroll offset
+pub fn help_line_count(keybindings: &Keybindings) -> usize {
+    create_help_text(&Theme::default(), keybindings).len()
+}
+
+/// Create help text content, labelling remappable actions with their bound keys
+fn create_help_text(theme: &Theme, keybindings: &Keybindings) -> Vec<Line<'static>> {
     let section_style = Style::default()
-        .fg(Color::Yellow)
+        .fg(theme.highlight)
         .add_modifier(Modifier::BOLD);
-    let key_style = Style::default().fg(Color::Green);
-    let desc_style = Style::default().fg(Color::White);
+    let key_style = Style::default().fg(theme.key);
+    let desc_style = Style::default().fg(theme.text);
+
+    let entry = |keys: String, description: &'static str| {
+        Line::from(vec![
+            Span::styled(format!("  {:<11} ", keys), key_style),
+            Span::styled(description, desc_style),
+        ])
+    };
+    let key = |action: Action| keybindings.label(action);
+    let pair = |first: Action, second: Action, separator: &str| {
+        format!("{}{}{}", key(first), separator, key(second))
+    };
 
     vec![
         Line::from(Span::styled("Global", section_style)),
-        Line::from(vec![
-            Span::styled("  Q           ", key_style),
-            Span::styled("Quit application", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  ?           ", key_style),
-            Span::styled("Toggle help overlay", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Tab         ", key_style),
-            Span::styled("Next widget", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Shift+Tab   ", key_style),
-            Span::styled("Previous widget", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Esc         ", key_style),
-            Span::styled("Cancel / Close overlay", desc_style),
-        ]),
+        entry(key(Action::Quit), "Quit application"),
+        entry(key(Action::Help), "Toggle help overlay"),
+        entry(key(Action::NextWidget), "Next widget"),
+        entry(key(Action::PrevWidget), "Previous widget"),
+        entry("Esc".to_string(), "Cancel / Close overlay"),
+        entry(key(Action::History), "Render history (Enter restores)"),
+        entry(key(Action::Crop), "Crop image (Enter applies, Del clears)"),
+        entry(
+            pair(Action::RotateLeft, Action::RotateRight, " "),
+            "Rotate image left / right",
+        ),
+        entry(
+            pair(Action::FlipHorizontal, Action::FlipVertical, " "),
+            "Flip image horizontally / vertically",
+        ),
+        entry(key(Action::ResetTransforms), "Reset rotations and flips"),
         Line::from(""),
         Line::from(Span::styled("Mode Selector", section_style)),
-        Line::from(vec![
-            Span::styled("  1, 2, 3     ", key_style),
-            Span::styled("Jump to mode", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  ↑ ↓         ", key_style),
-            Span::styled("Navigate modes", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Enter       ", key_style),
-            Span::styled("Select mode", desc_style),
-        ]),
+        entry("1-4".to_string(), "Jump to mode"),
+        entry("↑ ↓".to_string(), "Navigate modes"),
+        entry("Enter".to_string(), "Select mode"),
         Line::from(""),
         Line::from(Span::styled("Control Panel", section_style)),
-        Line::from(vec![
-            Span::styled("  ↑ ↓         ", key_style),
-            Span::styled("Navigate settings", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  ← →         ", key_style),
-            Span::styled("Adjust selection", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  + -         ", key_style),
-            Span::styled("Adjust numeric values", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Space       ", key_style),
-            Span::styled("Toggle / Render", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Enter / E    ", key_style),
-            Span::styled("Edit text (Text Stylizer input)", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  L           ", key_style),
-            Span::styled("Load image", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  S           ", key_style),
-            Span::styled("Save output", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  O           ", key_style),
-            Span::styled("Cycle output format", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Output Fmt  ", key_style),
-            Span::styled("ANSI/HTML/TXT/PNG/SVG (TXT only for ASCII)", desc_style),
-        ]),
-        Line::from(Span::styled("", Style::default())),
+        entry("↑ ↓".to_string(), "Navigate settings"),
+        entry("← →".to_string(), "Adjust selection"),
+        entry("+ -".to_string(), "Adjust numeric values"),
+        entry(key(Action::Render), "Toggle / Render"),
+        entry(
+            "Enter / E".to_string(),
+            "Edit text input or hex colors (Text Stylizer)",
+        ),
+        entry(
+            pair(Action::Undo, Action::Redo, " / "),
+            "Undo / redo settings change",
+        ),
+        entry(key(Action::Load), "Load image"),
+        entry(key(Action::ReloadLast), "Reload the last session's image"),
+        entry(key(Action::Paste), "Paste image from clipboard"),
+        entry(key(Action::Save), "Save output"),
+        entry(key(Action::CycleFormat), "Cycle output format"),
+        entry(
+            key(Action::AutoFit),
+            "Cycle sizing (fixed/fit width/fit height)",
+        ),
+        entry(
+            pair(Action::FontAspectDown, Action::FontAspectUp, " / "),
+            "Adjust font aspect ratio",
+        ),
+        entry(key(Action::CycleColor), "Cycle color level and re-render"),
+        entry(
+            key(Action::PreserveScroll),
+            "Keep scroll position on re-render",
+        ),
+        entry(key(Action::Presets), "Save/load render presets"),
+        entry(
+            "Output Fmt".to_string(),
+            "ANSI/HTML/TXT/PNG/SVG (TXT only for ASCII)",
+        ),
+        Line::from(""),
         Line::from(Span::styled("Preview Area", section_style)),
-        Line::from(vec![
-            Span::styled("  ↑ ↓ j k     ", key_style),
-            Span::styled("Scroll vertically", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  ← → h l     ", key_style),
-            Span::styled("Scroll horizontally (pan)", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  PgUp PgDn   ", key_style),
-            Span::styled("Scroll by page", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  Home End    ", key_style),
-            Span::styled("Jump to top/bottom", desc_style),
-        ]),
-        Line::from(vec![
-            Span::styled("  C           ", key_style),
-            Span::styled("Copy to clipboard", desc_style),
-        ]),
+        entry("↑ ↓ j k".to_string(), "Scroll vertically"),
+        entry("← → h l".to_string(), "Scroll horizontally (pan)"),
+        entry("PgUp PgDn".to_string(), "Scroll by page"),
+        entry("Home End".to_string(), "Jump to top/bottom"),
+        entry(
+            key(Action::Copy),
+            "Copy as plain text (HTML format: rich HTML)",
+        ),
+        entry(key(Action::CopyRaw), "Copy raw ANSI (keeps the colors)"),
+        entry(key(Action::WordWrap), "Toggle word wrap"),
+        entry(key(Action::LineNumbers), "Toggle line numbers"),
+        entry(key(Action::MemoryUsage), "Toggle memory usage readout"),
+        entry(key(Action::Compare), "Compare before/after renders"),
+        entry(key(Action::CompareLock), "Lock/unlock compare scrolling"),
         Line::from(""),
         Line::from(Span::styled("Zoom Tips", section_style)),
-        Line::from(vec![
-            Span::styled("  Width +/-   ", key_style),
-            Span::styled("Adjust in Control Panel, re-render", desc_style),
-        ]),
+        entry(
+            "Width +/-".to_string(),
+            "Adjust in Control Panel, re-render",
+        ),
         Line::from(""),
         Line::from(Span::styled(
-            "       [Press ? or Esc to close]",
-            Style::default().fg(Color::DarkGray),
+            format!("       [Press {} or Esc to close]", key(Action::Help)),
+            Style::default().fg(theme.muted),
         )),
     ]
 }
@@ -181,3 +196,35 @@ fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
         ])
         .split(vertical[1])[1]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn help_lines(keybindings: &Keybindings) -> Vec<String> {
+        create_help_text(&Theme::default(), keybindings)
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_help_shows_remapped_keys() {
+        let config = BTreeMap::from([("save".to_string(), vec!["ctrl+s".to_string()])]);
+        let lines = help_lines(&Keybindings::from_config(&config).unwrap());
+
+        assert!(lines
+            .iter()
+            .any(|l| l.contains("Ctrl+s") && l.contains("Save output")));
+        assert!(!lines.iter().any(|l| l.trim_start().starts_with("S ")));
+        assert!(lines
+            .iter()
+            .any(|l| l.contains("q / Q") && l.contains("Quit")));
+    }
+}
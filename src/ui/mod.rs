@@ -4,26 +4,59 @@
 
 mod help;
 mod preview;
+pub mod theme;
 mod widgets;
 
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
     Frame,
 };
 
+use crate::file_browser::FileBrowser;
+use crate::perf_monitor::format_bytes;
 use crate::state::{AppState, FocusedWidget, RenderMode};
 use ratatui::widgets::Clear;
 
+pub use help::help_line_count;
+pub use preview::line_rows;
+
+/// Terminals at least this wide get the side-by-side layout
+const WIDE_LAYOUT_MIN_WIDTH: u16 = 80;
+
+/// Width of the mode selector / control panel column in the wide layout
+const LEFT_PANEL_WIDTH: u16 = 25;
+
+/// Width inside the preview borders for a terminal `terminal_width` columns wide
+pub fn preview_inner_width(terminal_width: u16) -> u16 {
+    let preview = if terminal_width >= WIDE_LAYOUT_MIN_WIDTH {
+        terminal_width - LEFT_PANEL_WIDTH
+    } else {
+        terminal_width
+    };
+    preview.saturating_sub(2)
+}
+
+/// Height inside the preview borders for a `terminal_width` x `terminal_height` terminal
+pub fn preview_inner_height(terminal_width: u16, terminal_height: u16) -> u16 {
+    // Title and status bars, then the mode selector and control panel when stacked
+    let chrome = if terminal_width >= WIDE_LAYOUT_MIN_WIDTH {
+        2
+    } else {
+        2 + 5 + 8
+    };
+    terminal_height.saturating_sub(chrome).saturating_sub(2)
+}
+
 /// Main render function - draws the entire UI
 pub fn render(frame: &mut Frame, state: &AppState) {
     let size = frame.area();
 
     // Check minimum size
     if size.width < 40 || size.height < 15 {
-        render_size_warning(frame, size);
+        render_size_warning(frame, size, state);
         return;
     }
 
@@ -31,9 +64,9 @@ pub fn render(frame: &mut Frame, state: &AppState) {
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(1),  // Title bar
-            Constraint::Min(10),    // Main content
-            Constraint::Length(1),  // Status bar
+            Constraint::Length(1), // Title bar
+            Constraint::Min(10),   // Main content
+            Constraint::Length(1), // Status bar
         ])
         .split(size);
 
@@ -43,51 +76,84 @@ pub fn render(frame: &mut Frame, state: &AppState) {
 
     // Render help overlay if active
     if state.show_help {
-        help::render_help_overlay(frame, size);
+        help::render_help_overlay(frame, size, state);
     }
 
     // Render load prompt if active
     if state.load_prompt_active {
         render_load_prompt(frame, size, state);
     }
+
+    // Render file browser over the load prompt if open
+    if let Some(ref browser) = state.file_browser {
+        render_file_browser(frame, size, browser, state);
+    }
+
+    // Render custom charset prompt if active
+    if state.charset_prompt_active {
+        render_charset_prompt(frame, size, state);
+    }
+
+    // Render text color prompt if active
+    if state.color_prompt_active {
+        render_color_prompt(frame, size, state);
+    }
+
+    // Render preset prompt if active
+    if state.preset_prompt_active {
+        render_preset_prompt(frame, size, state);
+    }
+
+    // Render history overlay if active
+    if state.history_overlay_active {
+        render_history_overlay(frame, size, state);
+    }
+
+    // Render crop overlay if active
+    if state.crop_overlay_active {
+        render_crop_overlay(frame, size, state);
+    }
 }
 
 /// Render warning when terminal is too small
-fn render_size_warning(frame: &mut Frame, area: Rect) {
+fn render_size_warning(frame: &mut Frame, area: Rect, state: &AppState) {
     let warning = Paragraph::new("Terminal too small!\nMinimum: 40x15")
-        .style(Style::default().fg(Color::Red))
+        .style(Style::default().fg(state.theme.error))
         .block(Block::default().borders(Borders::ALL));
     frame.render_widget(warning, area);
 }
 
 /// Render the title bar
 fn render_title_bar(frame: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
     let title = Line::from(vec![
         Span::styled(
             " Glyphgen ",
             Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ),
-        Span::styled("v1.0", Style::default().fg(Color::DarkGray)),
+        Span::styled("v1.0", Style::default().fg(theme.muted)),
         Span::raw(" │ "),
         Span::styled(
             state.current_mode.name(),
-            Style::default().fg(Color::Yellow),
+            Style::default().fg(theme.highlight),
         ),
-        Span::raw(" ".repeat(
-            (area.width as usize)
-                .saturating_sub(35)
-                .saturating_sub(state.current_mode.name().len()),
-        )),
-        Span::styled("[?]", Style::default().fg(Color::Green)),
+        Span::raw(
+            " ".repeat(
+                (area.width as usize)
+                    .saturating_sub(35)
+                    .saturating_sub(state.current_mode.name().len()),
+            ),
+        ),
+        Span::styled("[?]", Style::default().fg(theme.key)),
         Span::raw(" Help  "),
-        Span::styled("[Q]", Style::default().fg(Color::Red)),
+        Span::styled("[Q]", Style::default().fg(theme.error)),
         Span::raw(" Quit "),
     ]);
 
-    let title_widget = Paragraph::new(title)
-        .style(Style::default().bg(Color::DarkGray).fg(Color::White));
+    let title_widget =
+        Paragraph::new(title).style(Style::default().bg(theme.title_bg).fg(theme.text));
 
     frame.render_widget(title_widget, area);
 }
@@ -95,7 +161,7 @@ fn render_title_bar(frame: &mut Frame, area: Rect, state: &AppState) {
 /// Render the main content area
 fn render_main_content(frame: &mut Frame, area: Rect, state: &AppState) {
     // Responsive layout: side-by-side if wide enough, stacked if narrow
-    if area.width >= 80 {
+    if area.width >= WIDE_LAYOUT_MIN_WIDTH {
         render_wide_layout(frame, area, state);
     } else {
         render_narrow_layout(frame, area, state);
@@ -107,8 +173,8 @@ fn render_wide_layout(frame: &mut Frame, area: Rect, state: &AppState) {
     let chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Length(25), // Left panel
-            Constraint::Min(40),    // Preview area
+            Constraint::Length(LEFT_PANEL_WIDTH), // Left panel
+            Constraint::Min(40),                  // Preview area
         ])
         .split(area);
 
@@ -131,9 +197,9 @@ fn render_narrow_layout(frame: &mut Frame, area: Rect, state: &AppState) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(5),  // Mode selector
-            Constraint::Length(8),  // Control panel
-            Constraint::Min(5),     // Preview area
+            Constraint::Length(5), // Mode selector
+            Constraint::Length(8), // Control panel
+            Constraint::Min(5),    // Preview area
         ])
         .split(area);
 
@@ -144,12 +210,13 @@ fn render_narrow_layout(frame: &mut Frame, area: Rect, state: &AppState) {
 
 /// Render the mode selector widget
 fn render_mode_selector(frame: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
     let is_focused = state.focus == FocusedWidget::ModeSelector;
 
     let border_style = if is_focused {
-        Style::default().fg(Color::Cyan)
+        Style::default().fg(theme.border_focused)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme.border_unfocused)
     };
 
     let block = Block::default()
@@ -173,23 +240,23 @@ fn render_mode_selector(frame: &mut Frame, area: Rect, state: &AppState) {
 
         let style = if is_selected {
             Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.highlight)
                 .add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(Color::White)
+            Style::default().fg(theme.text)
         };
 
         lines.push(Line::from(vec![
             Span::styled(
                 format!(" {} ", bullet),
                 if is_selected {
-                    Style::default().fg(Color::Yellow)
+                    Style::default().fg(theme.highlight)
                 } else {
-                    Style::default().fg(Color::DarkGray)
+                    Style::default().fg(theme.muted)
                 },
             ),
             Span::styled(mode.name(), style),
-            Span::styled(format!(" {}", shortcut), Style::default().fg(Color::DarkGray)),
+            Span::styled(format!(" {}", shortcut), Style::default().fg(theme.muted)),
         ]));
     }
 
@@ -202,9 +269,9 @@ fn render_control_panel(frame: &mut Frame, area: Rect, state: &AppState) {
     let is_focused = state.focus == FocusedWidget::ControlPanel;
 
     let border_style = if is_focused {
-        Style::default().fg(Color::Cyan)
+        Style::default().fg(state.theme.border_focused)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(state.theme.border_unfocused)
     };
 
     let block = Block::default()
@@ -224,15 +291,19 @@ fn render_control_panel(frame: &mut Frame, area: Rect, state: &AppState) {
             widgets::render_unicode_controls(frame, inner, state, is_focused)
         }
         RenderMode::TextStylizer => widgets::render_text_controls(frame, inner, state, is_focused),
+        RenderMode::ColorAscii => {
+            widgets::render_color_ascii_controls(frame, inner, state, is_focused)
+        }
     }
 }
 
 /// Render the status bar
 fn render_status_bar(frame: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
     let status_color = if state.status_is_error {
-        Color::Red
+        theme.error
     } else {
-        Color::White
+        theme.text
     };
 
     // Format performance metrics
@@ -252,9 +323,42 @@ fn render_status_bar(frame: &mut Frame, area: Rect, state: &AppState) {
     // Output format display
     let format_info = format!(" │ {}", state.preview_output_format.name());
 
+    // Optional memory readout: decoded image and current preview text
+    let memory_info = if state.config.ui.show_memory_usage {
+        format!(
+            " │ Img {} │ Out {}",
+            format_bytes(state.input_image_bytes),
+            format_bytes(state.preview_content.as_ref().map_or(0, |c| c.len()))
+        )
+    } else {
+        String::new()
+    };
+
+    // Show a progress bar in place of the status while a long render reports in
+    let status_text = match state.render_progress {
+        Some(fraction) => progress_text(fraction),
+        None => state.status_message.clone(),
+    };
+
     // Calculate spacing
-    let status_len = state.status_message.len();
-    let info_len = perf_info.len() + file_info.len() + format_info.len();
+    let status_len = status_text.chars().count();
+    let info_len = perf_info.chars().count()
+        + file_info.chars().count()
+        + memory_info.chars().count()
+        + format_info.chars().count();
+
+    // Source and output sizes, dropped first when the bar is too narrow
+    let source = state.render_dimensions().filter(|_| state.showing_image());
+    let grid = state.preview_content.as_ref().map(|_| state.preview_grid);
+    let mut dims_info = dimensions_text(source, grid);
+    if !dims_info.is_empty() {
+        dims_info = format!(" │ {}", dims_info);
+    }
+    if status_len + info_len + dims_info.chars().count() + 2 > area.width as usize {
+        dims_info.clear();
+    }
+    let info_len = info_len + dims_info.chars().count();
+
     let spacing = (area.width as usize)
         .saturating_sub(status_len)
         .saturating_sub(info_len)
@@ -262,20 +366,46 @@ fn render_status_bar(frame: &mut Frame, area: Rect, state: &AppState) {
 
     let status = Line::from(vec![
         Span::raw(" "),
-        Span::styled(&state.status_message, Style::default().fg(status_color)),
+        Span::styled(status_text, Style::default().fg(status_color)),
         Span::raw(" ".repeat(spacing)),
-        Span::styled(&perf_info, Style::default().fg(Color::DarkGray)),
-        Span::styled(&file_info, Style::default().fg(Color::Blue)),
-        Span::styled(&format_info, Style::default().fg(Color::Magenta)),
+        Span::styled(&perf_info, Style::default().fg(theme.muted)),
+        Span::styled(&dims_info, Style::default().fg(theme.muted)),
+        Span::styled(&file_info, Style::default().fg(theme.file_info)),
+        Span::styled(&memory_info, Style::default().fg(theme.highlight)),
+        Span::styled(&format_info, Style::default().fg(theme.format_info)),
         Span::raw(" "),
     ]);
 
-    let widget = Paragraph::new(status)
-        .style(Style::default().bg(Color::Black).fg(Color::White));
+    let widget = Paragraph::new(status).style(Style::default().bg(theme.status_bg).fg(theme.text));
 
     frame.render_widget(widget, area);
 }
 
+/// Format source pixels and output cells, e.g. `1920x1080 → 160x45 chars`
+fn dimensions_text(source: Option<(u32, u32)>, grid: Option<(usize, usize)>) -> String {
+    match (source, grid) {
+        (Some((width, height)), Some((columns, rows))) => {
+            format!("{}x{} → {}x{} chars", width, height, columns, rows)
+        }
+        (Some((width, height)), None) => format!("{}x{}", width, height),
+        (None, Some((columns, rows))) => format!("{}x{} chars", columns, rows),
+        (None, None) => String::new(),
+    }
+}
+
+/// Format render progress as e.g. `Rendering... ████░░░░░░  42%`
+fn progress_text(fraction: f32) -> String {
+    const BAR_WIDTH: usize = 10;
+    let fraction = fraction.clamp(0.0, 1.0);
+    let filled = (fraction * BAR_WIDTH as f32).round() as usize;
+    format!(
+        "Rendering... {}{} {:>3}%",
+        "█".repeat(filled),
+        "░".repeat(BAR_WIDTH - filled),
+        (fraction * 100.0).round() as u32
+    )
+}
+
 /// Render centered load prompt modal
 fn render_load_prompt(frame: &mut Frame, size: Rect, state: &AppState) {
     // Modal sizing
@@ -294,13 +424,374 @@ fn render_load_prompt(frame: &mut Frame, size: Rect, state: &AppState) {
     ));
 
     let mut lines = Vec::new();
-    lines.push(Line::from(Span::raw("Enter path to image and press Enter:")));
-    lines.push(Line::from(Span::raw(format!("{}", state.load_prompt_input))));
+    lines.push(Line::from(Span::raw(
+        "Enter path to image and press Enter (Tab completes, ↓ browses):",
+    )));
+    lines.push(Line::from(Span::raw(state.load_prompt_input.to_string())));
 
     if let Some(ref err) = state.load_prompt_error {
-        lines.push(Line::from(Span::styled(err, Style::default().fg(Color::Red))));
+        lines.push(Line::from(Span::styled(
+            err,
+            Style::default().fg(state.theme.error),
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines).block(border);
+    frame.render_widget(paragraph, area);
+}
+
+/// Render centered custom charset prompt modal
+fn render_charset_prompt(frame: &mut Frame, size: Rect, state: &AppState) {
+    // Modal sizing
+    let width = (size.width as f32 * 0.6) as u16;
+    let height = 5u16;
+    let x = (size.width - width) / 2;
+    let y = (size.height - height) / 2;
+    let area = Rect::new(x, y, width, height);
+
+    // Clear area under modal
+    frame.render_widget(Clear, area);
+
+    let border = Block::default().borders(Borders::ALL).title(Span::styled(
+        " Custom Charset ",
+        Style::default().add_modifier(Modifier::BOLD),
+    ));
+
+    let mut lines = Vec::new();
+    lines.push(Line::from(Span::raw(
+        "Enter characters from dark to light and press Enter:",
+    )));
+    lines.push(Line::from(Span::raw(state.charset_prompt_input.clone())));
+
+    if let Some(ref err) = state.charset_prompt_error {
+        lines.push(Line::from(Span::styled(
+            err,
+            Style::default().fg(state.theme.error),
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines).block(border);
+    frame.render_widget(paragraph, area);
+}
+
+/// Render centered text color prompt modal
+fn render_color_prompt(frame: &mut Frame, size: Rect, state: &AppState) {
+    // Modal sizing
+    let width = (size.width as f32 * 0.6) as u16;
+    let height = 5u16;
+    let x = (size.width - width) / 2;
+    let y = (size.height - height) / 2;
+    let area = Rect::new(x, y, width, height);
+
+    // Clear area under modal
+    frame.render_widget(Clear, area);
+
+    let title = if state.color_prompt_end {
+        " End Color "
+    } else {
+        " Start Color "
+    };
+    let border = Block::default().borders(Borders::ALL).title(Span::styled(
+        title,
+        Style::default().add_modifier(Modifier::BOLD),
+    ));
+
+    let mut lines = Vec::new();
+    lines.push(Line::from(Span::raw(
+        "Enter a color as #RRGGBB or #RGB and press Enter:",
+    )));
+    lines.push(Line::from(Span::raw(state.color_prompt_input.clone())));
+
+    if let Some(ref err) = state.color_prompt_error {
+        lines.push(Line::from(Span::styled(
+            err,
+            Style::default().fg(state.theme.error),
+        )));
+    }
+
+    let paragraph = Paragraph::new(lines).block(border);
+    frame.render_widget(paragraph, area);
+}
+
+/// Render centered preset save/load prompt modal
+fn render_preset_prompt(frame: &mut Frame, size: Rect, state: &AppState) {
+    // Modal sizing
+    let width = (size.width as f32 * 0.6) as u16;
+    let height = 6u16;
+    let x = (size.width - width) / 2;
+    let y = (size.height - height) / 2;
+    let area = Rect::new(x, y, width, height);
+
+    // Clear area under modal
+    frame.render_widget(Clear, area);
+
+    let title = if state.preset_prompt_saving {
+        " Save Preset "
+    } else {
+        " Load Preset "
+    };
+    let border = Block::default().borders(Borders::ALL).title(Span::styled(
+        title,
+        Style::default().add_modifier(Modifier::BOLD),
+    ));
+
+    let saved = if state.config.presets.is_empty() {
+        "Saved: (none)".to_string()
+    } else {
+        let names: Vec<&str> = state.config.presets.keys().map(String::as_str).collect();
+        format!("Saved: {}", names.join(", "))
+    };
+
+    let mut lines = Vec::new();
+    lines.push(Line::from(Span::styled(
+        saved,
+        Style::default().fg(state.theme.muted),
+    )));
+    lines.push(Line::from(Span::raw(
+        "Enter a preset name and press Enter (Tab: load/save):",
+    )));
+    lines.push(Line::from(Span::raw(state.preset_prompt_input.clone())));
+
+    if let Some(ref err) = state.preset_prompt_error {
+        lines.push(Line::from(Span::styled(
+            err,
+            Style::default().fg(state.theme.error),
+        )));
     }
 
     let paragraph = Paragraph::new(lines).block(border);
     frame.render_widget(paragraph, area);
 }
+
+/// Render the file browser overlay: folders and supported images in one directory
+fn render_file_browser(frame: &mut Frame, size: Rect, browser: &FileBrowser, state: &AppState) {
+    let width = (size.width as f32 * 0.7) as u16;
+    let height = (size.height as f32 * 0.8) as u16;
+    let x = (size.width - width) / 2;
+    let y = (size.height - height) / 2;
+    let area = Rect::new(x, y, width, height);
+
+    frame.render_widget(Clear, area);
+
+    let border = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(
+            format!(" {} ", browser.dir.display()),
+            Style::default().add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " Enter: open/load  Backspace: up  Esc: back ",
+            Style::default().fg(state.theme.muted),
+        ));
+
+    let items: Vec<ListItem> = browser
+        .entries
+        .iter()
+        .map(|entry| {
+            let color = if entry.is_dir {
+                state.theme.accent
+            } else {
+                state.theme.text
+            };
+            ListItem::new(Span::styled(entry.label(), Style::default().fg(color)))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(border)
+        .highlight_style(
+            Style::default()
+                .fg(state.theme.highlight)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("▸ ");
+
+    // The list scrolls itself to keep the selection visible
+    let mut list_state = ListState::default().with_selected(Some(browser.selected));
+    frame.render_stateful_widget(list, area, &mut list_state);
+}
+
+/// Lines of the selected render shown under the history list
+const HISTORY_THUMBNAIL_LINES: usize = 6;
+
+/// Render the render history overlay with a plain-text thumbnail of the selection
+fn render_history_overlay(frame: &mut Frame, size: Rect, state: &AppState) {
+    let width = (size.width as f32 * 0.7) as u16;
+    let height = (size.height as f32 * 0.8) as u16;
+    let x = (size.width - width) / 2;
+    let y = (size.height - height) / 2;
+    let area = Rect::new(x, y, width, height);
+
+    frame.render_widget(Clear, area);
+
+    let border = Block::default().borders(Borders::ALL).title(Span::styled(
+        " Render History (Enter: restore, Esc: close) ",
+        Style::default().add_modifier(Modifier::BOLD),
+    ));
+
+    // Keep the selection visible when the list is longer than the modal
+    let list_rows = (height as usize)
+        .saturating_sub(HISTORY_THUMBNAIL_LINES + 3)
+        .max(1);
+    let first = (state.history_selected + 1).saturating_sub(list_rows);
+
+    let mut lines = Vec::new();
+    for (index, entry) in state.history.iter().enumerate().skip(first).take(list_rows) {
+        let label = format!("{:>2}. {}", index + 1, entry.label());
+        if index == state.history_selected {
+            lines.push(Line::from(Span::styled(
+                format!("▸ {}", label),
+                Style::default()
+                    .fg(state.theme.highlight)
+                    .add_modifier(Modifier::BOLD),
+            )));
+        } else {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", label),
+                Style::default().fg(state.theme.text),
+            )));
+        }
+    }
+
+    if let Some(entry) = state.history.get(state.history_selected) {
+        lines.push(Line::from(""));
+        let plain = crate::unicode_handler::strip_ansi_codes(&entry.output);
+        for line in plain.lines().take(HISTORY_THUMBNAIL_LINES) {
+            lines.push(Line::from(Span::styled(
+                line.to_string(),
+                Style::default().fg(state.theme.muted),
+            )));
+        }
+    }
+
+    let paragraph = Paragraph::new(lines).block(border);
+    frame.render_widget(paragraph, area);
+}
+
+/// Thumbnail cells [start, end) covering `len` pixels from `start` of an `image_len`-pixel axis
+fn crop_cell_range(start: u32, len: u32, image_len: u32, cells: usize) -> (usize, usize) {
+    if image_len == 0 || cells == 0 {
+        return (0, 0);
+    }
+    let image_len = image_len as usize;
+    let first = (start as usize * cells / image_len).min(cells - 1);
+    // Round the end up so partly covered cells are highlighted too
+    let end = ((start + len) as usize * cells)
+        .div_ceil(image_len)
+        .clamp(first + 1, cells);
+    (first, end)
+}
+
+/// Render the crop overlay: an ASCII thumbnail of the whole image with the selection highlighted
+fn render_crop_overlay(frame: &mut Frame, size: Rect, state: &AppState) {
+    let Some(image) = state.input_image.as_ref() else {
+        return;
+    };
+    let thumbnail: Vec<&str> = state.crop_thumbnail.lines().collect();
+    let cols = thumbnail
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let rows = thumbnail.len();
+
+    let width = (cols as u16 + 2).max(40).min(size.width);
+    let height = (rows as u16 + 4).min(size.height);
+    let x = (size.width - width) / 2;
+    let y = (size.height - height) / 2;
+    let area = Rect::new(x, y, width, height);
+
+    frame.render_widget(Clear, area);
+
+    let border = Block::default().borders(Borders::ALL).title(Span::styled(
+        " Crop (Enter: apply, Del: clear, Esc: close) ",
+        Style::default().add_modifier(Modifier::BOLD),
+    ));
+
+    let selection = state.crop_selection;
+    let (col_start, col_end) = crop_cell_range(selection.x, selection.width, image.width(), cols);
+    let (row_start, row_end) = crop_cell_range(selection.y, selection.height, image.height(), rows);
+    let outside = Style::default().fg(state.theme.muted);
+    let inside = Style::default()
+        .fg(state.theme.highlight)
+        .add_modifier(Modifier::REVERSED);
+
+    let mut lines = Vec::with_capacity(rows + 2);
+    for (row, line) in thumbnail.iter().enumerate() {
+        if row < row_start || row >= row_end {
+            lines.push(Line::from(Span::styled(line.to_string(), outside)));
+            continue;
+        }
+        let chars: Vec<char> = line.chars().collect();
+        let before: String = chars.iter().take(col_start).collect();
+        let selected: String = chars.iter().take(col_end).skip(col_start).collect();
+        let after: String = chars.iter().skip(col_end).collect();
+        lines.push(Line::from(vec![
+            Span::styled(before, outside),
+            Span::styled(selected, inside),
+            Span::styled(after, outside),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!(
+            "{} of {}x{}",
+            selection.describe(),
+            image.width(),
+            image.height()
+        ),
+        Style::default().fg(state.theme.text),
+    )));
+
+    let paragraph = Paragraph::new(lines).block(border);
+    frame.render_widget(paragraph, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_inner_width_matches_layout() {
+        // Wide: left panel and both preview borders come off
+        assert_eq!(preview_inner_width(200), 173);
+        // Narrow: the preview spans the full width inside its borders
+        assert_eq!(preview_inner_width(60), 58);
+    }
+
+    #[test]
+    fn test_preview_inner_height_matches_layout() {
+        // Wide: title bar, status bar and both preview borders come off
+        assert_eq!(preview_inner_height(200, 50), 46);
+        // Narrow: the mode selector and control panel sit above the preview
+        assert_eq!(preview_inner_height(60, 50), 33);
+    }
+
+    #[test]
+    fn test_dimensions_text() {
+        assert_eq!(
+            dimensions_text(Some((1920, 1080)), Some((160, 45))),
+            "1920x1080 → 160x45 chars"
+        );
+        assert_eq!(dimensions_text(Some((640, 480)), None), "640x480");
+        assert_eq!(dimensions_text(None, Some((12, 1))), "12x1 chars");
+        assert_eq!(dimensions_text(None, None), "");
+    }
+
+    #[test]
+    fn test_crop_cell_range() {
+        // Left half of a 100px axis drawn in 10 cells
+        assert_eq!(crop_cell_range(0, 50, 100, 10), (0, 5));
+        // Partial cells at either end are included
+        assert_eq!(crop_cell_range(15, 20, 100, 10), (1, 4));
+        // A single pixel still highlights one cell
+        assert_eq!(crop_cell_range(99, 1, 100, 10), (9, 10));
+        assert_eq!(crop_cell_range(0, 1, 0, 10), (0, 0));
+    }
+
+    #[test]
+    fn test_progress_text() {
+        assert_eq!(progress_text(0.0), "Rendering... ░░░░░░░░░░   0%");
+        assert_eq!(progress_text(0.42), "Rendering... ████░░░░░░  42%");
+        assert_eq!(progress_text(1.5), "Rendering... ██████████ 100%");
+    }
+}
@@ -1,13 +1,15 @@
 //! Preview area rendering
 
 use ratatui::{
-    layout::Rect,
-    style::{Color, Modifier, Style},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState},
     Frame,
 };
 
+use super::theme::Theme;
+use crate::config::UiPreferences;
 use crate::state::{AppState, FocusedWidget};
 
 /// Render the preview area
@@ -15,81 +17,146 @@ pub fn render_preview(frame: &mut Frame, area: Rect, state: &AppState) {
     let is_focused = state.focus == FocusedWidget::Preview;
 
     let border_style = if is_focused {
-        Style::default().fg(Color::Cyan)
+        Style::default().fg(state.theme.border_focused)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(state.theme.border_unfocused)
+    };
+
+    let title = if state.compare_mode {
+        " Preview (Before │ After) "
+    } else {
+        " Preview "
     };
 
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(border_style)
         .title(Span::styled(
-            " Preview ",
+            title,
             Style::default().add_modifier(Modifier::BOLD),
         ));
 
     let inner = block.inner(area);
     frame.render_widget(block, area);
+    state.preview_area.set(Some(inner));
 
-    if let Some(ref content) = state.preview_content {
-        render_preview_content(frame, inner, content, state.preview_scroll, state.preview_scroll_x);
+    if state.graphics_visible() {
+        // Leave the cells blank; the graphics image is drawn over them after the frame
+        return;
+    }
+
+    if state.compare_mode && state.preview_content.is_some() {
+        render_compare(frame, inner, state);
+    } else if let Some(ref content) = state.preview_content {
+        render_preview_content(
+            frame,
+            inner,
+            content,
+            state.preview_scroll,
+            state.preview_scroll_x,
+            &state.config.ui,
+            &state.theme,
+        );
     } else {
         render_placeholder(frame, inner, state);
     }
 }
 
-/// Convert a line with ANSI codes to a Ratatui Line with colored Spans, applying horizontal offset
-fn ansi_line_to_ratatui_line(line: &str, h_offset: usize, max_width: usize) -> Line<'static> {
-    let parts = crate::input::parse_ansi_to_spans(line);
-    
-    if parts.is_empty() {
-        return Line::raw(String::new());
+/// Render the previous and current renders side by side
+fn render_compare(frame: &mut Frame, area: Rect, state: &AppState) {
+    let panes = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let divider = Block::default()
+        .borders(Borders::RIGHT)
+        .border_style(Style::default().fg(state.theme.muted));
+    let before_area = divider.inner(panes[0]);
+    frame.render_widget(divider, panes[0]);
+
+    if let Some(ref previous) = state.previous_preview_content {
+        let (scroll_y, scroll_x) = state.compare_pane_scroll();
+        render_preview_content(
+            frame,
+            before_area,
+            previous,
+            scroll_y,
+            scroll_x,
+            &state.config.ui,
+            &state.theme,
+        );
+    } else {
+        let message = Paragraph::new(vec![
+            Line::from(""),
+            Line::from(Span::styled(
+                "No previous render",
+                Style::default().fg(state.theme.muted),
+            )),
+        ])
+        .alignment(ratatui::layout::Alignment::Center);
+        frame.render_widget(message, before_area);
     }
 
-    // Build spans with colors, then apply horizontal offset
+    if let Some(ref content) = state.preview_content {
+        render_preview_content(
+            frame,
+            panes[1],
+            content,
+            state.preview_scroll,
+            state.preview_scroll_x,
+            &state.config.ui,
+            &state.theme,
+        );
+    }
+}
+
+/// Convert a line with ANSI codes into styled characters
+fn ansi_line_to_styled_chars(line: &str) -> Vec<(char, Style)> {
+    let parts = crate::input::parse_ansi_to_spans(line);
     let mut all_chars: Vec<(char, Style)> = Vec::new();
-    
-    for (text, fg, bg) in parts {
-        let mut style = Style::default();
-        if let Some(fg_color) = fg {
+
+    for (text, attrs) in parts {
+        let mut style = Style::default().add_modifier(attrs.modifiers());
+        if let Some(fg_color) = attrs.fg {
             style = style.fg(fg_color);
         }
-        if let Some(bg_color) = bg {
+        if let Some(bg_color) = attrs.bg {
             style = style.bg(bg_color);
         }
         for c in text.chars() {
             all_chars.push((c, style));
         }
     }
-    
-    // Apply horizontal scroll offset
-    let chars_to_show: Vec<(char, Style)> = all_chars
-        .into_iter()
-        .skip(h_offset)
-        .take(max_width)
-        .collect();
-    
-    if chars_to_show.is_empty() {
+
+    all_chars
+}
+
+/// Group consecutive chars with the same style into spans
+fn styled_chars_to_line(chars: Vec<(char, Style)>) -> Line<'static> {
+    if chars.is_empty() {
         return Line::raw(String::new());
     }
-    
-    // Group consecutive chars with same style into spans
+
     let mut spans: Vec<Span<'static>> = Vec::new();
     let mut current_text = String::new();
-    let mut current_style = chars_to_show[0].1;
-    
-    for (c, style) in chars_to_show {
+    let mut current_style = chars[0].1;
+
+    for (c, style) in chars {
         if style == current_style {
             current_text.push(c);
         } else {
             if !current_text.is_empty() {
-                spans.push(Span::styled(std::mem::take(&mut current_text), current_style));
+                spans.push(Span::styled(
+                    std::mem::take(&mut current_text),
+                    current_style,
+                ));
             }
             current_text.push(c);
             current_style = style;
         }
     }
-    
+
     if !current_text.is_empty() {
         spans.push(Span::styled(current_text, current_style));
     }
@@ -97,34 +164,204 @@ fn ansi_line_to_ratatui_line(line: &str, h_offset: usize, max_width: usize) -> L
     Line::from(spans)
 }
 
-/// Render the preview content with scrolling (vertical and horizontal) and ANSI color support
-fn render_preview_content(frame: &mut Frame, area: Rect, content: &str, scroll_y: usize, scroll_x: usize) {
-    let viewport_width = area.width as usize;
-    
-    let lines: Vec<Line> = content
-        .lines()
-        .skip(scroll_y)
-        .take(area.height as usize)
-        .map(|line| ansi_line_to_ratatui_line(line, scroll_x, viewport_width))
-        .collect();
+/// Convert a line with ANSI codes to a Ratatui Line with colored Spans, applying horizontal offset
+///
+/// `h_offset` and `max_width` count terminal cells. A wide char cut by either
+/// edge of the viewport is replaced with blank padding rather than split.
+fn ansi_line_to_ratatui_line(line: &str, h_offset: usize, max_width: usize) -> Line<'static> {
+    let mut chars_to_show: Vec<(char, Style)> = Vec::new();
+    let mut column = 0;
+    let mut used = 0;
+
+    for (c, style) in ansi_line_to_styled_chars(line) {
+        let width = crate::unicode_handler::char_width(c);
+        let start = column;
+        column += width;
+
+        if start < h_offset {
+            // Straddles the left edge: pad the cells that are in view
+            for _ in h_offset..column.min(h_offset + max_width) {
+                chars_to_show.push((' ', style));
+                used += 1;
+            }
+            continue;
+        }
+        if used + width > max_width {
+            // Straddles the right edge: pad out the remaining cells
+            while used < max_width {
+                chars_to_show.push((' ', style));
+                used += 1;
+            }
+            break;
+        }
+        chars_to_show.push((c, style));
+        used += width;
+    }
+
+    styled_chars_to_line(chars_to_show)
+}
+
+/// Wrap a line with ANSI codes into rows no wider than `max_width` terminal cells
+fn wrap_ansi_line(line: &str, max_width: usize) -> Vec<Line<'static>> {
+    let max_width = max_width.max(1);
+    let mut rows = Vec::new();
+    let mut row: Vec<(char, Style)> = Vec::new();
+    let mut row_width = 0;
+
+    for (c, style) in ansi_line_to_styled_chars(line) {
+        let width = crate::unicode_handler::char_width(c);
+        // Zero-width chars (combining marks) stay attached to the previous cell
+        if row_width + width > max_width && !row.is_empty() {
+            rows.push(styled_chars_to_line(std::mem::take(&mut row)));
+            row_width = 0;
+        }
+        row.push((c, style));
+        row_width += width;
+    }
+
+    if !row.is_empty() || rows.is_empty() {
+        rows.push(styled_chars_to_line(row));
+    }
+
+    rows
+}
+
+/// Rows a line with ANSI codes takes once wrapped like [`wrap_ansi_line`]
+fn wrapped_row_count(line: &str, max_width: usize) -> usize {
+    let max_width = max_width.max(1);
+    let mut rows = 1;
+    let mut row_width = 0;
+    let mut row_empty = true;
+
+    for c in crate::unicode_handler::strip_ansi_codes(line).chars() {
+        let width = crate::unicode_handler::char_width(c);
+        if row_width + width > max_width && !row_empty {
+            rows += 1;
+            row_width = 0;
+        }
+        row_empty = false;
+        row_width += width;
+    }
+
+    rows
+}
 
+/// Preview rows taken by each line of `content` in a pane `width` cells wide
+///
+/// Every line is one row unless word wrap is on, in which case lines wrap to
+/// the cells left beside the line-number gutter and the scrollbar column.
+pub fn line_rows<'a>(
+    content: &'a str,
+    width: u16,
+    ui: &UiPreferences,
+) -> impl Iterator<Item = usize> + 'a {
+    let wrap_width = ui.word_wrap.then(|| {
+        let gutter_width = if ui.show_line_numbers {
+            line_number_gutter_width(content.lines().count())
+        } else {
+            0
+        };
+        (width.saturating_sub(gutter_width) as usize).saturating_sub(1)
+    });
+    content.lines().map(move |line| match wrap_width {
+        Some(max_width) => wrapped_row_count(line, max_width),
+        None => 1,
+    })
+}
+
+/// Render the preview content with scrolling (vertical and horizontal) and ANSI color support
+///
+/// When word wrap is enabled, long lines wrap to the viewport width and the
+/// horizontal offset is ignored. `scroll_y` always counts source lines.
+fn render_preview_content(
+    frame: &mut Frame,
+    area: Rect,
+    content: &str,
+    scroll_y: usize,
+    scroll_x: usize,
+    ui: &UiPreferences,
+    theme: &Theme,
+) {
     let total_lines = content.lines().count();
     let visible_lines = area.height as usize;
 
+    // Optional line-number gutter on the left
+    let gutter_width = if ui.show_line_numbers {
+        line_number_gutter_width(total_lines)
+    } else {
+        0
+    };
+    let text_area = Rect {
+        x: area.x + gutter_width.min(area.width),
+        width: area.width.saturating_sub(gutter_width),
+        ..area
+    };
+    let viewport_width = text_area.width as usize;
+
+    let mut lines: Vec<Line> = Vec::with_capacity(visible_lines);
+    let mut gutter: Vec<Line> = Vec::with_capacity(visible_lines);
+
+    for (idx, line) in content.lines().enumerate().skip(scroll_y) {
+        if lines.len() >= visible_lines {
+            break;
+        }
+
+        let rows = if ui.word_wrap {
+            // Leave the last column free so the scrollbar never hides wrapped text
+            wrap_ansi_line(line, viewport_width.saturating_sub(1))
+        } else {
+            vec![ansi_line_to_ratatui_line(line, scroll_x, viewport_width)]
+        };
+
+        for (row_idx, row) in rows.into_iter().enumerate() {
+            if lines.len() >= visible_lines {
+                break;
+            }
+            if gutter_width > 0 {
+                // Only the first row of a wrapped line carries its number
+                let label = if row_idx == 0 {
+                    format_line_number(idx + 1, gutter_width as usize)
+                } else {
+                    " ".repeat(gutter_width as usize)
+                };
+                gutter.push(Line::from(Span::styled(
+                    label,
+                    Style::default().fg(theme.muted),
+                )));
+            }
+            lines.push(row);
+        }
+    }
+
+    if gutter_width > 0 {
+        let gutter_area = Rect {
+            width: gutter_width.min(area.width),
+            ..area
+        };
+        frame.render_widget(Paragraph::new(gutter), gutter_area);
+    }
+
     let widget = Paragraph::new(lines);
 
-    frame.render_widget(widget, area);
+    frame.render_widget(widget, text_area);
 
-    // Render vertical scrollbar if content is scrollable
-    if total_lines > visible_lines {
+    // Render vertical scrollbar if content is scrollable, counting wrapped rows
+    let (mut total_rows, mut rows_above) = (0, 0);
+    for (idx, rows) in line_rows(content, area.width, ui).enumerate() {
+        total_rows += rows;
+        if idx < scroll_y {
+            rows_above += rows;
+        }
+    }
+    if total_rows > visible_lines {
         let scrollbar = Scrollbar::default()
             .orientation(ScrollbarOrientation::VerticalRight)
             .begin_symbol(Some("↑"))
             .end_symbol(Some("↓"));
 
         let mut scrollbar_state = ScrollbarState::default()
-            .content_length(total_lines)
-            .position(scroll_y)
+            .content_length(total_rows)
+            .position(rows_above)
             .viewport_content_length(visible_lines);
 
         let scrollbar_area = Rect {
@@ -138,16 +375,34 @@ fn render_preview_content(frame: &mut Frame, area: Rect, content: &str, scroll_y
     }
 }
 
+/// Width of the line-number gutter: enough digits for the last line plus a separator
+fn line_number_gutter_width(total_lines: usize) -> u16 {
+    let digits = total_lines.max(1).to_string().len();
+    (digits + 1) as u16
+}
+
+/// Right-align a 1-based line number within the gutter, leaving a trailing space
+fn format_line_number(number: usize, gutter_width: usize) -> String {
+    format!(
+        "{:>width$} ",
+        number,
+        width = gutter_width.saturating_sub(1)
+    )
+}
+
 /// Render placeholder when no content
 fn render_placeholder(frame: &mut Frame, area: Rect, state: &AppState) {
+    let theme = &state.theme;
     let message = match state.current_mode {
-        crate::state::RenderMode::ImageToAscii | crate::state::RenderMode::ImageToUnicode => {
+        crate::state::RenderMode::ImageToAscii
+        | crate::state::RenderMode::ImageToUnicode
+        | crate::state::RenderMode::ColorAscii => {
             if state.input_image.is_some() {
                 vec![
                     Line::from(""),
                     Line::from(Span::styled(
                         "Press [Space] to render",
-                        Style::default().fg(Color::Yellow),
+                        Style::default().fg(theme.highlight),
                     )),
                 ]
             } else {
@@ -155,17 +410,17 @@ fn render_placeholder(frame: &mut Frame, area: Rect, state: &AppState) {
                     Line::from(""),
                     Line::from(Span::styled(
                         "No image loaded",
-                        Style::default().fg(Color::DarkGray),
+                        Style::default().fg(theme.muted),
                     )),
                     Line::from(""),
                     Line::from(Span::styled(
                         "Press [L] to load an image",
-                        Style::default().fg(Color::Green),
+                        Style::default().fg(theme.key),
                     )),
                     Line::from(""),
                     Line::from(Span::styled(
                         "Supported formats: PNG, JPEG, GIF, WebP, BMP",
-                        Style::default().fg(Color::DarkGray),
+                        Style::default().fg(theme.muted),
                     )),
                 ]
             }
@@ -176,17 +431,17 @@ fn render_placeholder(frame: &mut Frame, area: Rect, state: &AppState) {
                     Line::from(""),
                     Line::from(Span::styled(
                         "No text to stylize",
-                        Style::default().fg(Color::DarkGray),
+                        Style::default().fg(theme.muted),
                     )),
                     Line::from(""),
                     Line::from(Span::styled(
                         "Navigate to 'Input Text' and type your text",
-                        Style::default().fg(Color::Green),
+                        Style::default().fg(theme.key),
                     )),
                     Line::from(""),
                     Line::from(Span::styled(
                         "Press [Enter] to stylize",
-                        Style::default().fg(Color::Yellow),
+                        Style::default().fg(theme.highlight),
                     )),
                 ]
             } else {
@@ -194,7 +449,7 @@ fn render_placeholder(frame: &mut Frame, area: Rect, state: &AppState) {
                     Line::from(""),
                     Line::from(Span::styled(
                         "Press [Space] to stylize text",
-                        Style::default().fg(Color::Yellow),
+                        Style::default().fg(theme.highlight),
                     )),
                 ]
             }
@@ -202,8 +457,94 @@ fn render_placeholder(frame: &mut Frame, area: Rect, state: &AppState) {
     };
 
     let widget = Paragraph::new(message)
-        .style(Style::default().fg(Color::DarkGray))
+        .style(Style::default().fg(theme.muted))
         .alignment(ratatui::layout::Alignment::Center);
 
     frame.render_widget(widget, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    fn line_text(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_line_number_gutter() {
+        assert_eq!(line_number_gutter_width(9), 2);
+        assert_eq!(line_number_gutter_width(120), 4);
+        assert_eq!(format_line_number(7, 4), "  7 ");
+        assert_eq!(format_line_number(120, 4), "120 ");
+    }
+
+    #[test]
+    fn test_wrapped_row_count_matches_wrap() {
+        for (line, width) in [
+            ("abcdefghij", 4),
+            ("你好世界", 5),
+            ("\x1b[38;2;255;0;0mabcdef\x1b[0m", 3),
+            ("", 10),
+            ("abc", 3),
+        ] {
+            assert_eq!(
+                wrapped_row_count(line, width),
+                wrap_ansi_line(line, width).len(),
+                "{:?} at width {}",
+                line,
+                width
+            );
+        }
+    }
+
+    #[test]
+    fn test_line_rows_follow_word_wrap() {
+        let mut ui = UiPreferences::default();
+        let content = "abcdefghij\nab";
+        assert_eq!(line_rows(content, 5, &ui).collect::<Vec<_>>(), [1, 1]);
+
+        // Four cells of text beside the scrollbar column
+        ui.word_wrap = true;
+        assert_eq!(line_rows(content, 5, &ui).collect::<Vec<_>>(), [3, 1]);
+        // A two-cell gutter leaves two
+        ui.show_line_numbers = true;
+        assert_eq!(line_rows(content, 5, &ui).collect::<Vec<_>>(), [5, 1]);
+    }
+
+    #[test]
+    fn test_wrap_splits_long_line() {
+        let rows = wrap_ansi_line("abcdefghij", 4);
+        let texts: Vec<String> = rows.iter().map(line_text).collect();
+        assert_eq!(texts, vec!["abcd", "efgh", "ij"]);
+    }
+
+    #[test]
+    fn test_wrap_respects_cjk_width() {
+        // Each CJK char is two cells wide, so only two fit in five cells
+        let rows = wrap_ansi_line("你好世界", 5);
+        let texts: Vec<String> = rows.iter().map(line_text).collect();
+        assert_eq!(texts, vec!["你好", "世界"]);
+    }
+
+    #[test]
+    fn test_wrap_keeps_colors_and_empty_lines() {
+        let rows = wrap_ansi_line("\x1b[38;2;255;0;0mabcdef\x1b[0m", 3);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].spans[0].style.fg, Some(Color::Rgb(255, 0, 0)));
+
+        assert_eq!(wrap_ansi_line("", 10).len(), 1);
+    }
+
+    #[test]
+    fn test_horizontal_offset_respects_cjk_width() {
+        // "你好" fills cells 0-3, "abc" cells 4-6
+        let line = "你好abc";
+        assert_eq!(line_text(&ansi_line_to_ratatui_line(line, 0, 7)), "你好abc");
+        assert_eq!(line_text(&ansi_line_to_ratatui_line(line, 4, 10)), "abc");
+        // Offsets landing inside a wide char blank-pad its visible half
+        assert_eq!(line_text(&ansi_line_to_ratatui_line(line, 1, 4)), " 好a");
+        assert_eq!(line_text(&ansi_line_to_ratatui_line(line, 0, 3)), "你 ");
+    }
+}
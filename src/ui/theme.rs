@@ -0,0 +1,101 @@
+//! UI color theme
+//!
+//! Resolves the `[theme]` config section into ratatui colors.
+
+use ratatui::style::Color;
+
+use crate::config::ThemePreferences;
+
+/// Colors used by every UI widget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// App title, help border and the selected-setting marker
+    pub accent: Color,
+    /// Selected items, section headings and prompts
+    pub highlight: Color,
+    /// Keyboard shortcut labels
+    pub key: Color,
+    pub text: Color,
+    /// Hints, metrics and other secondary text
+    pub muted: Color,
+    pub error: Color,
+    pub border_focused: Color,
+    pub border_unfocused: Color,
+    pub title_bg: Color,
+    pub status_bg: Color,
+    /// Loaded file name in the status bar
+    pub file_info: Color,
+    /// Output format in the status bar
+    pub format_info: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            accent: Color::Cyan,
+            highlight: Color::Yellow,
+            key: Color::Green,
+            text: Color::White,
+            muted: Color::DarkGray,
+            error: Color::Red,
+            border_focused: Color::Cyan,
+            border_unfocused: Color::DarkGray,
+            title_bg: Color::DarkGray,
+            status_bg: Color::Black,
+            file_info: Color::Blue,
+            format_info: Color::Magenta,
+        }
+    }
+}
+
+impl Theme {
+    /// Build a theme from config, keeping the default for any value that doesn't parse
+    pub fn from_preferences(prefs: &ThemePreferences) -> Self {
+        let default = Theme::default();
+        Self {
+            accent: parse_color(&prefs.accent, default.accent),
+            highlight: parse_color(&prefs.highlight, default.highlight),
+            key: parse_color(&prefs.key, default.key),
+            text: parse_color(&prefs.text, default.text),
+            muted: parse_color(&prefs.muted, default.muted),
+            error: parse_color(&prefs.error, default.error),
+            border_focused: parse_color(&prefs.border_focused, default.border_focused),
+            border_unfocused: parse_color(&prefs.border_unfocused, default.border_unfocused),
+            title_bg: parse_color(&prefs.title_bg, default.title_bg),
+            status_bg: parse_color(&prefs.status_bg, default.status_bg),
+            file_info: parse_color(&prefs.file_info, default.file_info),
+            format_info: parse_color(&prefs.format_info, default.format_info),
+        }
+    }
+}
+
+/// Parse a `#rrggbb` hex string or a color name (e.g. `cyan`, `darkgray`)
+fn parse_color(value: &str, fallback: Color) -> Color {
+    value.trim().parse().unwrap_or(fallback)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_preferences_match_default_theme() {
+        let theme = Theme::from_preferences(&ThemePreferences::default());
+        assert_eq!(theme, Theme::default());
+    }
+
+    #[test]
+    fn test_hex_colors_and_invalid_fallback() {
+        let prefs = ThemePreferences {
+            accent: "#ff8800".to_string(),
+            highlight: "#zzzzzz".to_string(),
+            status_bg: "not a color".to_string(),
+            ..ThemePreferences::default()
+        };
+        let theme = Theme::from_preferences(&prefs);
+
+        assert_eq!(theme.accent, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.highlight, Theme::default().highlight);
+        assert_eq!(theme.status_bg, Theme::default().status_bg);
+    }
+}
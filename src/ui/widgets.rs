@@ -8,15 +8,24 @@ use ratatui::{
     Frame,
 };
 
+use super::theme::Theme;
+use crate::color_space::{quantize_to_ansi16, quantize_to_ansi256, Rgb};
 use crate::state::AppState;
+use crate::terminal_capabilities::ColorSupport;
+use crate::unicode_handler::{display_width, truncate_to_width};
+
+/// Most palette colors shown as swatches before the rest are summarised
+const MAX_PALETTE_SWATCHES: usize = 16;
 
 /// Render ASCII mode control panel
 pub fn render_ascii_controls(frame: &mut Frame, area: Rect, state: &AppState, is_focused: bool) {
+    let theme = &state.theme;
     let selected = state.ascii_state.selected_setting;
     let mut lines = Vec::new();
 
     // Width setting
     lines.push(create_setting_line(
+        theme,
         "Width",
         &format!("{}", state.ascii_state.width),
         selected == 0 && is_focused,
@@ -25,6 +34,7 @@ pub fn render_ascii_controls(frame: &mut Frame, area: Rect, state: &AppState, is
 
     // Charset setting
     lines.push(create_setting_line(
+        theme,
         "Charset",
         state.ascii_state.charset.name(),
         selected == 1 && is_focused,
@@ -33,14 +43,20 @@ pub fn render_ascii_controls(frame: &mut Frame, area: Rect, state: &AppState, is
 
     // Invert setting
     lines.push(create_setting_line(
+        theme,
         "Invert",
-        if state.ascii_state.invert { "On" } else { "Off" },
+        if state.ascii_state.invert {
+            "On"
+        } else {
+            "Off"
+        },
         selected == 2 && is_focused,
         Some("[Space]"),
     ));
 
     // Edge enhance setting
     lines.push(create_setting_line(
+        theme,
         "Edge Enhance",
         if state.ascii_state.edge_enhance {
             "On"
@@ -51,17 +67,67 @@ pub fn render_ascii_controls(frame: &mut Frame, area: Rect, state: &AppState, is
         Some("[Space]"),
     ));
 
+    // Edge method setting (only applied while edge enhance is on)
+    lines.push(create_setting_line(
+        theme,
+        "Edge Method",
+        state.ascii_state.edge_method.name(),
+        selected == 4 && is_focused,
+        Some("[←/→]"),
+    ));
+
+    // Resize filter setting
+    lines.push(create_setting_line(
+        theme,
+        "Resize Filter",
+        state.ascii_state.resize_filter.name(),
+        selected == 5 && is_focused,
+        Some("[←/→]"),
+    ));
+
+    // Luminance coefficients setting
+    lines.push(create_setting_line(
+        theme,
+        "Luma",
+        state.ascii_state.luma.name(),
+        selected == 6 && is_focused,
+        Some("[←/→]"),
+    ));
+
+    // Saturation multiplier
+    lines.push(create_setting_line(
+        theme,
+        "Saturation",
+        &format!("{:.1}x", state.ascii_state.saturation),
+        selected == 7 && is_focused,
+        Some("[←/→]"),
+    ));
+
+    // Keep square pixels instead of correcting for tall cells
+    lines.push(create_setting_line(
+        theme,
+        "Preserve Aspect",
+        if state.ascii_state.preserve_pixel_aspect {
+            "On"
+        } else {
+            "Off"
+        },
+        selected == 8 && is_focused,
+        Some("[Space]"),
+    ));
+
     // Action buttons
     lines.push(Line::from(""));
-    lines.push(create_action_line("[Space]", "Render"));
-    lines.push(create_action_line("[L]", "Load Image"));
-    lines.push(create_action_line("[S]", "Save Output"));
+    lines.push(create_action_line(theme, "[Space]", "Render"));
+    lines.push(create_action_line(theme, "[L]", "Load Image"));
+    lines.push(create_action_line(theme, "[S]", "Save Output"));
 
     // Output format
     lines.push(create_setting_line(
+        theme,
         "Output Format",
         state.preview_output_format.name(),
-        selected == 4 && is_focused,
+        selected == 9 && is_focused,
         Some("[←/→]"),
     ));
 
@@ -69,7 +135,7 @@ pub fn render_ascii_controls(frame: &mut Frame, area: Rect, state: &AppState, is
     if is_focused {
         lines.push(Line::from(Span::styled(
             "Tip: Press Tab to switch focus between Mode Selector, Control Panel and Preview",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
         )));
     }
 
@@ -79,11 +145,13 @@ pub fn render_ascii_controls(frame: &mut Frame, area: Rect, state: &AppState, is
 
 /// Render Unicode mode control panel
 pub fn render_unicode_controls(frame: &mut Frame, area: Rect, state: &AppState, is_focused: bool) {
+    let theme = &state.theme;
     let selected = state.unicode_state.selected_setting;
     let mut lines = Vec::new();
 
     // Width setting
     lines.push(create_setting_line(
+        theme,
         "Width",
         &format!("{}", state.unicode_state.width),
         selected == 0 && is_focused,
@@ -92,6 +160,7 @@ pub fn render_unicode_controls(frame: &mut Frame, area: Rect, state: &AppState,
 
     // Mode setting
     lines.push(create_setting_line(
+        theme,
         "Mode",
         state.unicode_state.mode.name(),
         selected == 1 && is_focused,
@@ -100,31 +169,205 @@ pub fn render_unicode_controls(frame: &mut Frame, area: Rect, state: &AppState,
 
     // Color mode setting
     lines.push(create_setting_line(
+        theme,
         "Color",
         state.unicode_state.color_mode.name(),
         selected == 2 && is_focused,
         Some("[←/→]"),
     ));
 
+    // Resize filter setting
+    lines.push(create_setting_line(
+        theme,
+        "Resize Filter",
+        state.unicode_state.resize_filter.name(),
+        selected == 3 && is_focused,
+        Some("[←/→]"),
+    ));
+
+    // Invert setting
+    lines.push(create_setting_line(
+        theme,
+        "Invert",
+        if state.unicode_state.invert {
+            "On"
+        } else {
+            "Off"
+        },
+        selected == 4 && is_focused,
+        Some("[Space]"),
+    ));
+
+    // Color negate setting
+    lines.push(create_setting_line(
+        theme,
+        "Negate Colors",
+        if state.unicode_state.negate {
+            "On"
+        } else {
+            "Off"
+        },
+        selected == 5 && is_focused,
+        Some("[Space]"),
+    ));
+
+    // Ordered dithering (Blocks mode only)
+    lines.push(create_setting_line(
+        theme,
+        "Dither",
+        if state.unicode_state.dither {
+            "On"
+        } else {
+            "Off"
+        },
+        selected == 6 && is_focused,
+        Some("[Space]"),
+    ));
+
+    // Saturation multiplier
+    lines.push(create_setting_line(
+        theme,
+        "Saturation",
+        &format!("{:.1}x", state.unicode_state.saturation),
+        selected == 7 && is_focused,
+        Some("[←/→]"),
+    ));
+
+    // Keep square pixels instead of correcting for tall cells
+    lines.push(create_setting_line(
+        theme,
+        "Preserve Aspect",
+        if state.unicode_state.preserve_pixel_aspect {
+            "On"
+        } else {
+            "Off"
+        },
+        selected == 8 && is_focused,
+        Some("[Space]"),
+    ));
+
+    // Palette colors are snapped to in Blocks/Half-Blocks modes
+    if let Some(line) = create_palette_line(
+        theme,
+        &state.unicode_state.palette,
+        state.capabilities.color_support,
+    ) {
+        lines.push(line);
+    }
+
     // Action buttons
     lines.push(Line::from(""));
-    lines.push(create_action_line("[Space]", "Render"));
-    lines.push(create_action_line("[L]", "Load Image"));
-    lines.push(create_action_line("[S]", "Save Output"));
+    lines.push(create_action_line(theme, "[Space]", "Render"));
+    lines.push(create_action_line(theme, "[L]", "Load Image"));
+    lines.push(create_action_line(theme, "[S]", "Save Output"));
 
     // Output format
     lines.push(create_setting_line(
+        theme,
         "Output Format",
         state.preview_output_format.name(),
+        selected == 9 && is_focused,
+        Some("[←/→]"),
+    ));
+
+    // Tip shown when this panel is focused
+    if is_focused {
+        lines.push(Line::from(Span::styled(
+            "Tip: Press Tab to switch focus between Mode Selector, Control Panel and Preview",
+            Style::default().fg(theme.muted),
+        )));
+    }
+
+    let widget = Paragraph::new(lines);
+    frame.render_widget(widget, area);
+}
+
+/// Render Color ASCII mode control panel
+pub fn render_color_ascii_controls(
+    frame: &mut Frame,
+    area: Rect,
+    state: &AppState,
+    is_focused: bool,
+) {
+    let theme = &state.theme;
+    let color = &state.color_ascii_state;
+    let selected = color.selected_setting;
+    let mut lines = Vec::new();
+
+    // Width setting
+    lines.push(create_setting_line(
+        theme,
+        "Width",
+        &format!("{}", color.width),
+        selected == 0 && is_focused,
+        Some("[+/-]"),
+    ));
+
+    // Charset setting
+    lines.push(create_setting_line(
+        theme,
+        "Charset",
+        color.charset.name(),
+        selected == 1 && is_focused,
+        Some("[←/→]"),
+    ));
+
+    // Invert setting
+    lines.push(create_setting_line(
+        theme,
+        "Invert",
+        if color.invert { "On" } else { "Off" },
+        selected == 2 && is_focused,
+        Some("[Space]"),
+    ));
+
+    // Color mode setting
+    lines.push(create_setting_line(
+        theme,
+        "Color",
+        color.color_mode.name(),
         selected == 3 && is_focused,
         Some("[←/→]"),
     ));
 
+    // Resize filter setting
+    lines.push(create_setting_line(
+        theme,
+        "Resize Filter",
+        color.resize_filter.name(),
+        selected == 4 && is_focused,
+        Some("[←/→]"),
+    ));
+
+    // Saturation multiplier
+    lines.push(create_setting_line(
+        theme,
+        "Saturation",
+        &format!("{:.1}x", color.saturation),
+        selected == 5 && is_focused,
+        Some("[←/→]"),
+    ));
+
+    // Action buttons
+    lines.push(Line::from(""));
+    lines.push(create_action_line(theme, "[Space]", "Render"));
+    lines.push(create_action_line(theme, "[L]", "Load Image"));
+    lines.push(create_action_line(theme, "[S]", "Save Output"));
+
+    // Output format
+    lines.push(create_setting_line(
+        theme,
+        "Output Format",
+        state.preview_output_format.name(),
+        selected == 6 && is_focused,
+        Some("[←/→]"),
+    ));
+
     // Tip shown when this panel is focused
     if is_focused {
         lines.push(Line::from(Span::styled(
             "Tip: Press Tab to switch focus between Mode Selector, Control Panel and Preview",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
         )));
     }
 
@@ -134,51 +377,55 @@ pub fn render_unicode_controls(frame: &mut Frame, area: Rect, state: &AppState,
 
 /// Render Text Stylizer mode control panel
 pub fn render_text_controls(frame: &mut Frame, area: Rect, state: &AppState, is_focused: bool) {
+    let theme = &state.theme;
     let selected = state.text_state.selected_setting;
     let mut lines = Vec::new();
 
     // Style setting
     lines.push(create_setting_line(
+        theme,
         "Style",
         state.text_state.style.name(),
         selected == 0 && is_focused,
         Some("[←/→]"),
     ));
 
-    // Gradient setting
+    // Transform setting (applied before the style)
     lines.push(create_setting_line(
-        "Gradient",
-        state.text_state.gradient.name(),
+        theme,
+        "Transform",
+        state.text_state.transform.name(),
         selected == 1 && is_focused,
         Some("[←/→]"),
     ));
 
-    // Start color
-    let start_color = format!(
-        "#{:02X}{:02X}{:02X}",
-        state.text_state.start_color.0,
-        state.text_state.start_color.1,
-        state.text_state.start_color.2
-    );
+    // Gradient setting
     lines.push(create_setting_line(
-        "Start Color",
-        &start_color,
+        theme,
+        "Gradient",
+        state.text_state.gradient.name(),
         selected == 2 && is_focused,
-        None,
+        Some("[←/→]"),
     ));
 
-    // End color
-    let end_color = format!(
-        "#{:02X}{:02X}{:02X}",
-        state.text_state.end_color.0,
-        state.text_state.end_color.1,
-        state.text_state.end_color.2
-    );
-    lines.push(create_setting_line(
-        "End Color",
-        &end_color,
+    // Start and end colors; +/- adjusts the selected channel
+    let channel = state.text_state.color_channel;
+    let color_support = state.capabilities.color_support;
+    lines.push(create_color_line(
+        theme,
+        "Start Color",
+        state.text_state.start_color,
         selected == 3 && is_focused,
-        None,
+        channel,
+        color_support,
+    ));
+    lines.push(create_color_line(
+        theme,
+        "End Color",
+        state.text_state.end_color,
+        selected == 4 && is_focused,
+        channel,
+        color_support,
     ));
 
     // Input text
@@ -186,50 +433,73 @@ pub fn render_text_controls(frame: &mut Frame, area: Rect, state: &AppState, is_
         format!("{}▌", &state.text_state.input_text)
     } else if state.text_state.input_text.is_empty() {
         "[Type here...]".to_string()
-    } else if state.text_state.input_text.len() > 15 {
-        format!("{}...", &state.text_state.input_text[..15])
+    } else if display_width(&state.text_state.input_text) > 15 {
+        format!("{}...", truncate_to_width(&state.text_state.input_text, 15))
     } else {
         state.text_state.input_text.clone()
     };
 
-    let is_input_selected = selected == 4 && is_focused;
+    let is_input_selected = selected == 5 && is_focused;
     lines.push(Line::from(vec![
         Span::styled(
             if is_input_selected { "▸ " } else { "  " },
-            Style::default().fg(Color::Cyan),
+            Style::default().fg(theme.accent),
         ),
         Span::styled(
             "Input: ",
             if is_input_selected {
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.highlight)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(theme.text)
             },
         ),
         Span::styled(
             &input_display,
             if state.text_state.editing_text {
-                Style::default().fg(Color::Green)
+                Style::default().fg(theme.key)
             } else if is_input_selected {
-                Style::default().fg(Color::Cyan)
+                Style::default().fg(theme.accent)
             } else {
-                Style::default().fg(Color::DarkGray)
+                Style::default().fg(theme.muted)
             },
         ),
     ]));
 
+    // Hyperlink setting (links to the first URL in the input)
+    lines.push(create_setting_line(
+        theme,
+        "Hyperlink",
+        if state.text_state.hyperlink {
+            "On"
+        } else {
+            "Off"
+        },
+        selected == 6 && is_focused,
+        Some("[Space]"),
+    ));
+
+    // Alignment of multi-line text
+    lines.push(create_setting_line(
+        theme,
+        "Alignment",
+        state.text_state.alignment.name(),
+        selected == 7 && is_focused,
+        Some("[←/→]"),
+    ));
+
     // Action buttons
     lines.push(Line::from(""));
-    lines.push(create_action_line("[Space]", "Stylize"));
-    lines.push(create_action_line("[S]", "Save Output"));
+    lines.push(create_action_line(theme, "[Space]", "Stylize"));
+    lines.push(create_action_line(theme, "[S]", "Save Output"));
 
     // Output format
     lines.push(create_setting_line(
+        theme,
         "Output Format",
         state.preview_output_format.name(),
-        selected == 5 && is_focused,
+        selected == 8 && is_focused,
         Some("[←/→]"),
     ));
 
@@ -237,7 +507,7 @@ pub fn render_text_controls(frame: &mut Frame, area: Rect, state: &AppState, is_
     if is_focused {
         lines.push(Line::from(Span::styled(
             "Tip: Press Tab to switch focus between Mode Selector, Control Panel and Preview",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
         )));
     }
 
@@ -247,26 +517,27 @@ pub fn render_text_controls(frame: &mut Frame, area: Rect, state: &AppState, is_
 
 /// Create a setting line with label, value, and optional hint
 fn create_setting_line(
+    theme: &Theme,
     label: &str,
     value: &str,
     is_selected: bool,
     hint: Option<&str>,
 ) -> Line<'static> {
     let indicator = if is_selected { "▸" } else { " " };
-    let indicator_style = Style::default().fg(Color::Cyan);
+    let indicator_style = Style::default().fg(theme.accent);
 
     let label_style = if is_selected {
         Style::default()
-            .fg(Color::Yellow)
+            .fg(theme.highlight)
             .add_modifier(Modifier::BOLD)
     } else {
-        Style::default().fg(Color::White)
+        Style::default().fg(theme.text)
     };
 
     let value_style = if is_selected {
-        Style::default().fg(Color::Cyan)
+        Style::default().fg(theme.accent)
     } else {
-        Style::default().fg(Color::DarkGray)
+        Style::default().fg(theme.muted)
     };
 
     let mut spans = vec![
@@ -278,18 +549,146 @@ fn create_setting_line(
     if let Some(hint_text) = hint {
         spans.push(Span::styled(
             format!(" {}", hint_text),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.muted),
         ));
     }
 
     Line::from(spans)
 }
 
+/// Create a color setting line with a swatch; when selected, the hint names the channel
+/// that +/- adjusts
+fn create_color_line(
+    theme: &Theme,
+    label: &str,
+    (r, g, b): (u8, u8, u8),
+    is_selected: bool,
+    channel: usize,
+    color_support: ColorSupport,
+) -> Line<'static> {
+    let value = format!("#{:02X}{:02X}{:02X}", r, g, b);
+    let hint = if is_selected {
+        format!("[←/→ {}, +/-, Enter]", ["R", "G", "B"][channel.min(2)])
+    } else {
+        "[Enter]".to_string()
+    };
+    let mut line = create_setting_line(theme, label, &value, is_selected, Some(&hint));
+    if let Some(swatch) = color_swatch(Rgb::new(r, g, b), color_support) {
+        line.spans.push(Span::raw(" "));
+        line.spans.push(swatch);
+    }
+    line
+}
+
+/// Create a line of swatches for the Unicode palette, or `None` without colors to show
+fn create_palette_line(
+    theme: &Theme,
+    palette: &[Rgb],
+    color_support: ColorSupport,
+) -> Option<Line<'static>> {
+    if palette.is_empty() || color_support == ColorSupport::NoColor {
+        return None;
+    }
+    let mut spans = vec![Span::styled("  Palette: ", Style::default().fg(theme.text))];
+    spans.extend(
+        palette
+            .iter()
+            .take(MAX_PALETTE_SWATCHES)
+            .filter_map(|&rgb| color_swatch(rgb, color_support)),
+    );
+    if palette.len() > MAX_PALETTE_SWATCHES {
+        spans.push(Span::styled(
+            format!(" +{}", palette.len() - MAX_PALETTE_SWATCHES),
+            Style::default().fg(theme.muted),
+        ));
+    }
+    Some(Line::from(spans))
+}
+
+/// Two blank cells filled with `rgb`, matched to the terminal's color level;
+/// `None` under `NoColor`, where only the hex value is shown
+fn color_swatch(rgb: Rgb, color_support: ColorSupport) -> Option<Span<'static>> {
+    let color = match color_support {
+        ColorSupport::NoColor => return None,
+        ColorSupport::Color16 => Color::Indexed(quantize_to_ansi16(rgb)),
+        ColorSupport::Color256 => Color::Indexed(quantize_to_ansi256(rgb)),
+        ColorSupport::TrueColor => Color::Rgb(rgb.r, rgb.g, rgb.b),
+    };
+    Some(Span::styled("  ", Style::default().bg(color)))
+}
+
 /// Create an action line (button-like)
-fn create_action_line(key: &str, label: &str) -> Line<'static> {
+fn create_action_line(theme: &Theme, key: &str, label: &str) -> Line<'static> {
     Line::from(vec![
         Span::styled("  ", Style::default()),
-        Span::styled(key.to_string(), Style::default().fg(Color::Green)),
-        Span::styled(format!(" {}", label), Style::default().fg(Color::White)),
+        Span::styled(key.to_string(), Style::default().fg(theme.key)),
+        Span::styled(format!(" {}", label), Style::default().fg(theme.text)),
     ])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_line_swatch_carries_color() {
+        let theme = Theme::default();
+        let line = create_color_line(
+            &theme,
+            "Start Color",
+            (255, 128, 0),
+            false,
+            0,
+            ColorSupport::TrueColor,
+        );
+        let swatch = line.spans.last().unwrap();
+        assert_eq!(swatch.content, "  ");
+        assert_eq!(swatch.style.bg, Some(Color::Rgb(255, 128, 0)));
+
+        let line = create_color_line(
+            &theme,
+            "Start Color",
+            (255, 0, 0),
+            false,
+            0,
+            ColorSupport::Color256,
+        );
+        assert_eq!(
+            line.spans.last().unwrap().style.bg,
+            Some(Color::Indexed(196))
+        );
+    }
+
+    #[test]
+    fn test_no_color_shows_only_hex() {
+        let theme = Theme::default();
+        let line = create_color_line(
+            &theme,
+            "End Color",
+            (0, 0, 255),
+            false,
+            0,
+            ColorSupport::NoColor,
+        );
+        assert!(line.spans.iter().all(|span| span.style.bg.is_none()));
+        assert!(line.spans.iter().any(|span| span.content == "#0000FF"));
+
+        let palette = [Rgb::new(0, 0, 0), Rgb::new(255, 255, 255)];
+        assert!(create_palette_line(&theme, &palette, ColorSupport::NoColor).is_none());
+        assert!(create_palette_line(&theme, &[], ColorSupport::TrueColor).is_none());
+    }
+
+    #[test]
+    fn test_palette_line_caps_swatches() {
+        let theme = Theme::default();
+        let palette: Vec<Rgb> = (0..20).map(|i| Rgb::new(i * 10, 0, 0)).collect();
+        let line = create_palette_line(&theme, &palette, ColorSupport::TrueColor).unwrap();
+        let swatches = line
+            .spans
+            .iter()
+            .filter(|span| span.style.bg.is_some())
+            .count();
+        assert_eq!(swatches, MAX_PALETTE_SWATCHES);
+        assert_eq!(line.spans.last().unwrap().content, " +4");
+    }
+}
@@ -1,6 +1,7 @@
 //! Unicode width and validation utilities
 //!
-//! Handles East Asian Width calculations and grapheme cluster validation.
+//! Handles East Asian Width calculations, grapheme cluster validation and stripping
+//! ANSI escapes before text is measured.
 
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
@@ -54,6 +55,17 @@ pub fn pad_to_width(s: &str, target_width: usize, align_right: bool) -> String {
     }
 }
 
+/// Right-pad every line to the display width of the widest one
+pub fn pad_lines(text: &str) -> String {
+    let max_width = text.lines().map(display_width).max().unwrap_or(0);
+    let mut result = String::with_capacity(text.len());
+    for line in text.lines() {
+        result.push_str(&pad_to_width(line, max_width, false));
+        result.push('\n');
+    }
+    result
+}
+
 /// Count grapheme clusters in a string
 pub fn grapheme_count(s: &str) -> usize {
     s.graphemes(true).count()
@@ -91,6 +103,61 @@ pub fn check_unicode_support() -> bool {
     terminal_supports_unicode()
 }
 
+/// Whether `c` ends a CSI sequence (ECMA-48 final bytes `@` through `~`)
+pub(crate) fn is_csi_final_byte(c: char) -> bool {
+    ('\x40'..='\x7e').contains(&c)
+}
+
+/// Consume one escape sequence whose ESC has already been read
+///
+/// - CSI (`ESC [`) runs to its final byte, e.g. `m` or `~`
+/// - OSC, DCS, SOS, PM and APC strings (such as OSC 8 hyperlinks and
+///   `ESC ] 0 ; title BEL`) run to BEL or ST (`ESC \`)
+/// - anything else is a short escape: optional intermediate bytes plus one final char
+pub(crate) fn skip_escape_sequence(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    match chars.next() {
+        Some('[') => {
+            for next in chars.by_ref() {
+                if is_csi_final_byte(next) {
+                    break;
+                }
+            }
+        }
+        Some(']' | 'P' | 'X' | '^' | '_') => {
+            while let Some(next) = chars.next() {
+                if next == '\x07' {
+                    break;
+                }
+                if next == '\x1b' {
+                    chars.next_if_eq(&'\\');
+                    break;
+                }
+            }
+        }
+        Some(' '..='/') => {
+            // nF escapes such as `ESC ( B`: more intermediates, then the final char
+            while chars.next_if(|c| (' '..='/').contains(c)).is_some() {}
+            chars.next();
+        }
+        _ => {}
+    }
+}
+
+/// Strip ANSI escape codes (SGR colors, OSC 8 hyperlinks, titles, ...) from text
+pub fn strip_ansi_codes(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            skip_escape_sequence(&mut chars);
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
 
 #[cfg(test)]
 mod tests {
@@ -126,6 +193,13 @@ mod tests {
         assert_eq!(pad_to_width("Hi", 5, true), "   Hi");
     }
 
+    #[test]
+    fn test_pad_lines() {
+        let padded = pad_lines("你好\nab\n\nabc\n");
+        assert_eq!(padded, "你好\nab  \n    \nabc \n");
+        assert!(padded.lines().all(|line| display_width(line) == 4));
+    }
+
     #[test]
     fn test_validate_graphemes() {
         assert!(validate_graphemes("Hello"));
@@ -147,4 +221,42 @@ mod tests {
         assert!(check_unicode_support());
         std::env::remove_var("LC_CTYPE");
     }
+
+    #[test]
+    fn test_strip_ansi_codes() {
+        let input = "\x1b[38;2;255;0;0mRed\x1b[0m";
+        let output = strip_ansi_codes(input);
+        assert_eq!(output, "Red");
+    }
+
+    #[test]
+    fn test_strip_ansi_preserves_text() {
+        let input = "Hello, World!";
+        let output = strip_ansi_codes(input);
+        assert_eq!(output, "Hello, World!");
+    }
+
+    #[test]
+    fn test_strip_ansi_complex() {
+        let input = "\x1b[38;5;196mR\x1b[38;5;208mA\x1b[38;5;226mI\x1b[38;5;46mN\x1b[38;5;21mB\x1b[38;5;129mO\x1b[38;5;196mW\x1b[0m";
+        let output = strip_ansi_codes(input);
+        assert_eq!(output, "RAINBOW");
+    }
+
+    #[test]
+    fn test_strip_ansi_osc_and_short_escapes() {
+        // Hyperlink in the middle of a line; the URL's letters must not end the sequence early
+        let mid = "before \x1b]8;id=x;https://example.com/a?b=c\x1b\\link\x1b]8;;\x1b\\ after";
+        assert_eq!(strip_ansi_codes(mid), "before link after");
+
+        // Window title set with a BEL terminator
+        assert_eq!(strip_ansi_codes("\x1b]0;My Title\x07Body"), "Body");
+
+        // Two-char escapes consume exactly one char, charset selection two
+        assert_eq!(strip_ansi_codes("\x1b7save\x1b8"), "save");
+        assert_eq!(strip_ansi_codes("\x1b(Btext"), "text");
+
+        // CSI sequences ending in non-letter final bytes
+        assert_eq!(strip_ansi_codes("\x1b[2~key\x1b[@"), "key");
+    }
 }
@@ -2,17 +2,28 @@
 //!
 //! Handles background rendering on dedicated worker threads.
 
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use image::DynamicImage;
 
-use crate::render_engines::ascii::{render_ascii, AsciiConfig, CharacterSet};
-use crate::render_engines::text_stylizer::{stylize_text, GradientMode, UnicodeStyle};
-use crate::render_engines::unicode::{render_unicode, UnicodeConfig, UnicodeMode};
-use crate::terminal_capabilities::ColorSupport;
+use crate::graphics::{render_graphics, GraphicsMode};
+use crate::render_engines::ascii::{render_ascii_streaming, AsciiConfig};
+use crate::render_engines::text_stylizer::{
+    hyperlink, stylize_text, stylize_text_plain, GradientMode, TextAlignment, TextTransform,
+    UnicodeStyle,
+};
+use crate::render_engines::unicode::{
+    render_unicode_timed, render_unicode_with_progress, UnicodeConfig,
+};
+use crate::render_engines::RenderTiming;
+use crate::terminal_capabilities::UnicodeSupport;
+use crate::unicode_handler::pad_lines;
 
 /// Messages sent from main thread to workers
 #[derive(Debug)]
@@ -20,25 +31,43 @@ pub enum WorkerMessage {
     /// Request ASCII rendering
     AsciiRequest {
         image: Arc<DynamicImage>,
-        width: usize,
-        charset: CharacterSet,
-        invert: bool,
-        edge_enhance: bool,
+        config: AsciiConfig,
     },
     /// Request Unicode rendering
     UnicodeRequest {
         image: Arc<DynamicImage>,
-        width: usize,
-        mode: UnicodeMode,
-        color_mode: ColorSupport,
+        config: UnicodeConfig,
+    },
+    /// Request a pixel graphics preview (plus Unicode art for saving and copying)
+    GraphicsRequest {
+        image: Arc<DynamicImage>,
+        config: UnicodeConfig,
+        mode: GraphicsMode,
+        /// Pixel bounds the bitmap must fit in
+        max_size: (u32, u32),
     },
     /// Request text stylization
     TextRequest {
         text: String,
+        transform: TextTransform,
         style: UnicodeStyle,
         gradient: GradientMode,
         start_color: (u8, u8, u8),
         end_color: (u8, u8, u8),
+        /// Alignment of multi-line text
+        alignment: TextAlignment,
+        /// Wrap the output in an OSC 8 hyperlink to this URL
+        link: Option<String>,
+        /// Below `Full`, styles fall back to plain text with SGR attributes
+        unicode_support: UnicodeSupport,
+    },
+    /// A request whose responses go to `reply` rather than the shared response channel
+    ///
+    /// Skipped if `cancelled` is set before a worker picks it up.
+    Routed {
+        request: Box<WorkerMessage>,
+        reply: Sender<WorkerResponse>,
+        cancelled: Arc<AtomicBool>,
     },
     /// Shutdown signal
     Shutdown,
@@ -48,11 +77,34 @@ pub enum WorkerMessage {
 #[derive(Debug)]
 pub enum WorkerResponse {
     /// ASCII rendering complete
-    AsciiComplete { output: String, render_time: u64 },
+    AsciiComplete {
+        output: String,
+        render_time: u64,
+        /// Time spent resizing vs mapping characters
+        timing: RenderTiming,
+    },
     /// Unicode rendering complete
-    UnicodeComplete { output: String, render_time: u64 },
+    UnicodeComplete {
+        output: String,
+        render_time: u64,
+        timing: RenderTiming,
+    },
+    /// Graphics preview complete; `output` is the Unicode fallback
+    GraphicsComplete {
+        output: String,
+        /// Escape sequences that draw the image at the cursor
+        graphics: String,
+        render_time: u64,
+    },
     /// Text stylization complete
     TextComplete { output: String, render_time: u64 },
+    /// Partial progress (0.0-1.0) of the image render in flight
+    Progress { fraction: f32 },
+    /// The next `CHUNK_ROWS` rows of the ASCII render in flight; `seq` counts from 0
+    ///
+    /// Only sent for renders taller than one chunk. `AsciiComplete` still follows
+    /// with the whole output.
+    AsciiChunk { seq: usize, rows: String },
     /// Error occurred
     Error(String),
 }
@@ -65,6 +117,27 @@ pub struct WorkerHandle {
 }
 
 impl WorkerHandle {
+    /// Number of worker threads
+    pub fn worker_count(&self) -> usize {
+        self.threads.len()
+    }
+
+    /// Queue `msg` with its responses kept apart from other requests', so several
+    /// callers can share one pool
+    pub fn submit(&self, msg: WorkerMessage) -> Job {
+        let (reply, response_rx) = unbounded();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let _ = self.request_tx.send(WorkerMessage::Routed {
+            request: Box::new(msg),
+            reply,
+            cancelled: Arc::clone(&cancelled),
+        });
+        Job {
+            response_rx,
+            cancelled,
+        }
+    }
+
     /// Shutdown all worker threads
     pub fn shutdown(self) {
         // Send shutdown signal to all workers
@@ -79,24 +152,54 @@ impl WorkerHandle {
     }
 }
 
-/// Spawn worker threads for rendering
-pub fn spawn_workers() -> WorkerHandle {
+/// A request queued with [`WorkerHandle::submit`], answered on a channel of its own
+///
+/// Dropping it cancels the request if no worker has started on it yet; a render that
+/// is already running finishes, and its responses are discarded.
+pub struct Job {
+    pub response_rx: Receiver<WorkerResponse>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Drop for Job {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Worker count for parallel batch rendering: one per CPU, up to 4
+pub fn default_worker_count() -> usize {
+    num_cpus().clamp(1, 4)
+}
+
+/// Name prefix of render worker threads, followed by the worker's index
+pub const WORKER_THREAD_PREFIX: &str = "render-worker-";
+
+/// Renders one request and sends its response(s)
+type RequestHandler = fn(WorkerMessage, &Sender<WorkerResponse>);
+
+/// Spawn `num` worker threads for rendering (at least one)
+///
+/// Several workers can finish requests out of order; a single worker completes them FIFO.
+pub fn spawn_workers(num: usize) -> WorkerHandle {
+    spawn_workers_with(num, handle_message)
+}
+
+/// [`spawn_workers`] with workers that answer requests through `handler`
+fn spawn_workers_with(num: usize, handler: RequestHandler) -> WorkerHandle {
     let (request_tx, request_rx) = unbounded::<WorkerMessage>();
     let (response_tx, response_rx) = unbounded::<WorkerResponse>();
 
     let mut threads = Vec::new();
 
-    // Spawn multiple worker threads for parallel processing
-    let num_workers = num_cpus().min(4).max(1);
-
-    for id in 0..num_workers {
+    for id in 0..num.max(1) {
         let rx = request_rx.clone();
         let tx = response_tx.clone();
 
         let handle = thread::Builder::new()
-            .name(format!("render-worker-{}", id))
+            .name(format!("{}{}", WORKER_THREAD_PREFIX, id))
             .spawn(move || {
-                worker_loop(rx, tx);
+                worker_loop(rx, tx, handler);
             })
             .expect("Failed to spawn worker thread");
 
@@ -110,87 +213,252 @@ pub fn spawn_workers() -> WorkerHandle {
     }
 }
 
+/// Rows per `AsciiChunk` streamed to the preview
+pub const CHUNK_ROWS: usize = 50;
+
+/// Groups finished rows into `AsciiChunk`s
+///
+/// Nothing is sent until a full chunk is ready, so short renders arrive in one piece.
+struct ChunkStream<'a> {
+    tx: &'a Sender<WorkerResponse>,
+    seq: usize,
+    pending: String,
+    pending_rows: usize,
+}
+
+impl<'a> ChunkStream<'a> {
+    fn new(tx: &'a Sender<WorkerResponse>) -> Self {
+        Self {
+            tx,
+            seq: 0,
+            pending: String::new(),
+            pending_rows: 0,
+        }
+    }
+
+    fn push(&mut self, rows: &str) {
+        self.pending.push_str(rows);
+        self.pending_rows += rows.matches('\n').count();
+        if self.pending_rows >= CHUNK_ROWS {
+            self.send();
+        }
+    }
+
+    /// Send the leftover rows, if the render was streamed at all
+    fn finish(&mut self) {
+        if self.seq > 0 && !self.pending.is_empty() {
+            self.send();
+        }
+    }
+
+    fn send(&mut self) {
+        let rows = std::mem::take(&mut self.pending);
+        let _ = self.tx.send(WorkerResponse::AsciiChunk {
+            seq: self.seq,
+            rows,
+        });
+        self.seq += 1;
+        self.pending_rows = 0;
+    }
+}
+
+/// Minimum gap between progress messages (~20 per second)
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Rate-limits progress reports so a large render doesn't flood the channel
+///
+/// Renders that finish within one interval send no progress at all.
+struct ProgressThrottle<'a> {
+    tx: &'a Sender<WorkerResponse>,
+    last_sent: Instant,
+}
+
+impl<'a> ProgressThrottle<'a> {
+    fn new(tx: &'a Sender<WorkerResponse>) -> Self {
+        Self {
+            tx,
+            last_sent: Instant::now(),
+        }
+    }
+
+    fn report(&mut self, fraction: f32) {
+        // Completion is signalled by the final response instead
+        if fraction >= 1.0 || self.last_sent.elapsed() < PROGRESS_INTERVAL {
+            return;
+        }
+        self.last_sent = Instant::now();
+        let _ = self.tx.send(WorkerResponse::Progress { fraction });
+    }
+}
+
 /// Main worker loop - processes messages until shutdown
-fn worker_loop(rx: Receiver<WorkerMessage>, tx: Sender<WorkerResponse>) {
+///
+/// A render that panics is answered with [`WorkerResponse::Error`] and the worker
+/// moves on to the next request.
+fn worker_loop(rx: Receiver<WorkerMessage>, tx: Sender<WorkerResponse>, handler: RequestHandler) {
     while let Ok(msg) = rx.recv() {
-        match msg {
+        let (msg, reply_tx) = match msg {
             WorkerMessage::Shutdown => break,
-
-            WorkerMessage::AsciiRequest {
-                image,
-                width,
-                charset,
-                invert,
-                edge_enhance,
+            WorkerMessage::Routed {
+                request,
+                reply,
+                cancelled,
             } => {
-                let start = Instant::now();
-
-                let config = AsciiConfig {
-                    target_width: width,
-                    charset,
-                    invert,
-                    edge_enhance,
-                };
-
-                let response = match render_ascii(&image, &config) {
-                    Ok(output) => WorkerResponse::AsciiComplete {
-                        output,
-                        render_time: start.elapsed().as_millis() as u64,
-                    },
-                    Err(e) => WorkerResponse::Error(e.to_string()),
-                };
-
-                let _ = tx.send(response);
+                if cancelled.load(Ordering::Relaxed) {
+                    continue;
+                }
+                (*request, reply)
             }
+            msg => (msg, tx.clone()),
+        };
+        // The request and its image are dropped with the panic, so nothing is reused half-updated
+        let handled = panic::catch_unwind(AssertUnwindSafe(|| handler(msg, &reply_tx)));
+        if let Err(payload) = handled {
+            let _ = reply_tx.send(WorkerResponse::Error(format!(
+                "Render failed unexpectedly: {}",
+                panic_message(payload.as_ref())
+            )));
+        }
+    }
+}
 
-            WorkerMessage::UnicodeRequest {
-                image,
-                width,
-                mode,
-                color_mode,
-            } => {
-                let start = Instant::now();
-
-                let config = UnicodeConfig {
-                    target_width: width,
-                    mode,
-                    color_mode,
-                };
-
-                let response = match render_unicode(&image, &config) {
-                    Ok(output) => WorkerResponse::UnicodeComplete {
-                        output,
-                        render_time: start.elapsed().as_millis() as u64,
+/// Render one request and send its response
+fn handle_message(msg: WorkerMessage, tx: &Sender<WorkerResponse>) {
+    match msg {
+        // Handled by `worker_loop`
+        WorkerMessage::Shutdown | WorkerMessage::Routed { .. } => {}
+
+        WorkerMessage::AsciiRequest { image, config } => {
+            let start = Instant::now();
+
+            let mut throttle = ProgressThrottle::new(tx);
+            let mut progress = |f| throttle.report(f);
+            let mut chunks = ChunkStream::new(tx);
+            let mut on_rows = |rows: &str| chunks.push(rows);
+
+            let rendered = render_ascii_streaming(&image, &config, &mut progress, &mut on_rows);
+            chunks.finish();
+            let response = match rendered {
+                Ok((output, timing)) => WorkerResponse::AsciiComplete {
+                    output: if config.pad_lines {
+                        pad_lines(&output)
+                    } else {
+                        output
                     },
-                    Err(e) => WorkerResponse::Error(e.to_string()),
-                };
+                    render_time: start.elapsed().as_millis() as u64,
+                    timing,
+                },
+                Err(e) => WorkerResponse::Error(e.to_string()),
+            };
 
-                let _ = tx.send(response);
-            }
+            let _ = tx.send(response);
+        }
+
+        WorkerMessage::UnicodeRequest { image, config } => {
+            let start = Instant::now();
+
+            let mut throttle = ProgressThrottle::new(tx);
+            let mut progress = |f| throttle.report(f);
 
-            WorkerMessage::TextRequest {
-                text,
+            let response = match render_unicode_timed(&image, &config, &mut progress) {
+                Ok((output, timing)) => WorkerResponse::UnicodeComplete {
+                    output,
+                    render_time: start.elapsed().as_millis() as u64,
+                    timing,
+                },
+                Err(e) => WorkerResponse::Error(e.to_string()),
+            };
+
+            let _ = tx.send(response);
+        }
+
+        WorkerMessage::GraphicsRequest {
+            image,
+            config,
+            mode,
+            max_size,
+        } => {
+            let start = Instant::now();
+
+            let mut throttle = ProgressThrottle::new(tx);
+            let mut progress = |f| throttle.report(f);
+
+            let response =
+                render_unicode_with_progress(&image, &config, &mut progress).and_then(|output| {
+                    let graphics = render_graphics(
+                        &image,
+                        mode,
+                        max_size.0,
+                        max_size.1,
+                        config.resize_filter,
+                    )?;
+                    Ok((output, graphics))
+                });
+            let response = match response {
+                Ok((output, graphics)) => WorkerResponse::GraphicsComplete {
+                    output,
+                    graphics,
+                    render_time: start.elapsed().as_millis() as u64,
+                },
+                Err(e) => WorkerResponse::Error(e.to_string()),
+            };
+
+            let _ = tx.send(response);
+        }
+
+        WorkerMessage::TextRequest {
+            text,
+            transform,
+            style,
+            gradient,
+            start_color,
+            end_color,
+            alignment,
+            link,
+            unicode_support,
+        } => {
+            let start = Instant::now();
+
+            let stylize = if unicode_support == UnicodeSupport::Full {
+                stylize_text
+            } else {
+                stylize_text_plain
+            };
+            let response = match stylize(
+                &text,
+                transform,
                 style,
                 gradient,
                 start_color,
                 end_color,
-            } => {
-                let start = Instant::now();
-
-                let response = match stylize_text(&text, style, gradient, start_color, end_color) {
-                    Ok(output) => WorkerResponse::TextComplete {
-                        output,
-                        render_time: start.elapsed().as_millis() as u64,
+                alignment,
+            ) {
+                Ok(output) => WorkerResponse::TextComplete {
+                    output: match link {
+                        Some(url) => hyperlink(&output, &url),
+                        None => output,
                     },
-                    Err(e) => WorkerResponse::Error(e.to_string()),
-                };
+                    render_time: start.elapsed().as_millis() as u64,
+                },
+                Err(e) => WorkerResponse::Error(e.to_string()),
+            };
 
-                let _ = tx.send(response);
-            }
+            let _ = tx.send(response);
         }
     }
 }
 
+/// The message a panic was raised with, if it was a string
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
+
 /// Get number of CPUs (fallback to 1)
 fn num_cpus() -> usize {
     thread::available_parallelism()
@@ -201,17 +469,97 @@ fn num_cpus() -> usize {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::render_engines::ascii::CharacterSet;
     use image::RgbImage;
 
+    /// Next response that ends a request, skipping progress and streamed chunks
+    fn recv_final(workers: &WorkerHandle) -> WorkerResponse {
+        loop {
+            match workers
+                .response_rx
+                .recv_timeout(std::time::Duration::from_secs(5))
+                .unwrap()
+            {
+                WorkerResponse::Progress { .. } | WorkerResponse::AsciiChunk { .. } => {}
+                response => return response,
+            }
+        }
+    }
+
+    fn text_request(text: &str) -> WorkerMessage {
+        WorkerMessage::TextRequest {
+            text: text.to_string(),
+            transform: TextTransform::None,
+            style: UnicodeStyle::Bold,
+            gradient: GradientMode::None,
+            start_color: (255, 0, 0),
+            end_color: (0, 0, 255),
+            alignment: TextAlignment::Left,
+            link: None,
+            unicode_support: UnicodeSupport::Full,
+        }
+    }
+
     #[test]
     fn test_spawn_and_shutdown() {
-        let workers = spawn_workers();
+        let workers = spawn_workers(default_worker_count());
+        workers.shutdown();
+    }
+
+    #[test]
+    fn test_single_worker_completes_in_request_order() {
+        let workers = spawn_workers(1);
+
+        // A slow render queued ahead of quick ones must still finish first
+        let img = Arc::new(DynamicImage::ImageRgb8(RgbImage::new(800, 800)));
+        workers
+            .request_tx
+            .send(WorkerMessage::AsciiRequest {
+                image: img,
+                config: AsciiConfig {
+                    target_width: 300,
+                    ..AsciiConfig::default()
+                },
+            })
+            .unwrap();
+        for text in ["a", "b", "c"] {
+            workers
+                .request_tx
+                .send(WorkerMessage::TextRequest {
+                    text: text.to_string(),
+                    transform: TextTransform::None,
+                    style: UnicodeStyle::Bold,
+                    gradient: GradientMode::None,
+                    start_color: (255, 0, 0),
+                    end_color: (0, 0, 255),
+                    alignment: TextAlignment::Left,
+                    link: None,
+                    unicode_support: UnicodeSupport::Full,
+                })
+                .unwrap();
+        }
+
+        let mut completed = Vec::new();
+        while completed.len() < 4 {
+            match workers
+                .response_rx
+                .recv_timeout(std::time::Duration::from_secs(10))
+                .unwrap()
+            {
+                WorkerResponse::Progress { .. } | WorkerResponse::AsciiChunk { .. } => {}
+                WorkerResponse::AsciiComplete { .. } => completed.push("ascii".to_string()),
+                WorkerResponse::TextComplete { output, .. } => completed.push(output),
+                other => panic!("Unexpected response {:?}", other),
+            }
+        }
+        assert_eq!(completed, ["ascii", "𝐚", "𝐛", "𝐜"]);
+
         workers.shutdown();
     }
 
     #[test]
     fn test_ascii_request() {
-        let workers = spawn_workers();
+        let workers = spawn_workers(1);
 
         let img = Arc::new(DynamicImage::ImageRgb8(RgbImage::new(10, 10)));
 
@@ -219,19 +567,15 @@ mod tests {
             .request_tx
             .send(WorkerMessage::AsciiRequest {
                 image: img,
-                width: 10,
-                charset: CharacterSet::Standard,
-                invert: false,
-                edge_enhance: false,
+                config: AsciiConfig {
+                    target_width: 10,
+                    charset: CharacterSet::Standard,
+                    ..AsciiConfig::default()
+                },
             })
             .unwrap();
 
-        let response = workers
-            .response_rx
-            .recv_timeout(std::time::Duration::from_secs(5))
-            .unwrap();
-
-        match response {
+        match recv_final(&workers) {
             WorkerResponse::AsciiComplete { output, .. } => {
                 assert!(!output.is_empty());
             }
@@ -241,18 +585,60 @@ mod tests {
         workers.shutdown();
     }
 
+    #[test]
+    fn test_ascii_request_pads_lines() {
+        let workers = spawn_workers(1);
+
+        // A wide glyph in the ramp makes bright cells two columns wide
+        let stripes = RgbImage::from_fn(16, 16, |x, y| {
+            let v = if (x + y) % 3 == 0 { 255 } else { 0 };
+            image::Rgb([v, v, v])
+        });
+        let img = Arc::new(DynamicImage::ImageRgb8(stripes));
+        workers
+            .request_tx
+            .send(WorkerMessage::AsciiRequest {
+                image: img,
+                config: AsciiConfig {
+                    target_width: 16,
+                    charset: CharacterSet::Custom(" 你".to_string()),
+                    resize_filter: crate::render_engines::ResizeFilter::Nearest,
+                    pad_lines: true,
+                    ..AsciiConfig::default()
+                },
+            })
+            .unwrap();
+
+        match recv_final(&workers) {
+            WorkerResponse::AsciiComplete { output, .. } => {
+                let widths: Vec<usize> = output
+                    .lines()
+                    .map(crate::unicode_handler::display_width)
+                    .collect();
+                assert!(widths.windows(2).all(|w| w[0] == w[1]));
+            }
+            other => panic!("Unexpected response {:?}", other),
+        }
+
+        workers.shutdown();
+    }
+
     #[test]
     fn test_text_request() {
-        let workers = spawn_workers();
+        let workers = spawn_workers(1);
 
         workers
             .request_tx
             .send(WorkerMessage::TextRequest {
                 text: "Hello".to_string(),
+                transform: TextTransform::None,
                 style: UnicodeStyle::Bold,
                 gradient: GradientMode::None,
                 start_color: (255, 0, 0),
                 end_color: (0, 0, 255),
+                alignment: TextAlignment::Left,
+                link: None,
+                unicode_support: UnicodeSupport::Full,
             })
             .unwrap();
 
@@ -271,4 +657,198 @@ mod tests {
 
         workers.shutdown();
     }
+
+    #[test]
+    fn test_submitted_jobs_get_their_own_responses() {
+        static HANDLED: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        // Counts requests; "slow" keeps the worker busy for a moment
+        fn counting(msg: WorkerMessage, tx: &Sender<WorkerResponse>) {
+            HANDLED.fetch_add(1, Ordering::SeqCst);
+            if matches!(&msg, WorkerMessage::TextRequest { text, .. } if text == "slow") {
+                thread::sleep(Duration::from_millis(200));
+            }
+            handle_message(msg, tx);
+        }
+
+        let workers = spawn_workers_with(1, counting);
+        let slow = workers.submit(text_request("slow"));
+        // Dropped while queued behind "slow", so never rendered
+        drop(workers.submit(text_request("dropped")));
+        let hello = workers.submit(text_request("Hello"));
+
+        let recv = |job: &Job| {
+            job.response_rx
+                .recv_timeout(Duration::from_secs(5))
+                .unwrap()
+        };
+        assert!(matches!(recv(&hello), WorkerResponse::TextComplete { .. }));
+        assert!(matches!(recv(&slow), WorkerResponse::TextComplete { .. }));
+        assert!(slow.response_rx.try_recv().is_err());
+        assert!(workers.response_rx.try_recv().is_err());
+        assert_eq!(HANDLED.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_panicking_render_reports_error_and_worker_survives() {
+        // Renders normally, except that the text "boom" panics mid-request
+        fn panic_on_boom(msg: WorkerMessage, tx: &Sender<WorkerResponse>) {
+            if matches!(&msg, WorkerMessage::TextRequest { text, .. } if text == "boom") {
+                panic!("injected test panic");
+            }
+            handle_message(msg, tx);
+        }
+
+        let workers = spawn_workers_with(1, panic_on_boom);
+        let recv = || {
+            workers
+                .response_rx
+                .recv_timeout(std::time::Duration::from_secs(5))
+                .unwrap()
+        };
+
+        workers.request_tx.send(text_request("boom")).unwrap();
+        match recv() {
+            WorkerResponse::Error(message) => {
+                assert!(message.contains("injected test panic"), "{}", message)
+            }
+            other => panic!("Expected an error, got {:?}", other),
+        }
+
+        // The same (only) worker answers the next request
+        workers.request_tx.send(text_request("Hello")).unwrap();
+        assert!(matches!(recv(), WorkerResponse::TextComplete { .. }));
+
+        workers.shutdown();
+    }
+
+    #[test]
+    fn test_text_request_with_link() {
+        let workers = spawn_workers(1);
+
+        workers
+            .request_tx
+            .send(WorkerMessage::TextRequest {
+                text: "Hi".to_string(),
+                transform: TextTransform::None,
+                style: UnicodeStyle::Bold,
+                gradient: GradientMode::None,
+                start_color: (255, 0, 0),
+                end_color: (0, 0, 255),
+                alignment: TextAlignment::Left,
+                link: Some("https://example.com".to_string()),
+                unicode_support: UnicodeSupport::Full,
+            })
+            .unwrap();
+
+        match workers
+            .response_rx
+            .recv_timeout(std::time::Duration::from_secs(5))
+            .unwrap()
+        {
+            WorkerResponse::TextComplete { output, .. } => {
+                assert!(output.starts_with("\x1b]8;;https://example.com\x1b\\𝐇"));
+                assert!(output.ends_with("\x1b]8;;\x1b\\"));
+            }
+            _ => panic!("Unexpected response type"),
+        }
+
+        workers.shutdown();
+    }
+
+    #[test]
+    fn test_graphics_request() {
+        let workers = spawn_workers(1);
+
+        let img = Arc::new(DynamicImage::ImageRgb8(RgbImage::new(20, 10)));
+
+        workers
+            .request_tx
+            .send(WorkerMessage::GraphicsRequest {
+                image: img,
+                config: UnicodeConfig {
+                    target_width: 10,
+                    ..UnicodeConfig::default()
+                },
+                mode: GraphicsMode::Sixel,
+                max_size: (40, 40),
+            })
+            .unwrap();
+
+        match recv_final(&workers) {
+            WorkerResponse::GraphicsComplete {
+                output, graphics, ..
+            } => {
+                assert!(!output.is_empty());
+                assert!(graphics.starts_with("\x1bPq"));
+            }
+            _ => panic!("Unexpected response type"),
+        }
+
+        workers.shutdown();
+    }
+
+    #[test]
+    fn test_tall_ascii_render_is_streamed_in_chunks() {
+        let workers = spawn_workers(1);
+
+        let img = Arc::new(DynamicImage::ImageRgb8(RgbImage::from_fn(
+            200,
+            300,
+            |x, y| image::Rgb([(x % 256) as u8, (y % 256) as u8, 128]),
+        )));
+        workers
+            .request_tx
+            .send(WorkerMessage::AsciiRequest {
+                image: img,
+                config: AsciiConfig {
+                    target_width: 120,
+                    ..AsciiConfig::default()
+                },
+            })
+            .unwrap();
+
+        let mut streamed = String::new();
+        let mut next_seq = 0;
+        let output = loop {
+            match workers
+                .response_rx
+                .recv_timeout(std::time::Duration::from_secs(10))
+                .unwrap()
+            {
+                WorkerResponse::Progress { .. } => {}
+                WorkerResponse::AsciiChunk { seq, rows } => {
+                    assert_eq!(seq, next_seq);
+                    next_seq += 1;
+                    streamed.push_str(&rows);
+                }
+                WorkerResponse::AsciiComplete { output, .. } => break output,
+                other => panic!("Unexpected response {:?}", other),
+            }
+        };
+        assert!(next_seq > 1);
+        assert_eq!(streamed, output);
+
+        workers.shutdown();
+    }
+
+    #[test]
+    fn test_progress_throttle() {
+        let (tx, rx) = unbounded();
+        let mut throttle = ProgressThrottle::new(&tx);
+
+        // Too soon after the render started, and completion is never reported
+        throttle.report(0.1);
+        assert!(rx.try_recv().is_err());
+        throttle.last_sent -= PROGRESS_INTERVAL;
+        throttle.report(1.0);
+        assert!(rx.try_recv().is_err());
+
+        throttle.report(0.5);
+        match rx.try_recv() {
+            Ok(WorkerResponse::Progress { fraction }) => assert_eq!(fraction, 0.5),
+            other => panic!("Unexpected response: {:?}", other),
+        }
+        throttle.report(0.6);
+        assert!(rx.try_recv().is_err());
+    }
 }
@@ -1,8 +1,11 @@
 //! Integration tests for Glyphgen
 
 use glyphgen::render_engines::ascii::{render_ascii, AsciiConfig, CharacterSet};
-use glyphgen::render_engines::text_stylizer::{stylize_text, GradientMode, UnicodeStyle};
+use glyphgen::render_engines::text_stylizer::{
+    stylize_text, GradientMode, TextAlignment, TextTransform, UnicodeStyle,
+};
 use glyphgen::render_engines::unicode::{render_unicode, UnicodeConfig, UnicodeMode};
+use glyphgen::render_engines::PARALLEL_MIN_CELLS;
 use glyphgen::terminal_capabilities::ColorSupport;
 use image::{DynamicImage, RgbImage};
 
@@ -41,6 +44,7 @@ mod ascii_tests {
             charset: CharacterSet::Standard,
             invert: false,
             edge_enhance: false,
+            ..AsciiConfig::default()
         };
 
         let result = render_ascii(&image, &config).unwrap();
@@ -51,7 +55,7 @@ mod ascii_tests {
 
         // Check dimensions
         let lines: Vec<&str> = result.lines().collect();
-        assert!(lines.len() > 0);
+        assert!(!lines.is_empty());
 
         // First line should be target width
         assert_eq!(lines[0].chars().count(), 40);
@@ -61,12 +65,17 @@ mod ascii_tests {
     fn test_ascii_charsets() {
         let image = create_gradient_image(50, 50);
 
-        for charset in [CharacterSet::Standard, CharacterSet::Extended, CharacterSet::Blocks] {
+        for charset in [
+            CharacterSet::Standard,
+            CharacterSet::Extended,
+            CharacterSet::Blocks,
+        ] {
             let config = AsciiConfig {
                 target_width: 20,
                 charset: charset.clone(),
                 invert: false,
                 edge_enhance: false,
+                ..AsciiConfig::default()
             };
 
             let result = render_ascii(&image, &config).unwrap();
@@ -83,6 +92,7 @@ mod ascii_tests {
             charset: CharacterSet::Standard,
             invert: false,
             edge_enhance: false,
+            ..AsciiConfig::default()
         };
 
         let config_inverted = AsciiConfig {
@@ -90,6 +100,7 @@ mod ascii_tests {
             charset: CharacterSet::Standard,
             invert: true,
             edge_enhance: false,
+            ..AsciiConfig::default()
         };
 
         let result_normal = render_ascii(&image, &config_normal).unwrap();
@@ -108,6 +119,7 @@ mod ascii_tests {
             charset: CharacterSet::Extended,
             invert: false,
             edge_enhance: false,
+            ..AsciiConfig::default()
         };
 
         let config_enhanced = AsciiConfig {
@@ -115,6 +127,7 @@ mod ascii_tests {
             charset: CharacterSet::Extended,
             invert: false,
             edge_enhance: true,
+            ..AsciiConfig::default()
         };
 
         let result_normal = render_ascii(&image, &config_normal).unwrap();
@@ -135,13 +148,38 @@ mod ascii_tests {
                 charset: CharacterSet::Extended,
                 invert: false,
                 edge_enhance: false,
+                ..AsciiConfig::default()
             };
 
             let result = render_ascii(&image, &config).unwrap();
             let first_line = result.lines().next().unwrap();
-            assert_eq!(first_line.chars().count(), width, "Width mismatch for {}", width);
+            assert_eq!(
+                first_line.chars().count(),
+                width,
+                "Width mismatch for {}",
+                width
+            );
         }
     }
+
+    #[test]
+    fn test_parallel_matches_serial() {
+        let image = create_color_image(800, 600);
+        let parallel = AsciiConfig {
+            target_width: 400,
+            edge_enhance: true,
+            ..AsciiConfig::default()
+        };
+        let serial = AsciiConfig {
+            parallel: false,
+            ..parallel.clone()
+        };
+
+        let output = render_ascii(&image, &parallel).unwrap();
+        // Smaller grids render serially even with `parallel` set
+        assert!(400 * output.lines().count() >= PARALLEL_MIN_CELLS);
+        assert_eq!(output, render_ascii(&image, &serial).unwrap());
+    }
 }
 
 mod unicode_tests {
@@ -154,6 +192,7 @@ mod unicode_tests {
             target_width: 40,
             mode: UnicodeMode::Blocks,
             color_mode: ColorSupport::NoColor,
+            ..UnicodeConfig::default()
         };
 
         let result = render_unicode(&image, &config).unwrap();
@@ -167,6 +206,7 @@ mod unicode_tests {
             target_width: 40,
             mode: UnicodeMode::HalfBlocks,
             color_mode: ColorSupport::NoColor,
+            ..UnicodeConfig::default()
         };
 
         let result = render_unicode(&image, &config).unwrap();
@@ -181,6 +221,7 @@ mod unicode_tests {
             target_width: 40,
             mode: UnicodeMode::Braille,
             color_mode: ColorSupport::NoColor,
+            ..UnicodeConfig::default()
         };
 
         let result = render_unicode(&image, &config).unwrap();
@@ -201,6 +242,7 @@ mod unicode_tests {
                 target_width: 20,
                 mode: UnicodeMode::HalfBlocks,
                 color_mode,
+                ..UnicodeConfig::default()
             };
 
             let result = render_unicode(&image, &config).unwrap();
@@ -212,6 +254,42 @@ mod unicode_tests {
             }
         }
     }
+
+    #[test]
+    fn test_parallel_matches_serial() {
+        let image = create_color_image(800, 600);
+
+        for mode in [
+            UnicodeMode::Blocks,
+            UnicodeMode::HalfBlocks,
+            UnicodeMode::Braille,
+        ] {
+            let parallel = UnicodeConfig {
+                target_width: 400,
+                mode,
+                dither: true,
+                ..UnicodeConfig::default()
+            };
+            let serial = UnicodeConfig {
+                parallel: false,
+                ..parallel.clone()
+            };
+
+            let output = render_unicode(&image, &parallel).unwrap();
+            // Smaller grids render serially even with `parallel` set
+            assert!(
+                400 * output.lines().count() >= PARALLEL_MIN_CELLS,
+                "Grid too small to render in parallel for {:?}",
+                mode
+            );
+            assert_eq!(
+                output,
+                render_unicode(&image, &serial).unwrap(),
+                "Parallel output differs for {:?}",
+                mode
+            );
+        }
+    }
 }
 
 mod text_stylizer_tests {
@@ -221,10 +299,12 @@ mod text_stylizer_tests {
     fn test_basic_stylization() {
         let result = stylize_text(
             "Hello",
+            TextTransform::None,
             UnicodeStyle::Bold,
             GradientMode::None,
             (255, 0, 0),
             (0, 0, 255),
+            TextAlignment::Left,
         )
         .unwrap();
 
@@ -239,10 +319,12 @@ mod text_stylizer_tests {
         for style in UnicodeStyle::all() {
             let result = stylize_text(
                 text,
+                TextTransform::None,
                 *style,
                 GradientMode::None,
                 (255, 0, 0),
                 (0, 0, 255),
+                TextAlignment::Left,
             )
             .unwrap();
 
@@ -254,10 +336,12 @@ mod text_stylizer_tests {
     fn test_gradient_horizontal() {
         let result = stylize_text(
             "Rainbow",
+            TextTransform::None,
             UnicodeStyle::Bold,
             GradientMode::Horizontal,
             (255, 0, 0),
             (0, 0, 255),
+            TextAlignment::Left,
         )
         .unwrap();
 
@@ -269,10 +353,12 @@ mod text_stylizer_tests {
     fn test_gradient_rainbow() {
         let result = stylize_text(
             "Rainbow",
+            TextTransform::None,
             UnicodeStyle::Bold,
             GradientMode::Rainbow,
             (0, 0, 0),
             (0, 0, 0),
+            TextAlignment::Left,
         )
         .unwrap();
 
@@ -284,10 +370,12 @@ mod text_stylizer_tests {
     fn test_preserves_spaces() {
         let result = stylize_text(
             "A B C",
+            TextTransform::None,
             UnicodeStyle::Bold,
             GradientMode::None,
             (255, 0, 0),
             (0, 0, 255),
+            TextAlignment::Left,
         )
         .unwrap();
 
@@ -298,10 +386,12 @@ mod text_stylizer_tests {
     fn test_empty_string() {
         let result = stylize_text(
             "",
+            TextTransform::None,
             UnicodeStyle::Bold,
             GradientMode::None,
             (255, 0, 0),
             (0, 0, 255),
+            TextAlignment::Left,
         )
         .unwrap();
 
@@ -312,10 +402,12 @@ mod text_stylizer_tests {
     fn test_special_characters() {
         let result = stylize_text(
             "Hello, World! @#$%",
+            TextTransform::None,
             UnicodeStyle::Bold,
             GradientMode::None,
             (255, 0, 0),
             (0, 0, 255),
+            TextAlignment::Left,
         )
         .unwrap();
 
@@ -352,7 +444,7 @@ mod color_space_tests {
         // Pure red
         let red = Rgb::new(255, 0, 0);
         let code = quantize_to_ansi256(red);
-        assert!(code >= 16 && code <= 231);
+        assert!((16..=231).contains(&code));
 
         // Gray
         let gray = Rgb::new(128, 128, 128);